@@ -0,0 +1,58 @@
+//! Times classifying a year of days against growing numbers of events.
+//! 
+//! Run with `cargo bench --bench interval`.
+
+use std::hint::black_box;
+
+use chrono::{Days, NaiveDate};
+use chrono_tz::Asia::Shanghai;
+use choliday::{Choliday, Conf, Priority};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+/// Generates a calendar of pseudo-random events of up to ten days over about 27 years
+fn calendar(count: usize) -> String {
+    let mut seed: u64 = 42;
+    let mut next = |bound: u64| {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (seed >> 33) % bound
+    };
+    let origin = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+    let mut ics = String::from("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//choliday//bench//EN\r\n");
+    for i in 0..count {
+        let start = origin + Days::new(next(10_000));
+        let end = start + Days::new(1 + next(10));
+        let summary = if next(2) == 0 { "Holiday" } else { "Workday" };
+        ics.push_str(&format!(
+            "BEGIN:VEVENT\r\nUID:{i}@bench\r\nDTSTAMP:20000101T000000Z\r\nDTSTART;VALUE=DATE:{}\r\nDTEND;VALUE=DATE:{}\r\nSUMMARY:{summary}\r\nEND:VEVENT\r\n",
+            start.format("%Y%m%d"),
+            end.format("%Y%m%d"),
+        ));
+    }
+    ics.push_str("END:VCALENDAR\r\n");
+    ics
+}
+
+fn work_days_between(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    let start = NaiveDate::from_ymd_opt(2012, 1, 1).unwrap();
+    let end = NaiveDate::from_ymd_opt(2012, 12, 31).unwrap();
+
+    let mut group = c.benchmark_group("work days of a year");
+    for count in [1_000, 10_000, 100_000] {
+        let conf = Conf::builder(Priority::RestOverWork).work("Workday").rest("Holiday").build().unwrap();
+        let choliday = Choliday::builder(conf)
+            .date(start)
+            .timezone(Shanghai)
+            .use_cache(false)
+            .events(calendar(count))
+            .build()
+            .unwrap();
+        group.bench_with_input(BenchmarkId::new("events", count), &choliday, |b, choliday| {
+            b.iter(|| runtime.block_on(choliday.work_days_between(black_box(start), black_box(end))).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, work_days_between);
+criterion_main!(benches);
@@ -0,0 +1,74 @@
+//! Build script, generating the C header of the `ffi` feature.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "ffi")]
+    {
+        header();
+        // The ffi test compiles examples/ffi.c for the same target
+        println!("cargo:rustc-env=CHOLIDAY_TARGET={}", std::env::var("TARGET").unwrap_or_default());
+    }
+}
+
+/// Writes `choliday.h` to `OUT_DIR` from the declarations in `src/ffi.rs`
+/// 
+/// # Note
+/// * The build never writes to the source tree. The copy in `include/` is
+///   checked in, so C callers need no Rust tooling to read it; the `ffi` test
+///   fails when it differs from the generated one, and
+///   `CHOLIDAY_UPDATE_HEADER=1 cargo test --features ffi --test ffi` replaces it
+#[cfg(feature = "ffi")]
+fn header() {
+    use std::{collections::HashMap, env, path::Path};
+
+    use cbindgen::{Builder, Config, EnumConfig, ExportConfig, Language, RenameRule};
+
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    let config = Config {
+        language: Language::C,
+        header: Some("/* C bindings of choliday, MIT License, Copyright (c) 2026 66f94eae */".to_string()),
+        autogen_warning: Some("/* Generated by build.rs from src/ffi.rs with the `ffi` feature, do not edit */".to_string()),
+        include_guard: Some("CHOLIDAY_H".to_string()),
+        cpp_compat: true,
+        documentation: true,
+        enumeration: EnumConfig {
+            prefix_with_name: true,
+            rename_variants: RenameRule::ScreamingSnakeCase,
+            ..EnumConfig::default()
+        },
+        export: ExportConfig {
+            rename: HashMap::from([("CholidayHandle".to_string(), "Choliday".to_string())]),
+            ..ExportConfig::default()
+        },
+        ..Config::default()
+    };
+    let bindings = Builder::new()
+        .with_config(config)
+        .with_src("src/ffi.rs")
+        .generate()
+        .expect("src/ffi.rs declares valid C bindings");
+    let out_dir = env::var("OUT_DIR").expect("cargo sets OUT_DIR for build scripts");
+    bindings.write_to_file(Path::new(&out_dir).join("choliday.h"));
+}
@@ -0,0 +1,53 @@
+/*
+ * Exercises the C bindings of the `ffi` feature.
+ *
+ * MIT License, Copyright (c) 2026 66f94eae
+ *
+ * Build the library and run the harness against a configuration:
+ *
+ *   cargo rustc --release --lib --features ffi --crate-type cdylib
+ *   cc -Iinclude examples/ffi.c -Ltarget/release -lcholiday -o target/ffi
+ *   LD_LIBRARY_PATH=target/release target/ffi config.toml 1759320000000
+ *
+ * Exits with the status of the last call, so 0 for a work or rest day
+ * answered, or the status of the failure.
+ */
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "choliday.h"
+
+int main(int argc, char **argv) {
+    if (argc != 3) {
+        fprintf(stderr, "Usage: %s CONFIG EPOCH_MILLIS\n", argv[0]);
+        return CHOLIDAY_STATUS_INVALID_ARGUMENT;
+    }
+
+    /* Failures before a handle exists are reported on the thread */
+    if (choliday_new_from_config_path(NULL) != NULL || choliday_last_error_message(NULL) == NULL) {
+        fprintf(stderr, "A NULL path was accepted\n");
+        return CHOLIDAY_STATUS_ERROR;
+    }
+
+    Choliday *handle = choliday_new_from_config_path(argv[1]);
+    if (handle == NULL) {
+        fprintf(stderr, "Error: %s\n", choliday_last_error_message(NULL));
+        return CHOLIDAY_STATUS_ERROR;
+    }
+
+    bool work = false;
+    if (choliday_is_work_day(handle, 0, NULL) != CHOLIDAY_STATUS_INVALID_ARGUMENT) {
+        fprintf(stderr, "A NULL output was accepted\n");
+        choliday_free(handle);
+        return CHOLIDAY_STATUS_ERROR;
+    }
+
+    CholidayStatus status = choliday_is_work_day(handle, strtoll(argv[2], NULL, 10), &work);
+    if (status == CHOLIDAY_STATUS_OK) {
+        printf("%s\n", work ? "work day" : "rest day");
+    } else {
+        fprintf(stderr, "Error (%d): %s\n", status, choliday_last_error_message(handle));
+    }
+    choliday_free(handle);
+    return status;
+}
@@ -0,0 +1,132 @@
+/* C bindings of choliday, MIT License, Copyright (c) 2026 66f94eae */
+
+#ifndef CHOLIDAY_H
+#define CHOLIDAY_H
+
+/* Generated by build.rs from src/ffi.rs with the `ffi` feature, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Outcome of a call, returned by every function that can fail
+ * 
+ * # Note
+ * * `Error`, `Unknown` and `Unresolved` have the values of the matching exit
+ *   codes of the command line
+ */
+typedef enum CholidayStatus {
+  /**
+   * The call succeeded
+   */
+  CHOLIDAY_STATUS_OK = 0,
+  /**
+   * A pointer argument was null, a string not UTF-8 or a timestamp out of range
+   */
+  CHOLIDAY_STATUS_INVALID_ARGUMENT = 1,
+  /**
+   * The configuration could not be loaded or the call failed otherwise
+   */
+  CHOLIDAY_STATUS_ERROR = 2,
+  /**
+   * An event matching both work and rest decides the day under `on_conflict = "error"`
+   */
+  CHOLIDAY_STATUS_UNRESOLVED = 3,
+  /**
+   * The calendar data is not enough to classify the day
+   */
+  CHOLIDAY_STATUS_UNKNOWN = 4,
+  /**
+   * The library panicked; the handle is best freed
+   */
+  CHOLIDAY_STATUS_PANIC = 5,
+} CholidayStatus;
+
+/**
+ * Analyzer behind a handle, named `Choliday` in the C header
+ * 
+ * # Note
+ * * A handle may move between threads, but must not be used by two at once
+ */
+typedef struct Choliday Choliday;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Loads a configuration file and creates a handle answering from its sources
+ * 
+ * # Arguments
+ * * `path` - Path of the TOML configuration file, a NUL-terminated UTF-8 string
+ * 
+ * # Returns
+ * * A handle to pass to the other functions and free with [`choliday_free`]
+ * * `NULL` on failure; `choliday_last_error_message(NULL)` then tells why
+ * 
+ * # Note
+ * * Sources are read by the first query, not here
+ * 
+ * # Safety
+ * * `path` is `NULL` or points to a NUL-terminated string
+ */
+struct Choliday *choliday_new_from_config_path(const char *path);
+
+/**
+ * Determines if an instant falls on a work day
+ * 
+ * # Arguments
+ * * `handle` - Handle from [`choliday_new_from_config_path`]
+ * * `epoch_millis` - Unix timestamp in milliseconds, classified at its
+ *   wall-clock time in the target zone like `-d` does
+ * * `out` - Set to whether the instant falls on a work day, on success only
+ * 
+ * # Returns
+ * * `CHOLIDAY_STATUS_OK` with `*out` set, or the reason it was not
+ * 
+ * # Note
+ * * Blocks until the sources are read; all events are read by the first
+ *   call and kept for later ones
+ * * Decisions are the same as `Choliday::is_work_day` for the instant
+ * 
+ * # Safety
+ * * `handle` is `NULL` or a live handle not used by another thread meanwhile
+ * * `out` is `NULL` or points to writable memory for a `bool`
+ */
+enum CholidayStatus choliday_is_work_day(struct Choliday *handle, int64_t epoch_millis, bool *out);
+
+/**
+ * Returns the message of the last failed call
+ * 
+ * # Arguments
+ * * `handle` - Handle the call was made with, or `NULL` for the last
+ *   failed [`choliday_new_from_config_path`] on this thread
+ * 
+ * # Returns
+ * * A NUL-terminated UTF-8 string owned by the library, valid until the next
+ *   call with the same handle (or on this thread) or until it is freed
+ * * `NULL` when the last call succeeded
+ * 
+ * # Safety
+ * * `handle` is `NULL` or a live handle
+ */
+const char *choliday_last_error_message(const struct Choliday *handle);
+
+/**
+ * Frees a handle and everything it keeps
+ * 
+ * # Arguments
+ * * `handle` - Handle from [`choliday_new_from_config_path`], or `NULL`
+ * 
+ * # Safety
+ * * `handle` is `NULL` or a live handle, not used afterwards
+ */
+void choliday_free(struct Choliday *handle);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* CHOLIDAY_H */
@@ -0,0 +1,83 @@
+//! Blocking wrappers of the async API, for callers without an async runtime.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use tokio::runtime::{Builder, Handle, Runtime};
+
+use crate::{choliday::Choliday, decision::DayDecision};
+
+impl Choliday {
+    /// Determines if the target date is a workday, blocking the calling thread
+    /// 
+    /// # Returns
+    /// * Same as [`Choliday::is_work_day`]
+    /// 
+    /// # Note
+    /// * See [`Choliday::evaluate_blocking`]
+    pub fn is_work_day_blocking(&self) -> Result<bool, String> {
+        self.evaluate_blocking().map(|decision| decision.is_work_day())
+    }
+
+    /// Classifies the target date, blocking the calling thread
+    /// 
+    /// # Returns
+    /// * Same as [`Choliday::evaluate`]
+    /// 
+    /// # Note
+    /// * Runs the async implementation on a private current-thread runtime,
+    ///   so fetching and classification are shared with the async API
+    /// * Returns an error when called from within an async runtime; use `evaluate` there
+    /// 
+    /// # Examples
+    /// ```no_run
+    /// use chrono::NaiveDate;
+    /// use choliday::{Choliday, Conf};
+    /// 
+    /// # fn main() -> Result<(), String> {
+    /// let conf = Conf::load("config.toml")?;
+    /// let noon = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap().and_hms_opt(12, 0, 0).unwrap();
+    /// let decision = Choliday::new(conf, noon).evaluate_blocking()?;
+    /// println!("{:?} by {}", decision.day_type(), decision.source());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn evaluate_blocking(&self) -> Result<DayDecision, String> {
+        runtime()?.block_on(self.evaluate())
+    }
+}
+
+/// Builds the runtime a blocking call runs on
+/// 
+/// # Returns
+/// * `Ok(Runtime)` - Current-thread runtime with I/O and timers enabled
+/// * `Err(String)` - The calling thread runs an async runtime already, which
+///   blocking on another one would panic, or the runtime could not be created
+pub(crate) fn runtime() -> Result<Runtime, String> {
+    if Handle::try_current().is_ok() {
+        return Err("Blocking calls cannot run within an async runtime, use the async API instead".to_string());
+    }
+    Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| format!("Failed to start runtime: {}", e))
+}
@@ -0,0 +1,613 @@
+//! On-disk cache of remote calendar bodies, their HTTP validators and fetch metadata.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    ops::Deref,
+    path::{Path, PathBuf},
+    process,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use directories::ProjectDirs;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    conf::{CacheBackend, Conf},
+    source::Freshness,
+};
+
+/// Name of the index file inside the cache directory
+const INDEX_FILE: &str = "index.toml";
+/// Extension of cached calendar bodies
+const BODY_EXT: &str = "ics";
+/// Extension of files being written before they are renamed into place
+const TMP_EXT: &str = "tmp";
+/// Name of the database of the SQLite backend inside the cache directory
+pub(crate) const DB_FILE: &str = "cache.sqlite";
+
+/// Cached response of a remote source
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CacheEntry {
+    /// URL the body was fetched from
+    url: String,
+    /// Fetch time in seconds since the UNIX epoch
+    #[serde(default)]
+    fetched_at: u64,
+    /// HTTP status of the cached response
+    #[serde(default)]
+    status: u16,
+    /// `ETag` header of the cached response
+    etag: Option<String>,
+    /// `Last-Modified` header of the cached response
+    last_modified: Option<String>,
+    /// Number of events parsed from the body when it was last read
+    events: Option<usize>,
+    /// Time until which the body is used without asking the server, in
+    /// seconds since the UNIX epoch
+    fresh_until: Option<u64>,
+}
+
+impl CacheEntry {
+    /// Creates the metadata of a cached response
+    pub(crate) fn new(
+        url: String,
+        fetched_at: u64,
+        status: u16,
+        etag: Option<String>,
+        last_modified: Option<String>,
+        events: Option<usize>,
+        fresh_until: Option<u64>,
+    ) -> Self {
+        CacheEntry { url, fetched_at, status, etag, last_modified, events, fresh_until }
+    }
+
+    /// Returns the URL the body was fetched from
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the HTTP status of the cached response
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// Returns the `ETag` of the cached response
+    pub fn etag(&self) -> Option<&str> {
+        self.etag.as_deref()
+    }
+
+    /// Returns the `Last-Modified` value of the cached response
+    pub fn last_modified(&self) -> Option<&str> {
+        self.last_modified.as_deref()
+    }
+
+    /// Returns the number of events parsed from the body, if it was parsed
+    pub fn events(&self) -> Option<usize> {
+        self.events
+    }
+
+    /// Returns how long ago the response was fetched
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(now_secs().saturating_sub(self.fetched_at))
+    }
+
+    /// Returns how much longer the body is used without asking the server
+    /// 
+    /// # Returns
+    /// * `None` if it is revalidated before every use, or has expired
+    pub fn fresh_for(&self) -> Option<Duration> {
+        let secs = self.fresh_until?.checked_sub(now_secs()).filter(|secs| *secs > 0)?;
+        Some(Duration::from_secs(secs))
+    }
+}
+
+/// Converts a freshness lifetime starting now to the time it ends
+fn fresh_until(fresh_for: Option<Duration>) -> Option<u64> {
+    fresh_for.map(|lifetime| now_secs().saturating_add(lifetime.as_secs()))
+}
+
+/// Storage of cached remote calendars, implemented by every cache backend
+/// 
+/// # Note
+/// * Caching is best-effort: failures to write are logged, not returned
+/// * Entries are keyed by the normalized URL of the source
+/// 
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use choliday::{Cache, CacheStore, FileStore};
+/// 
+/// // The same checks hold for every backend
+/// fn exercise(open: impl Fn() -> Box<dyn CacheStore>) {
+///     let url = "https://example.com/holidays.ics";
+///     let store = open();
+///     assert!(store.lookup(url).is_none());
+///     assert!(store.fallback(url).is_none());
+/// 
+///     store.store(url, 200, b"BEGIN:VCALENDAR", Some("\"v1\"".to_string()), None, Some(Duration::from_secs(3600)));
+///     store.record_events(url, 12);
+///     store.save();
+/// 
+///     // Kept across runs
+///     let store = open();
+///     let entry = store.lookup(url).unwrap();
+///     assert_eq!(entry.etag(), Some("\"v1\""));
+///     assert_eq!(entry.status(), 200);
+///     assert_eq!(store.body(url).unwrap(), b"BEGIN:VCALENDAR");
+///     assert_eq!(store.fallback(url).unwrap().0, b"BEGIN:VCALENDAR");
+///     assert!(entry.fresh_for().is_some_and(|left| left > Duration::from_secs(3500)));
+/// 
+///     // A new body replaces the old one and its event count
+///     store.store(url, 200, b"BEGIN:VCALENDAR\r\n", None, Some("Mon, 05 Jan 2026 00:00:00 GMT".to_string()), None);
+///     assert_eq!(store.lookup(url).unwrap().fresh_for(), None);
+///     store.touch(url, Some(Duration::from_secs(60)));
+///     let entries = store.entries();
+///     assert_eq!(entries.len(), 1);
+///     assert_eq!(entries[0].url(), url);
+///     assert_eq!(entries[0].etag(), None);
+///     assert_eq!(entries[0].events(), None);
+///     assert!(entries[0].fresh_for().is_some());
+///     store.save();
+/// }
+/// 
+/// # fn main() -> Result<(), String> {
+/// let temp = tempfile::tempdir().map_err(|e| e.to_string())?;
+/// let dir = temp.path().join("cache");
+/// exercise(|| Box::new(FileStore::open(dir.clone())));
+/// # #[cfg(feature = "sqlite")]
+/// exercise(|| Box::new(choliday::SqliteStore::open(&dir).unwrap()));
+/// assert!(Cache::clear(&dir)? > 0);
+/// # Ok(())
+/// # }
+/// ```
+pub trait CacheStore: Send + Sync {
+    /// Looks up the cached entry of a URL
+    /// 
+    /// # Returns
+    /// * `Some(CacheEntry)` - Metadata of the cached response, if its body still exists
+    fn lookup(&self, url: &str) -> Option<CacheEntry>;
+
+    /// Reads the cached body of a URL
+    fn body(&self, url: &str) -> Result<Vec<u8>, String>;
+
+    /// Stores a fresh response
+    /// 
+    /// # Arguments
+    /// * `url` - URL the body was fetched from
+    /// * `status` - HTTP status of the response
+    /// * `body` - Response body
+    /// * `etag` / `last_modified` - Validators of the response
+    /// * `fresh_for` - How long the body is used without asking the server,
+    ///   `None` to revalidate it before every use
+    fn store(
+        &self,
+        url: &str,
+        status: u16,
+        body: &[u8],
+        etag: Option<String>,
+        last_modified: Option<String>,
+        fresh_for: Option<Duration>,
+    );
+
+    /// Marks the cached copy of a URL as fetched now, after a `304 Not Modified`
+    /// 
+    /// # Arguments
+    /// * `url` - URL of the cached copy
+    /// * `fresh_for` - New freshness lifetime from the `304` response, as for `store`
+    fn touch(&self, url: &str, fresh_for: Option<Duration>);
+
+    /// Records the number of events parsed from the cached body of a URL
+    fn record_events(&self, url: &str, events: usize);
+
+    /// Lists every cached entry, ordered by URL
+    fn entries(&self) -> Vec<CacheEntry>;
+
+    /// Writes pending changes back to disk
+    fn save(&self) {}
+
+    /// Returns the last successfully fetched copy of a URL
+    /// 
+    /// # Returns
+    /// * `Some((body, age))` - Cached body and how long ago it was fetched
+    /// * `None` - URL was never cached
+    fn fallback(&self, url: &str) -> Option<(Vec<u8>, Duration)> {
+        let entry = self.lookup(url)?;
+        let body = self.body(url).ok()?;
+        Some((body, entry.age()))
+    }
+}
+
+/// Cache of remote calendars in the backend a configuration selects
+/// 
+/// # Note
+/// * Dereferences to the [`CacheStore`] of the backend
+pub struct Cache {
+    /// Backend holding the cached calendars
+    store: Box<dyn CacheStore>,
+    /// Freshness lifetime set by `cache.ttl`, overriding the servers'
+    ttl: Option<Duration>,
+    /// Whether fresh copies are revalidated anyway
+    revalidate: bool,
+}
+
+impl Cache {
+    /// Opens the cache of a configuration in its configured backend
+    /// 
+    /// # Returns
+    /// * `None` if no cache directory can be determined
+    /// 
+    /// # Note
+    /// * Falls back to the file backend with a warning when the SQLite
+    ///   database cannot be opened or the `sqlite` feature is not compiled in
+    pub fn open(conf: &Conf) -> Option<Self> {
+        let dir = Cache::dir_for(conf)?;
+        let cache = |store: Box<dyn CacheStore>| Cache { store, ttl: conf.cache_ttl(), revalidate: false };
+        if conf.cache_backend() == CacheBackend::Sqlite {
+            #[cfg(feature = "sqlite")]
+            match crate::sqlite::SqliteStore::open(&dir) {
+                Ok(store) => return Some(cache(Box::new(store))),
+                Err(e) => warn!("Using the file cache instead of SQLite: {}", e),
+            }
+            #[cfg(not(feature = "sqlite"))]
+            warn!("cache.backend = \"sqlite\" needs the sqlite feature, using the file cache");
+        }
+        Some(cache(Box::new(FileStore::open(dir))))
+    }
+
+    /// Sets whether cached copies are revalidated with the server even while fresh
+    /// 
+    /// # Arguments
+    /// * `revalidate` - `true` to always send a (conditional) request, as `--refresh` does
+    pub fn revalidate(mut self, revalidate: bool) -> Self {
+        self.revalidate = revalidate;
+        self
+    }
+
+    /// Returns how much longer the cached copy of a URL is used without a request
+    /// 
+    /// # Returns
+    /// * `None` if there is no fresh copy, or fresh copies are revalidated anyway
+    pub fn fresh_for(&self, url: &str) -> Option<Duration> {
+        if self.revalidate {
+            return None;
+        }
+        self.lookup(url)?.fresh_for()
+    }
+
+    /// Returns the freshness lifetime to store a response with
+    /// 
+    /// # Arguments
+    /// * `freshness` - Freshness given by the response's headers
+    /// 
+    /// # Returns
+    /// * `cache.ttl` if configured, else the server's lifetime, `None` to
+    ///   revalidate before every use
+    pub fn lifetime(&self, freshness: Freshness) -> Option<Duration> {
+        match freshness {
+            _ if self.ttl.is_some() => self.ttl,
+            Freshness::FreshFor(lifetime) => Some(lifetime),
+            Freshness::NoStore | Freshness::Revalidate => None,
+        }
+    }
+
+    /// Resolves the cache directory of a configuration
+    /// 
+    /// # Returns
+    /// * `cache.dir` if configured, else the platform cache directory
+    ///   (e.g. `~/.cache/choliday` on Linux)
+    /// * `None` if no home directory can be determined
+    pub fn dir_for(conf: &Conf) -> Option<PathBuf> {
+        conf.cache_dir().map(Path::to_path_buf).or_else(|| {
+            ProjectDirs::from("", "", "choliday").map(|dirs| dirs.cache_dir().to_path_buf())
+        })
+    }
+
+    /// Removes every cached body, the index and the database from a cache directory
+    /// 
+    /// # Arguments
+    /// * `dir` - Cache directory
+    /// 
+    /// # Returns
+    /// * `Ok(usize)` - Number of removed files
+    /// * `Err(String)` - Directory could not be read or a file not removed
+    /// 
+    /// # Note
+    /// * Only files created by either backend are removed, the directory is kept
+    pub fn clear(dir: &Path) -> Result<usize, String> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(format!("Cannot read cache directory '{}': {}", dir.display(), e)),
+        };
+
+        let mut removed = 0;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let owned = path.file_name().is_some_and(|name| {
+                name == INDEX_FILE || name.to_str().is_some_and(|name| name.starts_with(DB_FILE))
+            }) || path.extension().is_some_and(|ext| ext == BODY_EXT || ext == TMP_EXT);
+            if owned && path.is_file() {
+                fs::remove_file(&path)
+                    .map_err(|e| format!("Cannot remove '{}': {}", path.display(), e))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+impl Deref for Cache {
+    type Target = dyn CacheStore;
+
+    fn deref(&self) -> &Self::Target {
+        self.store.as_ref()
+    }
+}
+
+/// Cache backend of files in a directory, the default
+/// 
+/// # Note
+/// * Bodies are stored as `<hash>.ics`, metadata in `index.toml`
+/// * The index is only written back by [`CacheStore::save`], which merges
+///   the entries this run changed into the index on disk, so concurrent runs
+///   keep each other's entries
+/// * Files are written to a temporary name and renamed into place, so
+///   concurrent runs never read a partially written file
+pub struct FileStore {
+    /// Directory holding the bodies and the index
+    dir: PathBuf,
+    /// Entries known to the cache
+    index: Mutex<Index>,
+}
+
+/// Index of cached entries, keyed by the URL hash
+#[derive(Serialize, Deserialize, Default)]
+struct Index {
+    #[serde(default)]
+    entries: HashMap<String, CacheEntry>,
+    /// Keys of the entries changed since the index was read
+    #[serde(skip)]
+    changed: HashSet<String>,
+}
+
+impl FileStore {
+    /// Opens the cache in a directory, starting empty if the index is unreadable
+    /// 
+    /// # Arguments
+    /// * `dir` - Cache directory, created on first store
+    pub fn open(dir: PathBuf) -> Self {
+        let index = Index::read(&dir);
+        FileStore { dir, index: Mutex::new(index) }
+    }
+
+    /// Returns the path of a cached body
+    fn body_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", key, BODY_EXT))
+    }
+}
+
+impl CacheStore for FileStore {
+    fn lookup(&self, url: &str) -> Option<CacheEntry> {
+        let key = key(url);
+        let entry = self.index.lock().ok()?.entries.get(&key).cloned()?;
+        self.body_path(&key).is_file().then_some(entry)
+    }
+
+    fn body(&self, url: &str) -> Result<Vec<u8>, String> {
+        let path = self.body_path(&key(url));
+        fs::read(&path).map_err(|e| format!("Cannot read cached body '{}': {}", path.display(), e))
+    }
+
+    fn store(
+        &self,
+        url: &str,
+        status: u16,
+        body: &[u8],
+        etag: Option<String>,
+        last_modified: Option<String>,
+        fresh_for: Option<Duration>,
+    ) {
+        let key = key(url);
+        let Ok(mut index) = self.index.lock() else {
+            return;
+        };
+        if let Err(e) = fs::create_dir_all(&self.dir).and_then(|_| write_atomic(&self.body_path(&key), body)) {
+            warn!("Cannot cache '{}' in '{}': {}", url, self.dir.display(), e);
+            return;
+        }
+        debug!("Cached '{}' as {}", url, key);
+        let entry = CacheEntry::new(url.to_string(), now_secs(), status, etag, last_modified, None, fresh_until(fresh_for));
+        index.changed.insert(key.clone());
+        index.entries.insert(key, entry);
+    }
+
+    fn touch(&self, url: &str, fresh_for: Option<Duration>) {
+        let key = key(url);
+        if let Ok(mut index) = self.index.lock()
+            && let Some(entry) = index.entries.get_mut(&key)
+        {
+            entry.fetched_at = now_secs();
+            entry.fresh_until = fresh_until(fresh_for);
+            index.changed.insert(key);
+        }
+    }
+
+    fn record_events(&self, url: &str, events: usize) {
+        let key = key(url);
+        if let Ok(mut index) = self.index.lock()
+            && let Some(entry) = index.entries.get_mut(&key)
+        {
+            entry.events = Some(events);
+            index.changed.insert(key);
+        }
+    }
+
+    fn entries(&self) -> Vec<CacheEntry> {
+        let Ok(index) = self.index.lock() else {
+            return Vec::new();
+        };
+        let mut entries: Vec<CacheEntry> = index
+            .entries
+            .iter()
+            .filter(|(key, _)| self.body_path(key).is_file())
+            .map(|(_, entry)| entry.clone())
+            .collect();
+        entries.sort_by(|a, b| a.url.cmp(&b.url));
+        entries
+    }
+
+    fn save(&self) {
+        let Ok(mut index) = self.index.lock() else {
+            return;
+        };
+        if index.changed.is_empty() {
+            return;
+        }
+
+        // Another run may have written the index since it was read
+        let mut merged = Index::read(&self.dir);
+        for key in &index.changed {
+            let Some(entry) = index.entries.get(key) else {
+                continue;
+            };
+            if merged.entries.get(key).is_none_or(|theirs| theirs.fetched_at <= entry.fetched_at) {
+                merged.entries.insert(key.clone(), entry.clone());
+            }
+        }
+        *index = merged;
+
+        let result = toml::to_string(&*index)
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                fs::create_dir_all(&self.dir)
+                    .and_then(|_| write_atomic(&self.dir.join(INDEX_FILE), text.as_bytes()))
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            warn!("Cannot write cache index in '{}': {}", self.dir.display(), e);
+        }
+    }
+}
+
+impl Index {
+    /// Reads the index of a cache directory, empty if missing or unreadable
+    fn read(dir: &Path) -> Self {
+        match fs::read_to_string(dir.join(INDEX_FILE)) {
+            Ok(text) => toml::from_str(&text).unwrap_or_else(|e| {
+                warn!("Ignoring corrupt cache index in '{}': {}", dir.display(), e);
+                Index::default()
+            }),
+            Err(_) => Index::default(),
+        }
+    }
+}
+
+/// Writes a file through a temporary file in the same directory and a rename
+/// 
+/// # Note
+/// * The temporary name includes the process id so concurrent runs do not
+///   write to the same temporary file
+fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let tmp = path.with_extension(format!("{}.{}", process::id(), TMP_EXT));
+    fs::write(&tmp, contents).and_then(|_| fs::rename(&tmp, path)).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+/// Returns the current time in seconds since the UNIX epoch
+pub(crate) fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Hashes a URL into a stable cache key (64-bit FNV-1a, hex encoded)
+fn key(url: &str) -> String {
+    format!("{:016x}", fnv1a(url.as_bytes()))
+}
+
+/// Hashes data with 64-bit FNV-1a
+/// 
+/// # Note
+/// * `DefaultHasher` is not guaranteed to be stable across Rust releases,
+///   which would orphan the cache after a toolchain update
+pub(crate) fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent_runs_keep_each_others_entries() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("cache");
+        let (first, second) = ("https://example.com/first.ics", "https://example.com/second.ics");
+
+        // Both runs read the index before either writes it back
+        let a = FileStore::open(dir.clone());
+        let b = FileStore::open(dir.clone());
+        a.store(first, 200, b"BEGIN:VCALENDAR", None, None, None);
+        b.store(second, 200, b"BEGIN:VCALENDAR", None, None, None);
+        a.save();
+        b.save();
+
+        let store = FileStore::open(dir);
+        let urls: Vec<String> = store.entries().iter().map(|entry| entry.url().to_string()).collect();
+        assert_eq!(urls, [first, second]);
+    }
+
+    #[test]
+    fn newer_entry_on_disk_is_kept() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("cache");
+        let url = "https://example.com/holidays.ics";
+
+        let stale = FileStore::open(dir.clone());
+        stale.store(url, 200, b"BEGIN:VCALENDAR", Some("\"v1\"".to_string()), None, None);
+        stale.index.lock().unwrap().entries.values_mut().for_each(|entry| entry.fetched_at -= 60);
+        let fresh = FileStore::open(dir.clone());
+        fresh.store(url, 200, b"BEGIN:VCALENDAR", Some("\"v2\"".to_string()), None, None);
+        fresh.save();
+        stale.save();
+
+        assert_eq!(FileStore::open(dir).lookup(url).unwrap().etag(), Some("\"v2\""));
+    }
+
+    #[test]
+    fn unchanged_store_does_not_write_the_index() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("cache");
+        FileStore::open(dir.clone()).save();
+        assert!(!dir.exists());
+    }
+}
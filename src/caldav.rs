@@ -0,0 +1,110 @@
+//! CalDAV calendar-query requests and multistatus responses.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+
+use chrono::{DateTime, Utc};
+use roxmltree::Document;
+
+use crate::ical::Window;
+
+/// XML namespace of CalDAV elements
+const CALDAV_NS: &str = "urn:ietf:params:xml:ns:caldav";
+
+/// XML namespace of WebDAV elements
+const DAV_NS: &str = "DAV:";
+
+/// Margin around the queried instants covered by the time-range filter,
+/// since servers may interpret all-day events in their own timezone
+const RANGE_MARGIN_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+/// Builds the body of a calendar-query REPORT for events
+/// 
+/// # Arguments
+/// * `filter` - Queried instants, if any
+/// 
+/// # Returns
+/// * A calendar-query asking for the calendar data of every VEVENT,
+///   restricted to one day either side of `filter` when given
+pub fn query(filter: Option<Window>) -> String {
+    let format = |ts: i64| {
+        DateTime::<Utc>::from_timestamp_millis(ts)
+            .unwrap_or_default()
+            .format("%Y%m%dT%H%M%SZ")
+            .to_string()
+    };
+    let time_range = filter
+        .map(|window| {
+            format!(
+                r#"<C:time-range start="{}" end="{}"/>"#,
+                format(window.start.saturating_sub(RANGE_MARGIN_MILLIS)),
+                format(window.end.saturating_add(RANGE_MARGIN_MILLIS)),
+            )
+        })
+        .unwrap_or_default();
+
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<C:calendar-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop>
+    <C:calendar-data/>
+  </D:prop>
+  <C:filter>
+    <C:comp-filter name="VCALENDAR">
+      <C:comp-filter name="VEVENT">{}</C:comp-filter>
+    </C:comp-filter>
+  </C:filter>
+</C:calendar-query>
+"#,
+        time_range
+    )
+}
+
+/// Extracts the calendar data of a multistatus response
+/// 
+/// # Arguments
+/// * `body` - Multistatus XML, already decoded to UTF-8
+/// 
+/// # Returns
+/// * `Ok(Vec<String>)` - iCalendar text of each calendar object in the response
+/// * `Err(String)` - The response is not a multistatus document
+pub fn calendar_data(body: &[u8]) -> Result<Vec<String>, String> {
+    let text = String::from_utf8_lossy(body);
+    let doc = Document::parse(&text).map_err(|e| format!("Invalid CalDAV response: {}", e))?;
+    let root = doc.root_element();
+    if !root.has_tag_name((DAV_NS, "multistatus")) {
+        return Err(format!("Invalid CalDAV response: expected a multistatus, found '{}'", root.tag_name().name()));
+    }
+
+    Ok(root
+        .descendants()
+        .filter(|node| node.has_tag_name((CALDAV_NS, "calendar-data")))
+        .map(|node| {
+            node.descendants()
+                .filter(|n| n.is_text())
+                .filter_map(|n| n.text())
+                .collect::<String>()
+        })
+        .filter(|data| !data.trim().is_empty())
+        .collect())
+}
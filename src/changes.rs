@@ -0,0 +1,133 @@
+//! Revisions of remote calendars found by comparing a download with the cached copy.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use std::{collections::BTreeMap, fmt};
+
+use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::ical::DayType;
+
+/// Classification of each day an event covers, keyed by the day and the
+/// event identity, with the summary of the event
+pub(crate) type DaySnapshot = BTreeMap<(NaiveDate, String), (String, DayType)>;
+
+/// What a revision of a calendar did to a day
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub enum ChangeKind {
+    /// An event now classifies the day
+    #[serde(rename = "added")]
+    Added,
+    /// An event no longer covers the day
+    #[serde(rename = "removed")]
+    Removed,
+    /// An event still covers the day but classifies it differently
+    #[serde(rename = "reclassified")]
+    Reclassified,
+}
+
+/// Day a revision of a remote calendar changed
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct Change {
+    /// Day affected
+    pub date: NaiveDate,
+    /// What happened to the day
+    pub kind: ChangeKind,
+    /// Summary of the event, as in the download for added and reclassified days
+    pub summary: String,
+    /// Calendar source the event belongs to
+    pub source: String,
+    /// How the event classified the day in the cached copy, `None` when added
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<DayType>,
+    /// How the event classifies the day in the download, `None` when removed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<DayType>,
+}
+
+impl fmt::Display for ChangeKind {
+    /// Writes the serialized name of the kind
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Reclassified => "reclassified",
+        })
+    }
+}
+
+impl fmt::Display for Change {
+    /// Describes the change in one line, e.g. `2025-10-11 added 'Makeup workday' (work_day) from '...'`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:<12} '{}' ", self.date, self.kind.to_string(), self.summary)?;
+        match (self.before, self.after) {
+            (Some(before), Some(after)) => write!(f, "({} -> {})", before, after)?,
+            (Some(day_type), None) | (None, Some(day_type)) => write!(f, "({})", day_type)?,
+            (None, None) => {},
+        }
+        write!(f, " from '{}'", self.source)
+    }
+}
+
+/// Lists the days two snapshots of a source disagree on
+/// 
+/// # Arguments
+/// * `source` - Name of the calendar source
+/// * `before` - Snapshot of the cached copy
+/// * `after` - Snapshot of the download
+/// 
+/// # Returns
+/// * Changes ordered by date, then by event identity
+/// 
+/// # Note
+/// * Events classifying nothing in both snapshots, or appearing or
+///   disappearing without classifying anything, are not changes
+pub(crate) fn compare(source: &str, before: &DaySnapshot, after: &DaySnapshot) -> Vec<Change> {
+    let mut keys: Vec<&(NaiveDate, String)> = before.keys().chain(after.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let classifies = |day_type: &DayType| *day_type != DayType::NormalDay;
+    keys.into_iter()
+        .filter_map(|key| {
+            let (kind, (summary, _)) = match (before.get(key), after.get(key)) {
+                (None, Some(new)) if classifies(&new.1) => (ChangeKind::Added, new),
+                (Some(old), None) if classifies(&old.1) => (ChangeKind::Removed, old),
+                (Some(old), Some(new)) if old.1 != new.1 => (ChangeKind::Reclassified, new),
+                _ => return None,
+            };
+            Some(Change {
+                date: key.0,
+                kind,
+                summary: summary.clone(),
+                source: source.to_string(),
+                before: before.get(key).map(|(_, day_type)| *day_type),
+                after: after.get(key).map(|(_, day_type)| *day_type),
+            })
+        })
+        .collect()
+}
@@ -22,77 +22,1500 @@
 //! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 //! SOFTWARE.
 
-use std::{cell::RefCell, collections::HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    iter::FusedIterator,
+    sync::{Arc, Mutex, PoisonError},
+};
 
-use chrono::{Datelike, NaiveDateTime};
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+use chrono_tz::Tz;
 
-use crate::{cli::Cli, ical::Ical};
+use log::{debug, warn};
+
+use crate::{
+    changes::Change,
+    conf::{Conf, OnConflict, TargetZone, WorkHours},
+    decision::{
+        Bridge, DayDecision, DayDiff, DecisionSource, DecisionTrace, PublicEvent, RestBlock, RestKind, Streak,
+        Transition, WorkDayCount,
+    },
+    feed::Feed,
+    ical::{DayType, Ical, Loaded, Resolution, Window},
+    resolver::ConflictResolver,
+    source::{SharedFetches, SourceReport, SourceStatus, format_age, redact_url},
+};
+
+/// Time of day a date is classified at outside whole-day mode, and that
+/// decisions about a date are reported at
+const END_OF_DAY: NaiveTime = NaiveTime::from_hms_opt(23, 59, 59).unwrap();
 
 /// Main workday/holiday analyzer
+/// 
+/// # Note
+/// * Events read from the sources are kept for later queries until `refresh`,
+///   and so are the decisions made from them by `evaluate`, `evaluate_many` and `days`
+/// * `Choliday` is `Send` and `Sync`: share it behind an `Arc` between tasks
+///   and threads. Concurrent queries each classify with the events they read;
+///   a `refresh` does not disturb queries already running
 pub struct Choliday {
-    /// iCalendar parser and analyzer with interior mutability
-    ical: RefCell<Ical>,
-    /// Target date for analysis
+    /// iCalendar parser and analyzer, caching the events it reads
+    ical: Ical,
+    /// Target date for analysis, on the wall clock of `zone`
     dt: NaiveDateTime,
+    /// Zone target dates are read in
+    zone: TargetZone,
     /// Configured workdays (1-7 where Monday = 1, Sunday = 7)
     /// If None, uses default weekend (Saturday and Sunday)
     work_days: Option<HashSet<i8>>,
+    /// Fail when any calendar source cannot be loaded
+    strict: bool,
+    /// Classify dates by every event overlapping the civil day rather than at 23:59:59
+    whole_day: bool,
+    /// Minimum number of calendar sources that must load successfully
+    min_sources: Option<usize>,
+    /// Leave days no calendar event classifies unknown
+    require_calendar: bool,
+    /// Number of days work day arithmetic searches
+    horizon_days: u32,
+    /// Working hours by day of the week, Monday first
+    work_hours: [WorkHours; 7],
+    /// Decisions already made, reused until `refresh`
+    memo: Mutex<Memo>,
+}
+
+/// Decisions made so far, keyed by the wall-clock time classified
+#[derive(Default)]
+struct Memo {
+    /// Bumped by `refresh`, so decisions made from older events are not kept
+    generation: u64,
+    /// Decisions by the wall-clock time in the target zone, 23:59:59 for dates
+    decisions: HashMap<NaiveDateTime, DayDecision>,
+    /// Lookups answered from `decisions`
+    hits: u64,
+    /// Lookups that had to classify
+    misses: u64,
+}
+
+/// Unbroken run of days sharing a classification, within a searched range
+struct Run {
+    /// Classification of the days
+    day_type: DayType,
+    /// First day of the run
+    start: NaiveDate,
+    /// Last day of the run, included
+    end: NaiveDate,
+    /// Whether any day of the run is a public holiday
+    public_holiday: bool,
+}
+
+/// Step-by-step construction of a [`Choliday`], see [`Choliday::builder`]
+#[must_use]
+pub struct CholidayBuilder {
+    /// Configuration with calendar sources and prediction rules
+    conf: Conf,
+    /// Target date and time, on the wall clock of the target zone
+    dt: Option<NaiveDateTime>,
+    /// Whether the target was set with `date`
+    date_only: bool,
+    /// Whole-day mode set with `whole_day`, by default on for targets set with `date`
+    whole_day: Option<bool>,
+    /// Fail when any calendar source cannot be loaded
+    strict: bool,
+    /// Use the on-disk cache of remote sources
+    use_cache: bool,
+    /// Revalidate cached copies that are still fresh
+    revalidate: bool,
+    /// iCalendar data used instead of the configured sources
+    events: Option<String>,
+    /// Resolver used instead of the configured priority rule
+    resolver: Option<Box<dyn ConflictResolver>>,
+    /// Downloads shared with other analyzers
+    shared: Option<SharedFetches>,
 }
 
 impl Choliday {
-    /// Creates a new holiday analyzer from command-line arguments
+    /// Creates a new holiday analyzer
     /// 
     /// # Arguments
-    /// * `cli` - Parsed command-line arguments containing config and date
+    /// * `conf` - Configuration with calendar sources and prediction rules
+    /// * `dt` - Target date and time, on the wall clock of [`Conf::target_zone`]
     /// 
     /// # Returns
-    /// * `Choliday` instance ready for analysis
-    pub fn new(cli: &Cli) -> Self {
-        let conf = cli.conf();
+    /// * `Choliday` instance ready for analysis, with the on-disk cache enabled
+    ///   and strict mode off
+    /// 
+    /// # Examples
+    /// ```no_run
+    /// use chrono::NaiveDate;
+    /// use choliday::{Choliday, Conf};
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), String> {
+    /// let conf = Conf::load("config.toml")?;
+    /// let noon = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap().and_hms_opt(12, 0, 0).unwrap();
+    /// let choliday = Choliday::new(conf, noon);
+    /// println!("work day: {}", choliday.is_work_day().await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(conf: Conf, dt: NaiveDateTime) -> Self {
         Self {
-            ical: RefCell::new(Ical::new(conf.clone())),
-            dt: cli.date(),
-            work_days: conf.work_day(),
+            work_days: conf.work_day().cloned(),
+            min_sources: conf.min_sources(),
+            require_calendar: conf.require_calendar(),
+            horizon_days: conf.horizon_days(),
+            zone: conf.target_zone(),
+            work_hours: [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun]
+                .map(|day| conf.work_hours(day)),
+            ical: Ical::new(conf),
+            dt,
+            strict: false,
+            whole_day: false,
+            memo: Mutex::default(),
         }
     }
 
+    /// Starts building an analyzer without a command line
+    /// 
+    /// # Arguments
+    /// * `conf` - Configuration with calendar sources and prediction rules
+    /// 
+    /// # Returns
+    /// * `CholidayBuilder` that needs a target date, set with `date` or
+    ///   `datetime`, before `build` succeeds
+    /// 
+    /// # Examples
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use choliday::{Choliday, Conf, Priority};
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), String> {
+    /// let conf = Conf::builder(Priority::RestOverWork).rest("Holiday").build()?;
+    /// let choliday = Choliday::builder(conf)
+    ///     .date(NaiveDate::from_ymd_opt(2025, 10, 1).unwrap())
+    ///     .timezone(chrono_tz::Asia::Shanghai)
+    ///     .events("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20251001\r\n\
+    ///              SUMMARY:National Holiday\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n")
+    ///     .build()?;
+    /// assert!(!choliday.is_work_day().await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder(conf: Conf) -> CholidayBuilder {
+        CholidayBuilder {
+            conf,
+            dt: None,
+            date_only: false,
+            whole_day: None,
+            strict: false,
+            use_cache: true,
+            revalidate: false,
+            events: None,
+            resolver: None,
+            shared: None,
+        }
+    }
+
+    /// Makes failed calendar sources and questionable event data errors
+    /// 
+    /// # Note
+    /// * Under strict mode `is_work_day` fails when any source cannot be
+    ///   loaded, and events whose DTEND precedes DTSTART are skipped
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+        self.ical.set_strict(strict);
+    }
+
+    /// Classifies dates over their whole civil day instead of at 23:59:59
+    /// 
+    /// # Note
+    /// * In whole-day mode every event overlapping any part of the day takes
+    ///   part in priority resolution, so a rest event from 09:00 to 18:00
+    ///   makes the day a rest day; the target date of `evaluate` and `trace`
+    ///   and the dates of range queries are all classified this way
+    /// * Off for analyzers created with `new`; `CholidayBuilder::date` turns it on
+    pub fn set_whole_day(&mut self, whole_day: bool) {
+        self.whole_day = whole_day;
+    }
+
+    /// Enables or disables the on-disk cache of remote sources
+    pub fn set_use_cache(&mut self, use_cache: bool) {
+        self.ical.set_use_cache(use_cache);
+    }
+
     /// Determines if the target date is a workday
     /// 
     /// # Returns
-    /// * `true` if the date is a workday
-    /// * `false` if the date is a holiday/rest day
+    /// * `Ok(true)` if the date is a workday
+    /// * `Ok(false)` if the date is a holiday/rest day
+    /// * `Err(String)` if the date is unknown: calendar sources failed under
+    ///   strict mode or `min_sources`, or no event classifies it while
+    ///   `predict.require_calendar` is set; or if an event matching both work
+    ///   and rest decides it under `predict.on_conflict = "error"`
     /// 
     /// # Algorithm
     /// 1. First checks calendar events for explicit work/rest designations
     /// 2. If no explicit designation found, falls back to weekday/weekend logic
     /// 3. For conflicting calendar events, follows priority rules from configuration
-    pub async fn is_work_day(&self) -> bool {
-        let timestamp = self.dt.and_utc().timestamp_millis();
-        let day_type = self.ical.borrow_mut().judge_by_priority(Some(timestamp)).await;
-        
-        match day_type {
-            crate::ical::DayType::NormalDay => {
-                // No explicit calendar designation, use weekday logic
-                self.is_weekday()
-            },
-            crate::ical::DayType::WorkDay => {
-                // Explicitly marked as work day in calendar
-                true
+    pub async fn is_work_day(&self) -> Result<bool, String> {
+        let decision = self.evaluate().await?;
+        match decision.source() {
+            DecisionSource::Unavailable { reason } => Err(reason.clone()),
+            source @ DecisionSource::ConflictingEvent { .. } => Err(Self::unresolved(source)),
+            _ => Ok(decision.is_work_day()),
+        }
+    }
+
+    /// Determines if the target instant lies within working hours
+    /// 
+    /// # Returns
+    /// * `Ok(true)` if a timed work event covers the instant, or the instant
+    ///   falls within the configured hours of a work day
+    /// * `Ok(false)` on rest days and outside working hours
+    /// * `Err(String)` if calendar sources failed under strict mode or
+    ///   `min_sources`, or the target day is unknown or unresolved like in `is_work_day`
+    /// 
+    /// # Note
+    /// * Work decided by a timed event is bounded by its DTSTART/DTEND, so an
+    ///   overnight shift event counts on both calendar days it covers
+    /// * Work days decided by an all-day event or the weekday fallback use
+    ///   `base.work_hours`; an overnight shift there belongs to the day it
+    ///   starts on, and its early hours count while that day is a work day
+    /// * All events are read once, then days are classified like `next_work_day` does
+    pub async fn is_working_hours(&self) -> Result<bool, String> {
+        let (loaded, _) = self.load().await?;
+        let instant = self.zone.to_instant(self.dt);
+        let resolution = self.ical.classify(&loaded, Some(Window::at(instant)));
+        match (&resolution.decided, resolution.span) {
+            (Some((DayType::WorkDay, ..)), Some((start, end))) => return Ok(start <= instant && instant < end),
+            (Some((DayType::RestDay, ..)), _) => return Ok(false),
+            (None, _) if self.require_calendar => return Err(self.uncovered(self.dt.date())),
+            (Some((DayType::ConflictDay, _, source)), _) => return Err(Self::unresolved(source)),
+            _ => {},
+        }
+
+        // Shifts starting the day before (overnight) and on the target day
+        let date = self.dt.date();
+        Ok([date - TimeDelta::days(1), date].into_iter().any(|day| {
+            let (start, end) = self.work_hours[day.weekday().num_days_from_monday() as usize].shift(day);
+            start <= self.dt && self.dt < end && {
+                let (dt, resolution) = self.resolve_date(&loaded, day);
+                self.decide(resolution.decided, dt).0 == DayType::WorkDay
+            }
+        }))
+    }
+
+    /// Classifies the target date and reports what decided it
+    /// 
+    /// # Returns
+    /// * `Ok(DayDecision)` with the classification, the deciding event or the
+    ///   weekday fallback, and warnings about failed sources or conflicting events
+    /// * `Err(String)` is not returned at present; the signature leaves room for
+    ///   failures that are not about missing calendar data
+    /// 
+    /// # Note
+    /// * When calendar sources failed under strict mode or `min_sources`, or no
+    ///   event classifies the date while `predict.require_calendar` is set, the
+    ///   decision is `DayType::Unknown` with the reason as its source
+    pub async fn evaluate(&self) -> Result<DayDecision, String> {
+        Ok(self.evaluate_at(self.dt).await)
+    }
+
+    /// Classifies a wall-clock time of the target zone like [`Choliday::evaluate`]
+    /// classifies the target date
+    /// 
+    /// # Arguments
+    /// * `dt` - Wall-clock time in the target zone
+    /// 
+    /// # Note
+    /// * Events already read for every instant are reused, so callers asking
+    ///   about many instants read all events once first
+    pub(crate) async fn evaluate_at(&self, dt: NaiveDateTime) -> DayDecision {
+        let generation = self.memo_generation();
+        if let Some(decision) = self.memoized(dt) {
+            return decision;
+        }
+        let window = self.window(dt);
+        let loaded = self.ical.loaded_within(Some(window)).await;
+        let warnings = self.source_warnings(loaded.reports());
+        if let Some(reason) = self.unmet_requirement(loaded.reports()) {
+            return DayDecision::unknown(dt, reason, warnings);
+        }
+        let resolution = self.ical.classify(&loaded, Some(window));
+        let decision = self.decision(dt, resolution, warnings);
+        self.memoize(generation, &decision);
+        decision
+    }
+
+    /// Classifies the instant of a Unix timestamp, reading all events once
+    /// 
+    /// # Arguments
+    /// * `timestamp` - Timestamp in milliseconds
+    /// 
+    /// # Returns
+    /// * Same as [`Choliday::evaluate`] for a target at the wall-clock time of
+    ///   the timestamp in the target zone
+    /// 
+    /// # Note
+    /// * Later calls are answered from the events in memory until a
+    ///   [`Choliday::refresh`]
+    #[cfg(feature = "ffi")]
+    pub(crate) async fn evaluate_instant(&self, timestamp: i64) -> DayDecision {
+        self.ical.load().await;
+        self.evaluate_at(self.zone.to_wall_clock(timestamp)).await
+    }
+
+    /// Classifies many dates with one read of the sources
+    /// 
+    /// # Arguments
+    /// * `dates` - Dates to classify, in any order and possibly repeated
+    /// 
+    /// # Returns
+    /// * One decision per date, in the order of `dates`; each equals what
+    ///   `evaluate` returns for the date at 23:59:59 in the target zone,
+    ///   `DayType::Unknown` included
+    /// 
+    /// # Note
+    /// * Only events within a day of the earliest to the latest date not
+    ///   decided before are read, unless all events were read already
+    pub async fn evaluate_many(&self, dates: &[NaiveDate]) -> Vec<DayDecision> {
+        let generation = self.memo_generation();
+        let memoized: Vec<Option<DayDecision>> = dates.iter().map(|date| self.memoized(date.and_time(END_OF_DAY))).collect();
+        let windows = dates
+            .iter()
+            .zip(&memoized)
+            .filter(|(_, decision)| decision.is_none())
+            .map(|(date, _)| self.window(date.and_time(END_OF_DAY)));
+        let Some((start, end)) = windows.clone().map(|w| w.start).min().zip(windows.map(|w| w.end).max()) else {
+            return memoized.into_iter().flatten().collect();
+        };
+        let loaded = self.ical.loaded_within(Some(Window { start, end })).await;
+        let warnings = self.source_warnings(loaded.reports());
+        let unmet = self.unmet_requirement(loaded.reports());
+
+        dates
+            .iter()
+            .zip(memoized)
+            .map(|(date, decision)| match (decision, &unmet) {
+                (Some(decision), _) => decision,
+                (None, Some(reason)) => DayDecision::unknown(date.and_time(END_OF_DAY), reason.clone(), warnings.clone()),
+                (None, None) => self.classify_date(&loaded, *date, &warnings, generation),
+            })
+            .collect()
+    }
+
+    /// Classifies the target date like `evaluate`, listing every event considered
+    /// 
+    /// # Returns
+    /// * `Ok(DecisionTrace)` with the decision, every event whose interval
+    ///   contains the target instant and what priority resolution did with
+    ///   it, and the classification the configured work days alone give
+    /// * `Err(String)` is not returned at present, like in `evaluate`
+    /// 
+    /// # Note
+    /// * Transparent events are listed even when `ignore_transparent` leaves them out of the decision
+    /// * Events are listed for an unknown decision too, as far as they were read
+    /// * Timed events are shown on the wall clock of the target zone, with
+    ///   their offset in `start_time` and `end_time`
+    /// 
+    /// # Examples
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use choliday::{Choliday, Conf, Priority};
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), String> {
+    /// let conf = Conf::builder(Priority::RestOverWork).rest("Holiday").build()?;
+    /// let choliday = Choliday::builder(conf)
+    ///     .datetime(NaiveDate::from_ymd_opt(2025, 10, 1).unwrap().and_hms_opt(12, 0, 0).unwrap())
+    ///     .timezone(chrono_tz::Asia::Shanghai)
+    ///     .events("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART:20251001T010000Z\r\nDTEND:20251001T100000Z\r\n\
+    ///              SUMMARY:Holiday shift\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n")
+    ///     .build()?;
+    /// let event = &choliday.trace().await?.events[0];
+    /// assert_eq!(event.start_time.unwrap().to_rfc3339(), "2025-10-01T09:00:00+08:00");
+    /// assert!(event.to_string().contains("2025-10-01 09:00 - 2025-10-01 18:00 CST  'Holiday shift'"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn trace(&self) -> Result<DecisionTrace, String> {
+        let window = self.window(self.dt);
+        let loaded = self.ical.loaded_within(Some(window)).await;
+        let (resolution, events) = self.ical.trace(&loaded, window);
+        let warnings = self.source_warnings(loaded.reports());
+        let decision = match self.unmet_requirement(loaded.reports()) {
+            Some(reason) => DayDecision::unknown(self.dt, reason, warnings),
+            None => self.decision(self.dt, resolution, warnings),
+        };
+        Ok(DecisionTrace { decision, events, fallback: self.fallback(self.dt) })
+    }
+
+    /// Finds the first work day after a date
+    /// 
+    /// # Arguments
+    /// * `from` - Date to start from, itself not considered
+    /// 
+    /// # Returns
+    /// * `Ok(Some(NaiveDate))` - The next work day
+    /// * `Ok(None)` - No work day within `base.horizon_days`
+    /// * `Err(String)` if calendar sources failed under strict mode or `min_sources`
+    /// 
+    /// # Note
+    /// * Same as `add_work_days(from, 1)`
+    pub async fn next_work_day(&self, from: NaiveDate) -> Result<Option<NaiveDate>, String> {
+        self.add_work_days(from, 1).await
+    }
+
+    /// Adds a number of work days to a date
+    /// 
+    /// # Arguments
+    /// * `from` - Date to start from
+    /// * `n` - Number of work days to move, backwards when negative
+    /// 
+    /// # Returns
+    /// * `Ok(Some(NaiveDate))` - The `n`-th work day after (or before) `from`,
+    ///   `from` itself when `n` is 0
+    /// * `Ok(None)` - The search went past `base.horizon_days` days
+    /// * `Err(String)` if calendar sources failed under strict mode or
+    ///   `min_sources`, or the search met an unknown or unresolved day like in `is_work_day`
+    /// 
+    /// # Note
+    /// * All events are read once, then every day is classified like `is_work_day`
+    ///   classifies a `YYYYmmDD` date, at 23:59:59 in the target zone
+    pub async fn add_work_days(&self, from: NaiveDate, n: i32) -> Result<Option<NaiveDate>, String> {
+        let (loaded, _) = self.load().await?;
+        if n == 0 {
+            return Ok(Some(from));
+        }
+
+        let dates = std::iter::successors(Some(from), |date| if n < 0 { date.pred_opt() } else { date.succ_opt() })
+            .skip(1)
+            .take(self.horizon_days as usize);
+        self.nth_work_day(&loaded, dates, n.unsigned_abs())
+    }
+
+    /// Finds the `n`-th work day of a month, e.g. the third one for payroll
+    /// 
+    /// # Arguments
+    /// * `year` - Year of the month
+    /// * `month` - Month, 1 to 12
+    /// * `n` - Position of the work day, 1 for the first
+    /// 
+    /// # Returns
+    /// * `Ok(Some(NaiveDate))` - The `n`-th work day of the month
+    /// * `Ok(None)` - The month has fewer than `n` work days, or `n` is 0
+    /// * `Err(String)` if the month is invalid, calendar sources failed under
+    ///   strict mode or `min_sources`, or the search met an unknown or
+    ///   unresolved day like in `is_work_day`
+    /// 
+    /// # Note
+    /// * Days are classified like `add_work_days` does
+    pub async fn nth_work_day_of_month(&self, year: i32, month: u32, n: u32) -> Result<Option<NaiveDate>, String> {
+        let (first, last) = Self::month_bounds(year, month)?;
+        let (loaded, _) = self.load().await?;
+        self.nth_work_day(&loaded, first.iter_days().take_while(|date| *date <= last), n)
+    }
+
+    /// Finds the `n`-th work day of a month counted from its end, e.g. the last one
+    /// 
+    /// # Arguments
+    /// * `year` - Year of the month
+    /// * `month` - Month, 1 to 12
+    /// * `n` - Position of the work day from the end, 1 for the last
+    /// 
+    /// # Returns
+    /// * As for `nth_work_day_of_month`
+    pub async fn nth_last_work_day_of_month(&self, year: i32, month: u32, n: u32) -> Result<Option<NaiveDate>, String> {
+        let (first, last) = Self::month_bounds(year, month)?;
+        let (loaded, _) = self.load().await?;
+        self.nth_work_day(&loaded, last.iter_days().rev().take_while(|date| *date >= first), n)
+    }
+
+    /// Counts the work and rest days of a date range
+    /// 
+    /// # Arguments
+    /// * `start` - First day of the range
+    /// * `end` - Last day of the range, included
+    /// 
+    /// # Returns
+    /// * `Ok(WorkDayCount)` - Numbers of work, rest, conflicting and unknown days
+    /// * `Err(String)` if calendar sources failed under strict mode or `min_sources`
+    /// 
+    /// # Note
+    /// * An inverted range is swapped, so both orders count the same days
+    /// * All events are read once, then every day is classified like `is_work_day`
+    ///   classifies a `YYYYmmDD` date, at 23:59:59 in the target zone
+    pub async fn work_days_between(&self, start: NaiveDate, end: NaiveDate) -> Result<WorkDayCount, String> {
+        let (loaded, _) = self.load().await?;
+
+        let mut count = WorkDayCount::default();
+        for date in start.min(end).iter_days().take_while(|date| *date <= start.max(end)) {
+            let (dt, resolution) = self.resolve_date(&loaded, date);
+            if let Some(contradiction) = &resolution.contradiction {
+                warn!("{}", contradiction);
+                count.conflict += 1;
+            }
+            match self.decide(resolution.decided, dt).0 {
+                DayType::WorkDay => count.work += 1,
+                DayType::Unknown => count.unknown += 1,
+                DayType::ConflictDay => count.unresolved += 1,
+                _ => count.rest += 1,
+            }
+        }
+        Ok(count)
+    }
+
+    /// Lists the next rest events, e.g. upcoming public holidays
+    /// 
+    /// # Arguments
+    /// * `n` - Number of events to return at most
+    /// 
+    /// # Returns
+    /// * `Ok(Vec<PublicEvent>)` - Events classified as rest on their own that
+    ///   end after the target time, ordered by start
+    /// * `Err(String)` if calendar sources failed under strict mode or `min_sources`
+    /// 
+    /// # Note
+    /// * Events starting more than `base.horizon_days` days after the target time are not considered
+    /// * An event counts even where another event overrules it under the
+    ///   priority rule; use `days` for the resulting classification of each day
+    /// 
+    /// # Examples
+    /// ```no_run
+    /// # use choliday::Choliday;
+    /// # async fn example(choliday: &Choliday) -> Result<(), String> {
+    /// for holiday in choliday.upcoming_rest_days(3).await? {
+    ///     println!("{}: {}", holiday.local_start().date(), holiday.summary());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn upcoming_rest_days(&self, n: usize) -> Result<Vec<PublicEvent>, String> {
+        let (loaded, _) = self.load().await?;
+        let until = self.dt.checked_add_days(Days::new(self.horizon_days.into())).unwrap_or(NaiveDateTime::MAX);
+        Ok(self.ical.public_events(&loaded, self.dt, until, |day_type| day_type == DayType::RestDay, n))
+    }
+
+    /// Finds the run of same-classification days around a date
+    /// 
+    /// # Arguments
+    /// * `from` - Date the run must contain
+    /// 
+    /// # Returns
+    /// * `Ok(Streak)` - Classification of `from` and the first and last day of
+    ///   the unbroken run of days classified the same
+    /// * `Err(String)` if calendar sources failed under strict mode or `min_sources`
+    /// 
+    /// # Note
+    /// * The run is searched at most `base.horizon_days` days either side of
+    ///   `from`, and marked truncated when it reaches that bound
+    /// * Days are classified like `days` classifies them
+    /// 
+    /// # Examples
+    /// ```no_run
+    /// # use chrono::NaiveDate;
+    /// # use choliday::{Choliday, DayType};
+    /// # async fn example(choliday: &Choliday) -> Result<(), String> {
+    /// let streak = choliday.streak(NaiveDate::from_ymd_opt(2026, 10, 1).unwrap()).await?;
+    /// if streak.day_type == DayType::RestDay && streak.days() >= 3 {
+    ///     println!("{} days off from {} to {}", streak.days(), streak.start, streak.end);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn streak(&self, from: NaiveDate) -> Result<Streak, String> {
+        let horizon = Days::new(self.horizon_days.into());
+        let first = from.checked_sub_days(horizon).unwrap_or(NaiveDate::MIN);
+        let last = from.checked_add_days(horizon).unwrap_or(NaiveDate::MAX);
+
+        let mut after = self.days(from, last).await?;
+        let day_type = after.next().map_or(DayType::WorkDay, |(_, decision)| decision.day_type());
+        let same = |(_, decision): &(NaiveDate, DayDecision)| decision.day_type() == day_type;
+        let end = after.take_while(same).last().map_or(from, |(date, _)| date);
+        let start = self.days(first, from).await?
+            .rev()
+            .skip(1)
+            .take_while(same)
+            .last()
+            .map_or(from, |(date, _)| date);
+
+        Ok(Streak { day_type, start, end, truncated: start == first || end == last })
+    }
+
+    /// Lists the next days on which the classification flips, e.g. from rest back to work
+    /// 
+    /// # Arguments
+    /// * `from` - Date to start from; its own classification is the starting point
+    /// * `n` - Number of transitions to return at most
+    /// 
+    /// # Returns
+    /// * `Ok(Vec<Transition>)` - Days classified differently from the day
+    ///   before, in date order; fewer than `n` when the search reaches
+    ///   `base.horizon_days` days after `from`
+    /// * `Err(String)` if calendar sources failed under strict mode or `min_sources`
+    /// 
+    /// # Note
+    /// * Days are classified like `days` classifies them; a change to or from
+    ///   an unknown or unresolved day is a transition too
+    /// 
+    /// # Examples
+    /// ```no_run
+    /// # use chrono::NaiveDate;
+    /// # use choliday::Choliday;
+    /// # async fn example(choliday: &Choliday) -> Result<(), String> {
+    /// for transition in choliday.transitions(NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(), 4).await? {
+    ///     println!("{}", transition);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn transitions(&self, from: NaiveDate, n: usize) -> Result<Vec<Transition>, String> {
+        let last = from.checked_add_days(Days::new(self.horizon_days.into())).unwrap_or(NaiveDate::MAX);
+        let mut days = self.days(from, last).await?;
+        let Some((_, first)) = days.next() else {
+            return Ok(Vec::new());
+        };
+
+        let mut previous = first.day_type();
+        let mut transitions = Vec::new();
+        for (date, decision) in days {
+            if transitions.len() >= n {
+                break;
+            }
+            if decision.day_type() != previous {
+                transitions.push(Transition {
+                    date,
+                    previous,
+                    day_type: decision.day_type(),
+                    rest_kind: decision.rest_kind(),
+                    source: decision.source().clone(),
+                });
+                previous = decision.day_type();
+            }
+        }
+        Ok(transitions)
+    }
+
+    /// Finds the work days of a year sandwiched between rest days, e.g. a
+    /// Friday between a Thursday holiday and the weekend
+    /// 
+    /// # Arguments
+    /// * `year` - Year the bridges start in
+    /// * `max_gap` - Longest run of work days to report, 1 for single days
+    /// 
+    /// # Returns
+    /// * `Ok(Vec<Bridge>)` - Runs of at most `max_gap` work days with a rest
+    ///   day right before and after, in date order, each with the rest block it would join
+    /// * `Err(String)` if the year is out of range, or calendar sources failed
+    ///   under strict mode or `min_sources`
+    /// 
+    /// # Note
+    /// * Days are classified like `days` classifies them; unknown and
+    ///   unresolved days are neither work nor rest and end a block
+    /// * Rest blocks are followed up to a month into the years either side
+    /// 
+    /// # Examples
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use choliday::{Choliday, Conf, Priority};
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), String> {
+    /// let conf = Conf::builder(Priority::RestOverWork).rest("Holiday").build()?;
+    /// let choliday = Choliday::builder(conf)
+    ///     .date(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap())
+    ///     .events("BEGIN:VCALENDAR\r\n\
+    ///              BEGIN:VEVENT\r\nUID:1\r\nDTSTART;VALUE=DATE:20260101\r\nSUMMARY:New Year Holiday\r\nEND:VEVENT\r\n\
+    ///              BEGIN:VEVENT\r\nUID:2\r\nDTSTART;VALUE=DATE:20260505\r\nSUMMARY:Spring Holiday\r\nEND:VEVENT\r\n\
+    ///              BEGIN:VEVENT\r\nUID:3\r\nDTSTART;VALUE=DATE:20261028\r\nSUMMARY:Autumn Holiday\r\nEND:VEVENT\r\n\
+    ///              END:VCALENDAR\r\n")
+    ///     .build()?;
+    /// 
+    /// // Friday 2 January and Monday 4 May; around Wednesday 28 October only two-day bridges
+    /// let bridges = choliday.bridges(2026, 1).await?;
+    /// let starts: Vec<String> = bridges.iter().map(|bridge| bridge.start.to_string()).collect();
+    /// assert_eq!(starts, ["2026-01-02", "2026-05-04"]);
+    /// assert_eq!(bridges[0].days_off(), 4);
+    /// 
+    /// let bridges = choliday.bridges(2026, 2).await?;
+    /// assert_eq!(bridges.len(), 4);
+    /// assert_eq!(bridges[2].work_days(), 2);
+    /// assert_eq!(bridges[2].days_off(), 5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn bridges(&self, year: i32, max_gap: u32) -> Result<Vec<Bridge>, String> {
+        let (first, last) = NaiveDate::from_ymd_opt(year, 1, 1)
+            .zip(NaiveDate::from_ymd_opt(year, 12, 31))
+            .ok_or_else(|| format!("Invalid year {}", year))?;
+        let start = first.checked_sub_days(Days::new(31)).unwrap_or(first);
+        let end = last.checked_add_days(Days::new(31)).unwrap_or(last);
+
+        Ok(self
+            .runs(start, end)
+            .await?
+            .windows(3)
+            .filter_map(|window| match window {
+                [before, bridge, after]
+                    if before.day_type == DayType::RestDay
+                        && bridge.day_type == DayType::WorkDay
+                        && after.day_type == DayType::RestDay
+                        && bridge.start.year() == year
+                        && (bridge.end - bridge.start).num_days() < i64::from(max_gap) =>
+                {
+                    Some(Bridge { start: bridge.start, end: bridge.end, rest_start: before.start, rest_end: after.end })
+                },
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Finds the blocks of consecutive rest days of a date range, e.g. long weekends
+    /// 
+    /// # Arguments
+    /// * `start` - First day of the range
+    /// * `end` - Last day of the range, included; the two may come in either order
+    /// * `min_days` - Shortest block to report
+    /// 
+    /// # Returns
+    /// * `Ok(Vec<RestBlock>)` - Blocks of at least `min_days` rest days, in date order
+    /// * `Err(String)` if calendar sources failed under strict mode or `min_sources`
+    /// 
+    /// # Note
+    /// * Days are classified like `days` classifies them; blocks are cut at
+    ///   the ends of the range
+    /// 
+    /// # Examples
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use choliday::{Choliday, Conf, Priority};
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), String> {
+    /// let conf = Conf::builder(Priority::RestOverWork).rest_public("Holiday").build()?;
+    /// let choliday = Choliday::builder(conf)
+    ///     .date(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap())
+    ///     .events("BEGIN:VCALENDAR\r\n\
+    ///              BEGIN:VEVENT\r\nUID:1\r\nDTSTART;VALUE=DATE:20260202\r\nSUMMARY:Spring Holiday\r\nEND:VEVENT\r\n\
+    ///              BEGIN:VEVENT\r\nUID:2\r\nDTSTART;VALUE=DATE:20260430\r\nDTEND;VALUE=DATE:20260502\r\n\
+    ///              SUMMARY:Labour Holiday\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n")
+    ///     .build()?;
+    /// 
+    /// // Both blocks span the end of a month
+    /// let start = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    /// let blocks = choliday.rest_blocks(start, NaiveDate::from_ymd_opt(2026, 6, 30).unwrap(), 3).await?;
+    /// assert_eq!(blocks.len(), 2);
+    /// assert_eq!((blocks[0].start, blocks[0].end), (NaiveDate::from_ymd_opt(2026, 1, 31).unwrap(), NaiveDate::from_ymd_opt(2026, 2, 2).unwrap()));
+    /// assert_eq!(blocks[1].days(), 4);
+    /// assert!(blocks.iter().all(|block| block.public_holiday));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn rest_blocks(&self, start: NaiveDate, end: NaiveDate, min_days: u32) -> Result<Vec<RestBlock>, String> {
+        Ok(self
+            .runs(start, end)
+            .await?
+            .into_iter()
+            .filter(|run| run.day_type == DayType::RestDay && (run.end - run.start).num_days() >= i64::from(min_days) - 1)
+            .map(|run| RestBlock { start: run.start, end: run.end, public_holiday: run.public_holiday })
+            .collect())
+    }
+
+    /// Splits a date range into runs of days sharing a classification
+    /// 
+    /// # Arguments
+    /// * `start` - First day of the range
+    /// * `end` - Last day of the range, included
+    async fn runs(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<Run>, String> {
+        let mut runs: Vec<Run> = Vec::new();
+        for (date, decision) in self.days(start, end).await? {
+            let public_holiday = decision.rest_kind() == Some(RestKind::PublicHoliday);
+            match runs.last_mut() {
+                Some(run) if run.day_type == decision.day_type() => {
+                    run.end = date;
+                    run.public_holiday |= public_holiday;
+                },
+                _ => runs.push(Run { day_type: decision.day_type(), start: date, end: date, public_holiday }),
+            }
+        }
+        Ok(runs)
+    }
+
+    /// Compares the classification of a date range with that of another analyzer,
+    /// e.g. one built from an edited configuration
+    /// 
+    /// # Arguments
+    /// * `other` - Analyzer to compare with
+    /// * `start` - First day of the range
+    /// * `end` - Last day of the range, included; the two may come in either order
+    /// 
+    /// # Returns
+    /// * `Ok(Vec<DayDiff>)` - Days whose day type or rest kind differs, in date
+    ///   order, with the decisions of both analyzers
+    /// * `Err(String)` if calendar sources of either analyzer failed under
+    ///   strict mode or `min_sources`
+    /// 
+    /// # Note
+    /// * Days are classified like `days` classifies them; build both analyzers
+    ///   with the same [`SharedFetches`] to download common sources once
+    /// 
+    /// # Examples
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use choliday::{Choliday, Conf, Priority};
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), String> {
+    /// let events = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20251001\r\n\
+    ///               SUMMARY:National Day\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    /// let date = NaiveDate::from_ymd_opt(2025, 10, 1).unwrap();
+    /// let current = Choliday::builder(Conf::builder(Priority::RestOverWork).rest("Holiday").build()?)
+    ///     .date(date)
+    ///     .events(events)
+    ///     .build()?;
+    /// let edited = Choliday::builder(Conf::builder(Priority::RestOverWork).rest("National").build()?)
+    ///     .date(date)
+    ///     .events(events)
+    ///     .build()?;
+    /// let changed = current.diff(&edited, date, NaiveDate::from_ymd_opt(2025, 10, 7).unwrap()).await?;
+    /// assert_eq!(changed.len(), 1);
+    /// assert_eq!(changed[0].date, date);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn diff(&self, other: &Choliday, start: NaiveDate, end: NaiveDate) -> Result<Vec<DayDiff>, String> {
+        let current = self.days(start, end).await?;
+        let against = other.days(start, end).await?;
+        Ok(current
+            .zip(against)
+            .filter(|((_, current), (_, against))| {
+                current.day_type() != against.day_type() || current.rest_kind() != against.rest_kind()
+            })
+            .map(|((date, current), (_, against))| DayDiff { date, current, against })
+            .collect())
+    }
+
+    /// Finds the days revisions of remote calendars changed since they were last cached
+    /// 
+    /// # Arguments
+    /// * `start` - First day to compare
+    /// * `end` - Last day to compare, included; the two may come in either order
+    /// 
+    /// # Returns
+    /// * `Ok(Vec<Change>)` - Days on which an event was added, removed or now
+    ///   classifies the day differently, by source, then by date
+    /// * `Err(String)` - The on-disk cache is disabled, or a remote source
+    ///   failed to download or parse
+    /// 
+    /// # Note
+    /// * Every HTTP(S) source is downloaded and compared with its cached copy,
+    ///   which the download then replaces; a source without a cached copy
+    ///   only records its first snapshot
+    /// * Events are matched by UID, or by start and summary when they have none
+    /// * The events used by the other queries are left as they are
+    /// 
+    /// # Examples
+    /// ```no_run
+    /// # use chrono::NaiveDate;
+    /// # use choliday::Choliday;
+    /// # async fn example(choliday: &Choliday) -> Result<(), String> {
+    /// let start = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    /// let end = NaiveDate::from_ymd_opt(2026, 12, 31).unwrap();
+    /// for change in choliday.changes(start, end).await? {
+    ///     println!("{}", change);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn changes(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<Change>, String> {
+        self.ical.changes(start.min(end), start.max(end)).await
+    }
+
+    /// Classifies every day of a date range
+    /// 
+    /// # Arguments
+    /// * `start` - First day of the range
+    /// * `end` - Last day of the range, included
+    /// 
+    /// # Returns
+    /// * `Ok(DayDecisions)` - Lazy iterator of `(date, decision)` pairs in date
+    ///   order, also walkable from the end
+    /// * `Err(String)` if calendar sources failed under strict mode or `min_sources`
+    /// 
+    /// # Note
+    /// * All events are read before this returns; iterating never fetches, and
+    ///   cloning the iterator walks the range again for free
+    /// * An inverted range is swapped, like in `work_days_between`
+    /// * Each decision equals what `evaluate` returns for the date at 23:59:59 in the target zone
+    /// 
+    /// # Examples
+    /// ```no_run
+    /// # use chrono::NaiveDate;
+    /// # use choliday::Choliday;
+    /// # async fn example(choliday: &Choliday) -> Result<(), String> {
+    /// let start = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    /// let end = NaiveDate::from_ymd_opt(2026, 12, 31).unwrap();
+    /// let last_rest_day = choliday
+    ///     .days(start, end)
+    ///     .await?
+    ///     .rev()
+    ///     .find(|(_, decision)| !decision.is_work_day());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn days(&self, start: NaiveDate, end: NaiveDate) -> Result<DayDecisions<'_>, String> {
+        let generation = self.memo_generation();
+        let (loaded, warnings) = self.load().await?;
+        Ok(DayDecisions {
+            choliday: self,
+            generation,
+            loaded,
+            front: start.min(end),
+            back: start.max(end),
+            done: false,
+            warnings,
+        })
+    }
+
+    /// Builds an iCalendar feed of the predicted rest days of a date range
+    /// 
+    /// # Arguments
+    /// * `start` - First day of the range
+    /// * `end` - Last day of the range, included; the two may come in either order
+    /// * `work` - Whether to list makeup work days too: work days calendar
+    ///   events make of days the configured work days leave off
+    /// 
+    /// # Returns
+    /// * `Ok(Feed)` with one all-day event per listed day, in date order
+    /// * `Err(String)` if calendar sources failed under strict mode or `min_sources`
+    /// 
+    /// # Note
+    /// * Days off by the configured work days alone, such as weekends, are
+    ///   left out, and so are unknown and unresolved days
+    /// * Days are classified like `days` does
+    pub async fn feed(&self, start: NaiveDate, end: NaiveDate, work: bool) -> Result<Feed, String> {
+        let listed: Vec<DayDecision> = self
+            .days(start, end)
+            .await?
+            .filter(|(_, decision)| match decision.day_type() {
+                DayType::RestDay => decision.rest_kind() != Some(RestKind::Weekend),
+                DayType::WorkDay => work && self.fallback(decision.date()) == DayType::RestDay,
+                _ => false,
+            })
+            .map(|(_, decision)| decision)
+            .collect();
+        Ok(Feed::new(&listed, self.ical.conf().explicit_property()))
+    }
+
+    /// Reads all events once for questions about many dates
+    /// 
+    /// # Returns
+    /// * `Ok((Arc<Loaded>, Vec<String>))` with all events, and the warnings
+    ///   about failed, stale and malformed sources
+    /// * `Err(String)` if calendar sources failed under strict mode or `min_sources`
+    async fn load(&self) -> Result<(Arc<Loaded>, Vec<String>), String> {
+        let loaded = self.ical.loaded_for(None).await;
+        let warnings = self.source_warnings(loaded.reports());
+        match self.unmet_requirement(loaded.reports()) {
+            Some(reason) => Err(reason),
+            None => Ok((loaded, warnings)),
+        }
+    }
+
+    /// Classifies a date by events already read, like a `YYYYmmDD` target
+    /// 
+    /// # Arguments
+    /// * `loaded` - All events, as read by `load`
+    /// * `date` - Date to classify
+    /// 
+    /// # Returns
+    /// * `(NaiveDateTime, Resolution)` - The wall-clock time the decision is
+    ///   reported at, 23:59:59, and the classification by calendar events
+    fn resolve_date(&self, loaded: &Loaded, date: NaiveDate) -> (NaiveDateTime, Resolution) {
+        let dt = date.and_time(END_OF_DAY);
+        let resolution = self.ical.classify(loaded, Some(self.window(dt)));
+        (dt, resolution)
+    }
+
+    /// Returns the instants classifying a wall-clock time considers
+    /// 
+    /// # Returns
+    /// * Every instant of its civil day in whole-day mode, otherwise the instant itself
+    fn window(&self, dt: NaiveDateTime) -> Window {
+        if self.whole_day {
+            Window::day(&self.zone, dt.date())
+        } else {
+            Window::at(self.zone.to_instant(dt))
+        }
+    }
+
+    /// Builds the decision for a date from events already read, or reuses the one made before
+    /// 
+    /// # Arguments
+    /// * `loaded` - All events, as read by `load`
+    /// * `date` - Date to classify
+    /// * `warnings` - Warnings about the sources, reported with every decision
+    /// * `generation` - Generation of memoized decisions read before `loaded`
+    fn decision_on(&self, loaded: &Loaded, date: NaiveDate, warnings: &[String], generation: u64) -> DayDecision {
+        self.memoized(date.and_time(END_OF_DAY))
+            .unwrap_or_else(|| self.classify_date(loaded, date, warnings, generation))
+    }
+
+    /// Builds the decision for a date from events already read and memoizes it,
+    /// see [`Choliday::decision_on`]
+    fn classify_date(&self, loaded: &Loaded, date: NaiveDate, warnings: &[String], generation: u64) -> DayDecision {
+        let (dt, resolution) = self.resolve_date(loaded, date);
+        let decision = self.decision(dt, resolution, warnings.to_vec());
+        self.memoize(generation, &decision);
+        decision
+    }
+
+    /// Returns the generation of memoized decisions, to be read before the events
+    /// a decision is made from
+    fn memo_generation(&self) -> u64 {
+        self.memo.lock().unwrap_or_else(PoisonError::into_inner).generation
+    }
+
+    /// Looks up the decision made before for a wall-clock time, counting the hit or miss
+    fn memoized(&self, dt: NaiveDateTime) -> Option<DayDecision> {
+        let mut memo = self.memo.lock().unwrap_or_else(PoisonError::into_inner);
+        let decision = memo.decisions.get(&dt).cloned();
+        match decision {
+            Some(_) => memo.hits += 1,
+            None => memo.misses += 1,
+        }
+        decision
+    }
+
+    /// Keeps a decision for later queries
+    /// 
+    /// # Arguments
+    /// * `generation` - Generation read before the events the decision was made from
+    /// * `decision` - Decision made from events meeting the source requirements
+    /// 
+    /// # Note
+    /// * Nothing is kept if `refresh` ran since `generation` was read
+    fn memoize(&self, generation: u64, decision: &DayDecision) {
+        let mut memo = self.memo.lock().unwrap_or_else(PoisonError::into_inner);
+        if memo.generation == generation {
+            memo.decisions.insert(decision.date(), decision.clone());
+        }
+    }
+
+    /// Finds the `n`-th work day among dates, in the order given
+    /// 
+    /// # Arguments
+    /// * `loaded` - All events, as read by `load`
+    /// * `dates` - Dates to search
+    /// * `n` - Position of the work day, 1 for the first
+    /// 
+    /// # Returns
+    /// * `Ok(Some(NaiveDate))` - The `n`-th work day
+    /// * `Ok(None)` - The dates hold fewer than `n` work days, or `n` is 0
+    /// * `Err(String)` if an unknown or unresolved day comes first, under
+    ///   `predict.require_calendar` or `predict.on_conflict = "error"`
+    fn nth_work_day(
+        &self,
+        loaded: &Loaded,
+        dates: impl Iterator<Item = NaiveDate>,
+        n: u32,
+    ) -> Result<Option<NaiveDate>, String> {
+        if n == 0 {
+            return Ok(None);
+        }
+        let mut remaining = n;
+        for date in dates {
+            let (dt, resolution) = self.resolve_date(loaded, date);
+            match self.decide(resolution.decided, dt) {
+                (DayType::WorkDay, ..) => {
+                    remaining -= 1;
+                    if remaining == 0 {
+                        return Ok(Some(date));
+                    }
+                },
+                (DayType::Unknown, ..) => return Err(self.uncovered(date)),
+                (DayType::ConflictDay, _, source) => return Err(Self::unresolved(&source)),
+                _ => {},
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the first and last day of a month
+    /// 
+    /// # Arguments
+    /// * `year` - Year of the month
+    /// * `month` - Month, 1 to 12
+    /// 
+    /// # Returns
+    /// * `Err(String)` if there is no such month
+    fn month_bounds(year: i32, month: u32) -> Result<(NaiveDate, NaiveDate), String> {
+        let first = NaiveDate::from_ymd_opt(year, month, 1)
+            .ok_or_else(|| format!("Invalid month {}-{:02}", year, month))?;
+        let last = first
+            .checked_add_months(Months::new(1))
+            .and_then(|next| next.pred_opt())
+            .ok_or_else(|| format!("Invalid month {}-{:02}", year, month))?;
+        Ok((first, last))
+    }
+
+    /// Builds the decision for an instant from its calendar classification
+    /// 
+    /// # Arguments
+    /// * `dt` - The wall-clock time classified
+    /// * `resolution` - Classification of the instant by calendar events
+    /// * `warnings` - Warnings about the sources
+    /// 
+    /// # Note
+    /// * Events contradicting each other are logged as a warning and attached
+    ///   to the decision
+    fn decision(&self, dt: NaiveDateTime, resolution: Resolution, warnings: Vec<String>) -> DayDecision {
+        if let Some(contradiction) = &resolution.contradiction {
+            warn!("{}", contradiction);
+        }
+        let (day_type, rest_kind, source) = self.decide(resolution.decided, dt);
+        DayDecision::new(dt, day_type, rest_kind, source, warnings)
+            .with_provenance(resolution.provenance)
+            .with_contradiction(resolution.contradiction)
+    }
+
+    /// Settles the classification of an instant from its calendar classification
+    /// 
+    /// # Arguments
+    /// * `decided` - Day type, rest kind and deciding event from the calendar, if any
+    /// * `dt` - The wall-clock time classified
+    /// 
+    /// # Returns
+    /// * `(DayType, Option<RestKind>, DecisionSource)` - `WorkDay` or `RestDay`,
+    ///   the kind of rest day and what decided it, the configured work days
+    ///   when no event classifies the instant; `Unknown` instead under
+    ///   `predict.require_calendar`
+    fn decide(
+        &self,
+        decided: Option<(DayType, Option<RestKind>, DecisionSource)>,
+        dt: NaiveDateTime,
+    ) -> (DayType, Option<RestKind>, DecisionSource) {
+        match decided {
+            Some(decided) => decided,
+            None if self.require_calendar => {
+                (DayType::Unknown, None, DecisionSource::Unavailable { reason: self.uncovered(dt.date()) })
             },
-            crate::ical::DayType::RestDay => {
-                // Explicitly marked as rest day in calendar
-                false
+            None => {
+                // No explicit calendar designation, use weekday logic
+                let source = DecisionSource::WeekdayFallback { weekday: dt.weekday() };
+                match self.fallback(dt) {
+                    DayType::WorkDay => (DayType::WorkDay, None, source),
+                    day_type => (day_type, Some(RestKind::Weekend), source),
+                }
             },
-            crate::ical::DayType::ConflictDay => {
-                // This should not happen with proper priority rules
-                // If it does, default to treating it as a work day
-                // Could also panic or log an error based on requirements
-                true
+        }
+    }
+
+    /// Classifies an instant by the configured work days alone
+    /// 
+    /// # Arguments
+    /// * `dt` - The wall-clock time classified
+    /// 
+    /// # Returns
+    /// * `WorkDay` on a configured work day, `RestDay` otherwise
+    fn fallback(&self, dt: NaiveDateTime) -> DayType {
+        if self.is_weekday(dt) { DayType::WorkDay } else { DayType::RestDay }
+    }
+
+    /// Explains why a date is unknown under `predict.require_calendar`
+    /// 
+    /// # Arguments
+    /// * `date` - Date no calendar event classifies
+    fn uncovered(&self, date: NaiveDate) -> String {
+        format!("No calendar event classifies {} and predict.require_calendar is set", date)
+    }
+
+    /// Explains why a day is left unresolved under `predict.on_conflict = "error"`
+    /// 
+    /// # Arguments
+    /// * `source` - The conflicting event that decided the day
+    fn unresolved(source: &DecisionSource) -> String {
+        format!("Unresolved conflict: {}", source)
+    }
+
+    /// Reports failed, stale and malformed calendar sources
+    /// 
+    /// # Arguments
+    /// * `reports` - Outcome of loading each source, from the read being checked
+    /// 
+    /// # Returns
+    /// * Warnings about the sources, also logged, regardless of the source requirements
+    fn source_warnings(&self, reports: &[SourceReport]) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let mut warn = |message: String| {
+            warn!("{}", message);
+            warnings.push(message);
+        };
+        
+        for report in reports {
+            // Warnings reach the output, so credentials in the URL are hidden
+            let name = redact_url(report.source());
+            let redact = |cause: &str| cause.replace(report.source(), &name);
+            match report.status() {
+                SourceStatus::Loaded { events } => {
+                    match report.final_url() {
+                        Some(url) => debug!("Calendar source '{}' loaded {} events from '{}'", report.source(), events, url),
+                        None => debug!("Calendar source '{}' loaded {} events", report.source(), events),
+                    }
+                },
+                SourceStatus::Stale { events, age, cause } => {
+                    warn(format!(
+                        "Calendar source '{}' failed: {}; stale data used, {} events from a copy fetched {} ago",
+                        name,
+                        redact(cause),
+                        events,
+                        format_age(*age)
+                    ));
+                },
+                SourceStatus::Failed(cause) => {
+                    warn(format!("Calendar source '{}' failed: {}", name, redact(cause)));
+                },
+            }
+            let stats = report.stats();
+            if stats.recovered > 0 || stats.discarded > 0 {
+                warn(format!(
+                    "Calendar source '{}' is malformed: recovered {} events, discarded {} components",
+                    name,
+                    stats.recovered,
+                    stats.discarded
+                ));
+            }
+            if stats.malformed > 0 {
+                warn(format!(
+                    "Calendar source '{}' skipped {} malformed events",
+                    name,
+                    stats.malformed
+                ));
             }
         }
+        
+        warnings
     }
 
-    /// Determines if the target date is a weekday based on configuration
+    /// Enforces strict mode and `min_sources`
+    /// 
+    /// # Arguments
+    /// * `reports` - Outcome of loading each source, from the read being checked
+    /// 
+    /// # Returns
+    /// * `Some(String)` describing the unmet requirement
+    /// * `None` if the loaded sources satisfy strict mode and `min_sources`
+    fn unmet_requirement(&self, reports: &[SourceReport]) -> Option<String> {
+        let loaded = reports.iter().filter(|r| r.is_loaded()).count();
+        if self.strict && loaded < reports.len() {
+            return Some(format!(
+                "{} of {} calendar sources failed to load",
+                reports.len() - loaded,
+                reports.len()
+            ));
+        }
+        if let Some(min_sources) = self.min_sources
+            && loaded < min_sources
+        {
+            return Some(format!(
+                "Only {} calendar sources loaded, at least {} required",
+                loaded,
+                min_sources
+            ));
+        }
+        None
+    }
+
+    /// Drops the events read so far and reads all sources again
+    /// 
+    /// # Returns
+    /// * Outcome of loading each configured source, in configuration order
+    /// 
+    /// # Note
+    /// * Meant for long-running embedders, to pick up changed calendars
+    ///   without creating a new `Choliday`
+    /// * Failed sources are reported rather than returned as errors; strict
+    ///   mode and `min_sources` apply to the next query as usual
+    /// * Decisions made before are forgotten, queries classify again
+    /// 
+    /// # Examples
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use choliday::{Choliday, Conf, Priority};
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), String> {
+    /// let dir = tempfile::tempdir().map_err(|e| e.to_string())?;
+    /// let path = dir.path().join("holidays.ics");
+    /// let calendar = |summary: &str| format!(
+    ///     "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20251001\r\nSUMMARY:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+    ///     summary,
+    /// );
+    /// std::fs::write(&path, calendar("Holiday")).map_err(|e| e.to_string())?;
+    /// 
+    /// let conf = Conf::builder(Priority::RestOverWork).source(path.to_string_lossy()).rest("Holiday").build()?;
+    /// let choliday = Choliday::builder(conf).date(NaiveDate::from_ymd_opt(2025, 10, 1).unwrap()).build()?;
+    /// assert!(!choliday.is_work_day().await?);
+    /// 
+    /// // The decision made before is reused until the sources are read again
+    /// std::fs::write(&path, calendar("Team offsite")).map_err(|e| e.to_string())?;
+    /// assert!(!choliday.is_work_day().await?);
+    /// choliday.refresh().await;
+    /// assert!(choliday.is_work_day().await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn refresh(&self) -> Vec<SourceReport> {
+        self.ical.invalidate();
+        {
+            let mut memo = self.memo.lock().unwrap_or_else(PoisonError::into_inner);
+            memo.generation += 1;
+            memo.decisions.clear();
+        }
+        self.ical.read(None).await.reports().to_vec()
+    }
+
+    /// Reads all sources again, keeping the events read before unless every
+    /// source was fetched
+    /// 
+    /// # Returns
+    /// * `Ok(Vec<SourceReport>)` - Every source was fetched; queries are now
+    ///   answered from the new events
+    /// * `Err(Vec<SourceReport>)` - A source failed or fell back to its cached
+    ///   copy; queries keep being answered from the events read before
+    /// 
+    /// # Note
+    /// * Unlike [`Choliday::refresh`], queries running meanwhile are answered
+    ///   from the previous events instead of reading the sources themselves
+    /// * Without events read before, the new events are kept whatever the
+    ///   outcome, like [`Choliday::refresh`]
+    /// * Decisions made before are forgotten once the new events are swapped in
+    /// 
+    /// # Examples
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use choliday::{Choliday, Conf, Priority};
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), String> {
+    /// let dir = tempfile::tempdir().map_err(|e| e.to_string())?;
+    /// let path = dir.path().join("holidays.ics");
+    /// let calendar = |summary: &str| format!(
+    ///     "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20251001\r\nSUMMARY:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+    ///     summary,
+    /// );
+    /// std::fs::write(&path, calendar("Holiday")).map_err(|e| e.to_string())?;
+    /// 
+    /// let conf = Conf::builder(Priority::RestOverWork).source(path.to_string_lossy()).rest("Holiday").build()?;
+    /// let choliday = Choliday::builder(conf).date(NaiveDate::from_ymd_opt(2025, 10, 1).unwrap()).build()?;
+    /// assert!(choliday.reload().await.is_ok());
+    /// 
+    /// // While the source is unavailable, the events read before keep answering
+    /// std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+    /// assert!(choliday.reload().await.is_err());
+    /// assert!(!choliday.is_work_day().await?);
+    /// 
+    /// // Once it is back, its new contents are swapped in
+    /// std::fs::write(&path, calendar("Team offsite")).map_err(|e| e.to_string())?;
+    /// assert!(choliday.reload().await.is_ok());
+    /// assert!(choliday.is_work_day().await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn reload(&self) -> Result<Vec<SourceReport>, Vec<SourceReport>> {
+        let reports = self.ical.reload().await?;
+        let mut memo = self.memo.lock().unwrap_or_else(PoisonError::into_inner);
+        memo.generation += 1;
+        memo.decisions.clear();
+        Ok(reports)
+    }
+
+    /// Formats the fetch and parse statistics of every calendar source as a table
+    /// 
+    /// # Returns
+    /// * One header line and one line per source, in configuration order,
+    ///   followed by the memoized decisions once any query looked them up
+    /// 
+    /// # Note
+    /// * EVENTS counts everything read from the data, KEPT what is left after
+    ///   the date window, WORK/REST/BOTH how the kept events classify their day
+    /// * Credentials in source URLs are hidden, see [`redact_url`]
+    pub fn source_table(&self) -> String {
+        let mut rows = vec![
+            ["SOURCE", "STATUS", "HTTP", "BYTES", "FETCH", "EVENTS", "KEPT", "WORK", "REST", "BOTH", "SKIPPED"]
+                .map(str::to_string)
+                .to_vec(),
+        ];
+        for report in self.ical.source_reports() {
+            let status = match report.status() {
+                SourceStatus::Loaded { .. } => "loaded",
+                SourceStatus::Stale { .. } => "stale",
+                SourceStatus::Failed(_) => "failed",
+            };
+            let mut row = vec![redact_url(report.source()), status.to_string()];
+            if report.is_loaded() {
+                let stats = report.stats();
+                row.extend([
+                    stats.http_status.map_or("-".to_string(), |status| status.to_string()),
+                    stats.bytes.to_string(),
+                    format!("{}ms", stats.fetch_time.as_millis()),
+                    stats.parsed.to_string(),
+                    stats.kept.to_string(),
+                    stats.work.to_string(),
+                    stats.rest.to_string(),
+                    stats.conflict.to_string(),
+                    stats.malformed.to_string(),
+                ]);
+            } else {
+                row.extend(std::iter::repeat_n("-".to_string(), 9));
+            }
+            rows.push(row);
+        }
+
+        let widths: Vec<usize> = (0..rows[0].len())
+            .map(|column| rows.iter().map(|row| row[column].chars().count()).max().unwrap_or(0))
+            .collect();
+        rows.iter()
+            .map(|row| {
+                let cells: Vec<String> = row
+                    .iter()
+                    .zip(&widths)
+                    .enumerate()
+                    .map(|(column, (cell, width))| match column {
+                        // Text columns left-aligned, counters right-aligned
+                        0 | 1 => format!("{:<width$}", cell),
+                        _ => format!("{:>width$}", cell),
+                    })
+                    .collect();
+                cells.join("  ").trim_end().to_string()
+            })
+            .chain(self.memo_line())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Describes the memoized decisions and how often they were reused
+    /// 
+    /// # Returns
+    /// * `None` until a query looked up a decision
+    fn memo_line(&self) -> Option<String> {
+        let memo = self.memo.lock().unwrap_or_else(PoisonError::into_inner);
+        (memo.hits + memo.misses > 0).then(|| {
+            format!(
+                "Memoized decisions: {} ({} hits, {} misses)",
+                memo.decisions.len(),
+                memo.hits,
+                memo.misses
+            )
+        })
+    }
+
+    /// Determines if a date is a weekday based on configuration
+    /// 
+    /// # Arguments
+    /// * `dt` - Date and time to check
     /// 
     /// # Returns
     /// * `true` if the date is a configured workday
@@ -101,17 +1524,234 @@ impl Choliday {
     /// # Note
     /// * If work_days is None, uses default Saturday and Sunday as weekend
     /// * Weekday numbers: Monday = 1, Tuesday = 2, ..., Sunday = 7
-    fn is_weekday(&self) -> bool {
-        let weekday_number = self.dt.weekday().number_from_monday() as i8;
+    fn is_weekday(&self, dt: NaiveDateTime) -> bool {
+        let weekday_number = dt.weekday().number_from_monday() as i8;
         
         if let Some(work_days) = &self.work_days {
             // Use configured work days
             work_days.contains(&weekday_number)
         } else {
             // Default: Monday-Friday are workdays, Saturday-Sunday are weekends
-            !matches!(self.dt.weekday(), 
+            !matches!(dt.weekday(), 
                 chrono::Weekday::Sat | chrono::Weekday::Sun
             )
         }
     }
-}
\ No newline at end of file
+}
+
+impl CholidayBuilder {
+    /// Sets the target date, classified over its whole civil day like a
+    /// `YYYYmmDD` date on the command line, see [`Choliday::set_whole_day`]
+    /// 
+    /// # Examples
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use choliday::{Choliday, Conf, Priority};
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), String> {
+    /// // A rest event from 09:00 to 18:00 only, long over by 23:59:59
+    /// let events = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART:20251001T010000Z\r\nDTEND:20251001T100000Z\r\n\
+    ///               SUMMARY:Holiday\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    /// let date = NaiveDate::from_ymd_opt(2025, 10, 1).unwrap();
+    /// let analyzer = |whole_day| {
+    ///     Choliday::builder(Conf::builder(Priority::RestOverWork).rest("Holiday").build()?)
+    ///         .date(date)
+    ///         .whole_day(whole_day)
+    ///         .timezone(chrono_tz::Asia::Shanghai)
+    ///         .events(events)
+    ///         .build()
+    /// };
+    /// assert!(!analyzer(true)?.is_work_day().await?);
+    /// assert_eq!(analyzer(true)?.evaluate_many(&[date]).await[0].is_work_day(), false);
+    /// assert!(analyzer(false)?.is_work_day().await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn date(mut self, date: NaiveDate) -> Self {
+        self.dt = Some(date.and_time(END_OF_DAY));
+        self.date_only = true;
+        self
+    }
+
+    /// Sets the target date and time, on the wall clock of the target zone
+    pub fn datetime(mut self, dt: NaiveDateTime) -> Self {
+        self.dt = Some(dt);
+        self.date_only = false;
+        self
+    }
+
+    /// Classifies dates over their whole civil day, or at 23:59:59, regardless
+    /// of how the target was set, see [`Choliday::set_whole_day`]
+    pub fn whole_day(mut self, whole_day: bool) -> Self {
+        self.whole_day = Some(whole_day);
+        self
+    }
+
+    /// Sets the zone of target dates and floating calendar times, overriding `base.timezone`
+    pub fn timezone(mut self, timezone: Tz) -> Self {
+        self.conf.set_timezone(timezone);
+        self
+    }
+
+    /// Makes failed calendar sources and questionable event data errors, see [`Choliday::set_strict`]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets how a day decided by an event matching both work and rest is
+    /// handled, overriding `predict.on_conflict`
+    pub fn on_conflict(mut self, on_conflict: OnConflict) -> Self {
+        self.conf.set_on_conflict(on_conflict);
+        self
+    }
+
+    /// Decides days classified by several calendar events with a custom
+    /// resolver instead of `predict.priority`, see [`ConflictResolver`]
+    /// 
+    /// # Note
+    /// * `on_conflict = "error"` still leaves a day unresolved when the event
+    ///   the resolver reports matches both work and rest
+    pub fn resolver(mut self, resolver: impl ConflictResolver + 'static) -> Self {
+        self.resolver = Some(Box::new(resolver));
+        self
+    }
+
+    /// Reuses the remote calendar data of other analyzers built with the same
+    /// handle, see [`SharedFetches`]
+    pub fn shared_fetches(mut self, shared: SharedFetches) -> Self {
+        self.shared = Some(shared);
+        self
+    }
+
+    /// Enables or disables the on-disk cache of remote sources (enabled by default)
+    pub fn use_cache(mut self, use_cache: bool) -> Self {
+        self.use_cache = use_cache;
+        self
+    }
+
+    /// Asks servers for every remote source even while the cached copy is
+    /// fresh (disabled by default)
+    /// 
+    /// # Note
+    /// * Cached copies are still revalidated with `If-None-Match` and
+    ///   `If-Modified-Since`, so unchanged calendars are not downloaded again
+    pub fn revalidate(mut self, revalidate: bool) -> Self {
+        self.revalidate = revalidate;
+        self
+    }
+
+    /// Uses the events of iCalendar data instead of the configured sources
+    /// 
+    /// # Note
+    /// * Meant for tests and for callers that fetch calendars themselves; the
+    ///   data is parsed with the configured settings by `build`
+    /// * The configured sources are only read after `Choliday::refresh`
+    pub fn events(mut self, data: impl Into<String>) -> Self {
+        self.events = Some(data.into());
+        self
+    }
+
+    /// Creates the analyzer
+    /// 
+    /// # Returns
+    /// * `Ok(Choliday)` - Analyzer ready for queries
+    /// * `Err(String)` - No target date was set, or the events passed to
+    ///   `events` contain no readable calendar
+    /// 
+    /// # Examples
+    /// ```
+    /// use choliday::{Choliday, Conf, Priority};
+    /// 
+    /// # fn main() -> Result<(), String> {
+    /// let conf = Conf::builder(Priority::RestOverWork).build()?;
+    /// let missing_date = Choliday::builder(conf).build();
+    /// assert!(missing_date.is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn build(self) -> Result<Choliday, String> {
+        let dt = self.dt.ok_or("No target date set, call date or datetime before build")?;
+        let mut choliday = Choliday::new(self.conf, dt);
+        choliday.set_strict(self.strict);
+        choliday.set_whole_day(self.whole_day.unwrap_or(self.date_only));
+        choliday.set_use_cache(self.use_cache);
+        choliday.ical.set_revalidate(self.revalidate);
+        if let Some(resolver) = self.resolver {
+            choliday.ical.set_resolver(resolver);
+        }
+        if let Some(shared) = self.shared {
+            choliday.ical.set_shared(shared);
+        }
+        if let Some(data) = &self.events {
+            choliday.ical.preload(data)?;
+        }
+        Ok(choliday)
+    }
+}
+
+// Fails to compile if `Choliday` can no longer be shared between threads
+const _: fn() = || {
+    fn assert<T: Send + Sync>() {}
+    assert::<Choliday>();
+    assert::<DayDecisions<'static>>();
+};
+
+/// Iterator over the decisions of every day in a date range, see [`Choliday::days`]
+#[derive(Clone)]
+pub struct DayDecisions<'a> {
+    /// Analyzer whose settings classify the days
+    choliday: &'a Choliday,
+    /// Generation of memoized decisions read before the events
+    generation: u64,
+    /// All events, kept even if the analyzer reads its sources again meanwhile
+    loaded: Arc<Loaded>,
+    /// Next day from the front
+    front: NaiveDate,
+    /// Next day from the back
+    back: NaiveDate,
+    /// Whether the front has passed the back
+    done: bool,
+    /// Warnings about the sources, reported with every decision
+    warnings: Vec<String>,
+}
+
+impl Iterator for DayDecisions<'_> {
+    type Item = (NaiveDate, DayDecision);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let date = self.front;
+        match date.succ_opt() {
+            Some(next) if date < self.back => self.front = next,
+            _ => self.done = true,
+        }
+        Some((date, self.choliday.decision_on(&self.loaded, date, &self.warnings, self.generation)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = if self.done { 0 } else { (self.back - self.front).num_days() as usize + 1 };
+        (remaining, Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for DayDecisions<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let date = self.back;
+        match date.pred_opt() {
+            Some(previous) if date > self.front => self.back = previous,
+            _ => self.done = true,
+        }
+        Some((date, self.choliday.decision_on(&self.loaded, date, &self.warnings, self.generation)))
+    }
+}
+
+impl ExactSizeIterator for DayDecisions<'_> {}
+
+impl FusedIterator for DayDecisions<'_> {}
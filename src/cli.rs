@@ -22,21 +22,28 @@
 //! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 //! SOFTWARE.
 
-use std::{fs::File, io::Read};
+use std::path::{Path, PathBuf};
 
-use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime};
-use clap::{builder::TypedValueParser, Parser};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use clap::{builder::TypedValueParser, CommandFactory, Parser, Subcommand, ValueEnum};
 
-use crate::conf::Conf;
+use choliday::{Conf, OnConflict};
 
 /// Help message for date format specification
 const HELP_MSG: &str = "Date format must be one of: \"YYYYmmDD\", \"YYYYmmDDHHMMss\" or UNIX timestamp(millisecond)\nLeave empty to use today at 23:59:59";
+/// Default title of notifications
+const DEFAULT_NOTIFY_TITLE: &str = "{date}: {kind}";
+/// Default text of notifications
+const DEFAULT_NOTIFY_MESSAGE: &str = "{date} ({weekday}) is a {kind}, decided by {source}";
+/// Help message for notification templates
+const NOTIFY_MESSAGE_HELP: &str = "Text of the notification; placeholders: {date}, {time}, {weekday}, {result}, \
+                                   {day_type}, {kind}, {summary}, {pattern}, {source}";
 /// Date format string (YYYYmmDD)
 const DATE_FORMAT: &str = "%Y%m%d";
-/// Date and time format string (YYYYmmDDHHMMSS)
-const DATETIME_FORMAT: &str = "%Y%m%d%H%M%S";
 
 const DEFAULT_DATE_VAUE: &str = "today";
+/// Time a target given as a date is checked at
+const END_OF_DAY: NaiveTime = NaiveTime::from_hms_opt(23, 59, 59).expect("23:59:59 is a valid time");
 
 /// Command-line interface structure
 #[derive(Parser)]
@@ -51,7 +58,7 @@ pub struct Cli {
     /// Target date for schedule analysis
     /// 
     /// Supports multiple formats:
-    /// - "today": Use today's date at 23:59:59
+    /// - "today": Use today's date in the configured timezone at 23:59:59
     /// - "YYYYmmDD": Specific date (e.g., 20241225 for Christmas 2024)
     /// - "YYYYmmDDHHMMSS": Specific date and time
     /// - UNIX timestamp in millisecond
@@ -63,40 +70,504 @@ pub struct Cli {
         default_value = DEFAULT_DATE_VAUE,
         help = HELP_MSG
     )]
-    date: NaiveDateTime,
+    date: TargetDate,
+
+    /// Classify date-only targets at 23:59:59 instead of over the whole day
+    #[arg(
+        long,
+        help = "Classify dates at 23:59:59 only, instead of by every event overlapping the day"
+    )]
+    at: bool,
     
-    /// Configuration file path
+    /// Configuration file paths
     /// 
-    /// TOML configuration file containing work/rest patterns,
-    /// priority rules, and calendar sources.
+    /// TOML configuration files containing work/rest patterns,
+    /// priority rules, and calendar sources, merged left to right.
+    #[arg(
+        long = "conf",
+        short = 'c',
+        required = true,
+        value_name = "CONF",
+        help = "Path to TOML configuration file; repeat to merge files, later ones taking precedence"
+    )]
+    conf_files: Vec<PathBuf>,
+
+    /// Configuration merged from the files, loaded by `Cli::load`
+    #[arg(skip)]
+    conf: Option<Conf>,
+
+    /// Print the merged configuration instead of the answer
+    #[arg(
+        long,
+        help = "Print the configuration merged from every -c file as TOML and exit"
+    )]
+    show_effective_config: bool,
+
+    /// Answer unknown instead of warning when a calendar source cannot be loaded
+    #[arg(
+        long,
+        help = "Answer unknown (exit code 4) when any calendar source fails to load"
+    )]
+    strict: bool,
+
+    /// Fail on calendar events contradicting each other
+    #[arg(
+        long,
+        help = "Fail (exit code 2) when calendar events classify a checked day in opposite ways"
+    )]
+    strict_data: bool,
+
+    /// Bypass the on-disk cache of remote calendars
+    #[arg(
+        long,
+        help = "Always download remote calendars and never fall back to cached copies"
+    )]
+    no_cache: bool,
+
+    /// Revalidate cached calendars that are still fresh
+    #[arg(
+        long,
+        help = "Ask servers again even for cached calendars still fresh by Cache-Control, Expires or cache.ttl"
+    )]
+    refresh: bool,
+
+    /// Print per-source statistics after the answer
     #[arg(
         long,
         short,
-        required = true,
-        value_parser = ConfParser,
-        help = "Path to TOML configuration file"
+        visible_alias = "explain",
+        help = "Print fetch and parse statistics of every calendar source to stderr"
+    )]
+    verbose: bool,
+
+    /// Print the decision as JSON instead of `true`/`false`
+    #[arg(
+        long,
+        help = "Print the classification, what decided it and any warnings as JSON"
+    )]
+    json: bool,
+
+    /// Check working hours at the target time instead of the work day
+    #[arg(
+        long,
+        conflicts_with = "json",
+        help = "Exit 0 if the target time is within working hours, 1 otherwise"
+    )]
+    hours: bool,
+
+    /// Fields of the decision to print instead of `true`/`false`
+    #[arg(
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = ["json", "hours"],
+        help = "Print only these fields of the decision, tab-separated, e.g. --print date,result"
+    )]
+    print: Vec<PrintField>,
+
+    /// Handling of a day decided by an event matching both work and rest
+    #[arg(
+        long,
+        value_name = "priority|error",
+        help = "With 'error', exit 3 when an event matching both work and rest decides the day (overrides predict.on_conflict)"
+    )]
+    on_conflict: Option<OnConflict>,
+
+    /// Year file written by `precompute` answering without reading the calendars
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["json", "hours", "print", "verbose"],
+        help = "Answer from a file written by precompute when it covers the date and is up to date"
     )]
-    conf: Conf,
+    use_precomputed: Option<PathBuf>,
+
+    /// Maintenance command to run instead of the workday check
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Maintenance subcommands
+#[derive(Subcommand)]
+pub enum Command {
+    /// List the work days of a year between rest days, worth taking off
+    Bridges {
+        /// Year to search
+        #[arg(long, help = "Year to search (YYYY)")]
+        year: i32,
+        /// Longest run of work days to report
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..), help = "Longest run of work days between rest days to report")]
+        max_gap: u32,
+    },
+    /// Manage the cache of remote calendars
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Report the days revisions of remote calendars changed since they were cached
+    Changes {
+        /// Number of days after the target date to compare
+        #[arg(long, help = "Days after the target date to compare (default: base.horizon_days)")]
+        days: Option<u32>,
+        /// URL the changes are posted to as JSON when any were found
+        #[arg(long, value_name = "URL", help = "POST the changes as JSON to this URL when any were found")]
+        notify_url: Option<String>,
+    },
+    /// Count the work and rest days of a date range
+    Count {
+        /// First day of the range
+        #[arg(long, value_parser = parse_date, help = "First day of the range (YYYYmmDD)")]
+        from: NaiveDate,
+        /// Last day of the range, included
+        #[arg(long, value_parser = parse_date, help = "Last day of the range, included (YYYYmmDD)")]
+        to: NaiveDate,
+    },
+    /// Print a crontab line, or systemd units, running a command on work days only
+    CronSnippet {
+        /// When to run, in crontab syntax
+        #[arg(long, help = "Schedule in crontab syntax, e.g. \"30 7 * * 1-5\" or @daily")]
+        schedule: String,
+        /// Shell command to run on work days
+        #[arg(long, help = "Shell command to run on work days")]
+        command: String,
+        /// Print systemd units instead of a crontab line
+        #[arg(long, help = "Print a .service with ExecCondition= and a .timer instead of a crontab line")]
+        systemd: bool,
+        /// Name of the systemd units
+        #[arg(long, default_value = "choliday-job", requires = "systemd", help = "Name of the systemd units")]
+        name: String,
+    },
+    /// Print the days of a range another configuration classifies differently
+    Diff {
+        /// Configuration to compare with, boxed as it dwarfs the other subcommands
+        #[arg(long, value_parser = ConfParser.map(Box::new), help = "Path to the TOML configuration to compare with")]
+        against: Box<Conf>,
+        /// First day of the range
+        #[arg(long, value_parser = parse_date, help = "First day of the range (YYYYmmDD)")]
+        from: NaiveDate,
+        /// Last day of the range, included
+        #[arg(long, value_parser = parse_date, help = "Last day of the range, included (YYYYmmDD)")]
+        to: NaiveDate,
+    },
+    /// Print an iCalendar feed of the predicted rest days of a date range
+    Export {
+        /// First day of the range
+        #[arg(long, value_parser = parse_date, help = "First day of the range (YYYYmmDD)")]
+        from: NaiveDate,
+        /// Last day of the range, included
+        #[arg(long, value_parser = parse_date, help = "Last day of the range, included (YYYYmmDD)")]
+        to: NaiveDate,
+        /// List makeup work days too
+        #[arg(long, help = "Also list work days on days the configured work days leave off")]
+        work: bool,
+        /// Print every day as CSV instead of a feed
+        #[arg(long, conflicts_with = "work", help = "Print the decision and provenance of every day as CSV instead of a feed")]
+        csv: bool,
+    },
+    /// List every event considered for the target date and what became of it
+    List,
+    /// List the blocks of consecutive rest days of a date range, e.g. long weekends
+    LongWeekends {
+        /// First day of the range
+        #[arg(long, value_parser = parse_date, help = "First day of the range (YYYYmmDD)")]
+        from: NaiveDate,
+        /// Last day of the range, included
+        #[arg(long, value_parser = parse_date, help = "Last day of the range, included (YYYYmmDD)")]
+        to: NaiveDate,
+        /// Shortest block to list
+        #[arg(long, default_value_t = 3, help = "Shortest block of rest days to list")]
+        min: u32,
+        /// Order of the blocks
+        #[arg(long, value_enum, default_value_t = BlockOrder::Date, help = "Order of the blocks: by date, or longest first")]
+        sort: BlockOrder,
+    },
+    /// Send the classification of the target date as a push notification or email
+    #[command(group = clap::ArgGroup::new("transport").required(true).multiple(true))]
+    Notify {
+        /// ntfy topic to publish to
+        #[arg(long, value_name = "URL", group = "transport", help = "ntfy topic URL to publish to, e.g. https://ntfy.sh/mytopic")]
+        ntfy: Option<String>,
+        /// Title of the notification and subject of the email
+        #[arg(long, default_value = DEFAULT_NOTIFY_TITLE, help = "Title and email subject, with the placeholders of --message")]
+        title: String,
+        /// Text of the notification and the email
+        #[arg(long, default_value = DEFAULT_NOTIFY_MESSAGE, help = NOTIFY_MESSAGE_HELP)]
+        message: String,
+        /// ntfy priority
+        #[arg(
+            long,
+            value_parser = ["1", "2", "3", "4", "5", "min", "low", "default", "high", "urgent"],
+            help = "ntfy priority, 1-5 or min, low, default, high, urgent"
+        )]
+        priority: Option<String>,
+        /// SMTP server to send an email through
+        #[arg(
+            long,
+            value_name = "URL",
+            group = "transport",
+            requires = "to",
+            help = "SMTP server, e.g. smtps://smtp.example.com; credentials from CHOLIDAY_SMTP_USER and CHOLIDAY_SMTP_PASSWORD"
+        )]
+        smtp_url: Option<String>,
+        /// Recipients of the email
+        #[arg(long, value_name = "ADDRESS", requires = "smtp_url", help = "Email recipient, repeatable")]
+        to: Vec<String>,
+        /// Sender of the email
+        #[arg(long, value_name = "ADDRESS", help = "Email sender (default: the first recipient)")]
+        from: Option<String>,
+    },
+    /// Print the n-th work day of a month
+    Nth {
+        /// Month to search
+        #[arg(long, value_parser = parse_month, help = "Month to search (YYYYmm)")]
+        month: NaiveDate,
+        /// Position of the work day, 1 for the first
+        #[arg(long = "n", value_parser = clap::value_parser!(u32).range(1..), help = "Position of the work day, 1 for the first")]
+        n: u32,
+        /// Count from the end of the month, 1 for the last work day
+        #[arg(long, help = "Count from the end of the month, --n 1 being the last work day")]
+        from_end: bool,
+    },
+    /// Classify every day of a year into a file for --use-precomputed
+    Precompute {
+        /// Year to classify
+        #[arg(long, help = "Year to classify (YYYY)")]
+        year: i32,
+        /// File to write
+        #[arg(long, value_name = "FILE", help = "File to write, read back with --use-precomputed")]
+        out: PathBuf,
+    },
+    /// Print the JSON Schema of the --json output
+    Schema,
+    /// Print the next days on which the classification changes, from the target date
+    Transitions {
+        /// Number of transitions to print
+        #[arg(long, default_value_t = 4, help = "Number of transitions to print")]
+        count: usize,
+    },
+    /// Follow the classification of the current day, running a command on changes
+    Watch {
+        /// Command to run when the classification changes
+        #[arg(
+            long,
+            value_name = "COMMAND",
+            help = "Command to run when today's classification changes, e.g. './update-light.sh {date} {result}'; \
+                    takes the placeholders of notify --message"
+        )]
+        on_change: Option<String>,
+        /// Seconds between reads of the calendar sources
+        #[arg(long, default_value_t = 300, value_name = "SECONDS", help = "Seconds between reads of the calendar sources")]
+        interval: u64,
+        /// File remembering the last classification
+        #[arg(long, value_name = "PATH", help = "File remembering the last classification (default: watch-state.json in the cache directory)")]
+        state: Option<PathBuf>,
+    },
+    /// Serve the predictions over HTTP as an iCalendar feed at /feed.ics
+    Serve {
+        /// Address and port to listen on
+        #[arg(long, default_value = "127.0.0.1:8080", help = "Address and port to listen on")]
+        listen: String,
+    },
+}
+
+/// Field of a decision printed by `--print`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum PrintField {
+    /// Date classified, YYYY-mm-DD
+    Date,
+    /// Day of the week, e.g. Mon
+    Weekday,
+    /// true, false, unknown or conflict, as printed without --print
+    Result,
+    /// Stable name of the classification, e.g. rest_day
+    #[value(name = "daytype")]
+    DayType,
+    /// What decided the classification
+    Source,
+    /// Pattern or category the deciding event matched, empty if none
+    #[value(name = "matched_pattern")]
+    MatchedPattern,
+}
+
+impl PrintField {
+    /// Returns the template placeholder printing the field
+    pub fn placeholder(&self) -> &'static str {
+        match self {
+            PrintField::Date => "{date}",
+            PrintField::Weekday => "{weekday}",
+            PrintField::Result => "{result}",
+            PrintField::DayType => "{day_type}",
+            PrintField::Source => "{source}",
+            PrintField::MatchedPattern => "{pattern}",
+        }
+    }
+}
+
+/// Order of the blocks listed by `long-weekends`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum BlockOrder {
+    /// Earliest block first
+    Date,
+    /// Longest block first, earliest first among equals
+    Length,
+}
+
+/// Cache maintenance actions
+#[derive(Subcommand)]
+pub enum CacheAction {
+    /// Remove all cached calendars
+    Clear,
+    /// List the cached calendars with when they were fetched and their number of events
+    List,
 }
 
 impl Cli {
+    /// Parses the command line and loads the configuration files
+    /// 
+    /// # Note
+    /// * Exits with a usage error, like any invalid argument, when a file
+    ///   cannot be loaded or the merged configuration is invalid
+    /// * With `--show-effective-config` the files are only merged, so that
+    ///   an invalid result can still be printed
+    pub fn load() -> Self {
+        let mut cli = Cli::parse();
+        if !cli.show_effective_config {
+            match Conf::load_all(&cli.conf_files) {
+                Ok(conf) => cli.conf = Some(conf),
+                Err(e) => <Cli as CommandFactory>::command().error(clap::error::ErrorKind::InvalidValue, e).exit(),
+            }
+            if let Err(errors) = cli.conf().validate() {
+                let lines: Vec<String> = errors.iter().map(|e| format!("  {}", e)).collect();
+                let message = format!("invalid configuration:\n{}", lines.join("\n"));
+                <Cli as CommandFactory>::command().error(clap::error::ErrorKind::InvalidValue, message).exit()
+            }
+        }
+        cli
+    }
+
     /// Returns a reference to the parsed configuration
+    /// 
+    /// # Note
+    /// * Only called after [`Cli::load`] loaded it, i.e. without `--show-effective-config`
     pub fn conf(&self) -> &Conf {
-        &self.conf
+        self.conf.as_ref().expect("configuration loaded by Cli::load")
+    }
+
+    /// Returns the configuration files given with `-c`, in merge order
+    pub fn conf_files(&self) -> &[PathBuf] {
+        &self.conf_files
+    }
+
+    /// Returns whether the merged configuration is printed instead of the answer
+    pub fn show_effective_config(&self) -> bool {
+        self.show_effective_config
     }
     
     /// Returns the target date for analysis
+    /// 
+    /// # Returns
+    /// * Wall-clock time in the configured target zone; a millisecond
+    ///   timestamp is converted to that zone's wall clock, and `today` is
+    ///   the current date in that zone at 23:59:59
     pub fn date(&self) -> NaiveDateTime {
-        self.date
+        match self.date {
+            TargetDate::Today => self.conf().target_zone().today(Utc::now()).and_time(END_OF_DAY),
+            TargetDate::Day(date) => date.and_time(END_OF_DAY),
+            TargetDate::WallClock(dt) => dt,
+            TargetDate::Timestamp(ts) => self.conf().target_zone().to_wall_clock(ts),
+        }
+    }
+
+    /// Returns whether dates are classified over their whole civil day
+    /// 
+    /// # Returns
+    /// * `true` for a date-only target, `today` included, unless `--at` is given
+    pub fn whole_day(&self) -> bool {
+        !self.at && matches!(self.date, TargetDate::Today | TargetDate::Day(_))
+    }
+
+    /// Returns whether failed calendar sources are fatal
+    pub fn strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Returns whether contradicting calendar events are errors
+    pub fn strict_data(&self) -> bool {
+        self.strict_data
+    }
+
+    /// Returns whether the on-disk cache is bypassed
+    pub fn no_cache(&self) -> bool {
+        self.no_cache
+    }
+
+    /// Returns whether fresh cached calendars are revalidated anyway
+    pub fn refresh(&self) -> bool {
+        self.refresh
+    }
+
+    /// Returns whether per-source statistics are printed
+    pub fn verbose(&self) -> bool {
+        self.verbose
+    }
+
+    /// Returns whether the decision is printed as JSON
+    pub fn json(&self) -> bool {
+        self.json
+    }
+
+    /// Returns the fields selected by `--print`, empty to print the answer
+    pub fn print(&self) -> &[PrintField] {
+        &self.print
+    }
+
+    /// Returns whether working hours are checked instead of the work day
+    pub fn hours(&self) -> bool {
+        self.hours
+    }
+
+    /// Returns the conflict handling given on the command line, if any
+    pub fn on_conflict(&self) -> Option<OnConflict> {
+        self.on_conflict
+    }
+
+    /// Returns the precomputed year file to answer from, if any
+    pub fn use_precomputed(&self) -> Option<&Path> {
+        self.use_precomputed.as_deref()
+    }
+
+    /// Describes the options besides the configuration that change decisions
+    /// 
+    /// # Returns
+    /// * Text hashed into precomputed year files, which only answer for the same
+    pub fn settings(&self) -> String {
+        format!("strict={} on_conflict={:?} whole_day={}", self.strict, self.on_conflict, self.whole_day())
+    }
+
+    /// Returns the requested maintenance command, if any
+    pub fn command(&self) -> Option<&Command> {
+        self.command.as_ref()
     }
 }
 
+/// Target date as given on the command line
+#[derive(Clone, Copy, Debug)]
+enum TargetDate {
+    /// Today in the target zone, at 23:59:59
+    Today,
+    /// A date without a time, at 23:59:59
+    Day(NaiveDate),
+    /// Date and time on the wall clock of the target zone
+    WallClock(NaiveDateTime),
+    /// UNIX timestamp in millisecond, an absolute instant
+    Timestamp(i64),
+}
+
 /// Custom parser for timestamp values
 #[derive(Clone)]
 struct TimestampParser;
 
 impl TypedValueParser for TimestampParser {
-    type Value = NaiveDateTime;
+    type Value = TargetDate;
 
     /// Parses timestamp strings from command-line arguments
     /// 
@@ -104,13 +575,17 @@ impl TypedValueParser for TimestampParser {
     /// * `value` - String value from command line
     /// 
     /// # Returns
-    /// * `Result<NaiveDateTime, clap::Error>` - Parsed datetime or error
+    /// * `Result<TargetDate, clap::Error>` - Parsed datetime or error
     /// 
     /// # Supported Formats
-    /// * "today": Today's date at 23:59:59
+    /// * "today": Today's date at 23:59:59, resolved in the configured timezone by [`Cli::date`]
     /// * "YYYYmmDD": Date only (e.g., 20241225)
     /// * "YYYYmmDDHHMMSS": Full timestamp (e.g., 20241225143000)
     /// * UNIX timestamp in millisecond
+    /// 
+    /// # Note
+    /// * Eight or fourteen digits are always read as a date, so an impossible
+    ///   one such as 20250230 is reported as such instead of as a timestamp
     fn parse_ref(
         &self,
         _cmd: &clap::Command,
@@ -122,48 +597,24 @@ impl TypedValueParser for TimestampParser {
         };
         
         match value_str {
-            DEFAULT_DATE_VAUE => {
-                // Default to today at 23:59:59
-                let end_of_day = NaiveTime::from_hms_opt(23, 59, 59)
-                    .ok_or_else(|| clap::Error::raw(
-                        clap::error::ErrorKind::InvalidValue,
-                        "Failed to create today time 23:59:59"
-                    ))?;
-                
-                match Local::now().with_time(end_of_day) {
-                    chrono::offset::LocalResult::Single(dt) => Ok(dt.naive_local()),
-                    chrono::offset::LocalResult::Ambiguous(_, later) => Ok(later.naive_local()),
-                    chrono::offset::LocalResult::None => Err(clap::Error::raw(
-                        clap::error::ErrorKind::InvalidValue,
-                        "Invalid date/time combination"
-                    )),
-                }
-            },
+            // Resolved once the configuration, and with it the zone, is loaded
+            DEFAULT_DATE_VAUE => Ok(TargetDate::Today),
+            // Calendar dates, even when the day or time does not exist
+            _ if value_str.bytes().all(|b| b.is_ascii_digit()) && value_str.len() == 8 => calendar_date(value_str)
+                .map(TargetDate::Day)
+                .map_err(|e| clap::Error::raw(clap::error::ErrorKind::InvalidValue, format!("{}\n", e))),
+            _ if value_str.bytes().all(|b| b.is_ascii_digit()) && value_str.len() == 14 => calendar_datetime(value_str)
+                .map(TargetDate::WallClock)
+                .map_err(|e| clap::Error::raw(clap::error::ErrorKind::InvalidValue, format!("{}\n", e))),
             _ => {
-                // Try parsing as date only first (YYYYmmDD)
-                if let Ok(dt) = NaiveDate::parse_from_str(value_str, DATE_FORMAT) {
-                    let end_of_day = NaiveTime::from_hms_opt(23, 59, 59)
-                    .ok_or_else(|| clap::Error::raw(
-                        clap::error::ErrorKind::InvalidValue,
-                        "Failed to create today time 23:59:59"
-                    ))?;
-                    return Ok(dt.and_time(end_of_day));
-                }
-                
-                // Try parsing as full timestamp (YYYYmmDDHHMMSS)
-                if let Ok(dt) = NaiveDateTime::parse_from_str(value_str, DATETIME_FORMAT) {
-                    return Ok(dt);
-                }
-
                 // Try parsing as unix timestamp
-                if let Ok(time_stamp) = value_str.parse::<i64>() {
-                    if let Some(dt) = DateTime::from_timestamp_millis(time_stamp) {
-                        return Ok(dt.naive_local())
-                    }
-
+                if let Ok(time_stamp) = value_str.parse::<i64>()
+                    && DateTime::from_timestamp_millis(time_stamp).is_some()
+                {
+                    return Ok(TargetDate::Timestamp(time_stamp))
                 }
                 
-                // Both formats failed
+                // No format matched
                 Err(clap::Error::raw(
                     clap::error::ErrorKind::InvalidValue,
                     HELP_MSG
@@ -173,6 +624,73 @@ impl TypedValueParser for TimestampParser {
     }
 }
 
+/// Reads the date of `YYYYmmDD` digits, telling which part does not exist
+/// 
+/// # Arguments
+/// * `value` - At least eight ASCII digits, the date in the first eight
+/// 
+/// # Returns
+/// * `Result<NaiveDate, String>` - The date, or an error naming the month
+///   or the day out of range, e.g. for February 30
+fn calendar_date(value: &str) -> Result<NaiveDate, String> {
+    let number = |range: std::ops::Range<usize>| value[range].parse::<u32>().unwrap_or_default();
+    let (year, month, day) = (number(0..4) as i32, number(4..6), number(6..8));
+    if !(1..=12).contains(&month) {
+        return Err(format!("Invalid date '{}': month {:02} out of range (01-12)", value, month));
+    }
+    NaiveDate::from_ymd_opt(year, month, day).ok_or_else(|| {
+        format!("Invalid date '{}': day {:02} out of range for month {:04}-{:02}", value, day, year, month)
+    })
+}
+
+/// Reads the date and time of `YYYYmmDDHHMMSS` digits, telling which part does not exist
+/// 
+/// # Arguments
+/// * `value` - Fourteen ASCII digits
+/// 
+/// # Returns
+/// * `Result<NaiveDateTime, String>` - The wall-clock time, or an error
+///   naming the part out of range, e.g. for hour 25
+fn calendar_datetime(value: &str) -> Result<NaiveDateTime, String> {
+    let date = calendar_date(value)?;
+    let number = |range: std::ops::Range<usize>| value[range].parse::<u32>().unwrap_or_default();
+    let (hour, minute, second) = (number(8..10), number(10..12), number(12..14));
+    NaiveTime::from_hms_opt(hour, minute, second)
+        .map(|time| date.and_time(time))
+        .ok_or_else(|| format!(
+            "Invalid time '{}': {:02}:{:02}:{:02} is not a time of day (00:00:00 to 23:59:59)",
+            value, hour, minute, second
+        ))
+}
+
+/// Parses a date argument in `YYYYmmDD` format
+/// 
+/// # Arguments
+/// * `value` - String value from command line
+/// 
+/// # Returns
+/// * `Result<NaiveDate, String>` - Parsed date or error message
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    if value.len() == 8 && value.bytes().all(|b| b.is_ascii_digit()) {
+        return calendar_date(value);
+    }
+    Err(format!("Date format must be \"YYYYmmDD\", got '{}'", value))
+}
+
+/// Parses a month argument in `YYYYmm` format
+/// 
+/// # Arguments
+/// * `value` - String value from command line
+/// 
+/// # Returns
+/// * `Result<NaiveDate, String>` - First day of the month or error message
+fn parse_month(value: &str) -> Result<NaiveDate, String> {
+    Some(value)
+        .filter(|month| month.len() == 6 && month.bytes().all(|b| b.is_ascii_digit()))
+        .and_then(|month| NaiveDate::parse_from_str(&format!("{}01", month), DATE_FORMAT).ok())
+        .ok_or_else(|| format!("Month format must be \"YYYYmm\", got '{}'", value))
+}
+
 /// Custom parser for configuration file loading
 #[derive(Clone)]
 struct ConfParser;
@@ -201,32 +719,7 @@ impl TypedValueParser for ConfParser {
         let Some(file_path) = value.to_str() else {
             return Err(clap::Error::new(clap::error::ErrorKind::DisplayHelp));
         };
-        
-        // Open configuration file
-        let mut file = File::open(file_path).map_err(|e| {
-            let error_msg = match e.kind() {
-                std::io::ErrorKind::NotFound => format!("Configuration file '{}' not found", file_path),
-                std::io::ErrorKind::PermissionDenied => format!("Permission denied for '{}'", file_path),
-                _ => format!("Cannot access configuration file '{}': {}", file_path, e),
-            };
-            clap::Error::raw(clap::error::ErrorKind::InvalidValue, error_msg)
-        })?;
-        
-        // Read file contents
-        let mut config_content = String::new();
-        file.read_to_string(&mut config_content).map_err(|e| {
-            clap::Error::raw(
-                clap::error::ErrorKind::InvalidValue,
-                format!("Failed to read configuration file '{}': {}", file_path, e)
-            )
-        })?;
-        
-        // Parse TOML configuration
-        toml::from_str(&config_content).map_err(|e| {
-            clap::Error::raw(
-                clap::error::ErrorKind::InvalidValue,
-                format!("Invalid configuration in '{}': {}", file_path, e)
-            )
-        })
+
+        Conf::load(file_path).map_err(|e| clap::Error::raw(clap::error::ErrorKind::InvalidValue, e))
     }
 }
\ No newline at end of file
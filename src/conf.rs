@@ -22,9 +22,13 @@
 //! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 //! SOFTWARE.
 
-use std::collections::HashSet;
+use std::{collections::{HashMap, HashSet}, fmt, fs, str::FromStr, path::{Path, PathBuf}, time::Duration};
 
-use serde::{de::{Error, Visitor}, Deserialize};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{de::{Error, Visitor}, Deserialize, Serialize};
+
+use crate::{decision::RestKind, source::STDIN_SOURCE};
 
 /// Main configuration structure for the application.
 /// 
@@ -37,18 +41,172 @@ pub struct Conf {
     /// Calendar configuration for external data sources
     calendar: Option<Calnedar>,
     /// Prediction rules and patterns
-    predict: Predict    
+    predict: Predict,
+    /// HTTP settings for remote calendar sources
+    #[serde(default)]
+    http: Http,
+    /// Cache settings for remote calendar sources
+    cache: Option<Cache>,
+    /// Settings of the HTTP server mode
+    #[serde(default)]
+    serve: Serve,
+    /// Directory of the loaded configuration file, relative sources resolve against it
+    #[serde(skip)]
+    conf_dir: Option<PathBuf>,
+    /// Absolute paths of the loaded configuration files, in merge order
+    #[serde(skip)]
+    conf_files: Vec<PathBuf>,
+}
+
+/// A problem found by [`Conf::validate`], located by its configuration key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfError {
+    /// Dotted path of the offending key, e.g. `predict.rest`
+    key: String,
+    /// Description of the problem
+    message: String,
+}
+
+impl ConfError {
+    fn new(key: &str, message: impl Into<String>) -> Self {
+        Self { key: key.to_string(), message: message.into() }
+    }
+
+    /// Returns the dotted path of the offending key, e.g. `predict.rest`
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the description of the problem
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ConfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
 }
 
 /// Basic configuration settings.
 /// 
 /// Contains fundamental settings like workday definitions.
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Clone, Default)]
+#[serde(try_from = "BaseFile")]
 struct Base {
     /// Set of workdays represented as numbers 1-7 (Monday=1 through Sunday=7)
-    /// Deserialized from string formats like "1-5", "1,3,5", or "1,3-5"
-    #[serde(deserialize_with = "deserialize_workday")]
-    workday: HashSet<i8>,
+    /// Read from string formats like "1-5", "1,3,5", or "1,3-5"
+    workday: Option<HashSet<i8>>,
+    /// Whether no work days were asked for on purpose, with "none" or in code
+    no_workdays: bool,
+    /// Numbering the day numbers of `workday` strings were written in
+    week_numbering: WeekNumbering,
+    /// IANA timezone for floating calendar times, overriding X-WR-TIMEZONE
+    timezone: Option<Tz>,
+    /// Number of days searched by work day arithmetic before giving up
+    horizon_days: Option<u32>,
+    /// Working hours by day number (1-7), from "09:00-18:00" or a table keyed
+    /// by workday strings like { "1-5" = "09:00-18:00", "6" = "10:00-14:00" }
+    work_hours: Option<HashMap<i8, WorkHours>>,
+}
+
+/// The `[base]` table as written, before its workday strings are read.
+/// 
+/// Day numbers depend on `week_numbering`, which may come after them.
+#[derive(Deserialize)]
+struct BaseFile {
+    /// Workday string like "1-5", "1,3,5", or "1,3-5"
+    workday: Option<String>,
+    /// Numbering of the days in `workday` and the keys of `work_hours`
+    #[serde(default)]
+    week_numbering: WeekNumbering,
+    /// IANA timezone for floating calendar times, overriding X-WR-TIMEZONE
+    timezone: Option<Tz>,
+    /// Number of days searched by work day arithmetic before giving up
+    horizon_days: Option<u32>,
+    /// Working hours keyed by workday strings, in the order written
+    #[serde(default, deserialize_with = "deserialize_work_hours")]
+    work_hours: Option<Vec<(String, WorkHours)>>,
+}
+
+impl TryFrom<BaseFile> for Base {
+    type Error = String;
+
+    /// Reads the workday strings of the table with its week numbering.
+    /// 
+    /// # Note
+    /// * Days listed under several `work_hours` keys take the hours of the last one
+    fn try_from(file: BaseFile) -> Result<Self, Self::Error> {
+        let visitor = WorkDayVisitor(file.week_numbering);
+        let days = |workday: &str| {
+            visitor
+                .visit_str::<serde::de::value::Error>(workday)
+                .map_err(|e| e.to_string())
+        };
+        let workday = file.workday.as_deref().map(days).transpose()?;
+        let no_workdays = file.workday.as_deref().is_some_and(|workday| workday.trim().eq_ignore_ascii_case("none"));
+        let work_hours = match file.work_hours {
+            Some(by_days) => {
+                let mut hours_by_day = HashMap::new();
+                for (workday, hours) in by_days {
+                    for day in days(&workday)? {
+                        hours_by_day.insert(day, hours);
+                    }
+                }
+                Some(hours_by_day)
+            },
+            None => None,
+        };
+        Ok(Base {
+            workday,
+            no_workdays,
+            week_numbering: file.week_numbering,
+            timezone: file.timezone,
+            horizon_days: file.horizon_days,
+            work_hours,
+        })
+    }
+}
+
+/// Numbering of the days in `workday` strings.
+/// 
+/// Serialized as `iso` and `sunday0`, the names used in the configuration file.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum WeekNumbering {
+    /// ISO 8601: 1 = Monday through 7 = Sunday (default)
+    #[default]
+    Iso,
+    /// As in cron: 0 = Sunday, 1 = Monday through 6 = Saturday
+    Sunday0,
+}
+
+impl WeekNumbering {
+    /// Returns the first and the last day number of a week
+    fn bounds(self) -> (i8, i8) {
+        match self {
+            WeekNumbering::Iso => (1, 7),
+            WeekNumbering::Sunday0 => (0, 6),
+        }
+    }
+
+    /// Returns the number of a weekday
+    fn number(self, weekday: Weekday) -> i8 {
+        match self {
+            WeekNumbering::Iso => weekday.number_from_monday() as i8,
+            WeekNumbering::Sunday0 => weekday.num_days_from_sunday() as i8,
+        }
+    }
+
+    /// Converts a valid day number to its ISO 8601 number
+    fn to_iso(self, day: i8) -> i8 {
+        match (self, day) {
+            (WeekNumbering::Sunday0, 0) => 7,
+            _ => day,
+        }
+    }
 }
 
 /// Prediction configuration for work schedule forecasting.
@@ -60,6 +218,25 @@ pub struct Predict {
     work: Vec<String>,
     /// Patterns used to identify rest days in schedule prediction
     rest: Vec<String>,
+    /// Rest patterns, or rest categories, marking public holidays
+    rest_public: Option<Vec<String>>,
+    /// Rest patterns, or rest categories, marking personal leave
+    rest_personal: Option<Vec<String>>,
+    /// Event fields matched against the patterns, in order of precedence
+    match_fields: Option<Vec<MatchField>>,
+    /// Event categories identifying work days, matched exactly (case-insensitive)
+    work_categories: Option<Vec<String>>,
+    /// Event categories identifying rest days, matched exactly (case-insensitive)
+    rest_categories: Option<Vec<String>>,
+    /// Event property holding an explicit WORK/REST classification
+    explicit_property: Option<String>,
+    /// Whether a day no calendar event classifies is unknown rather than
+    /// decided by the configured work days
+    #[serde(default)]
+    require_calendar: bool,
+    /// Handling of a day decided by an event matching both work and rest
+    #[serde(default)]
+    on_conflict: OnConflict,
     /// Priority rule for resolving conflicts between work and rest predictions
     priority: Priority
 }
@@ -67,195 +244,2189 @@ pub struct Predict {
 /// Priority rules for resolving prediction conflicts.
 /// 
 /// Determines which prediction takes precedence when conflicts arise.
-#[derive(Deserialize, Clone)]
+/// Serialized and displayed as `WorkOverRest`, `RestOverWork`,
+/// `KeepCurrent` and `UseLatest`, the names used in the configuration file.
+/// These names are stable. Parsing and deserialization also accept the
+/// snake_case and kebab-case spellings, in any case; other names are
+/// rejected with the list of accepted spellings.
+/// 
+/// # Examples
+/// ```
+/// use choliday::Priority;
+/// 
+/// for priority in Priority::ALL {
+///     for spelling in priority.spellings() {
+///         assert_eq!(spelling.parse::<Priority>(), Ok(priority));
+///         assert_eq!(spelling.to_uppercase().parse::<Priority>(), Ok(priority));
+///     }
+/// }
+/// assert_eq!("work_over_rest".parse(), Ok(Priority::WorkOverRest));
+/// assert_eq!("Rest-Over-Work".parse(), Ok(Priority::RestOverWork));
+/// assert_eq!("keepcurrent".parse(), Ok(Priority::KeepCurrent));
+/// 
+/// let err = "work_over_reset".parse::<Priority>().unwrap_err();
+/// assert!(err.starts_with("Unknown priority 'work_over_reset', expected one of: WorkOverRest, work_over_rest, work-over-rest, "));
+/// ```
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
 pub enum Priority {
     /// Work predictions override rest predictions
+    #[serde(rename = "WorkOverRest")]
     WorkOverRest,
     /// Rest predictions override work predictions
+    #[serde(rename = "RestOverWork")]
     RestOverWork,
-    /// Keep the current state without change
+    /// Use the earliest-starting matching event
+    #[serde(rename = "KeepCurrent")]
     KeepCurrent,
-    /// Use the most recent prediction
+    /// Use the most recently authored matching event
+    /// (LAST-MODIFIED, then DTSTAMP, then SEQUENCE)
+    #[serde(rename = "UseLatest")]
     UseLatest
 }
 
-/// Calendar configuration for external data sources.
-/// 
-/// Allows integration with external calendar systems or data sources.
-#[derive(Deserialize, Clone)]
-pub struct Calnedar {
-    /// List of calendar data source identifiers or URLs
-    source: Option<Vec<String>>
-}
+impl Priority {
+    /// Every priority rule, in declaration order
+    pub const ALL: [Priority; 4] = [Priority::WorkOverRest, Priority::RestOverWork, Priority::KeepCurrent, Priority::UseLatest];
 
-impl Conf {
-    /// Returns the list of calendar data sources if configured.
+    /// Returns the stable name of the rule, as in the configuration file
+    pub fn as_str(&self) -> &'static str {
+        self.spellings()[0]
+    }
+
+    /// Returns every accepted spelling of the rule, the stable name first,
+    /// then its snake_case and kebab-case forms
     /// 
-    /// # Returns
-    /// - `Some(&[String])`: Reference to calendar source identifiers
-    /// - `None`: No calendar sources configured
-    pub fn get_describe_calendar(&self) -> Option<&[String]> {
-        if let Some(cal) = &self.calendar {
-            return cal.source.as_deref();
+    /// # Note
+    /// * Parsing compares them ignoring case
+    pub fn spellings(&self) -> [&'static str; 3] {
+        match self {
+            Priority::WorkOverRest => ["WorkOverRest", "work_over_rest", "work-over-rest"],
+            Priority::RestOverWork => ["RestOverWork", "rest_over_work", "rest-over-work"],
+            Priority::KeepCurrent => ["KeepCurrent", "keep_current", "keep-current"],
+            Priority::UseLatest => ["UseLatest", "use_latest", "use-latest"],
         }
-        None
     }
+}
 
-    /// Returns the priority rule for prediction conflict resolution.
+impl fmt::Display for Priority {
+    /// Writes the stable name of the rule
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Priority {
+    type Err = String;
+
+    /// Parses any accepted spelling of a rule, ignoring case
     /// 
     /// # Returns
-    /// - Reference to the configured priority rule
-    pub fn priority(&self) -> &Priority {
-        &self.predict.priority
+    /// * `Err(String)` listing every accepted spelling for unknown names
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Priority::ALL
+            .into_iter()
+            .find(|priority| priority.spellings().iter().any(|spelling| spelling.eq_ignore_ascii_case(s.trim())))
+            .ok_or_else(|| format!(
+                "Unknown priority '{}', expected one of: {}",
+                s,
+                Priority::ALL.iter().flat_map(Priority::spellings).collect::<Vec<_>>().join(", ")
+            ))
     }
+}
 
-    /// Returns the work day prediction patterns.
+impl<'de> Deserialize<'de> for Priority {
+    /// Deserializes any spelling [`FromStr`] accepts
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+    }
+}
+
+/// Handling of a day decided by an event matching both work and rest.
+/// 
+/// Written `priority` and `error` in the configuration file and on the command line.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OnConflict {
+    /// The priority rule decides, as for events disagreeing with each other
+    #[default]
+    Priority,
+    /// The day is left unresolved, as a `ConflictDay`
+    Error,
+}
+
+impl FromStr for OnConflict {
+    type Err = String;
+
+    /// Parses `priority` or `error`
     /// 
     /// # Returns
-    /// - Reference to vector of work day patterns
-    pub fn predict_work(&self) -> &Vec<String>{
-        self.predict.work.as_ref()
+    /// * `Err(String)` naming the accepted values for unknown names
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "priority" => Ok(OnConflict::Priority),
+            "error" => Ok(OnConflict::Error),
+            _ => Err(format!("Unknown conflict handling '{}', expected one of: priority, error", s)),
+        }
     }
+}
 
-    /// Returns the rest day prediction patterns.
+/// Event fields that work/rest patterns can be matched against.
+#[derive(Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchField {
+    /// Event title (SUMMARY)
+    Summary,
+    /// Event description (DESCRIPTION)
+    Description,
+    /// Event location (LOCATION)
+    Location,
+}
+
+impl MatchField {
+    /// Returns the name of the field, as in `predict.match_fields`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchField::Summary => "summary",
+            MatchField::Description => "description",
+            MatchField::Location => "location",
+        }
+    }
+}
+
+/// Property read when `predict.explicit_property` is not configured
+const DEFAULT_EXPLICIT_PROPERTY: &str = "X-CHOLIDAY-TYPE";
+
+/// Fields matched when `predict.match_fields` is not configured
+const DEFAULT_MATCH_FIELDS: &[MatchField] = &[MatchField::Summary, MatchField::Description];
+
+impl Nager {
+    /// Returns the country code, upper-cased
+    pub fn country(&self) -> String {
+        self.country.trim().to_uppercase()
+    }
+
+    /// Returns the subdivision code, upper-cased, if configured
+    pub fn subdivision(&self) -> Option<String> {
+        self.subdivision.as_deref().map(|s| s.trim().to_uppercase())
+    }
+
+    /// Returns the base URL of the API (default `https://date.nager.at`)
+    pub fn api(&self) -> &str {
+        self.api.as_deref().unwrap_or(DEFAULT_NAGER_API).trim_end_matches('/')
+    }
+}
+
+impl CalDav {
+    /// Returns the URL of the calendar collection
+    pub fn url(&self) -> &str {
+        &self.caldav
+    }
+
+    /// Returns the credentials for HTTP basic authentication
     /// 
     /// # Returns
-    /// - Reference to vector of rest day patterns
-    pub fn predict_rest(&self) -> &Vec<String> {
-        self.predict.rest.as_ref()
+    /// - `Ok(Some((username, password)))`: Credentials, the password read from
+    ///   `password_env` (empty when `password_env` is not configured)
+    /// - `Ok(None)`: No `username` configured
+    /// - `Err(String)`: The `password_env` variable is not set
+    pub fn credentials(&self) -> Result<Option<(String, String)>, String> {
+        let Some(username) = &self.username else {
+            return Ok(None);
+        };
+        let password = match &self.password_env {
+            Some(var) => std::env::var(var)
+                .map_err(|_| format!("Environment variable '{}' with the CalDAV password is not set", var))?,
+            None => String::new(),
+        };
+        Ok(Some((username.clone(), password)))
     }
+}
 
-    /// Returns the set of configured work days.
+impl Exec {
+    /// Returns the program to run, the first item of `exec`
+    pub fn program(&self) -> &str {
+        self.exec.first().map_or("", String::as_str)
+    }
+
+    /// Returns the arguments passed to the program
+    pub fn args(&self) -> &[String] {
+        self.exec.get(1..).unwrap_or(&[])
+    }
+
+    /// Returns the time the command may run (default 30s)
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.unwrap_or(30))
+    }
+}
+
+impl Google {
+    /// Returns the calendar ID
+    pub fn calendar_id(&self) -> &str {
+        &self.calendar_id
+    }
+
+    /// Returns the path to the service account key file, if configured
+    pub fn credentials(&self) -> Option<&str> {
+        self.credentials.as_deref()
+    }
+
+    /// Returns the API key for public calendars
     /// 
     /// # Returns
-    /// - `Some(HashSet<i8>)`: Set of work days (1-7)
-    /// - `None`: No work day configuration available
-    pub fn work_day(&self) -> Option<HashSet<i8>>{
-        self.base.clone().map(|base| base.workday)
+    /// - `Ok(Some(key))`: Key read from `api_key_env`
+    /// - `Ok(None)`: No `api_key_env` configured
+    /// - `Err(String)`: The `api_key_env` variable is not set
+    pub fn api_key(&self) -> Result<Option<String>, String> {
+        self.api_key_env
+            .as_ref()
+            .map(|var| {
+                std::env::var(var)
+                    .map_err(|_| format!("Environment variable '{}' with the Google API key is not set", var))
+            })
+            .transpose()
+    }
+
+    /// Returns the base URL of the API (default `https://www.googleapis.com`)
+    pub fn api(&self) -> &str {
+        self.api.as_deref().unwrap_or(DEFAULT_GOOGLE_API).trim_end_matches('/')
     }
 }
 
-/// Deserializes workday string into a HashSet of day numbers.
-/// 
-/// # Arguments
-/// * `deserializer` - Serde deserializer instance
-/// 
-/// # Returns
-/// * `Result<HashSet<i8>, D::Error>` - Set of work days or deserialization error
+/// Google Calendar API endpoint used when `api` is not configured
+const DEFAULT_GOOGLE_API: &str = "https://www.googleapis.com";
+
+/// Nager.Date instance used when `api` is not configured
+const DEFAULT_NAGER_API: &str = "https://date.nager.at";
+
+/// Days searched by work day arithmetic when `horizon_days` is not configured
+const DEFAULT_HORIZON_DAYS: u32 = 366;
+
+/// Working hours of days `work_hours` does not cover, 09:00 to 17:00
+const DEFAULT_WORK_HOURS: WorkHours = WorkHours {
+    start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+    end: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+};
+
+/// Columns of CSV sources when `columns` is not configured
+const DEFAULT_CSV_COLUMNS: &[&str] = &["date", "type", "name"];
+
+/// HTTP settings for fetching remote calendars.
 /// 
-/// # Supported Formats
-/// * Single days: "1", "2", "3"
-/// * Day ranges: "1-5"
-/// * Mixed formats: "1,3,5" or "1,3-5"
-fn deserialize_workday<'de, D>(deserializer: D) -> Result<HashSet<i8>, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    deserializer.deserialize_str(WorkDayVisitor)
+/// Every field is optional; the getters apply the defaults.
+#[derive(Deserialize, Clone, Default)]
+pub struct Http {
+    /// Timeout for a single request, including the body, in seconds
+    timeout_secs: Option<u64>,
+    /// Timeout for establishing a connection, in seconds
+    connect_timeout_secs: Option<u64>,
+    /// Number of retries after the first attempt
+    retries: Option<u32>,
+    /// Base delay of the exponential backoff between retries, in milliseconds
+    backoff_ms: Option<u64>,
+    /// Upper bound for fetching one source including all retries, in seconds
+    max_time_secs: Option<u64>,
+    /// Upper bound for the total delay between retries of one request, in seconds
+    retry_budget_secs: Option<u64>,
+    /// Scheme that `webcal://` URLs are fetched with
+    #[serde(default)]
+    webcal: WebcalScheme,
+    /// Maximum number of redirects followed per request
+    max_redirects: Option<usize>,
+    /// Maximum size of a response body, in bytes
+    max_body_bytes: Option<u64>,
+    /// Refuse remote sources and redirect targets that are not HTTPS
+    #[serde(default)]
+    require_https: bool,
 }
 
-/// Error message format for workday deserialization errors.
-const ERR_FMT: &str = "a workday string like '1-5' or '1,3,5' or '1,3-5' (numbers 1-7 only)";
+/// Scheme substituted for `webcal://` URLs.
+#[derive(Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebcalScheme {
+    /// Fetch over `https://`
+    #[default]
+    Https,
+    /// Fetch over plain `http://`
+    Http,
+}
 
-/// Visitor for deserializing workday strings into HashSet<i8>.
-struct WorkDayVisitor;
+impl Http {
+    /// Returns the timeout for a single request (default 30s)
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.unwrap_or(30))
+    }
 
-impl<'a> Visitor<'a> for WorkDayVisitor {
-    type Value = HashSet<i8>;
+    /// Returns the connect timeout (default 10s)
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.connect_timeout_secs.unwrap_or(10))
+    }
 
-    /// Describes the expected format for error messages.
-    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(formatter, "{}", &ERR_FMT)
+    /// Returns the number of retries after the first attempt (default 2)
+    pub fn retries(&self) -> u32 {
+        self.retries.unwrap_or(2)
     }
 
-    /// Processes a string representation of workdays.
+    /// Returns the base backoff delay between retries (default 500ms)
+    pub fn backoff(&self) -> Duration {
+        Duration::from_millis(self.backoff_ms.unwrap_or(500))
+    }
+
+    /// Returns the upper bound for fetching one source (default 120s)
+    pub fn max_time(&self) -> Duration {
+        Duration::from_secs(self.max_time_secs.unwrap_or(120))
+    }
+
+    /// Returns the upper bound for the delays between retries of one request (default 60s)
+    pub fn retry_budget(&self) -> Duration {
+        Duration::from_secs(self.retry_budget_secs.unwrap_or(60))
+    }
+
+    /// Returns the scheme `webcal://` URLs are fetched with (default https)
+    pub fn webcal(&self) -> WebcalScheme {
+        self.webcal
+    }
+
+    /// Returns the maximum number of redirects followed per request (default 5)
+    pub fn max_redirects(&self) -> usize {
+        self.max_redirects.unwrap_or(5)
+    }
+
+    /// Returns the maximum size of a response body in bytes (default 10 MiB)
+    pub fn max_body_bytes(&self) -> u64 {
+        self.max_body_bytes.unwrap_or(10 * 1024 * 1024)
+    }
+
+    /// Returns whether only HTTPS is allowed for remote sources (default false)
+    pub fn require_https(&self) -> bool {
+        self.require_https
+    }
+}
+
+/// Settings of the HTTP server mode.
+/// 
+/// Every field is optional; the getters apply the defaults.
+#[derive(Deserialize, Clone, Default)]
+pub struct Serve {
+    /// Interval between background refreshes of all sources, from strings
+    /// like "90s", "30m", "2h" or "1d"
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    refresh: Option<Duration>,
+}
+
+impl Serve {
+    /// Returns the interval between background refreshes of all sources (default 30m)
+    pub fn refresh(&self) -> Duration {
+        self.refresh.unwrap_or(Duration::from_secs(30 * 60))
+    }
+}
+
+/// Reading of a wall-clock time that occurs twice when clocks fall back.
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum DstAmbiguous {
+    /// The first occurrence, before the clocks fall back
+    Earlier,
+    /// The second occurrence, after the clocks fall back
+    Later,
+}
+
+/// Reading of a wall-clock time skipped when clocks spring forward.
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum DstGap {
+    /// The first valid instant after the gap
+    #[default]
+    ShiftForward,
+    /// Reject the value as invalid
+    Error,
+}
+
+/// Zone target dates are given in and all-day events are read in.
+/// 
+/// All-day events have no zone of their own (RFC 5545), so a day's holiday
+/// covers that civil day in this zone.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TargetZone {
+    /// Zone set through `base.timezone`
+    Configured(Tz),
+    /// Zone of the system the program runs on
+    System,
+}
+
+impl TargetZone {
+    /// Converts a wall-clock time in this zone to a Unix timestamp
     /// 
     /// # Arguments
-    /// * `v` - String containing workday specification
+    /// * `wall` - Wall-clock time
     /// 
     /// # Returns
-    /// * `Result<HashSet<i8>, E>` - Parsed workday set or error
+    /// * Timestamp in milliseconds; the earlier instant of an ambiguous time,
+    ///   and for a time skipped by a spring-forward gap the instant an hour later
+    pub fn to_instant(&self, wall: NaiveDateTime) -> i64 {
+        let resolve = |wall: NaiveDateTime| match self {
+            TargetZone::Configured(tz) => tz.from_local_datetime(&wall).earliest().map(|dt| dt.timestamp_millis()),
+            TargetZone::System => Local.from_local_datetime(&wall).earliest().map(|dt| dt.timestamp_millis()),
+        };
+        resolve(wall)
+            .or_else(|| resolve(wall + TimeDelta::hours(1)))
+            .unwrap_or_else(|| wall.and_utc().timestamp_millis())
+    }
+
+    /// Converts a Unix timestamp to the wall-clock time in this zone
+    /// 
+    /// # Arguments
+    /// * `timestamp` - Timestamp in milliseconds
+    pub fn to_wall_clock(&self, timestamp: i64) -> NaiveDateTime {
+        self.to_zoned(timestamp).naive_local()
+    }
+
+    /// Converts a Unix timestamp to the time in this zone, with the offset in effect then
+    /// 
+    /// # Arguments
+    /// * `timestamp` - Timestamp in milliseconds
     /// 
     /// # Examples
-    /// * "1-5" → {1, 2, 3, 4, 5}
-    /// * "1,3,5" → {1, 3, 5}
-    /// * "1,3-5" → {1, 3, 4, 5}
-    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
-    where
-        E: serde::de::Error, 
-    {
-        match v.len() {
-            0 => Err(Error::invalid_length(0, &ERR_FMT)),
-            _ => {
-                let mut workday_set: HashSet<i8> = HashSet::new();
-                
-                // Split by comma to handle multiple day specifications
-                for day_spec in v.split(",") {   
-                    match day_spec.parse::<i8>() {
-                        // Single day number
-                        Ok(day) => {
-                            let validated_day = weekday_check(day)?;
-                            workday_set.insert(validated_day);
-                        },
-                        // Could be a range or invalid format
-                        Err(_) => {
-                            let day_range: Vec<&str> = day_spec.split("-").collect();
-                            
-                            // Must be exactly two parts for a range
-                            if day_range.len() != 2 {
-                                return Err(Error::invalid_type(
-                                    serde::de::Unexpected::Str(day_spec), 
-                                    &ERR_FMT
-                                ));
-                            }
-                            
-                            // Parse start and end of range
-                            match day_range[0].parse::<i8>()
-                                .and_then(|a| day_range[1].parse::<i8>().map(|b| (a, b))) 
-                            {
-                                Ok((start, end)) => {
-                                    // Validate both ends of the range
-                                    weekday_check(start)?;
-                                    weekday_check(end)?;
-                                    
-                                    // Add all days in the range (inclusive)
-                                    workday_set.extend(start.min(end)..=start.max(end));
-                                },
-                                Err(_) => {
-                                    return Err(Error::invalid_type(
-                                        serde::de::Unexpected::Str(day_spec), 
-                                        &ERR_FMT
-                                    ));
-                                }
-                            }
-                        }
-                    }
-                }
-                Ok(workday_set)
-            }
+    /// ```
+    /// use choliday::TargetZone;
+    /// 
+    /// let zone = TargetZone::Configured(chrono_tz::Asia::Shanghai);
+    /// assert_eq!(zone.to_zoned(1_759_305_600_000).to_rfc3339(), "2025-10-01T16:00:00+08:00");
+    /// ```
+    pub fn to_zoned(&self, timestamp: i64) -> DateTime<FixedOffset> {
+        let utc = DateTime::from_timestamp_millis(timestamp).unwrap_or_default();
+        match self {
+            TargetZone::Configured(tz) => utc.with_timezone(tz).fixed_offset(),
+            TargetZone::System => utc.with_timezone(&Local).fixed_offset(),
+        }
+    }
+
+    /// Returns the civil date in this zone at an instant
+    /// 
+    /// # Arguments
+    /// * `now` - Instant to read the date at, usually `Utc::now()`
+    /// 
+    /// # Note
+    /// * The date the CLI's `today` stands for, which differs from the host's
+    ///   date when `base.timezone` is not the system zone
+    /// 
+    /// # Examples
+    /// ```
+    /// use chrono::{NaiveDate, TimeZone, Utc};
+    /// use choliday::TargetZone;
+    /// 
+    /// // 01:00 UTC is already 09:00 in Shanghai, but still the evening before in Los Angeles
+    /// let now = Utc.with_ymd_and_hms(2025, 10, 1, 1, 0, 0).unwrap();
+    /// assert_eq!(TargetZone::Configured(chrono_tz::Asia::Shanghai).today(now), NaiveDate::from_ymd_opt(2025, 10, 1).unwrap());
+    /// assert_eq!(TargetZone::Configured(chrono_tz::America::Los_Angeles).today(now), NaiveDate::from_ymd_opt(2025, 9, 30).unwrap());
+    /// ```
+    pub fn today(&self, now: DateTime<Utc>) -> NaiveDate {
+        self.to_wall_clock(now.timestamp_millis()).date()
+    }
+
+    /// Returns the IANA name of the zone, `None` for the system zone
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            TargetZone::Configured(tz) => Some(tz.name()),
+            TargetZone::System => None,
         }
     }
 }
 
-/// Validates that a day number is within the valid range (1-7).
+/// Working hours of a day.
 /// 
-/// # Arguments
-/// * `x` - Day number to validate
+/// An end not after the start marks an overnight shift running into the next day.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct WorkHours {
+    /// Start of the working hours
+    start: NaiveTime,
+    /// End of the working hours, on the next day for overnight shifts
+    end: NaiveTime,
+}
+
+impl WorkHours {
+    /// Creates working hours from `start` to `end`
+    /// 
+    /// # Arguments
+    /// * `start` - Start of the working hours
+    /// * `end` - End of the working hours; not after `start` for an overnight shift
+    pub fn new(start: NaiveTime, end: NaiveTime) -> Self {
+        WorkHours { start, end }
+    }
+
+    /// Returns the start of the working hours
+    pub fn start(&self) -> NaiveTime {
+        self.start
+    }
+
+    /// Returns the end of the working hours
+    pub fn end(&self) -> NaiveTime {
+        self.end
+    }
+
+    /// Returns whether the working hours run past midnight
+    pub fn is_overnight(&self) -> bool {
+        self.end <= self.start
+    }
+
+    /// Returns the shift starting on a date
+    /// 
+    /// # Arguments
+    /// * `date` - Day the shift starts on
+    /// 
+    /// # Returns
+    /// * `(start, end)` - Wall-clock bounds of the shift, end excluded
+    pub fn shift(&self, date: NaiveDate) -> (NaiveDateTime, NaiveDateTime) {
+        let end_date = if self.is_overnight() { date + TimeDelta::days(1) } else { date };
+        (date.and_time(self.start), end_date.and_time(self.end))
+    }
+}
+
+/// Cache settings for remote calendars.
+#[derive(Deserialize, Clone)]
+struct Cache {
+    /// Directory for cached calendar bodies
+    dir: Option<PathBuf>,
+    /// Storage of the cached calendars
+    #[serde(default)]
+    backend: CacheBackend,
+    /// Time a cached calendar is used without asking the server, from
+    /// strings like "90s", "30m", "2h" or "1d"
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    ttl: Option<Duration>,
+}
+
+/// Storage of cached remote calendars.
 /// 
-/// # Returns
-/// * `Result<i8, E>` - Validated day number or error
+/// Written `file` or `sqlite` in the configuration file.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheBackend {
+    /// A file per body and a TOML index in the cache directory
+    #[default]
+    File,
+    /// One SQLite database in the cache directory (`sqlite` feature)
+    Sqlite,
+}
+
+/// Calendar configuration for external data sources.
 /// 
-/// # Note
-/// * 1 = Monday, 7 = Sunday (ISO 8601 standard)
-fn weekday_check<E>(x: i8) -> Result<i8, E> 
-where
-    E: serde::de::Error,
-{
-    if x < 1 || x > 7 {
-        return Err(Error::invalid_value(
-            serde::de::Unexpected::Signed(x as i64), 
-            &"numbers 1-7 only"
-        ));
-    }
-    Ok(x)
-}
\ No newline at end of file
+/// Allows integration with external calendar systems or data sources.
+#[derive(Deserialize, Clone, Default)]
+pub struct Calnedar {
+    /// List of calendar data sources
+    source: Option<Vec<Source>>,
+    /// Minimum number of sources that must load for a definite answer
+    min_sources: Option<usize>,
+    /// Exclude events marked TRANSP:TRANSPARENT from classification
+    #[serde(default)]
+    ignore_transparent: bool,
+    /// Also classify days by the due dates of VTODO components
+    #[serde(default)]
+    include_todos: bool,
+    /// Recover events from malformed calendar data
+    #[serde(default)]
+    lenient: bool,
+    /// Repair events whose DTEND precedes DTSTART instead of skipping them
+    fix_inverted: Option<bool>,
+    /// Reading of ambiguous wall-clock times at fall-back transitions
+    dst_ambiguous: Option<DstAmbiguous>,
+    /// Reading of nonexistent wall-clock times at spring-forward transitions
+    #[serde(default)]
+    dst_gap: DstGap,
+}
+
+/// A calendar data source.
+/// 
+/// Configured either as a plain URL or path, as a table with options,
+/// or as a table naming a holiday API provider.
+#[derive(Deserialize, Clone)]
+#[serde(from = "SourceSpec")]
+pub struct Source {
+    /// Identifier in reports: the URL or path, or `<provider>:<id>` for APIs
+    name: String,
+    /// Where the calendar data comes from
+    location: Location,
+    /// Data format, detected from the response or file name when unset
+    format: Option<SourceFormat>,
+    /// Column names of CSV data
+    columns: Option<Vec<String>>,
+    /// Recover events from malformed data, overriding `calendar.lenient`
+    lenient: Option<bool>,
+}
+
+/// Where a calendar source's data comes from.
+#[derive(Clone)]
+pub enum Location {
+    /// URL or local path of calendar data
+    Url(String),
+    /// Nager.Date public holiday API
+    Nager(Nager),
+    /// CalDAV calendar collection
+    CalDav(CalDav),
+    /// Google Calendar API
+    Google(Google),
+    /// Standard output of an external command
+    Exec(Exec),
+}
+
+/// External command settings.
+/// 
+/// The command's standard output is read as the calendar data, in the
+/// source's `format` or detected from the data.
+/// 
+/// # Examples
+/// ```
+/// use chrono::NaiveDate;
+/// use choliday::{Choliday, Conf, Location, SourceStatus};
+/// 
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), String> {
+/// # #[cfg(unix)] {
+/// let conf = Conf::from_toml(r#"
+///     [calendar]
+///     source = [{ exec = ["printf", "2025-01-06,rest,Holiday\n"], format = "csv" }]
+///     [predict]
+///     work = []
+///     rest = ["Holiday"]
+///     priority = "RestOverWork"
+/// "#)?;
+/// let Location::Exec(exec) = conf.get_describe_calendar().unwrap()[0].location() else { unreachable!() };
+/// assert_eq!(exec.program(), "printf");
+/// 
+/// let date = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+/// let choliday = Choliday::builder(conf).date(date).build()?;
+/// assert!(!choliday.is_work_day().await?);
+/// 
+/// // A failing command is a failed source, reported with its standard error
+/// let conf = Conf::from_toml(r#"
+///     [calendar]
+///     source = [{ exec = ["sh", "-c", "echo not logged in >&2; exit 1"] }]
+///     [predict]
+///     work = []
+///     rest = ["Holiday"]
+///     priority = "RestOverWork"
+/// "#)?;
+/// let choliday = Choliday::builder(conf).date(date).build()?;
+/// let reports = choliday.refresh().await;
+/// assert!(matches!(reports[0].status(), SourceStatus::Failed(cause) if cause.contains("not logged in")));
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Deserialize, Clone)]
+pub struct Exec {
+    /// Program and arguments, run without a shell
+    exec: Vec<String>,
+    /// Time the command may run before it is killed, in seconds
+    timeout_secs: Option<u64>,
+}
+
+/// Google Calendar API settings.
+#[derive(Deserialize, Clone)]
+pub struct Google {
+    /// Calendar ID, e.g. `team@group.calendar.google.com`
+    calendar_id: String,
+    /// Path to a service account key file, for private calendars
+    credentials: Option<String>,
+    /// Environment variable holding an API key, for public calendars
+    api_key_env: Option<String>,
+    /// Base URL of the API, for testing against another endpoint
+    api: Option<String>,
+}
+
+/// CalDAV calendar collection settings.
+#[derive(Deserialize, Clone)]
+pub struct CalDav {
+    /// URL of the calendar collection
+    caldav: String,
+    /// User name for HTTP basic authentication
+    username: Option<String>,
+    /// Environment variable holding the password
+    password_env: Option<String>,
+}
+
+/// Nager.Date public holiday API settings.
+#[derive(Deserialize, Clone)]
+pub struct Nager {
+    /// ISO 3166-1 country code, e.g. `DE`
+    country: String,
+    /// ISO 3166-2 subdivision code, e.g. `DE-BY`
+    subdivision: Option<String>,
+    /// Base URL of the API, for self-hosted instances
+    api: Option<String>,
+}
+
+/// Calendar source as written in the configuration file.
+#[derive(Deserialize)]
+#[serde(
+    untagged,
+    expecting = "a URL or path, a table like { url = \"...\", format = \"ics\" | \"jcal\" | \"csv\" }, \
+                 a provider table like { provider = \"nager\", country = \"DE\" } \
+                 or { provider = \"google\", calendar_id = \"...\", credentials = \"...\" }, \
+                 a CalDAV table like { caldav = \"https://...\", username = \"...\", password_env = \"...\" }, \
+                 or a command table like { exec = [\"corp-cal\", \"dump\", \"--ics\"] }"
+)]
+enum SourceSpec {
+    /// Plain URL or path
+    Url(String),
+    /// Table with a URL and options
+    Table {
+        url: String,
+        format: Option<SourceFormat>,
+        columns: Option<Vec<String>>,
+        lenient: Option<bool>,
+    },
+    /// Table naming a holiday API provider
+    Provider(ProviderSpec),
+    /// Table naming a CalDAV calendar collection
+    CalDav(CalDav),
+    /// Table naming an external command
+    Exec {
+        #[serde(flatten)]
+        exec: Exec,
+        format: Option<SourceFormat>,
+        columns: Option<Vec<String>>,
+        lenient: Option<bool>,
+    },
+}
+
+/// Holiday API provider table, tagged by its `provider` key.
+#[derive(Deserialize)]
+#[serde(tag = "provider", rename_all = "lowercase")]
+enum ProviderSpec {
+    /// Nager.Date public holiday API
+    Nager(Nager),
+    /// Google Calendar API
+    Google(Google),
+}
+
+impl From<SourceSpec> for Source {
+    fn from(spec: SourceSpec) -> Self {
+        let (location, format, columns, lenient) = match spec {
+            SourceSpec::Url(url) => (Location::Url(url), None, None, None),
+            SourceSpec::Table { url, format, columns, lenient } => (Location::Url(url), format, columns, lenient),
+            SourceSpec::Provider(ProviderSpec::Nager(nager)) => (Location::Nager(nager), None, None, None),
+            SourceSpec::Provider(ProviderSpec::Google(google)) => (Location::Google(google), None, None, None),
+            SourceSpec::CalDav(caldav) => (Location::CalDav(caldav), None, None, None),
+            SourceSpec::Exec { exec, format, columns, lenient } => (Location::Exec(exec), format, columns, lenient),
+        };
+        let name = match &location {
+            Location::Url(url) => url.clone(),
+            Location::CalDav(caldav) => caldav.caldav.clone(),
+            Location::Nager(nager) => format!("nager:{}", nager.subdivision().unwrap_or_else(|| nager.country())),
+            Location::Google(google) => format!("google:{}", google.calendar_id),
+            Location::Exec(exec) => format!("exec:{}", exec.exec.join(" ")),
+        };
+        Source { name, location, format, columns, lenient }
+    }
+}
+
+/// Data format of a calendar source.
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceFormat {
+    /// iCalendar text (RFC 5545)
+    Ics,
+    /// jCal, the JSON form of iCalendar (RFC 7265)
+    Jcal,
+    /// CSV rows of dates, or a plain list of dates
+    Csv,
+}
+
+impl Source {
+    /// Returns the identifier of the source in reports
+    /// 
+    /// # Returns
+    /// - The URL or path of URL and CalDAV sources, `nager:<region>` for the
+    ///   holiday API, `google:<calendar_id>` for Google Calendar,
+    ///   `exec:<program> <args>` for external commands
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns where the calendar data comes from
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
+
+    /// Returns the configured data format, if any
+    pub fn format(&self) -> Option<SourceFormat> {
+        self.format
+    }
+
+    /// Returns the column names of CSV data (default `date`, `type`, `name`)
+    pub fn columns(&self) -> Vec<&str> {
+        match &self.columns {
+            Some(columns) => columns.iter().map(String::as_str).collect(),
+            None => DEFAULT_CSV_COLUMNS.to_vec(),
+        }
+    }
+
+    /// Returns whether malformed data of this source is recovered, if set
+    pub fn lenient(&self) -> Option<bool> {
+        self.lenient
+    }
+}
+
+impl Conf {
+    /// Loads the configuration from a TOML file.
+    /// 
+    /// # Arguments
+    /// * `path` - Path to the configuration file
+    /// 
+    /// # Returns
+    /// - `Ok(Conf)`: Configuration whose relative sources resolve against the file's directory
+    /// - `Err(String)`: The file cannot be read or is not a valid configuration
+    pub fn load(path: impl AsRef<Path>) -> Result<Conf, String> {
+        let path = path.as_ref();
+        let content = read_conf_file(path)?;
+
+        let mut conf = Conf::from_toml(&content)
+            .map_err(|e| format!("Invalid configuration in '{}': {}", path.display(), e))?;
+
+        // Relative calendar sources resolve against the configuration's directory
+        let path = canonical(path);
+        if let Some(dir) = path.parent() {
+            conf.set_conf_dir(dir.to_path_buf());
+        }
+        conf.conf_files = vec![path];
+        Ok(conf)
+    }
+
+    /// Loads several TOML files merged into one configuration.
+    /// 
+    /// # Arguments
+    /// * `paths` - Paths to the configuration files, later files taking precedence
+    /// 
+    /// # Returns
+    /// - `Ok(Conf)`: Configuration of the merged files, see [`Conf::merge_files`]
+    /// - `Err(String)`: A file cannot be read or parsed, or the merged result
+    ///   is not a valid configuration
+    /// 
+    /// # Note
+    /// * A single file loads exactly as with [`Conf::load`]; with several,
+    ///   [`Conf::conf_file`] is the last one
+    /// 
+    /// # Examples
+    /// ```
+    /// use choliday::Conf;
+    /// 
+    /// # fn main() -> Result<(), String> {
+    /// let temp = tempfile::tempdir().map_err(|e| e.to_string())?;
+    /// let dir = temp.path();
+    /// let shared = dir.join("shared.toml");
+    /// std::fs::write(&shared, "[base]\nworkday = \"1-5\"\n[predict]\nwork = [\"Workday\"]\nrest = [\"Holiday\"]\npriority = \"WorkOverRest\"\n")
+    ///     .map_err(|e| e.to_string())?;
+    /// let machine = dir.join("machine.toml");
+    /// std::fs::write(&machine, "[base]\nworkday = \"1-6\"\n[calendar]\nsource = [\"holidays.ics\"]\n[predict]\nrest = [\"Vacation\"]\n")
+    ///     .map_err(|e| e.to_string())?;
+    /// 
+    /// let conf = Conf::load_all(&[&shared, &machine])?;
+    /// // Lists are joined, scalars such as the work days come from the last file
+    /// assert_eq!(conf.predict_rest(), &["Holiday", "Vacation"]);
+    /// assert!(conf.work_day().is_some_and(|days| days.contains(&6)));
+    /// // Relative sources still resolve against their own file's directory
+    /// let source = conf.get_describe_calendar().unwrap()[0].name();
+    /// assert_eq!(std::path::Path::new(source), dir.canonicalize().unwrap().join("holidays.ics"));
+    /// 
+    /// // In the other order, the work days of the shared file win
+    /// let conf = Conf::load_all(&[&machine, &shared])?;
+    /// assert!(conf.work_day().is_some_and(|days| !days.contains(&6)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_all(paths: &[impl AsRef<Path>]) -> Result<Conf, String> {
+        if let [path] = paths {
+            return Conf::load(path);
+        }
+        let table = Conf::merge_files(paths)?;
+        let files = paths.iter().map(|path| canonical(path.as_ref())).collect::<Vec<_>>();
+        let names = files.iter().map(|path| format!("'{}'", path.display())).collect::<Vec<_>>().join(", ");
+        let mut conf: Conf = toml::Value::Table(table)
+            .try_into()
+            .map_err(|e| format!("Invalid configuration merged from {}: {}", names, e))?;
+        conf.check_sources().map_err(|e| format!("Invalid configuration merged from {}: {}", names, e))?;
+        if let Some(dir) = files.last().and_then(|path| path.parent()) {
+            conf.set_conf_dir(dir.to_path_buf());
+        }
+        conf.conf_files = files;
+        Ok(conf)
+    }
+
+    /// Merges TOML files into the table of one configuration.
+    /// 
+    /// # Arguments
+    /// * `paths` - Paths to the configuration files, later files taking precedence
+    /// 
+    /// # Returns
+    /// - `Ok(Table)`: The merged table, as `--show-effective-config` prints it
+    /// - `Err(String)`: A file cannot be read or is not valid TOML
+    /// 
+    /// # Note
+    /// * Files are parsed one by one and merged left to right: tables merge
+    ///   key by key, arrays (e.g. `calendar.source`, `predict.rest`) are
+    ///   joined with the items of later files appended unless already present,
+    ///   and any other value, e.g. `base.workday`, is replaced by the last file
+    ///   setting it
+    /// * Relative paths of local calendar sources and Google `credentials`
+    ///   are made absolute against the directory of the file naming them
+    pub fn merge_files(paths: &[impl AsRef<Path>]) -> Result<toml::Table, String> {
+        let mut merged = toml::Table::new();
+        for path in paths {
+            let path = path.as_ref();
+            let mut table: toml::Table = read_conf_file(path)?
+                .parse()
+                .map_err(|e| format!("Invalid configuration in '{}': {}", path.display(), e))?;
+            if let Some(dir) = canonical(path).parent() {
+                rebase_sources(&mut table, dir);
+            }
+            merge_tables(&mut merged, table);
+        }
+        Ok(merged)
+    }
+
+    /// Parses the configuration from TOML text.
+    /// 
+    /// # Arguments
+    /// * `content` - TOML text in the format of the configuration file
+    /// 
+    /// # Returns
+    /// - `Ok(Conf)`: Configuration with relative sources resolving against the working directory
+    /// - `Err(String)`: The text is not a valid configuration
+    pub fn from_toml(content: &str) -> Result<Conf, String> {
+        let conf: Conf = toml::from_str(content).map_err(|e| e.to_string())?;
+        conf.check_sources()?;
+        Ok(conf)
+    }
+
+    /// Starts a configuration built in code rather than read from a file.
+    /// 
+    /// # Arguments
+    /// * `priority` - Rule for resolving conflicts between work and rest predictions
+    pub fn builder(priority: Priority) -> ConfBuilder {
+        ConfBuilder::new(priority)
+    }
+
+    /// Checks constraints across calendar sources.
+    /// 
+    /// # Returns
+    /// - `Err(String)` if more than one source reads standard input,
+    ///   which can only be consumed once
+    fn check_sources(&self) -> Result<(), String> {
+        let stdin_sources = self.get_describe_calendar()
+            .unwrap_or(&[])
+            .iter()
+            .filter(|source| source.name() == STDIN_SOURCE)
+            .count();
+        if stdin_sources > 1 {
+            return Err(format!("only one calendar source may be '{}' (standard input)", STDIN_SOURCE));
+        }
+        let empty_exec = self.get_describe_calendar()
+            .unwrap_or(&[])
+            .iter()
+            .any(|source| matches!(source.location(), Location::Exec(exec) if exec.program().is_empty()));
+        if empty_exec {
+            return Err("the exec array of a calendar source must start with a program".to_string());
+        }
+        Ok(())
+    }
+
+    /// Checks the configuration for settings that parse but contradict each other.
+    /// 
+    /// # Returns
+    /// - `Ok(())` if no problems were found
+    /// - `Err(Vec<ConfError>)`: Every problem found, each with the key it concerns
+    /// 
+    /// # Note
+    /// * Reported: a pattern listed in both `predict.work` and `predict.rest`
+    ///   (or `rest_public`/`rest_personal`), a category in both
+    ///   `predict.work_categories` and `predict.rest_categories`, a
+    ///   `base.workday` string listing no day (but not "none", which asks for
+    ///   no work days on purpose), `require_calendar`
+    ///   without any calendar source, and `calendar.min_sources` above the
+    ///   number of configured sources
+    /// 
+    /// # Examples
+    /// ```
+    /// use choliday::Conf;
+    /// 
+    /// let problems = |toml: &str| -> Vec<String> {
+    ///     match Conf::from_toml(toml).unwrap().validate() {
+    ///         Ok(()) => Vec::new(),
+    ///         Err(errors) => errors.iter().map(ToString::to_string).collect(),
+    ///     }
+    /// };
+    /// 
+    /// assert!(problems("[predict]\nwork = ['Shift']\nrest = ['Holiday']\npriority = 'RestOverWork'").is_empty());
+    /// assert_eq!(
+    ///     problems("[predict]\nwork = ['Shift']\nrest = ['Holiday']\nrest_public = ['Shift']\npriority = 'RestOverWork'"),
+    ///     ["predict.rest_public: pattern 'Shift' is also listed in predict.work"],
+    /// );
+    /// assert_eq!(
+    ///     problems("[predict]\nwork = []\nrest = []\nwork_categories = ['Duty']\nrest_categories = ['duty']\npriority = 'RestOverWork'"),
+    ///     ["predict.rest_categories: category 'duty' is also listed in predict.work_categories"],
+    /// );
+    /// assert!(problems("[base]\nworkday = 'none'\n[predict]\nwork = []\nrest = []\npriority = 'RestOverWork'").is_empty());
+    /// assert_eq!(
+    ///     problems("[predict]\nwork = []\nrest = []\nrequire_calendar = true\npriority = 'RestOverWork'"),
+    ///     ["predict.require_calendar: no calendar source is configured, every day would be unknown"],
+    /// );
+    /// assert_eq!(
+    ///     problems("[calendar]\nsource = ['a.ics']\nmin_sources = 2\n[predict]\nwork = []\nrest = []\npriority = 'RestOverWork'"),
+    ///     ["calendar.min_sources: requires 2 loaded sources but only 1 is configured"],
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<ConfError>> {
+        let mut errors = Vec::new();
+
+        if let Err(e) = self.check_sources() {
+            errors.push(ConfError::new("calendar.source", e));
+        }
+
+        let rest_lists = [
+            ("predict.rest", &self.predict.rest[..]),
+            ("predict.rest_public", self.rest_public()),
+            ("predict.rest_personal", self.rest_personal()),
+        ];
+        for (key, patterns) in rest_lists {
+            for pattern in patterns.iter().filter(|p| self.predict.work.contains(p)) {
+                errors.push(ConfError::new(key, format!("pattern '{}' is also listed in predict.work", pattern)));
+            }
+        }
+
+        // Categories match case-insensitively, so the overlap does too
+        for category in self.rest_categories() {
+            if self.work_categories().iter().any(|c| c.eq_ignore_ascii_case(category)) {
+                errors.push(ConfError::new(
+                    "predict.rest_categories",
+                    format!("category '{}' is also listed in predict.work_categories", category),
+                ));
+            }
+        }
+
+        let no_workdays = self.base.as_ref().is_some_and(|base| base.no_workdays);
+        if self.work_day().is_some_and(HashSet::is_empty) && !no_workdays && !self.require_calendar() {
+            errors.push(ConfError::new(
+                "base.workday",
+                "lists no day; write \"none\" if every day without a matching event is a rest day",
+            ));
+        }
+
+        let sources = self.get_describe_calendar().map_or(0, <[Source]>::len);
+        if self.require_calendar() && sources == 0 {
+            errors.push(ConfError::new(
+                "predict.require_calendar",
+                "no calendar source is configured, every day would be unknown",
+            ));
+        }
+        if let Some(min) = self.min_sources().filter(|&min| min > sources) {
+            errors.push(ConfError::new(
+                "calendar.min_sources",
+                format!("requires {} loaded sources but only {} {} configured", min, sources, if sources == 1 { "is" } else { "are" }),
+            ));
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// Returns the list of calendar data sources if configured.
+    /// 
+    /// # Returns
+    /// - `Some(&[Source])`: Reference to calendar sources
+    /// - `None`: No calendar sources configured
+    pub fn get_describe_calendar(&self) -> Option<&[Source]> {
+        if let Some(cal) = &self.calendar {
+            return cal.source.as_deref();
+        }
+        None
+    }
+
+    /// Returns the minimum number of calendar sources that must load.
+    /// 
+    /// # Returns
+    /// - `Some(usize)`: Required number of successfully loaded sources
+    /// - `None`: Failed sources only produce warnings
+    pub fn min_sources(&self) -> Option<usize> {
+        self.calendar.as_ref().and_then(|cal| cal.min_sources)
+    }
+
+    /// Returns whether transparent events are excluded from classification.
+    /// 
+    /// # Returns
+    /// - `true` if `calendar.ignore_transparent` is enabled, `false` by default
+    pub fn ignore_transparent(&self) -> bool {
+        self.calendar.as_ref().is_some_and(|cal| cal.ignore_transparent)
+    }
+
+    /// Returns whether events are recovered from malformed calendar data.
+    /// 
+    /// # Arguments
+    /// - `source`: Source whose own `lenient` setting takes precedence
+    /// 
+    /// # Returns
+    /// - `true` if enabled for the source or through `calendar.lenient`, `false` by default
+    pub fn lenient(&self, source: &Source) -> bool {
+        source
+            .lenient()
+            .unwrap_or_else(|| self.calendar.as_ref().is_some_and(|cal| cal.lenient))
+    }
+
+    /// Returns whether VTODO components are read as all-day events.
+    /// 
+    /// # Returns
+    /// - `true` if `calendar.include_todos` is enabled, `false` by default
+    pub fn include_todos(&self) -> bool {
+        self.calendar.as_ref().is_some_and(|cal| cal.include_todos)
+    }
+
+    /// Returns whether events ending before they start are repaired.
+    /// 
+    /// # Returns
+    /// - `calendar.fix_inverted` if set, `true` by default
+    pub fn fix_inverted(&self) -> bool {
+        self.calendar.as_ref().and_then(|cal| cal.fix_inverted).unwrap_or(true)
+    }
+
+    /// Returns how ambiguous wall-clock times are read, if configured.
+    /// 
+    /// # Returns
+    /// - `Some(DstAmbiguous)`: The occurrence set by `calendar.dst_ambiguous`
+    /// - `None`: DTSTART takes the earlier and DTEND the later occurrence
+    pub fn dst_ambiguous(&self) -> Option<DstAmbiguous> {
+        self.calendar.as_ref().and_then(|cal| cal.dst_ambiguous)
+    }
+
+    /// Returns how nonexistent wall-clock times are read.
+    /// 
+    /// # Returns
+    /// - The policy set by `calendar.dst_gap`, `DstGap::ShiftForward` by default
+    pub fn dst_gap(&self) -> DstGap {
+        self.calendar.as_ref().map(|cal| cal.dst_gap).unwrap_or_default()
+    }
+
+    /// Returns the HTTP settings for remote calendar sources.
+    /// 
+    /// # Returns
+    /// - Reference to the HTTP settings, defaults apply when `[http]` is absent
+    pub fn http(&self) -> &Http {
+        &self.http
+    }
+
+    /// Returns the settings of the HTTP server mode.
+    /// 
+    /// # Returns
+    /// - Reference to the server settings, defaults apply when `[serve]` is absent
+    pub fn serve(&self) -> &Serve {
+        &self.serve
+    }
+
+    /// Returns the directory of the loaded configuration file.
+    /// 
+    /// # Returns
+    /// - `Some(&Path)`: Directory relative calendar sources resolve against
+    /// - `None`: Configuration was not loaded from a file
+    pub fn conf_dir(&self) -> Option<&Path> {
+        self.conf_dir.as_deref()
+    }
+
+    /// Returns the path of the loaded configuration file.
+    /// 
+    /// # Returns
+    /// - `Some(&Path)`: Canonical path the configuration was loaded from, the
+    ///   last one of files merged by [`Conf::load_all`]
+    /// - `None`: Configuration was not loaded from a file
+    pub fn conf_file(&self) -> Option<&Path> {
+        self.conf_files.last().map(PathBuf::as_path)
+    }
+
+    /// Returns the paths of all loaded configuration files.
+    /// 
+    /// # Returns
+    /// - Canonical paths in merge order, empty if not loaded from files
+    pub fn conf_files(&self) -> &[PathBuf] {
+        &self.conf_files
+    }
+
+    /// Records the directory of the loaded configuration file.
+    /// 
+    /// # Arguments
+    /// * `dir` - Directory containing the configuration file
+    pub fn set_conf_dir(&mut self, dir: PathBuf) {
+        self.conf_dir = Some(dir);
+    }
+
+    /// Returns the directory for cached remote calendars.
+    /// 
+    /// # Returns
+    /// - `Some(&Path)`: Directory set through `cache.dir`
+    /// - `None`: Use the default cache directory
+    pub fn cache_dir(&self) -> Option<&Path> {
+        self.cache.as_ref().and_then(|cache| cache.dir.as_deref())
+    }
+
+    /// Returns the storage of cached remote calendars.
+    /// 
+    /// # Returns
+    /// - The `cache.backend` setting, [`CacheBackend::File`] by default
+    pub fn cache_backend(&self) -> CacheBackend {
+        self.cache.as_ref().map(|cache| cache.backend).unwrap_or_default()
+    }
+
+    /// Returns how long a cached calendar is used without asking the server.
+    /// 
+    /// # Returns
+    /// - `Some(Duration)`: Set through `cache.ttl`, overriding the server's
+    ///   `Cache-Control` and `Expires` headers
+    /// - `None`: Follow the server's headers
+    pub fn cache_ttl(&self) -> Option<Duration> {
+        self.cache.as_ref().and_then(|cache| cache.ttl)
+    }
+
+    /// Returns the priority rule for prediction conflict resolution.
+    /// 
+    /// # Returns
+    /// - Reference to the configured priority rule
+    pub fn priority(&self) -> &Priority {
+        &self.predict.priority
+    }
+
+    /// Returns the work day prediction patterns.
+    /// 
+    /// # Returns
+    /// - Work day patterns, in the order configured
+    pub fn predict_work(&self) -> &[String] {
+        &self.predict.work
+    }
+
+    /// Returns the rest day prediction patterns.
+    /// 
+    /// # Returns
+    /// - Rest day patterns, in the order configured
+    pub fn predict_rest(&self) -> &[String] {
+        &self.predict.rest
+    }
+
+    /// Returns the patterns marking public holidays.
+    /// 
+    /// # Returns
+    /// - Slice of patterns, empty if not configured
+    pub fn rest_public(&self) -> &[String] {
+        self.predict.rest_public.as_deref().unwrap_or(&[])
+    }
+
+    /// Returns the patterns marking personal leave.
+    /// 
+    /// # Returns
+    /// - Slice of patterns, empty if not configured
+    pub fn rest_personal(&self) -> &[String] {
+        self.predict.rest_personal.as_deref().unwrap_or(&[])
+    }
+
+    /// Returns every pattern identifying rest days, whatever their kind.
+    /// 
+    /// # Returns
+    /// - Public holiday and personal leave patterns, followed by the `rest` patterns,
+    ///   so a tagged pattern is reported ahead of a generic one
+    pub fn rest_patterns(&self) -> impl Iterator<Item = &String> {
+        self.rest_public().iter().chain(self.rest_personal()).chain(&self.predict.rest)
+    }
+
+    /// Returns the kind of rest day a matched pattern or category marks.
+    /// 
+    /// # Arguments
+    /// - `matched` - Rest pattern or category that matched an event
+    /// 
+    /// # Returns
+    /// - `PublicHoliday` if listed in `rest_public`, `Leave` if listed in
+    ///   `rest_personal` (case-insensitive), `Rest` otherwise
+    pub fn rest_kind(&self, matched: &str) -> RestKind {
+        let listed = |list: &[String]| list.iter().any(|p| p.to_lowercase() == matched.to_lowercase());
+        if listed(self.rest_public()) {
+            RestKind::PublicHoliday
+        } else if listed(self.rest_personal()) {
+            RestKind::Leave
+        } else {
+            RestKind::Rest
+        }
+    }
+
+    /// Returns the event fields matched against the patterns.
+    /// 
+    /// # Returns
+    /// - Fields in order of precedence; the first field with a match decides.
+    ///   Defaults to summary, then description
+    pub fn match_fields(&self) -> &[MatchField] {
+        self.predict.match_fields.as_deref().unwrap_or(DEFAULT_MATCH_FIELDS)
+    }
+
+    /// Returns the event categories identifying work days.
+    /// 
+    /// # Returns
+    /// - Slice of categories, empty if not configured
+    pub fn work_categories(&self) -> &[String] {
+        self.predict.work_categories.as_deref().unwrap_or(&[])
+    }
+
+    /// Returns the event categories identifying rest days.
+    /// 
+    /// # Returns
+    /// - Slice of categories, empty if not configured
+    pub fn rest_categories(&self) -> &[String] {
+        self.predict.rest_categories.as_deref().unwrap_or(&[])
+    }
+
+    /// Returns the event property holding an explicit classification.
+    /// 
+    /// # Returns
+    /// - Property name, `X-CHOLIDAY-TYPE` if not configured
+    pub fn explicit_property(&self) -> &str {
+        self.predict.explicit_property.as_deref().unwrap_or(DEFAULT_EXPLICIT_PROPERTY)
+    }
+
+    /// Returns whether days no calendar event classifies are left unknown.
+    /// 
+    /// # Returns
+    /// - `true` if `predict.require_calendar` is enabled, `false` by default
+    pub fn require_calendar(&self) -> bool {
+        self.predict.require_calendar
+    }
+
+    /// Returns how a day decided by an event matching both work and rest is handled.
+    /// 
+    /// # Returns
+    /// - The handling set by `predict.on_conflict`, `OnConflict::Priority` by default
+    pub fn on_conflict(&self) -> OnConflict {
+        self.predict.on_conflict
+    }
+
+    /// Replaces `predict.on_conflict`
+    /// 
+    /// # Arguments
+    /// * `on_conflict` - Handling to use instead of the configured one
+    pub(crate) fn set_on_conflict(&mut self, on_conflict: OnConflict) {
+        self.predict.on_conflict = on_conflict;
+    }
+
+    /// Returns the configured timezone.
+    /// 
+    /// # Returns
+    /// - `Some(Tz)`: Zone set through `base.timezone`
+    /// - `None`: No timezone configured
+    pub fn timezone(&self) -> Option<Tz> {
+        self.base.as_ref().and_then(|base| base.timezone)
+    }
+
+    /// Returns how many days work day arithmetic searches.
+    /// 
+    /// # Returns
+    /// - `base.horizon_days` if set, one year (366 days) by default
+    pub fn horizon_days(&self) -> u32 {
+        self.base.as_ref().and_then(|base| base.horizon_days).unwrap_or(DEFAULT_HORIZON_DAYS)
+    }
+
+    /// Returns the working hours of a day of the week.
+    /// 
+    /// # Arguments
+    /// - `weekday` - Day of the week
+    /// 
+    /// # Returns
+    /// - Hours `base.work_hours` gives the day, 09:00 to 17:00 otherwise
+    pub fn work_hours(&self, weekday: Weekday) -> WorkHours {
+        self.base
+            .as_ref()
+            .and_then(|base| base.work_hours.as_ref())
+            .and_then(|hours| hours.get(&(weekday.number_from_monday() as i8)))
+            .copied()
+            .unwrap_or(DEFAULT_WORK_HOURS)
+    }
+
+    /// Returns the zone target dates and all-day events are read in.
+    /// 
+    /// # Returns
+    /// - `TargetZone::Configured` with `base.timezone` if set, the system zone otherwise
+    pub fn target_zone(&self) -> TargetZone {
+        match self.timezone() {
+            Some(tz) => TargetZone::Configured(tz),
+            None => TargetZone::System,
+        }
+    }
+
+    /// Replaces `base.timezone`, for both floating times and target dates
+    /// 
+    /// # Arguments
+    /// * `timezone` - Zone to use instead of the configured one
+    pub(crate) fn set_timezone(&mut self, timezone: Tz) {
+        self.base.get_or_insert_with(Base::default).timezone = Some(timezone);
+    }
+
+    /// Adds a calendar source, given as a URL or path like in the configuration file.
+    /// 
+    /// # Arguments
+    /// * `source` - URL, path, or `-` for standard input
+    /// 
+    /// # Returns
+    /// - `Err(String)`: The sources cannot be used together, e.g. standard
+    ///   input twice; the configuration is left unchanged
+    /// 
+    /// # Examples
+    /// ```
+    /// use chrono::Weekday;
+    /// use choliday::{Conf, Priority};
+    /// 
+    /// let mut conf = Conf::from_toml("[predict]\nwork = [\"Workday\"]\nrest = [\"Holiday\"]\npriority = \"WorkOverRest\"\n")?;
+    /// conf.add_source("holidays.ics")?;
+    /// conf.add_work_pattern("Makeup");
+    /// conf.add_rest_pattern("Vacation");
+    /// conf.set_priority(Priority::RestOverWork);
+    /// conf.set_workdays([Weekday::Sun, Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu]);
+    /// 
+    /// assert_eq!(conf.get_describe_calendar().unwrap().len(), 1);
+    /// assert_eq!(conf.predict_work(), ["Workday", "Makeup"]);
+    /// assert_eq!(conf.predict_rest(), ["Holiday", "Vacation"]);
+    /// assert_eq!(conf.priority(), &Priority::RestOverWork);
+    /// assert!(conf.work_day().is_some_and(|days| days.contains(&7) && !days.contains(&5)));
+    /// 
+    /// conf.add_source("-")?;
+    /// assert!(conf.add_source("-").is_err());
+    /// assert_eq!(conf.get_describe_calendar().unwrap().len(), 2);
+    /// # Ok::<(), String>(())
+    /// ```
+    pub fn add_source(&mut self, source: impl Into<String>) -> Result<(), String> {
+        let sources = self.calendar.get_or_insert_with(Calnedar::default).source.get_or_insert_with(Vec::new);
+        sources.push(Source::from(SourceSpec::Url(source.into())));
+        self.check_sources().inspect_err(|_| {
+            if let Some(sources) = self.calendar.as_mut().and_then(|calendar| calendar.source.as_mut()) {
+                sources.pop();
+            }
+        })
+    }
+
+    /// Adds a pattern identifying work days, after the configured ones.
+    pub fn add_work_pattern(&mut self, pattern: impl Into<String>) {
+        self.predict.work.push(pattern.into());
+    }
+
+    /// Adds a pattern identifying rest days, after the configured ones.
+    pub fn add_rest_pattern(&mut self, pattern: impl Into<String>) {
+        self.predict.rest.push(pattern.into());
+    }
+
+    /// Replaces `predict.priority`.
+    /// 
+    /// # Arguments
+    /// * `priority` - Rule for resolving conflicts between work and rest predictions
+    pub fn set_priority(&mut self, priority: Priority) {
+        self.predict.priority = priority;
+    }
+
+    /// Replaces `base.workday`, the weekdays worked when no event classifies a day.
+    /// 
+    /// # Arguments
+    /// * `days` - Work days; none at all makes every such day a rest day
+    pub fn set_workdays(&mut self, days: impl IntoIterator<Item = Weekday>) {
+        let days: HashSet<i8> = days.into_iter().map(|day| day.number_from_monday() as i8).collect();
+        let base = self.base.get_or_insert_with(Base::default);
+        base.no_workdays = days.is_empty();
+        base.workday = Some(days);
+    }
+
+    /// Returns the set of configured work days.
+    /// 
+    /// # Returns
+    /// - `Some(&HashSet<i8>)`: Set of work days (1-7)
+    /// - `None`: No work day configuration available
+    /// 
+    /// # Examples
+    /// ```
+    /// use std::collections::HashSet;
+    /// use choliday::Conf;
+    /// 
+    /// let work_day = |workday: &str| -> Result<HashSet<i8>, String> {
+    ///     let conf = Conf::from_toml(&format!("[base]\nworkday = \"{}\"\n[predict]\nwork = []\nrest = []\npriority = \"RestOverWork\"\n", workday))?;
+    ///     Ok(conf.work_day().cloned().unwrap_or_default())
+    /// };
+    /// assert_eq!(work_day("1-5"), Ok(HashSet::from([1, 2, 3, 4, 5])));
+    /// 
+    /// // A range starting later in the week wraps through Sunday back to Monday
+    /// assert_eq!(work_day("7-1"), Ok(HashSet::from([7, 1])));
+    /// assert_eq!(work_day("6-2"), Ok(HashSet::from([6, 7, 1, 2])));
+    /// assert_eq!(work_day("3-3"), Ok(HashSet::from([3])));
+    /// 
+    /// // Weekday names work like numbers
+    /// assert_eq!(work_day("fri-mon"), Ok(HashSet::from([5, 6, 7, 1])));
+    /// assert_eq!(work_day("Mon-Wed,sat"), Ok(HashSet::from([1, 2, 3, 6])));
+    /// 
+    /// // Whitespace, trailing commas and repeated days are tolerated
+    /// assert_eq!(work_day(" 1, 3 - 5 "), Ok(HashSet::from([1, 3, 4, 5])));
+    /// assert_eq!(work_day("1,2,"), Ok(HashSet::from([1, 2])));
+    /// assert_eq!(work_day("1,,1, 2"), Ok(HashSet::from([1, 2])));
+    /// assert_eq!(work_day("ALL"), Ok(HashSet::from([1, 2, 3, 4, 5, 6, 7])));
+    /// assert_eq!(work_day("none"), Ok(HashSet::new()));
+    /// 
+    /// // Errors quote every offending segment, its index and where it starts
+    /// let error = |workday: &str| work_day(workday).unwrap_err();
+    /// let invalid = [
+    ///     ("mon-funday", "segment 1 'mon-funday' at column 1: 'funday' is not a day number or weekday name"),
+    ///     ("1, 6-8", "segment 2 '6-8' at column 4: day 8 is out of range 1-7"),
+    ///     ("1,2-3-4", "segment 2 '2-3-4' at column 3: range has 3 ends, expected a start and an end day"),
+    ///     ("3-", "segment 1 '3-' at column 1: range has no end day"),
+    ///     ("-5", "segment 1 '-5' at column 1: range has no start day"),
+    ///     ("1, -", "segment 2 '-' at column 4: range has neither a start nor an end day"),
+    ///     ("all,6", "segment 1 'all' at column 1: 'all' is not a day number or weekday name"),
+    ///     ("0,8-9,x", "invalid workday '0,8-9,x': segment 1 '0' at column 1: day 0 is out of range 1-7; \
+    ///                  Sunday is 7, or set base.week_numbering = \"sunday0\" to number it 0; \
+    ///                  segment 2 '8-9' at column 3: day 8 is out of range 1-7; \
+    ///                  segment 3 'x' at column 7: 'x' is not a day number or weekday name; expected"),
+    /// ];
+    /// for (workday, message) in invalid {
+    ///     assert!(error(workday).contains(message), "{}: {}", workday, error(workday));
+    /// }
+    /// assert!(error(" ").contains("invalid length 0"));
+    /// 
+    /// // Numbering Sunday 0, as cron does, needs base.week_numbering
+    /// assert!(error("0-4").contains("set base.week_numbering = \"sunday0\""));
+    /// ```
+    pub fn work_day(&self) -> Option<&HashSet<i8>> {
+        self.base.as_ref().and_then(|base| base.workday.as_ref())
+    }
+
+    /// Returns the numbering the day numbers of `workday` strings are written in.
+    /// 
+    /// # Note
+    /// * Only affects reading the configuration: `work_day` and the working
+    ///   hours always number Monday 1 through Sunday 7
+    /// 
+    /// # Examples
+    /// ```
+    /// use choliday::{Conf, WeekNumbering};
+    /// 
+    /// let conf = |base: &str| -> Result<Conf, String> {
+    ///     Conf::from_toml(&format!("[base]\n{}\n[predict]\nwork = []\nrest = []\npriority = \"RestOverWork\"\n", base))
+    /// };
+    /// let iso = conf("workday = \"7-4\"\nwork_hours = { \"7\" = \"10:00-16:00\" }")?;
+    /// let cron = conf("week_numbering = \"sunday0\"\nworkday = \"0-4\"\nwork_hours = { \"0\" = \"10:00-16:00\" }")?;
+    /// assert_eq!(iso.week_numbering(), WeekNumbering::Iso);
+    /// assert_eq!(cron.week_numbering(), WeekNumbering::Sunday0);
+    /// 
+    /// // Sunday through Thursday, either way
+    /// assert_eq!(iso.work_day(), cron.work_day());
+    /// assert_eq!(cron.work_day().unwrap().len(), 5);
+    /// assert_eq!(iso.work_hours(chrono::Weekday::Sun), cron.work_hours(chrono::Weekday::Sun));
+    /// assert_eq!(conf("workday = \"fri-sun\"")?.work_day(), conf("week_numbering = \"sunday0\"\nworkday = \"5-0\"")?.work_day());
+    /// assert_eq!(conf("workday = \"1-5\"")?.work_day(), conf("week_numbering = \"sunday0\"\nworkday = \"mon-fri\"")?.work_day());
+    /// 
+    /// // Under sunday0, 7 is not a day
+    /// assert!(conf("week_numbering = \"sunday0\"\nworkday = \"1-7\"").is_err());
+    /// # Ok::<(), String>(())
+    /// ```
+    pub fn week_numbering(&self) -> WeekNumbering {
+        self.base.as_ref().map(|base| base.week_numbering).unwrap_or_default()
+    }
+}
+
+/// Builder for configurations constructed in code.
+/// 
+/// Settings not covered by the builder keep the defaults of an empty
+/// configuration file section.
+/// 
+/// # Examples
+/// ```
+/// use chrono::Weekday;
+/// use choliday::{Conf, Priority};
+/// 
+/// let conf = Conf::builder(Priority::RestOverWork)
+///     .source("holidays.ics")
+///     .work("Makeup workday")
+///     .rest("Holiday")
+///     .workdays([Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri])
+///     .timezone(chrono_tz::Europe::Berlin)
+///     .build()
+///     .unwrap();
+/// assert_eq!(conf.predict_rest(), &["Holiday"]);
+/// 
+/// // The same settings as a configuration file
+/// let file = Conf::from_toml(
+///     "[base]\nworkday = \"mon-fri\"\ntimezone = \"Europe/Berlin\"\n[calendar]\nsource = [\"holidays.ics\"]\n\
+///      [predict]\nwork = [\"Makeup workday\"]\nrest = [\"Holiday\"]\npriority = \"RestOverWork\"\n",
+/// )
+/// .unwrap();
+/// assert_eq!(conf.predict_work(), file.predict_work());
+/// assert_eq!(conf.predict_rest(), file.predict_rest());
+/// assert_eq!(conf.priority(), file.priority());
+/// assert_eq!(conf.work_day(), file.work_day());
+/// assert_eq!(conf.timezone(), file.timezone());
+/// assert_eq!(conf.get_describe_calendar().unwrap()[0].name(), file.get_describe_calendar().unwrap()[0].name());
+/// 
+/// // Settings can be replaced later in the chain, and sources are checked by build
+/// let conf = Conf::builder(Priority::RestOverWork).priority(Priority::UseLatest).build().unwrap();
+/// assert_eq!(conf.priority(), &Priority::UseLatest);
+/// assert!(Conf::builder(Priority::RestOverWork).source("-").source("-").build().is_err());
+/// ```
+#[derive(Clone)]
+pub struct ConfBuilder {
+    /// Configuration built so far
+    conf: Conf,
+}
+
+impl ConfBuilder {
+    /// Starts an empty configuration with the given priority rule
+    /// 
+    /// # Arguments
+    /// * `priority` - Rule for resolving conflicts between work and rest predictions
+    pub fn new(priority: Priority) -> Self {
+        ConfBuilder {
+            conf: Conf {
+                base: None,
+                calendar: None,
+                predict: Predict {
+                    work: Vec::new(),
+                    rest: Vec::new(),
+                    rest_public: None,
+                    rest_personal: None,
+                    match_fields: None,
+                    work_categories: None,
+                    rest_categories: None,
+                    explicit_property: None,
+                    require_calendar: false,
+                    on_conflict: OnConflict::Priority,
+                    priority,
+                },
+                http: Http::default(),
+                cache: None,
+                serve: Serve::default(),
+                conf_dir: None,
+                conf_files: Vec::new(),
+            },
+        }
+    }
+
+    /// Adds a calendar source, given as a URL or path like in the configuration file
+    /// 
+    /// # Note
+    /// * Sources that cannot be used together are reported by `build`
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        let calendar = self.conf.calendar.get_or_insert_with(Calnedar::default);
+        calendar.source
+            .get_or_insert_with(Vec::new)
+            .push(Source::from(SourceSpec::Url(source.into())));
+        self
+    }
+
+    /// Adds a pattern identifying work days
+    pub fn work(mut self, pattern: impl Into<String>) -> Self {
+        self.conf.add_work_pattern(pattern);
+        self
+    }
+
+    /// Adds a pattern identifying rest days
+    pub fn rest(mut self, pattern: impl Into<String>) -> Self {
+        self.conf.add_rest_pattern(pattern);
+        self
+    }
+
+    /// Replaces the priority rule given to `new`
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.conf.set_priority(priority);
+        self
+    }
+
+    /// Adds a pattern identifying public holidays
+    pub fn rest_public(mut self, pattern: impl Into<String>) -> Self {
+        self.conf.predict.rest_public.get_or_insert_with(Vec::new).push(pattern.into());
+        self
+    }
+
+    /// Adds a pattern identifying personal leave
+    pub fn rest_personal(mut self, pattern: impl Into<String>) -> Self {
+        self.conf.predict.rest_personal.get_or_insert_with(Vec::new).push(pattern.into());
+        self
+    }
+
+    /// Sets how a day decided by an event matching both work and rest is handled
+    pub fn on_conflict(mut self, on_conflict: OnConflict) -> Self {
+        self.conf.predict.on_conflict = on_conflict;
+        self
+    }
+
+    /// Leaves days no calendar event classifies unknown instead of using the work days
+    pub fn require_calendar(mut self, require: bool) -> Self {
+        self.conf.predict.require_calendar = require;
+        self
+    }
+
+    /// Sets the weekdays worked when no event classifies a day (default Monday to Friday)
+    pub fn workdays(mut self, days: impl IntoIterator<Item = Weekday>) -> Self {
+        self.conf.set_workdays(days);
+        self
+    }
+
+    /// Sets the zone of floating calendar times, overriding X-WR-TIMEZONE
+    pub fn timezone(mut self, timezone: Tz) -> Self {
+        self.conf.base.get_or_insert_with(Base::default).timezone = Some(timezone);
+        self
+    }
+
+    /// Sets the working hours of every day (default 09:00 to 17:00)
+    pub fn work_hours(mut self, hours: WorkHours) -> Self {
+        let days = (1..=7).map(|day| (day, hours)).collect();
+        self.conf.base.get_or_insert_with(Base::default).work_hours = Some(days);
+        self
+    }
+
+    /// Sets how many days work day arithmetic searches (default 366)
+    pub fn horizon_days(mut self, days: u32) -> Self {
+        self.conf.base.get_or_insert_with(Base::default).horizon_days = Some(days);
+        self
+    }
+
+    /// Sets the directory relative calendar sources resolve against
+    pub fn conf_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.conf.set_conf_dir(dir.into());
+        self
+    }
+
+    /// Finishes the configuration
+    /// 
+    /// # Returns
+    /// - `Ok(Conf)`: The configuration
+    /// - `Err(String)`: The sources cannot be used together, e.g. standard input twice
+    pub fn build(self) -> Result<Conf, String> {
+        self.conf.check_sources()?;
+        Ok(self.conf)
+    }
+}
+
+/// Reads a configuration file.
+/// 
+/// # Arguments
+/// * `path` - Path to the configuration file
+/// 
+/// # Returns
+/// - `Err(String)`: The file is missing or unreadable, naming it
+fn read_conf_file(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => format!("Configuration file '{}' not found", path.display()),
+        std::io::ErrorKind::PermissionDenied => format!("Permission denied for '{}'", path.display()),
+        _ => format!("Cannot read configuration file '{}': {}", path.display(), e),
+    })
+}
+
+/// Returns the canonical form of a path, or the path itself if it cannot be resolved
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Merges a table of a later configuration file into the tables before it.
+/// 
+/// # Arguments
+/// * `base` - Table merged so far
+/// * `overlay` - Table of the next file, taking precedence
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => merge_tables(base, overlay),
+            (Some(toml::Value::Array(base)), toml::Value::Array(overlay)) => {
+                for item in overlay {
+                    if !base.contains(&item) {
+                        base.push(item);
+                    }
+                }
+            },
+            (_, value) => {
+                base.insert(key, value);
+            },
+        }
+    }
+}
+
+/// Makes the relative local paths of calendar sources absolute.
+/// 
+/// # Arguments
+/// * `table` - Table of one configuration file
+/// * `dir` - Directory of that file
+fn rebase_sources(table: &mut toml::Table, dir: &Path) {
+    let rebase = |value: &mut toml::Value| {
+        if let toml::Value::String(path) = value
+            && is_relative_path(path)
+        {
+            *path = dir.join(&*path).to_string_lossy().into_owned();
+        }
+    };
+    let sources = table
+        .get_mut("calendar")
+        .and_then(toml::Value::as_table_mut)
+        .and_then(|calendar| calendar.get_mut("source"))
+        .and_then(toml::Value::as_array_mut);
+    for source in sources.into_iter().flatten() {
+        match source {
+            toml::Value::Table(source) => {
+                for key in ["url", "credentials"] {
+                    if let Some(value) = source.get_mut(key) {
+                        rebase(value);
+                    }
+                }
+                // A program given as a path, not a name looked up in PATH
+                let program = source.get_mut("exec").and_then(toml::Value::as_array_mut).and_then(|exec| exec.first_mut());
+                if let Some(program) = program.filter(|program| program.as_str().is_some_and(|p| p.contains('/'))) {
+                    rebase(program);
+                }
+            },
+            source => rebase(source),
+        }
+    }
+}
+
+/// Returns whether a source is a relative path on disk rather than a URL,
+/// standard input or a path from the home directory.
+fn is_relative_path(source: &str) -> bool {
+    source != STDIN_SOURCE
+        && !source.contains("://")
+        && !["~", "$HOME", "${HOME}"].iter().any(|home| source.starts_with(home))
+        && Path::new(source).is_relative()
+}
+
+/// Deserializes a duration from a string like "90s", "30m", "2h" or "1d"
+/// 
+/// # Arguments
+/// * `deserializer` - Serde deserializer
+/// 
+/// # Returns
+/// * `Result<Option<Duration>, D::Error>` - Positive duration or deserialization error
+fn deserialize_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let v = String::deserialize(deserializer)?;
+    let invalid = || Error::invalid_value(serde::de::Unexpected::Str(&v), &DURATION_ERR_FMT);
+    let split = v.trim().len().saturating_sub(1);
+    let (amount, unit) = v.trim().split_at_checked(split).ok_or_else(invalid)?;
+    let secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+    match amount.parse::<u64>() {
+        Ok(amount) if amount > 0 => amount
+            .checked_mul(secs)
+            .map(|secs| Some(Duration::from_secs(secs)))
+            .ok_or_else(invalid),
+        _ => Err(invalid()),
+    }
+}
+
+/// Error message format for duration deserialization errors.
+const DURATION_ERR_FMT: &str = "a positive duration like '90s', '30m', '2h' or '1d'";
+
+/// Error message format for workday deserialization errors.
+const ERR_FMT: &str = "a workday string like '1-5', '1,3,5', '1,3-5', 'fri-mon', 'all' or 'none' (day numbers or weekday names)";
+
+/// Visitor for deserializing workday strings into HashSet<i8>.
+/// 
+/// # Supported Formats
+/// * Single days: "1", "2", "3"
+/// * Day ranges: "1-5", wrapping past Sunday when the start is later: "6-2"
+/// * Weekday names instead of numbers: "mon-fri", "fri-mon"
+/// * Mixed formats: "1,3,5" or "1,3-5", with any whitespace around days and commas
+/// * Keywords: "all" for every day, "none" for no work days at all
+/// 
+/// Numbers are read in the week numbering it holds, and always returned as
+/// ISO 8601 numbers (Monday=1 through Sunday=7).
+#[derive(Clone, Copy)]
+struct WorkDayVisitor(WeekNumbering);
+
+impl<'a> Visitor<'a> for WorkDayVisitor {
+    type Value = HashSet<i8>;
+
+    /// Describes the expected format for error messages.
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "{}", &ERR_FMT)
+    }
+
+    /// Processes a string representation of workdays.
+    /// 
+    /// # Arguments
+    /// * `v` - String containing workday specification
+    /// 
+    /// # Returns
+    /// * `Result<HashSet<i8>, E>` - Parsed workday set or error
+    /// 
+    /// # Examples
+    /// * "1-5" → {1, 2, 3, 4, 5}
+    /// * "1,3,5" → {1, 3, 5}
+    /// * "1,3-5" → {1, 3, 4, 5}
+    /// * "6-2" → {6, 7, 1, 2}, a range whose start is later wraps past Sunday
+    /// * "fri-mon" → {5, 6, 7, 1}
+    /// * "1, 3 - 5," → {1, 3, 4, 5}, whitespace and empty segments are ignored
+    /// * "all" → {1, ..., 7}, "none" → {}
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error, 
+    {
+        match v.trim().to_ascii_lowercase().as_str() {
+            "" => return Err(Error::invalid_length(0, &ERR_FMT)),
+            "all" => return Ok((1..=7).collect()),
+            "none" => return Ok(HashSet::new()),
+            _ => {},
+        }
+
+        let mut workday_set: HashSet<i8> = HashSet::new();
+        let mut problems: Vec<String> = Vec::new();
+        let mut column = 1;
+
+        // Split by comma to handle multiple day specifications, reading every
+        // segment so that all problems are reported at once
+        for (index, segment) in v.split(',').enumerate() {
+            let day_spec = segment.trim();
+            let at = column + segment.chars().count() - segment.trim_start().chars().count();
+            column += segment.chars().count() + 1;
+
+            // Trailing or doubled commas leave nothing to parse
+            if day_spec.is_empty() {
+                continue;
+            }
+            match day_spec_days(day_spec, self.0) {
+                Ok(days) => workday_set.extend(days),
+                Err(reason) => {
+                    problems.push(format!("segment {} '{}' at column {}: {}", index + 1, day_spec, at, reason));
+                },
+            }
+        }
+        if !problems.is_empty() {
+            return Err(Error::custom(format!(
+                "invalid workday '{}': {}; expected {}",
+                v,
+                problems.join("; "),
+                ERR_FMT
+            )));
+        }
+        Ok(workday_set)
+    }
+}
+
+/// Reads one comma-separated segment of a workday string.
+/// 
+/// # Arguments
+/// * `day_spec` - Day or range of days, trimmed, e.g. "3", "fri" or "6 - 2"
+/// * `numbering` - Numbering the days are written in
+/// 
+/// # Returns
+/// * `Result<Vec<i8>, String>` - ISO 8601 numbers of the days of the segment,
+///   or why it is invalid
+fn day_spec_days(day_spec: &str, numbering: WeekNumbering) -> Result<Vec<i8>, String> {
+    // Range of two days, each possibly surrounded by whitespace; a leading
+    // dash is a missing start rather than a negative day
+    let day_range: Vec<&str> = day_spec.split('-').map(str::trim).collect();
+    let (start, end) = match day_range[..] {
+        [day] => {
+            let day = weekday_number(day, numbering).ok_or_else(|| not_a_day(day))?;
+            return Ok(vec![numbering.to_iso(weekday_check(day, numbering)?)]);
+        },
+        ["", ""] => return Err("range has neither a start nor an end day".to_string()),
+        ["", _] => return Err("range has no start day".to_string()),
+        [_, ""] => return Err("range has no end day".to_string()),
+        [start, end] => (
+            weekday_number(start, numbering).ok_or_else(|| not_a_day(start))?,
+            weekday_number(end, numbering).ok_or_else(|| not_a_day(end))?,
+        ),
+        _ => return Err(format!("range has {} ends, expected a start and an end day", day_range.len())),
+    };
+    let (start, end) = (weekday_check(start, numbering)?, weekday_check(end, numbering)?);
+
+    // Add all days in the range (inclusive), through the end of the week back
+    // to its start when it wraps
+    let (first, last) = numbering.bounds();
+    let days: Vec<i8> = if start <= end {
+        (start..=end).collect()
+    } else {
+        (start..=last).chain(first..=end).collect()
+    };
+    Ok(days.into_iter().map(|day| numbering.to_iso(day)).collect())
+}
+
+/// Deserializes working hours into a map of day numbers to hours.
+/// 
+/// # Arguments
+/// * `deserializer` - Serde deserializer instance
+/// 
+/// # Returns
+/// * `Result<Option<Vec<(String, WorkHours)>>, D::Error>` - Hours by workday
+///   string, in the order written, or deserialization error
+/// 
+/// # Supported Formats
+/// * Every day: "09:00-18:00"
+/// * By day, keyed like `workday`: { "1-5" = "09:00-18:00", "6" = "22:00-06:00" }
+fn deserialize_work_hours<'de, D>(deserializer: D) -> Result<Option<Vec<(String, WorkHours)>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    deserializer.deserialize_any(WorkHoursVisitor).map(Some)
+}
+
+/// Error message format for working hours deserialization errors.
+const HOURS_ERR_FMT: &str = "working hours like '09:00-18:00', or a table of them keyed by workday strings like '1-5'";
+
+/// Visitor for deserializing working hours into workday strings and their hours.
+struct WorkHoursVisitor;
+
+impl WorkHoursVisitor {
+    /// Parses a "HH:MM-HH:MM" range
+    /// 
+    /// # Arguments
+    /// * `v` - String containing the range
+    /// 
+    /// # Returns
+    /// * `Result<WorkHours, E>` - Working hours, or an error for malformed or empty ranges
+    fn parse_range<E>(v: &str) -> Result<WorkHours, E>
+    where
+        E: serde::de::Error,
+    {
+        let invalid = || Error::invalid_value(serde::de::Unexpected::Str(v), &HOURS_ERR_FMT);
+        let (start, end) = v.split_once('-').ok_or_else(invalid)?;
+        let parse = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").map_err(|_| invalid());
+        let (start, end) = (parse(start)?, parse(end)?);
+        if start == end {
+            return Err(invalid());
+        }
+        Ok(WorkHours::new(start, end))
+    }
+}
+
+impl<'a> Visitor<'a> for WorkHoursVisitor {
+    type Value = Vec<(String, WorkHours)>;
+
+    /// Describes the expected format for error messages.
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "{}", &HOURS_ERR_FMT)
+    }
+
+    /// Processes working hours applying to every day.
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(vec![("all".to_string(), Self::parse_range(v)?)])
+    }
+
+    /// Processes a table of working hours keyed by workday strings.
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'a>,
+    {
+        let mut by_days = Vec::new();
+        while let Some((days, hours)) = map.next_entry::<String, String>()? {
+            by_days.push((days, Self::parse_range(&hours)?));
+        }
+        Ok(by_days)
+    }
+}
+
+/// Describes a token of a workday string that is not a day.
+fn not_a_day(token: &str) -> String {
+    format!("'{}' is not a day number or weekday name", token)
+}
+
+/// Reads a day of a workday string, as a number or a weekday name.
+/// 
+/// # Arguments
+/// * `day` - Day number, or an English weekday name like "fri" or "Friday"
+/// * `numbering` - Numbering to number weekday names in
+/// 
+/// # Returns
+/// * `Option<i8>` - Day number, still to be validated, or `None` if it is neither
+fn weekday_number(day: &str, numbering: WeekNumbering) -> Option<i8> {
+    day.parse::<i8>()
+        .ok()
+        .or_else(|| day.parse::<Weekday>().ok().map(|weekday| numbering.number(weekday)))
+}
+
+/// Validates that a day number is within the valid range of its numbering.
+/// 
+/// # Arguments
+/// * `x` - Day number to validate
+/// * `numbering` - Numbering the day is written in
+/// 
+/// # Returns
+/// * `Result<i8, String>` - Validated day number or why it is invalid
+/// 
+/// # Note
+/// * 1 = Monday, 7 = Sunday (ISO 8601 standard) unless `base.week_numbering`
+///   is `sunday0`, where 0 = Sunday and 6 = Saturday
+fn weekday_check(x: i8, numbering: WeekNumbering) -> Result<i8, String> {
+    let (first, last) = numbering.bounds();
+    if !(first..=last).contains(&x) {
+        if x == 0 && numbering == WeekNumbering::Iso {
+            return Err("day 0 is out of range 1-7; Sunday is 7, or set base.week_numbering = \"sunday0\" to number it 0".to_string());
+        }
+        return Err(format!("day {} is out of range {}-{}", x, first, last));
+    }
+    Ok(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priorities_round_trip_through_serde() {
+        for priority in Priority::ALL {
+            // Exhaustive, so a new variant needs its configuration name here
+            let name = match priority {
+                Priority::WorkOverRest => "WorkOverRest",
+                Priority::RestOverWork => "RestOverWork",
+                Priority::KeepCurrent => "KeepCurrent",
+                Priority::UseLatest => "UseLatest",
+            };
+            let json = serde_json::to_string(&priority).unwrap();
+            assert_eq!(json, format!("\"{}\"", name));
+            assert_eq!(serde_json::from_str::<Priority>(&json).unwrap(), priority);
+
+            // The serialized name is what the configuration file reads
+            let conf = Conf::from_toml(&format!("[predict]\nwork = []\nrest = []\npriority = {}\n", json)).unwrap();
+            assert_eq!(*conf.priority(), priority);
+        }
+        assert!(serde_json::from_str::<Priority>("\"RestOverEverything\"").is_err());
+    }
+
+    #[test]
+    fn configuration_accepts_every_priority_spelling() {
+        let read = |spelling: &str| Conf::from_toml(&format!("[predict]\nwork = []\nrest = []\npriority = \"{}\"\n", spelling));
+        let spellings = [
+            (Priority::WorkOverRest, ["WorkOverRest", "work_over_rest", "work-over-rest", "WORK-OVER-REST", " workoverrest "]),
+            (Priority::RestOverWork, ["RestOverWork", "rest_over_work", "rest-over-work", "REST_OVER_WORK", "restoverwork"]),
+            (Priority::KeepCurrent, ["KeepCurrent", "keep_current", "keep-current", "Keep-Current", "KEEPCURRENT"]),
+            (Priority::UseLatest, ["UseLatest", "use_latest", "use-latest", "Use_Latest", "uselatest"]),
+        ];
+        assert_eq!(spellings.len(), Priority::ALL.len());
+        for (priority, spellings) in spellings {
+            for spelling in spellings {
+                assert_eq!(*read(spelling).unwrap().priority(), priority, "{}", spelling);
+            }
+        }
+
+        // Unknown values name the value and list every accepted spelling
+        let err = read("rest-over-everything").err().unwrap();
+        assert!(err.contains("Unknown priority 'rest-over-everything', expected one of: WorkOverRest, work_over_rest, work-over-rest, RestOverWork"), "{}", err);
+        assert!(err.contains("use_latest, use-latest"), "{}", err);
+        assert!(read("rest over work").is_err());
+    }
+
+    #[test]
+    fn today_is_the_date_in_the_target_zone() {
+        let shanghai = TargetZone::Configured(chrono_tz::Asia::Shanghai);
+        let at = |h: u32, m: u32, sec: u32| Utc.with_ymd_and_hms(2025, 10, 1, h, m, sec).unwrap();
+        let october = |d: u32| NaiveDate::from_ymd_opt(2025, 10, d).unwrap();
+
+        // 01:00 UTC is 09:00 in Shanghai, the same date
+        assert_eq!(shanghai.today(at(1, 0, 0)), october(1));
+        // The Shanghai date changes at 16:00 UTC, eight hours before UTC's
+        assert_eq!(shanghai.today(at(15, 59, 59)), october(1));
+        assert_eq!(shanghai.today(at(16, 0, 0)), october(2));
+        assert_eq!(TargetZone::Configured(chrono_tz::UTC).today(at(16, 0, 0)), october(1));
+        // Behind UTC, 01:00 UTC is still the evening before
+        let new_york = TargetZone::Configured(chrono_tz::America::New_York);
+        assert_eq!(new_york.today(at(1, 0, 0)), NaiveDate::from_ymd_opt(2025, 9, 30).unwrap());
+    }
+}
@@ -0,0 +1,375 @@
+//! Crontab lines and systemd units running a command only on work days.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use std::path::{Path, PathBuf};
+
+/// Job guarded by a work-day check, for cron or systemd
+/// 
+/// The guard runs `choliday -c <conf>`, which exits 0 on work days only, and
+/// the command runs after it succeeds.
+/// 
+/// # Examples
+/// ```
+/// use choliday::GuardedJob;
+/// 
+/// let job = GuardedJob::new("30 7 * * 1-5", "/usr/local/bin/backup.sh", "/usr/bin/choliday", "/etc/choliday.toml")?;
+/// assert_eq!(
+///     job.crontab(),
+///     "30 7 * * 1-5 /usr/bin/choliday -c /etc/choliday.toml >/dev/null && /usr/local/bin/backup.sh",
+/// );
+/// assert_eq!(job.on_calendar()?, "Mon..Fri *-*-* 07:30:00");
+/// 
+/// // Commands with quotes, spaces or shell syntax run through sh, quoted once
+/// // more; cron turns a bare % into a newline, so it is escaped
+/// let job = GuardedJob::new(
+///     "0 18 * * *",
+///     r#"echo "it's 100% done" >> '/var/log/my jobs.log'"#,
+///     "/opt/my tools/choliday",
+///     "/etc/choliday.toml",
+/// )?;
+/// assert_eq!(
+///     job.crontab(),
+///     r#"0 18 * * * '/opt/my tools/choliday' -c /etc/choliday.toml >/dev/null && sh -c 'echo "it'\''s 100\% done" >> '\''/var/log/my jobs.log'\'''"#,
+/// );
+/// assert!(job.systemd_service("report").contains(
+///     r#"ExecStart=/bin/sh -c "echo \"it's 100%% done\" >> '/var/log/my jobs.log'""#
+/// ));
+/// # Ok::<(), String>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct GuardedJob {
+    /// Schedule in crontab syntax, fields separated by single spaces
+    schedule: String,
+    /// Parsed schedule
+    fields: Fields,
+    /// Shell command to run on work days
+    command: String,
+    /// Path of the choliday executable
+    choliday: PathBuf,
+    /// Path of the configuration file
+    conf: PathBuf,
+}
+
+/// Times a crontab schedule selects
+#[derive(Clone, Debug)]
+struct Fields {
+    /// Minutes 0-59
+    minutes: Vec<u32>,
+    /// Hours 0-23
+    hours: Vec<u32>,
+    /// Days of the month 1-31
+    days: Vec<u32>,
+    /// Months 1-12
+    months: Vec<u32>,
+    /// Days of the week, 0 (Sunday) to 6
+    weekdays: Vec<u32>,
+    /// Whether both the day of the month and the day of the week are
+    /// restricted, so cron runs on either
+    either_day: bool,
+}
+
+/// Names of the months in crontab schedules, from January
+const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+/// Names of the days of the week in crontab schedules, from Sunday
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+/// Names of the days of the week in systemd calendar events, from Sunday
+const SYSTEMD_WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+impl GuardedJob {
+    /// Creates a guarded job
+    /// 
+    /// # Arguments
+    /// * `schedule` - Five crontab fields (minute, hour, day of month, month,
+    ///   day of week) with `*`, lists, ranges, steps and names, or a shorthand
+    ///   such as `@daily`
+    /// * `command` - Shell command to run on work days
+    /// * `choliday` - Path of the choliday executable
+    /// * `conf` - Path of the configuration file
+    /// 
+    /// # Returns
+    /// * `Err(String)` - The schedule is not valid or the command is empty
+    pub fn new(
+        schedule: &str,
+        command: &str,
+        choliday: impl Into<PathBuf>,
+        conf: impl Into<PathBuf>,
+    ) -> Result<Self, String> {
+        let schedule = schedule.split_whitespace().collect::<Vec<_>>().join(" ");
+        let fields = Fields::parse(&schedule)?;
+        if command.trim().is_empty() {
+            return Err("The command is empty".to_string());
+        }
+        Ok(GuardedJob {
+            schedule,
+            fields,
+            command: command.to_string(),
+            choliday: choliday.into(),
+            conf: conf.into(),
+        })
+    }
+
+    /// Formats the job as a crontab line
+    /// 
+    /// # Note
+    /// * The guard's answer is discarded so cron mails nothing on quiet days;
+    ///   its warnings still reach standard error
+    pub fn crontab(&self) -> String {
+        let command = if is_simple(&self.command) {
+            self.command.clone()
+        } else {
+            format!("sh -c {}", shell_quote(&self.command))
+        };
+        let line = format!(
+            "{} -c {} >/dev/null && {}",
+            shell_quote(&path_str(&self.choliday)),
+            shell_quote(&path_str(&self.conf)),
+            command,
+        );
+        format!("{} {}", self.schedule, line.replace('%', "\\%"))
+    }
+
+    /// Converts the schedule to a systemd calendar event, for `OnCalendar=`
+    /// 
+    /// # Returns
+    /// * `Err(String)` - The schedule restricts both the day of the month and
+    ///   the day of the week, which cron matches as either and systemd as both
+    pub fn on_calendar(&self) -> Result<String, String> {
+        let fields = &self.fields;
+        if fields.either_day {
+            return Err(format!(
+                "Schedule '{}' runs on either its days of the month or its days of the week, \
+                 which a systemd calendar event cannot express",
+                self.schedule
+            ));
+        }
+        let weekdays = if fields.weekdays.len() == 7 {
+            String::new()
+        } else {
+            // systemd weeks start on Monday
+            let mut monday_first: Vec<u32> = fields.weekdays.iter().map(|day| (day + 6) % 7).collect();
+            monday_first.sort_unstable();
+            format!("{} ", list(&monday_first, 7, |day| SYSTEMD_WEEKDAYS[((day + 1) % 7) as usize].to_string()))
+        };
+        let two_digits = |value: u32| format!("{:02}", value);
+        Ok(format!(
+            "{}*-{}-{} {}:{}:00",
+            weekdays,
+            list(&fields.months, 12, two_digits),
+            list(&fields.days, 31, two_digits),
+            list(&fields.hours, 24, two_digits),
+            list(&fields.minutes, 60, two_digits),
+        ))
+    }
+
+    /// Formats the systemd service running the job
+    /// 
+    /// # Arguments
+    /// * `name` - Name of the units, without `.service` or `.timer`
+    /// 
+    /// # Note
+    /// * `ExecCondition=` skips the run without failing the unit on days the
+    ///   guard answers anything but a work day
+    pub fn systemd_service(&self, name: &str) -> String {
+        let exec_start = if is_simple(&self.command) {
+            self.command.split_whitespace().map(systemd_quote).collect::<Vec<_>>().join(" ")
+        } else {
+            format!("/bin/sh -c {}", systemd_quote(&self.command))
+        };
+        format!(
+            "[Unit]\nDescription={} on work days\n\n[Service]\nType=oneshot\nExecCondition={} -c {}\nExecStart={}\n",
+            name,
+            systemd_quote(&path_str(&self.choliday)),
+            systemd_quote(&path_str(&self.conf)),
+            exec_start,
+        )
+    }
+
+    /// Formats the systemd timer starting the service on schedule
+    /// 
+    /// # Arguments
+    /// * `name` - Name of the units, without `.service` or `.timer`
+    /// 
+    /// # Returns
+    /// * `Err(String)` - The schedule has no systemd equivalent, see `on_calendar`
+    pub fn systemd_timer(&self, name: &str) -> Result<String, String> {
+        Ok(format!(
+            "[Unit]\nDescription=Schedule of {}.service ({})\n\n[Timer]\nOnCalendar={}\n\n[Install]\nWantedBy=timers.target\n",
+            name,
+            self.schedule,
+            self.on_calendar()?,
+        ))
+    }
+}
+
+impl Fields {
+    /// Parses a crontab schedule
+    fn parse(schedule: &str) -> Result<Self, String> {
+        let expanded = match schedule {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            _ if schedule.starts_with('@') => return Err(format!("Unsupported schedule '{}'", schedule)),
+            _ => schedule,
+        };
+        let parts: Vec<&str> = expanded.split(' ').collect();
+        let [minute, hour, day, month, weekday] = parts[..] else {
+            return Err(format!("Schedule '{}' must have 5 fields, got {}", schedule, parts.len()));
+        };
+        let mut weekdays = field(weekday, 0, 7, &WEEKDAYS)?;
+        // Both 0 and 7 are Sunday
+        if weekdays.last() == Some(&7) {
+            weekdays.pop();
+            if weekdays.first() != Some(&0) {
+                weekdays.insert(0, 0);
+            }
+        }
+        Ok(Fields {
+            minutes: field(minute, 0, 59, &[])?,
+            hours: field(hour, 0, 23, &[])?,
+            days: field(day, 1, 31, &[])?,
+            months: field(month, 1, 12, &MONTHS)?,
+            weekdays,
+            either_day: !day.starts_with('*') && !weekday.starts_with('*'),
+        })
+    }
+}
+
+/// Parses one crontab field into its sorted values
+/// 
+/// # Arguments
+/// * `text` - Comma-separated `*`, `N`, `N-M`, each optionally with `/STEP`
+/// * `min`, `max` - Range of values
+/// * `names` - Names standing for `min`, `min + 1`, ..., matched case-insensitively
+fn field(text: &str, min: u32, max: u32, names: &[&str]) -> Result<Vec<u32>, String> {
+    let value = |s: &str| -> Result<u32, String> {
+        let value = match names.iter().position(|name| name.eq_ignore_ascii_case(s)) {
+            Some(index) => min + index as u32,
+            None => s.parse().map_err(|_| format!("Invalid value '{}' in schedule field '{}'", s, text))?,
+        };
+        if !(min..=max).contains(&value) {
+            return Err(format!("Value {} in schedule field '{}' is outside {}-{}", value, text, min, max));
+        }
+        Ok(value)
+    };
+    let mut values = Vec::new();
+    for item in text.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(format!("Invalid step '{}' in schedule field '{}'", step, text)),
+            },
+            None => (item, 1),
+        };
+        let (first, last) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((first, last)) => (value(first)?, value(last)?),
+            // A single value with a step runs to the end of the range
+            None if step > 1 => (value(range)?, max),
+            None => (value(range)?, value(range)?),
+        };
+        if first > last {
+            return Err(format!("Range '{}' in schedule field '{}' is reversed", range, text));
+        }
+        values.extend((first..=last).step_by(step as usize));
+    }
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+/// Formats sorted values for a systemd calendar event
+/// 
+/// # Arguments
+/// * `values` - Sorted values
+/// * `all` - Number of possible values, `*` stands for all of them
+/// * `format` - Formats one value
+fn list(values: &[u32], all: usize, format: impl Fn(u32) -> String) -> String {
+    if values.len() == all {
+        return "*".to_string();
+    }
+    let mut parts = Vec::new();
+    let mut i = 0;
+    while i < values.len() {
+        let mut j = i;
+        while j + 1 < values.len() && values[j + 1] == values[j] + 1 {
+            j += 1;
+        }
+        if j - i >= 2 {
+            parts.push(format!("{}..{}", format(values[i]), format(values[j])));
+        } else {
+            parts.extend(values[i..=j].iter().map(|&value| format(value)));
+        }
+        i = j + 1;
+    }
+    parts.join(",")
+}
+
+/// Tells whether a command is plain words that need no quoting
+fn is_simple(command: &str) -> bool {
+    command.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ' || "_-./,:=@+".contains(c))
+}
+
+/// Returns a path as text, lossily
+fn path_str(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+/// Quotes a word for a POSIX shell
+/// 
+/// # Returns
+/// * The word itself when it needs no quoting, otherwise the word in single
+///   quotes, with each single quote written as `'\''`
+/// 
+/// # Examples
+/// ```
+/// use choliday::shell_quote;
+/// 
+/// assert_eq!(shell_quote("/usr/bin/choliday"), "/usr/bin/choliday");
+/// assert_eq!(shell_quote("my file"), "'my file'");
+/// assert_eq!(shell_quote("it's"), r"'it'\''s'");
+/// assert_eq!(shell_quote(""), "''");
+/// ```
+pub fn shell_quote(word: &str) -> String {
+    if !word.is_empty() && is_simple(word) && !word.contains(' ') {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+/// Quotes a word for a systemd command line
+/// 
+/// # Note
+/// * systemd expands `%` specifiers and `$` variables even in quotes, so
+///   both are doubled
+fn systemd_quote(word: &str) -> String {
+    let escaped = word.replace('%', "%%").replace('$', "$$");
+    if !word.is_empty() && is_simple(word) && !word.contains(' ') {
+        escaped
+    } else {
+        format!("\"{}\"", escaped.replace('\\', r"\\").replace('"', "\\\""))
+    }
+}
@@ -0,0 +1,146 @@
+//! CSV and plain date list calendar data, read as iCalendar properties.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+
+use chrono::NaiveDate;
+use ical::property::Property;
+use log::warn;
+
+use crate::ical::Ical;
+
+/// Column holding the date of a row
+const COLUMN_DATE: &str = "date";
+/// Column holding an explicit `work`/`rest` classification
+const COLUMN_TYPE: &str = "type";
+/// Column holding the name matched against the work/rest patterns
+const COLUMN_NAME: &str = "name";
+
+/// Converts CSV rows into the iCalendar properties of all-day events
+/// 
+/// # Arguments
+/// * `body` - CSV data, already decoded to UTF-8
+/// * `columns` - Column names in order; `date`, `type` and `name` are read,
+///   any other column is ignored
+/// * `explicit_property` - Property carrying the `type` column's classification
+/// 
+/// # Returns
+/// * `Ok(Vec<Property>)` - One VCALENDAR holding a VEVENT per readable row
+/// * `Err(String)` - `columns` has no `date` column
+/// 
+/// # Note
+/// * Dates are `YYYY-MM-DD` or `YYYYMMDD`
+/// * A row holding only a date is a rest day, so a plain list of dates works too
+/// * A `type` of `work` or `rest` classifies the row directly; otherwise the
+///   name is matched against the patterns
+/// * Blank lines, `#` comments and a header row are skipped; rows with an
+///   unreadable date are skipped with a warning naming the line
+pub fn properties(body: &[u8], columns: &[&str], explicit_property: &str) -> Result<Vec<Property>, String> {
+    let position = |name: &str| columns.iter().position(|c| c.eq_ignore_ascii_case(name));
+    let Some(date_col) = position(COLUMN_DATE) else {
+        return Err(format!("CSV columns {:?} have no '{}' column", columns, COLUMN_DATE));
+    };
+    let type_col = position(COLUMN_TYPE);
+    let name_col = position(COLUMN_NAME);
+
+    let text = String::from_utf8_lossy(body);
+    let mut props = vec![property("BEGIN", "VCALENDAR")];
+    for (number, line) in text.lines().enumerate().map(|(i, line)| (i + 1, line.trim())) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields = split_row(line);
+        let field = |col: Option<usize>| col.and_then(|col| fields.get(col)).map(|f| f.trim()).unwrap_or_default();
+        let date = match parse_date(field(Some(date_col))) {
+            Some(date) => date,
+            None if number == 1 && field(Some(date_col)).eq_ignore_ascii_case(COLUMN_DATE) => continue,
+            None => {
+                warn!("Skipping CSV line {}: unreadable date '{}'", number, field(Some(date_col)));
+                continue;
+            },
+        };
+
+        let kind = match field(type_col) {
+            "" if fields.len() == 1 => Some("REST"),
+            kind if kind.eq_ignore_ascii_case("work") => Some("WORK"),
+            kind if kind.eq_ignore_ascii_case("rest") => Some("REST"),
+            "" => None,
+            kind => {
+                warn!("CSV line {}: unknown type '{}', expected work or rest; matching its name instead", number, kind);
+                None
+            },
+        };
+
+        props.push(property("BEGIN", "VEVENT"));
+        props.push(property("SUMMARY", &Ical::escape_text(field(name_col))));
+        props.push(Property {
+            name: "DTSTART".to_string(),
+            params: Some(vec![("VALUE".to_string(), vec!["DATE".to_string()])]),
+            value: Some(date.format("%Y%m%d").to_string()),
+        });
+        if let Some(kind) = kind {
+            props.push(property(explicit_property, kind));
+        }
+        props.push(property("END", "VEVENT"));
+    }
+    props.push(property("END", "VCALENDAR"));
+    Ok(props)
+}
+
+/// Builds a property without parameters
+fn property(name: &str, value: &str) -> Property {
+    Property {
+        name: name.to_string(),
+        params: None,
+        value: Some(value.to_string()),
+    }
+}
+
+/// Parses a `YYYY-MM-DD` or `YYYYMMDD` date
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y%m%d"))
+        .ok()
+}
+
+/// Splits a CSV row on commas, honouring double-quoted fields with `""` escapes
+fn split_row(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            },
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
@@ -0,0 +1,804 @@
+//! Classification of a target date together with what decided it.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use std::fmt;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Utc, Weekday};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::ical::DayType;
+
+/// Classification of a target date and the reasons behind it
+/// 
+/// # Note
+/// * The serde representation, as printed by `--json`, is stable: field names,
+///   the `kind` tags of [`DecisionSource`] and the names of [`DayType`] and
+///   [`RestKind`] only ever gain new values
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DayDecision {
+    /// Target date and time, on the wall clock of the target zone
+    date: NaiveDateTime,
+    /// Final classification, `WorkDay`, `RestDay`, `Unknown`, or `ConflictDay`
+    /// for a conflict left unresolved
+    day_type: DayType,
+    /// Kind of rest day, `None` for work days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rest_kind: Option<RestKind>,
+    /// What decided the classification
+    source: DecisionSource,
+    /// Problems noticed along the way, such as failed sources or conflicting events
+    warnings: Vec<String>,
+    /// Audit record of why the classification was made, `None` for unknown days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    provenance: Option<Provenance>,
+    /// Events that classified the day in opposite ways, also described in `warnings`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    contradictions: Vec<Contradiction>,
+}
+
+/// Audit record of why a day was classified the way it was
+/// 
+/// # Note
+/// * Source URLs are passed through [`redact_url`](crate::redact_url), so
+///   credentials embedded in them never reach the output
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct Provenance {
+    /// Calendar source of the deciding event, its URL or path with credentials hidden
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// UID of the deciding event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+    /// Summary of the deciding event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Pattern or category the deciding event matched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Field the pattern matched in: `summary`, `description`, `location`,
+    /// `categories` or the explicit classification property
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Rule that chose among the classifying events, e.g. `priority RestOverWork`,
+    /// or `configured work days` when no event classified the day
+    pub rule: String,
+    /// Whether classifying events disagreed, so the rule settled a conflict
+    #[serde(default)]
+    pub conflict: bool,
+    /// Whether the explicit classification property decided, overriding patterns
+    #[serde(default)]
+    pub explicit_override: bool,
+    /// Whether no event classified the day and the configured work days decided
+    #[serde(default)]
+    pub fallback: bool,
+}
+
+/// Calendar events covering the same day that classify it in opposite ways
+/// 
+/// # Note
+/// * The priority rule (or a custom resolver) still decides the day; this
+///   only reports the disagreement, a data-quality problem in the sources
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct Contradiction {
+    /// Day the events disagree on
+    pub date: NaiveDate,
+    /// Events marking the day as a work or a rest day, ordered by start
+    pub events: Vec<ContradictingEvent>,
+    /// Rule that settled the disagreement, e.g. `priority RestOverWork`
+    pub rule: String,
+    /// Classification the rule chose, `None` when it chose neither and the
+    /// configured work days decided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub winner: Option<DayType>,
+}
+
+/// One side of a [`Contradiction`]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct ContradictingEvent {
+    /// Summary of the event
+    pub summary: String,
+    /// UID of the event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+    /// Calendar source of the event, its URL or path with credentials hidden
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// How the event alone classifies the day
+    pub day_type: DayType,
+    /// Whether the event decided the day
+    pub won: bool,
+}
+
+impl Provenance {
+    /// Creates the record of a day the configured work days decided
+    pub(crate) fn fallback() -> Self {
+        Provenance { rule: "configured work days".to_string(), fallback: true, ..Provenance::default() }
+    }
+}
+
+/// What decided the classification of a day
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum DecisionSource {
+    /// A calendar event matching a configured pattern or category
+    CalendarEvent {
+        /// Summary of the event
+        summary: String,
+        /// Calendar source the event was read from
+        source: Option<String>,
+        /// Pattern or category that matched, for the final classification
+        pattern: Option<String>,
+    },
+    /// A calendar event classified through the explicit classification property
+    ExplicitOverride {
+        /// Summary of the event
+        summary: String,
+        /// Calendar source the event was read from
+        source: Option<String>,
+        /// Name of the property, `X-CHOLIDAY-TYPE` by default
+        property: String,
+    },
+    /// A calendar event matching both work and rest, left unresolved under `on_conflict = "error"`
+    ConflictingEvent {
+        /// Summary of the event
+        summary: String,
+        /// Calendar source the event was read from
+        source: Option<String>,
+        /// Work pattern or category that matched
+        work_pattern: Option<String>,
+        /// Rest pattern or category that matched
+        rest_pattern: Option<String>,
+    },
+    /// No event classified the day, the configured work days decided
+    WeekdayFallback {
+        /// Day of the week of the target date
+        weekday: Weekday,
+    },
+    /// Not enough calendar data to classify the day
+    Unavailable {
+        /// Why the data was not enough, e.g. the unmet source requirement
+        reason: String,
+    },
+}
+
+/// Kind of a rest day
+/// 
+/// # Note
+/// * Serialized as `rest`, `weekend`, `public_holiday` and `leave`; unknown
+///   names are rejected
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub enum RestKind {
+    /// Rest day without a configured kind
+    #[serde(rename = "rest")]
+    Rest,
+    /// Day off by the configured work days
+    #[serde(rename = "weekend")]
+    Weekend,
+    /// Event matching a `rest_public` pattern or category
+    #[serde(rename = "public_holiday")]
+    PublicHoliday,
+    /// Event matching a `rest_personal` pattern or category
+    #[serde(rename = "leave")]
+    Leave,
+}
+
+impl RestKind {
+    /// Returns the stable name of the kind, as serialized
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RestKind::Rest => "rest",
+            RestKind::Weekend => "weekend",
+            RestKind::PublicHoliday => "public_holiday",
+            RestKind::Leave => "leave",
+        }
+    }
+}
+
+/// Every event considered for a target date, and the answer without them
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct DecisionTrace {
+    /// The decision, as `evaluate` returns it
+    pub decision: DayDecision,
+    /// Events whose interval contains the target instant, ordered by start
+    pub events: Vec<TracedEvent>,
+    /// Classification the configured work days give the date, as if no
+    /// calendar event applied
+    pub fallback: DayType,
+}
+
+/// An event considered for a target date
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct TracedEvent {
+    /// Summary of the event
+    pub summary: String,
+    /// Calendar source the event was read from
+    pub source: Option<String>,
+    /// Start of the event, on the wall clock of the target zone (floating for all-day events)
+    pub start: NaiveDateTime,
+    /// End of the event, excluded
+    pub end: NaiveDateTime,
+    /// Start of a timed event in the target zone, with its offset; `None` for all-day events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<DateTime<FixedOffset>>,
+    /// End of a timed event in the target zone, with its offset, excluded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<DateTime<FixedOffset>>,
+    /// IANA name of the target zone, `None` for the system zone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone: Option<String>,
+    /// Whether the event is an all-day event
+    pub all_day: bool,
+    /// How the event alone classifies the day, `normal_day` when nothing matched
+    pub day_type: DayType,
+    /// Work pattern or category that matched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub work_pattern: Option<String>,
+    /// Rest pattern or category that matched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rest_pattern: Option<String>,
+    /// Field the classification was read from: `summary`, `description`,
+    /// `location`, `categories` or the explicit classification property
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// What priority resolution did with the event
+    pub outcome: TraceOutcome,
+}
+
+/// What priority resolution did with an event
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub enum TraceOutcome {
+    /// The event decided the day
+    #[serde(rename = "decisive")]
+    Decisive,
+    /// The event agrees with the decision, another event decided it
+    #[serde(rename = "kept")]
+    Kept,
+    /// The event was overruled by the priority rule or by an explicitly classified event
+    #[serde(rename = "discarded")]
+    Discarded,
+    /// No pattern, category or explicit property classified the event
+    #[serde(rename = "unclassified")]
+    Unclassified,
+    /// The event is transparent and `ignore_transparent` is set
+    #[serde(rename = "transparent")]
+    Transparent,
+}
+
+/// A calendar event as library users see it, without parser internals
+/// 
+/// # Note
+/// * All-day events are floating: their start and end are midnights on the
+///   wall clock of the target zone
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct PublicEvent {
+    /// Summary of the event
+    pub(crate) summary: String,
+    /// Description of the event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) description: Option<String>,
+    /// Start of the event
+    pub(crate) start: DateTime<Utc>,
+    /// End of the event, excluded
+    pub(crate) end: DateTime<Utc>,
+    /// Start on the wall clock of the target zone
+    pub(crate) local_start: NaiveDateTime,
+    /// End on the wall clock of the target zone, excluded
+    pub(crate) local_end: NaiveDateTime,
+    /// Whether the event is an all-day event
+    pub(crate) all_day: bool,
+    /// Calendar source the event was read from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) source: Option<String>,
+    /// How the event alone classifies its days, `normal_day` when nothing matched
+    pub(crate) day_type: DayType,
+    /// Kind of rest day the event marks, `None` unless it is a rest event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) rest_kind: Option<RestKind>,
+}
+
+/// Numbers of work and rest days in a date range
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct WorkDayCount {
+    /// Days classified as work days
+    pub work: usize,
+    /// Days classified as rest days
+    pub rest: usize,
+    /// Days, among the work and rest days, on which calendar events disagreed
+    /// and the priority rule decided
+    pub conflict: usize,
+    /// Days no calendar event classified, with `predict.require_calendar`
+    #[serde(default)]
+    pub unknown: usize,
+    /// Days decided by an event matching both work and rest, with `predict.on_conflict = "error"`
+    #[serde(default)]
+    pub unresolved: usize,
+}
+
+/// Unbroken run of days sharing a classification
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Streak {
+    /// Classification shared by the days, `WorkDay`, `RestDay` or `Unknown`
+    pub day_type: DayType,
+    /// First day of the run
+    pub start: NaiveDate,
+    /// Last day of the run, included
+    pub end: NaiveDate,
+    /// Whether the run was cut off by `base.horizon_days` on either side
+    pub truncated: bool,
+}
+
+/// Run of work days between two runs of rest days, worth taking off
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct Bridge {
+    /// First work day of the bridge
+    pub start: NaiveDate,
+    /// Last work day of the bridge, included
+    pub end: NaiveDate,
+    /// First rest day of the block the bridge would join, before the bridge
+    pub rest_start: NaiveDate,
+    /// Last rest day of the block the bridge would join, after the bridge
+    pub rest_end: NaiveDate,
+}
+
+/// Block of consecutive rest days, e.g. a long weekend
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct RestBlock {
+    /// First rest day of the block
+    pub start: NaiveDate,
+    /// Last rest day of the block, included
+    pub end: NaiveDate,
+    /// Whether any day of the block is a public holiday, rather than only
+    /// weekends, leave and other rest days
+    pub public_holiday: bool,
+}
+
+/// Day on which the classification changes from that of the day before
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct Transition {
+    /// First day with the new classification
+    pub date: NaiveDate,
+    /// Classification of the day before
+    pub previous: DayType,
+    /// New classification, `WorkDay`, `RestDay`, `Unknown` or `ConflictDay`
+    pub day_type: DayType,
+    /// Kind of rest day the transition leads to, `None` for work days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rest_kind: Option<RestKind>,
+    /// What decided the new classification
+    pub source: DecisionSource,
+}
+
+/// Day two analyzers classify differently, see [`Choliday::diff`](crate::Choliday::diff)
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct DayDiff {
+    /// Date classified differently
+    pub date: NaiveDate,
+    /// Decision of the analyzer `diff` was called on
+    pub current: DayDecision,
+    /// Decision of the analyzer compared against
+    pub against: DayDecision,
+}
+
+impl Streak {
+    /// Returns the number of days in the run
+    pub fn days(&self) -> i64 {
+        (self.end - self.start).num_days() + 1
+    }
+}
+
+impl Bridge {
+    /// Returns the number of work days to take off
+    pub fn work_days(&self) -> i64 {
+        (self.end - self.start).num_days() + 1
+    }
+
+    /// Returns the number of days off in a row if the bridge were taken
+    pub fn days_off(&self) -> i64 {
+        (self.rest_end - self.rest_start).num_days() + 1
+    }
+}
+
+impl RestBlock {
+    /// Returns the number of days in the block
+    pub fn days(&self) -> i64 {
+        (self.end - self.start).num_days() + 1
+    }
+}
+
+impl WorkDayCount {
+    /// Returns the number of days counted
+    pub fn days(&self) -> usize {
+        self.work + self.rest + self.unknown + self.unresolved
+    }
+}
+
+impl PublicEvent {
+    /// Returns the summary of the event
+    pub fn summary(&self) -> &str {
+        &self.summary
+    }
+
+    /// Returns the description of the event, if any
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns the start of the event
+    pub fn start(&self) -> DateTime<Utc> {
+        self.start
+    }
+
+    /// Returns the end of the event, excluded
+    pub fn end(&self) -> DateTime<Utc> {
+        self.end
+    }
+
+    /// Returns the start on the wall clock of the target zone
+    pub fn local_start(&self) -> NaiveDateTime {
+        self.local_start
+    }
+
+    /// Returns the end on the wall clock of the target zone, excluded
+    /// 
+    /// # Note
+    /// * An all-day event on one day ends at midnight of the next day
+    pub fn local_end(&self) -> NaiveDateTime {
+        self.local_end
+    }
+
+    /// Returns whether the event is an all-day event
+    pub fn is_all_day(&self) -> bool {
+        self.all_day
+    }
+
+    /// Returns the name of the calendar source the event was read from
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Returns how the event alone classifies its days
+    pub fn day_type(&self) -> DayType {
+        self.day_type
+    }
+
+    /// Returns the kind of rest day the event marks, `None` unless it is a rest event
+    pub fn rest_kind(&self) -> Option<RestKind> {
+        self.rest_kind
+    }
+}
+
+impl DayDecision {
+    /// Creates a decision
+    /// 
+    /// # Arguments
+    /// * `date` - Target date and time, on the wall clock of the target zone
+    /// * `day_type` - Final classification
+    /// * `rest_kind` - Kind of rest day, `None` for work days
+    /// * `source` - What decided the classification
+    /// * `warnings` - Problems noticed while deciding
+    pub(crate) fn new(
+        date: NaiveDateTime,
+        day_type: DayType,
+        rest_kind: Option<RestKind>,
+        source: DecisionSource,
+        warnings: Vec<String>,
+    ) -> Self {
+        DayDecision { date, day_type, rest_kind, source, warnings, provenance: None, contradictions: Vec::new() }
+    }
+
+    /// Attaches events that disagreed on the day, also adding it to the warnings
+    /// 
+    /// # Arguments
+    /// * `contradiction` - Disagreement the priority rule settled, if any
+    pub(crate) fn with_contradiction(mut self, contradiction: Option<Contradiction>) -> Self {
+        if let Some(contradiction) = contradiction {
+            self.warnings.push(contradiction.to_string());
+            self.contradictions.push(contradiction);
+        }
+        self
+    }
+
+    /// Attaches the audit record of the decision
+    /// 
+    /// # Arguments
+    /// * `provenance` - Record of the deciding event, if an event decided
+    /// 
+    /// # Note
+    /// * Days decided by the configured work days get their own record
+    ///   whatever is passed, unknown days none
+    pub(crate) fn with_provenance(mut self, provenance: Option<Provenance>) -> Self {
+        self.provenance = match self.source {
+            DecisionSource::WeekdayFallback { .. } => Some(Provenance::fallback()),
+            DecisionSource::Unavailable { .. } => None,
+            _ => provenance,
+        };
+        self
+    }
+
+    /// Creates an unknown decision
+    /// 
+    /// # Arguments
+    /// * `date` - Target date and time, on the wall clock of the target zone
+    /// * `reason` - Why the calendar data was not enough
+    /// * `warnings` - Problems noticed while deciding
+    pub(crate) fn unknown(date: NaiveDateTime, reason: String, warnings: Vec<String>) -> Self {
+        DayDecision::new(date, DayType::Unknown, None, DecisionSource::Unavailable { reason }, warnings)
+    }
+
+    /// Returns why the classification was made, `None` for unknown days
+    pub fn provenance(&self) -> Option<&Provenance> {
+        self.provenance.as_ref()
+    }
+
+    /// Returns the target date and time, on the wall clock of the target zone
+    pub fn date(&self) -> NaiveDateTime {
+        self.date
+    }
+
+    /// Returns the final classification, `WorkDay`, `RestDay`, `Unknown`, or
+    /// `ConflictDay` for a conflict left unresolved
+    pub fn day_type(&self) -> DayType {
+        self.day_type
+    }
+
+    /// Returns whether the target date is a work day
+    /// 
+    /// # Note
+    /// * `false` for unknown days too; check `is_unknown` first where that matters
+    pub fn is_work_day(&self) -> bool {
+        self.day_type == DayType::WorkDay
+    }
+
+    /// Returns whether an event matching both work and rest left the target
+    /// date unresolved, under `on_conflict = "error"`
+    pub fn is_unresolved(&self) -> bool {
+        self.day_type == DayType::ConflictDay
+    }
+
+    /// Returns whether there was not enough calendar data to classify the target date
+    pub fn is_unknown(&self) -> bool {
+        self.day_type == DayType::Unknown
+    }
+
+    /// Returns the kind of rest day, `None` for work days
+    /// 
+    /// # Note
+    /// * Only tells rest days apart; `is_work_day` does not depend on it
+    pub fn rest_kind(&self) -> Option<RestKind> {
+        self.rest_kind
+    }
+
+    /// Returns what decided the classification
+    pub fn source(&self) -> &DecisionSource {
+        &self.source
+    }
+
+    /// Returns the problems noticed while deciding, empty when there were none
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Returns the calendar events that classified the day in opposite ways,
+    /// empty when they all agreed
+    /// 
+    /// # Examples
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use choliday::{Choliday, Conf, DayType, Priority};
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), String> {
+    /// // Two feeds disagreeing about May Day
+    /// let temp = tempfile::tempdir().map_err(|e| e.to_string())?;
+    /// let dir = temp.path();
+    /// let feed = |name: &str, summary: &str| -> Result<String, String> {
+    ///     let path = dir.join(name);
+    ///     let calendar = format!(
+    ///         "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:{}\r\nDTSTART;VALUE=DATE:20260501\r\nSUMMARY:{}\r\n\
+    ///          END:VEVENT\r\nEND:VCALENDAR\r\n",
+    ///         name, summary,
+    ///     );
+    ///     std::fs::write(&path, calendar).map_err(|e| e.to_string())?;
+    ///     Ok(path.to_string_lossy().into_owned())
+    /// };
+    /// let conf = Conf::builder(Priority::RestOverWork)
+    ///     .source(feed("work.ics", "Make-up workday")?)
+    ///     .source(feed("rest.ics", "Labour Day holiday")?)
+    ///     .work("workday")
+    ///     .rest("holiday")
+    ///     .build()?;
+    /// let choliday = Choliday::builder(conf).date(NaiveDate::from_ymd_opt(2026, 5, 1).unwrap()).build()?;
+    /// let decision = choliday.evaluate().await?;
+    /// assert_eq!(decision.day_type(), DayType::RestDay);
+    /// 
+    /// let contradiction = &decision.contradictions()[0];
+    /// assert_eq!(contradiction.winner, Some(DayType::RestDay));
+    /// let sides: Vec<_> = contradiction.events.iter().map(|e| (e.summary.as_str(), e.day_type, e.won)).collect();
+    /// assert_eq!(sides, [("Make-up workday", DayType::WorkDay, false), ("Labour Day holiday", DayType::RestDay, true)]);
+    /// assert!(contradiction.events[1].source.as_ref().unwrap().ends_with("/rest.ics"));
+    /// assert!(decision.warnings().contains(&contradiction.to_string()));
+    /// assert!(contradiction.to_string().ends_with("priority RestOverWork chose 'Labour Day holiday'"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn contradictions(&self) -> &[Contradiction] {
+        &self.contradictions
+    }
+}
+
+impl fmt::Display for RestKind {
+    /// Names the kind of rest day for humans
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RestKind::Rest => "rest day",
+            RestKind::Weekend => "weekend",
+            RestKind::PublicHoliday => "public holiday",
+            RestKind::Leave => "personal leave",
+        })
+    }
+}
+
+impl fmt::Display for TracedEvent {
+    /// Describes the event in one line, e.g. in `--explain` and `list` output
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<12} {:<12} ", self.outcome.to_string(), self.day_type.to_string())?;
+        write!(f, "{}  '{}'", crate::output::event_span(self), self.summary)?;
+        if let Some(source) = &self.source {
+            write!(f, " from '{}'", source)?;
+        }
+        let patterns: Vec<String> = [&self.work_pattern, &self.rest_pattern]
+            .into_iter()
+            .flatten()
+            .map(|pattern| format!("'{}'", pattern))
+            .collect();
+        match (&self.field, patterns.is_empty()) {
+            (Some(field), false) => write!(f, " ({} matching {})", field, patterns.join(" and ")),
+            (Some(field), true) => write!(f, " (by {})", field),
+            (None, _) => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Contradiction {
+    /// Describes the disagreement in one line, e.g. `Conflicting events on 2026-05-01:
+    /// 'Workday' (work_day) from 'a.ics', 'Labour Day' (rest_day) from 'b.ics';
+    /// priority RestOverWork chose 'Labour Day'`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Conflicting events on {}: ", self.date)?;
+        for (i, event) in self.events.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "'{}' ({})", event.summary, event.day_type)?;
+            if let Some(source) = &event.source {
+                write!(f, " from '{}'", source)?;
+            }
+        }
+        match (self.events.iter().find(|event| event.won), self.winner) {
+            (Some(event), _) => write!(f, "; {} chose '{}'", self.rule, event.summary),
+            (None, Some(winner)) => write!(f, "; {} chose {}", self.rule, winner),
+            (None, None) => write!(f, "; {} chose neither, the configured work days decided", self.rule),
+        }
+    }
+}
+
+impl fmt::Display for Bridge {
+    /// Describes the bridge in one line, e.g. `2026-01-02 (1 work day): 4 days off from 2026-01-01 to 2026-01-04`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "{} (1 work day)", self.start)?;
+        } else {
+            write!(f, "{} - {} ({} work days)", self.start, self.end, self.work_days())?;
+        }
+        write!(f, ": {} days off from {} to {}", self.days_off(), self.rest_start, self.rest_end)
+    }
+}
+
+impl fmt::Display for RestBlock {
+    /// Describes the block in one line, e.g. `2026-04-30 - 2026-05-03 (4 days, public holiday)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} - {} ({} days", self.start, self.end, self.days())?;
+        if self.public_holiday {
+            f.write_str(", public holiday")?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl fmt::Display for Transition {
+    /// Describes the change in one line, e.g. `rest_day -> work_day on 2025-10-09 (event 'Makeup workday')`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {} on {} ({})", self.previous, self.day_type, self.date, self.source)
+    }
+}
+
+impl fmt::Display for TraceOutcome {
+    /// Writes the serialized name of the outcome
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TraceOutcome::Decisive => "decisive",
+            TraceOutcome::Kept => "kept",
+            TraceOutcome::Discarded => "discarded",
+            TraceOutcome::Unclassified => "unclassified",
+            TraceOutcome::Transparent => "transparent",
+        })
+    }
+}
+
+impl fmt::Display for DecisionSource {
+    /// Describes the decision source for humans, e.g. in `--explain` output
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecisionSource::CalendarEvent { summary, source, pattern } => {
+                write!(f, "event '{}'", summary)?;
+                if let Some(source) = source {
+                    write!(f, " from '{}'", source)?;
+                }
+                match pattern {
+                    Some(pattern) => write!(f, " matching '{}'", pattern),
+                    None => Ok(()),
+                }
+            },
+            DecisionSource::ExplicitOverride { summary, source, property } => {
+                write!(f, "event '{}'", summary)?;
+                if let Some(source) = source {
+                    write!(f, " from '{}'", source)?;
+                }
+                write!(f, " classified by {}", property)
+            },
+            DecisionSource::ConflictingEvent { summary, source, work_pattern, rest_pattern } => {
+                write!(f, "event '{}'", summary)?;
+                if let Some(source) = source {
+                    write!(f, " from '{}'", source)?;
+                }
+                write!(f, " matching both")?;
+                if let Some(pattern) = work_pattern {
+                    write!(f, " work pattern '{}'", pattern)?;
+                }
+                if let Some(pattern) = rest_pattern {
+                    write!(f, " and rest pattern '{}'", pattern)?;
+                }
+                Ok(())
+            },
+            DecisionSource::WeekdayFallback { weekday } => write!(f, "configured work days ({})", weekday),
+            DecisionSource::Unavailable { reason } => write!(f, "missing calendar data: {}", reason),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! External commands printing calendar data.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use std::{path::Path, process::Stdio};
+
+use tokio::{
+    io::{AsyncRead, AsyncReadExt},
+    process::Command,
+    time,
+};
+
+use crate::{conf::Exec, source};
+
+/// Bytes of standard error kept for the error message of a failed command
+const STDERR_LIMIT: usize = 4096;
+
+/// Runs an external command and captures its standard output
+/// 
+/// # Arguments
+/// * `exec` - Program, arguments and timeout
+/// * `conf_dir` - Directory the command runs in and a relative program path resolves against
+/// * `max_bytes` - Largest output accepted
+/// 
+/// # Returns
+/// * `Ok(Vec<u8>)` - Standard output of a command that exited successfully
+/// * `Err(String)` - The command cannot be started, timed out, printed more
+///   than `max_bytes` or exited with a failure, with its standard error
+/// 
+/// # Note
+/// * The arguments are passed as they are, never through a shell
+/// * Standard input is empty, and the command is killed on timeout
+pub async fn run(exec: &Exec, conf_dir: Option<&Path>, max_bytes: u64) -> Result<Vec<u8>, String> {
+    // A program given as a path resolves like a local source, a bare name through PATH
+    let program = exec.program();
+    let program = if program.contains('/') {
+        source::resolve_path(program, conf_dir)
+    } else {
+        program.into()
+    };
+    let mut command = Command::new(&program);
+    command
+        .args(exec.args())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(dir) = conf_dir {
+        command.current_dir(dir);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Cannot run '{}': {}", program.display(), e))?;
+    let (Some(stdout), Some(stderr)) = (child.stdout.take(), child.stderr.take()) else {
+        return Err(format!("Cannot capture the output of '{}'", program.display()));
+    };
+
+    let timeout = exec.timeout();
+    let finished = time::timeout(timeout, async {
+        let limit = usize::try_from(max_bytes).unwrap_or(usize::MAX);
+        let (stdout, stderr) = tokio::join!(read_capped(stdout, limit, false), read_capped(stderr, STDERR_LIMIT, true));
+        let status = child.wait().await;
+        (stdout, stderr, status)
+    })
+    .await;
+    // Dropping the child on timeout kills it
+    let Ok((stdout, stderr, status)) = finished else {
+        return Err(format!("Command timed out after {}s", timeout.as_secs()));
+    };
+
+    let (stdout, complete) = stdout.map_err(|e| format!("Cannot read the output of the command: {}", e))?;
+    // Checked first, as closing the output early usually ends the command with SIGPIPE
+    if !complete {
+        return Err(format!("Command output exceeds http.max_body_bytes ({} bytes)", max_bytes));
+    }
+    let status = status.map_err(|e| format!("Cannot wait for the command: {}", e))?;
+    if !status.success() {
+        let stderr = stderr.map(|(bytes, _)| String::from_utf8_lossy(&bytes).trim().to_string()).unwrap_or_default();
+        return Err(match stderr.is_empty() {
+            true => format!("Command failed with {}", status),
+            false => format!("Command failed with {}: {}", status, stderr),
+        });
+    }
+    Ok(stdout)
+}
+
+/// Reads a stream, keeping at most `limit` bytes
+/// 
+/// # Arguments
+/// * `reader` - Stream to read
+/// * `limit` - Largest number of bytes kept
+/// * `drain` - Whether to read past `limit` to the end, discarding the
+///   rest, rather than stop and let the stream close
+/// 
+/// # Returns
+/// * The bytes kept, and whether they are all the stream held
+async fn read_capped(mut reader: impl AsyncRead + Unpin, limit: usize, drain: bool) -> std::io::Result<(Vec<u8>, bool)> {
+    let mut kept = Vec::new();
+    let mut buf = [0u8; 8192];
+    let mut complete = true;
+    loop {
+        let read = reader.read(&mut buf).await?;
+        if read == 0 {
+            return Ok((kept, complete));
+        }
+        let room = limit.saturating_sub(kept.len());
+        kept.extend_from_slice(&buf[..read.min(room)]);
+        if read > room {
+            complete = false;
+            if !drain {
+                return Ok((kept, complete));
+            }
+        }
+    }
+}
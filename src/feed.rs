@@ -0,0 +1,150 @@
+//! iCalendar feed of predicted rest days and makeup work days.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use chrono::TimeDelta;
+
+use crate::{
+    cache::fnv1a,
+    decision::{DayDecision, DecisionSource},
+    ical::DayType,
+};
+
+/// Product identifier of generated feeds, stable across releases
+const PRODID: &str = "-//66f94eae//choliday predictions//EN";
+/// Longest content line of a generated feed in octets, excluding the line break
+const MAX_LINE_OCTETS: usize = 75;
+/// Date value format of iCalendar (YYYYmmDD)
+const ICS_DATE_FORMAT: &str = "%Y%m%d";
+
+/// iCalendar feed of predicted days, see [`Choliday::feed`](crate::Choliday::feed)
+/// 
+/// # Note
+/// * The feed only depends on the decisions it lists: UIDs are derived from
+///   the date and DTSTAMP from the day itself, so unchanged calendar data
+///   gives the same feed and the same ETag
+#[derive(Clone, Debug)]
+pub struct Feed {
+    /// VCALENDAR text with CRLF line breaks
+    body: String,
+    /// Quoted entity tag of the body
+    etag: String,
+}
+
+impl Feed {
+    /// Writes the feed for decided days
+    /// 
+    /// # Arguments
+    /// * `days` - Work or rest days to list, one all-day event each
+    /// * `property` - Explicit classification property marking every event
+    ///   `WORK` or `REST`, so the feed classifies its days when read back
+    pub(crate) fn new(days: &[DayDecision], property: &str) -> Self {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            format!("PRODID:{}", PRODID),
+            "CALSCALE:GREGORIAN".to_string(),
+            "METHOD:PUBLISH".to_string(),
+            "X-WR-CALNAME:Choliday predictions".to_string(),
+        ];
+        for decision in days {
+            let date = decision.date().date();
+            let (kind, value, fallback) = match decision.day_type() {
+                DayType::WorkDay => ("work", "WORK", "Work day"),
+                _ => ("rest", "REST", "Rest day"),
+            };
+            let summary = match decision.source() {
+                DecisionSource::CalendarEvent { summary, .. } | DecisionSource::ExplicitOverride { summary, .. } => {
+                    summary.as_str()
+                },
+                _ => fallback,
+            };
+            let start = date.format(ICS_DATE_FORMAT);
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                format!("UID:{}-{}@choliday", start, kind),
+                format!("DTSTAMP:{}T000000Z", start),
+                format!("DTSTART;VALUE=DATE:{}", start),
+                format!("DTEND;VALUE=DATE:{}", (date + TimeDelta::days(1)).format(ICS_DATE_FORMAT)),
+                format!("SUMMARY:{}", escape(summary)),
+                format!("{}:{}", property, value),
+                "END:VEVENT".to_string(),
+            ]);
+        }
+        lines.push("END:VCALENDAR".to_string());
+
+        let body: String = lines.iter().map(|line| fold(line) + "\r\n").collect();
+        let etag = format!("\"{:016x}\"", fnv1a(body.as_bytes()));
+        Feed { body, etag }
+    }
+
+    /// Returns the VCALENDAR text, with CRLF line breaks
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Returns the entity tag of the body, quoted for an HTTP `ETag` header
+    pub fn etag(&self) -> &str {
+        &self.etag
+    }
+}
+
+/// Escapes a TEXT value (RFC 5545 section 3.3.11)
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            },
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {},
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Folds a content line longer than 75 octets (RFC 5545 section 3.1)
+/// 
+/// # Note
+/// * Lines are only broken between characters, never inside a UTF-8 sequence
+/// * Spaces before a break move to the continuation line, as some readers
+///   trim trailing whitespace before unfolding
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            let carried = folded.split_off(folded.trim_end_matches(' ').len());
+            folded.push_str("\r\n ");
+            folded.push_str(&carried);
+            // The leading space counts towards the continuation line
+            octets = 1 + carried.len();
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
@@ -0,0 +1,237 @@
+//! C bindings over the blocking API, for callers in other languages.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    ffi::{CStr, CString, c_char},
+    panic::{AssertUnwindSafe, catch_unwind},
+    ptr,
+};
+
+use chrono::{DateTime, Utc};
+use tokio::runtime::Runtime;
+
+use crate::{blocking, choliday::Choliday, conf::Conf, decision::DecisionSource};
+
+thread_local! {
+    /// Message of the last failed `choliday_new_from_config_path` on this thread
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Outcome of a call, returned by every function that can fail
+/// 
+/// # Note
+/// * `Error`, `Unknown` and `Unresolved` have the values of the matching exit
+///   codes of the command line
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CholidayStatus {
+    /// The call succeeded
+    Ok = 0,
+    /// A pointer argument was null, a string not UTF-8 or a timestamp out of range
+    InvalidArgument = 1,
+    /// The configuration could not be loaded or the call failed otherwise
+    Error = 2,
+    /// An event matching both work and rest decides the day under `on_conflict = "error"`
+    Unresolved = 3,
+    /// The calendar data is not enough to classify the day
+    Unknown = 4,
+    /// The library panicked; the handle is best freed
+    Panic = 5,
+}
+
+/// Analyzer behind a handle, named `Choliday` in the C header
+/// 
+/// # Note
+/// * A handle may move between threads, but must not be used by two at once
+pub struct CholidayHandle {
+    /// Analyzer keeping the events it read between calls
+    choliday: Choliday,
+    /// Runtime every call blocks on
+    runtime: Runtime,
+    /// Message of the last failed call, `None` after a successful one
+    last_error: Option<CString>,
+}
+
+impl CholidayHandle {
+    /// Records the message of a failed call and returns its status
+    fn fail(&mut self, status: CholidayStatus, message: impl Into<String>) -> CholidayStatus {
+        self.last_error = Some(c_string(message.into()));
+        status
+    }
+}
+
+/// Loads a configuration file and creates a handle answering from its sources
+/// 
+/// # Arguments
+/// * `path` - Path of the TOML configuration file, a NUL-terminated UTF-8 string
+/// 
+/// # Returns
+/// * A handle to pass to the other functions and free with [`choliday_free`]
+/// * `NULL` on failure; `choliday_last_error_message(NULL)` then tells why
+/// 
+/// # Note
+/// * Sources are read by the first query, not here
+/// 
+/// # Safety
+/// * `path` is `NULL` or points to a NUL-terminated string
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn choliday_new_from_config_path(path: *const c_char) -> *mut CholidayHandle {
+    let created = catch_unwind(|| {
+        if path.is_null() {
+            return Err("Configuration path is NULL".to_string());
+        }
+        // SAFETY: the caller passes a NUL-terminated string
+        let path = unsafe { CStr::from_ptr(path) }
+            .to_str()
+            .map_err(|_| "Configuration path is not UTF-8".to_string())?;
+        let conf = Conf::load(path)?;
+        let now = conf.target_zone().to_wall_clock(Utc::now().timestamp_millis());
+        let runtime = blocking::runtime()?;
+        Ok(CholidayHandle { choliday: Choliday::new(conf, now), runtime, last_error: None })
+    });
+    let error = match created {
+        Ok(Ok(handle)) => {
+            LAST_ERROR.with(|last| *last.borrow_mut() = None);
+            return Box::into_raw(Box::new(handle));
+        },
+        Ok(Err(e)) => e,
+        Err(panic) => panic_message(panic),
+    };
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(c_string(error)));
+    ptr::null_mut()
+}
+
+/// Determines if an instant falls on a work day
+/// 
+/// # Arguments
+/// * `handle` - Handle from [`choliday_new_from_config_path`]
+/// * `epoch_millis` - Unix timestamp in milliseconds, classified at its
+///   wall-clock time in the target zone like `-d` does
+/// * `out` - Set to whether the instant falls on a work day, on success only
+/// 
+/// # Returns
+/// * `CHOLIDAY_STATUS_OK` with `*out` set, or the reason it was not
+/// 
+/// # Note
+/// * Blocks until the sources are read; all events are read by the first
+///   call and kept for later ones
+/// * Decisions are the same as `Choliday::is_work_day` for the instant
+/// 
+/// # Safety
+/// * `handle` is `NULL` or a live handle not used by another thread meanwhile
+/// * `out` is `NULL` or points to writable memory for a `bool`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn choliday_is_work_day(
+    handle: *mut CholidayHandle,
+    epoch_millis: i64,
+    out: *mut bool,
+) -> CholidayStatus {
+    // SAFETY: the caller passes a live handle used by this thread only
+    let Some(handle) = (unsafe { handle.as_mut() }) else {
+        return CholidayStatus::InvalidArgument;
+    };
+    handle.last_error = None;
+    if out.is_null() {
+        return handle.fail(CholidayStatus::InvalidArgument, "Output pointer is NULL");
+    }
+    if DateTime::from_timestamp_millis(epoch_millis).is_none() {
+        return handle.fail(CholidayStatus::InvalidArgument, format!("Timestamp {} is out of range", epoch_millis));
+    }
+
+    let evaluated = catch_unwind(AssertUnwindSafe(|| {
+        handle.runtime.block_on(handle.choliday.evaluate_instant(epoch_millis))
+    }));
+    let decision = match evaluated {
+        Ok(decision) => decision,
+        Err(panic) => return handle.fail(CholidayStatus::Panic, panic_message(panic)),
+    };
+    match decision.source() {
+        DecisionSource::Unavailable { reason } => handle.fail(CholidayStatus::Unknown, reason.clone()),
+        source if decision.is_unresolved() => {
+            handle.fail(CholidayStatus::Unresolved, format!("Unresolved conflict: {}", source))
+        },
+        _ => {
+            // SAFETY: checked for NULL above, the caller passes writable memory
+            unsafe { out.write(decision.is_work_day()) };
+            CholidayStatus::Ok
+        },
+    }
+}
+
+/// Returns the message of the last failed call
+/// 
+/// # Arguments
+/// * `handle` - Handle the call was made with, or `NULL` for the last
+///   failed [`choliday_new_from_config_path`] on this thread
+/// 
+/// # Returns
+/// * A NUL-terminated UTF-8 string owned by the library, valid until the next
+///   call with the same handle (or on this thread) or until it is freed
+/// * `NULL` when the last call succeeded
+/// 
+/// # Safety
+/// * `handle` is `NULL` or a live handle
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn choliday_last_error_message(handle: *const CholidayHandle) -> *const c_char {
+    // SAFETY: the caller passes a live handle
+    match unsafe { handle.as_ref() } {
+        Some(handle) => handle.last_error.as_ref().map_or(ptr::null(), |message| message.as_ptr()),
+        None => LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr())),
+    }
+}
+
+/// Frees a handle and everything it keeps
+/// 
+/// # Arguments
+/// * `handle` - Handle from [`choliday_new_from_config_path`], or `NULL`
+/// 
+/// # Safety
+/// * `handle` is `NULL` or a live handle, not used afterwards
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn choliday_free(handle: *mut CholidayHandle) {
+    if handle.is_null() {
+        return;
+    }
+    // SAFETY: the handle was created by `Box::into_raw` and is freed once
+    let handle = unsafe { Box::from_raw(handle) };
+    // A panic must not unwind into C; the handle is leaked instead
+    let _ = catch_unwind(AssertUnwindSafe(|| drop(handle)));
+}
+
+/// Converts a message to a C string, replacing any NUL byte
+fn c_string(message: String) -> CString {
+    CString::new(message.replace('\0', " ")).unwrap_or_default()
+}
+
+/// Extracts the message of a caught panic
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    let message = panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown cause");
+    format!("Panicked: {}", message)
+}
@@ -0,0 +1,366 @@
+//! Google Calendar API events, read as iCalendar properties.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+
+use std::{fs, path::Path, time::{SystemTime, UNIX_EPOCH}};
+
+use aws_lc_rs::{rand::SystemRandom, signature::{RSA_PKCS1_SHA256, RsaKeyPair}};
+use base64::{Engine, engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD}};
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use ical::property::Property;
+use log::warn;
+use reqwest::{Url, header::CONTENT_TYPE};
+use serde::Deserialize;
+
+use crate::{conf::{Google, Http}, ical::{Ical, Window}, source::{self, SourceStats}};
+
+/// OAuth scope granting read access to calendars
+const SCOPE: &str = "https://www.googleapis.com/auth/calendar.readonly";
+
+/// Token endpoint used when the service account key names none
+const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+
+/// Lifetime of the signed service account assertion, in seconds
+const ASSERTION_LIFETIME_SECS: u64 = 3600;
+
+/// Margin around the queried instants covered by `timeMin`/`timeMax`,
+/// so all-day events in any timezone are included
+const RANGE_MARGIN_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+/// Largest page size the events.list endpoint accepts
+const PAGE_SIZE: &str = "2500";
+
+/// Upper bound on the number of pages read, guarding against endless pagination
+const MAX_PAGES: usize = 100;
+
+/// Service account key file, as downloaded from the Google Cloud console
+#[derive(Deserialize)]
+struct ServiceAccount {
+    /// Account the assertion is issued for
+    client_email: String,
+    /// PKCS#8 private key in PEM form
+    private_key: String,
+    /// Endpoint exchanging the assertion for an access token
+    token_uri: Option<String>,
+}
+
+/// Response of the token endpoint
+#[derive(Deserialize)]
+struct Token {
+    /// Bearer token for API requests
+    access_token: String,
+}
+
+/// A page of the events.list response
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventList {
+    /// Events of this page
+    #[serde(default)]
+    items: Vec<Item>,
+    /// Token of the next page, if any
+    next_page_token: Option<String>,
+}
+
+/// An event of the events.list response
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Item {
+    /// Event ID, distinct for each instance of a recurring event
+    id: String,
+    /// `confirmed`, `tentative` or `cancelled`
+    status: Option<String>,
+    /// Event title
+    summary: Option<String>,
+    /// Event description
+    description: Option<String>,
+    /// Event location
+    location: Option<String>,
+    /// Start of the event
+    start: Option<Time>,
+    /// End of the event, exclusive
+    end: Option<Time>,
+    /// `opaque` or `transparent`
+    transparency: Option<String>,
+    /// Last modification as RFC 3339
+    updated: Option<String>,
+    /// Revision number
+    sequence: Option<i64>,
+}
+
+/// Start or end of an event
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Time {
+    /// Date of an all-day event as `YYYY-MM-DD`
+    date: Option<String>,
+    /// Time of a timed event as RFC 3339 with an offset
+    date_time: Option<String>,
+}
+
+/// How API requests are authorized
+enum Auth {
+    /// API key, enough for public calendars
+    Key(String),
+    /// OAuth access token of a service account
+    Bearer(String),
+}
+
+/// Lists the events of a Google calendar as iCalendar properties
+/// 
+/// # Arguments
+/// * `client` - HTTP client
+/// * `google` - Source settings
+/// * `http` - Retry and time budget settings
+/// * `conf_dir` - Directory a relative `credentials` path resolves against
+/// * `filter` - Queried instants, if any
+/// * `stats` - Statistics of the source, counting the bytes of every page
+/// 
+/// # Returns
+/// * `Ok(Vec<Property>)` - One VCALENDAR holding a VEVENT per event
+/// * `Err(String)` - Authentication or a request failed
+/// 
+/// # Note
+/// * Recurring events are expanded by the API (`singleEvents=true`), so
+///   every instance arrives as an event of its own
+/// * With a filter, only events within a day of its instants are listed
+pub async fn properties(
+    client: &reqwest::Client,
+    google: &Google,
+    http: &Http,
+    conf_dir: Option<&Path>,
+    filter: Option<Window>,
+    stats: &mut SourceStats,
+) -> Result<Vec<Property>, String> {
+    let auth = authenticate(client, google, http, conf_dir).await?;
+
+    let mut url = Url::parse(google.api()).map_err(|e| format!("Invalid Google API URL '{}': {}", google.api(), e))?;
+    url.path_segments_mut()
+        .map_err(|_| format!("Invalid Google API URL '{}'", google.api()))?
+        .pop_if_empty()
+        .extend(["calendar", "v3", "calendars", google.calendar_id(), "events"]);
+    {
+        let mut query = url.query_pairs_mut();
+        query.append_pair("singleEvents", "true").append_pair("maxResults", PAGE_SIZE);
+        if let Some(window) = filter {
+            query
+                .append_pair("timeMin", &rfc3339(window.start.saturating_sub(RANGE_MARGIN_MILLIS)))
+                .append_pair("timeMax", &rfc3339(window.end.saturating_add(RANGE_MARGIN_MILLIS)));
+        }
+    }
+
+    let mut props = vec![property("BEGIN", "VCALENDAR")];
+    let mut page_token: Option<String> = None;
+    for _ in 0..MAX_PAGES {
+        let mut page = url.clone();
+        if let Some(token) = &page_token {
+            page.query_pairs_mut().append_pair("pageToken", token);
+        }
+        let fetched = source::send(page.as_str(), http, || {
+            let request = client.get(page.clone());
+            match &auth {
+                // A header keeps the key out of URLs in log messages
+                Auth::Key(key) => request.header("X-goog-api-key", key),
+                Auth::Bearer(token) => request.bearer_auth(token),
+            }
+        })
+        .await?;
+        stats.record(&fetched);
+        let list: EventList = serde_json::from_slice(&fetched.into_body())
+            .map_err(|e| format!("Invalid Google Calendar response: {}", e))?;
+
+        for item in &list.items {
+            event(item, &mut props);
+        }
+        match list.next_page_token {
+            Some(token) => page_token = Some(token),
+            None => {
+                props.push(property("END", "VCALENDAR"));
+                return Ok(props);
+            },
+        }
+    }
+    Err(format!("Gave up after {} pages of events", MAX_PAGES))
+}
+
+/// Obtains the authorization for API requests
+/// 
+/// # Note
+/// * A service account key in `credentials` takes precedence over an API key
+async fn authenticate(
+    client: &reqwest::Client,
+    google: &Google,
+    http: &Http,
+    conf_dir: Option<&Path>,
+) -> Result<Auth, String> {
+    let Some(credentials) = google.credentials() else {
+        return match google.api_key()? {
+            Some(key) => Ok(Auth::Key(key)),
+            None => Err("Google Calendar source needs credentials or api_key_env".to_string()),
+        };
+    };
+
+    let path = source::resolve_path(credentials, conf_dir);
+    let data = fs::read(&path).map_err(|e| format!("Cannot read '{}': {}", path.display(), e))?;
+    let account: ServiceAccount = serde_json::from_slice(&data)
+        .map_err(|e| format!("Invalid service account key '{}': {}", path.display(), e))?;
+    let token_uri = account.token_uri.as_deref().unwrap_or(DEFAULT_TOKEN_URI);
+
+    let body = format!(
+        "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer&assertion={}",
+        assertion(&account, token_uri)?
+    );
+    let fetched = source::send(token_uri, http, || {
+        client
+            .post(token_uri)
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(body.clone())
+    })
+    .await
+    .map_err(|e| format!("Cannot obtain an access token: {}", e))?;
+    let token: Token = serde_json::from_slice(&fetched.into_body())
+        .map_err(|e| format!("Invalid access token response: {}", e))?;
+    Ok(Auth::Bearer(token.access_token))
+}
+
+/// Signs the JWT a service account exchanges for an access token (RFC 7523)
+fn assertion(account: &ServiceAccount, audience: &str) -> Result<String, String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"RS256","typ":"JWT"}"#);
+    let claims = serde_json::json!({
+        "iss": account.client_email,
+        "scope": SCOPE,
+        "aud": audience,
+        "iat": now,
+        "exp": now + ASSERTION_LIFETIME_SECS,
+    });
+    let message = format!("{}.{}", header, URL_SAFE_NO_PAD.encode(claims.to_string()));
+
+    let pem: String = account
+        .private_key
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .map(str::trim)
+        .collect();
+    let der = STANDARD
+        .decode(pem)
+        .map_err(|e| format!("Invalid service account private key: {}", e))?;
+    let key = RsaKeyPair::from_pkcs8(&der).map_err(|e| format!("Invalid service account private key: {}", e))?;
+    let mut signature = vec![0; key.public_modulus_len()];
+    key.sign(&RSA_PKCS1_SHA256, &SystemRandom::new(), message.as_bytes(), &mut signature)
+        .map_err(|_| "Cannot sign the service account assertion".to_string())?;
+    Ok(format!("{}.{}", message, URL_SAFE_NO_PAD.encode(signature)))
+}
+
+/// Appends the VEVENT of an API event, skipping events without a readable start
+fn event(item: &Item, props: &mut Vec<Property>) {
+    let Some(start) = item.start.as_ref().and_then(time) else {
+        warn!("Skipping Google Calendar event '{}' without a readable start", item.id);
+        return;
+    };
+
+    props.push(property("BEGIN", "VEVENT"));
+    props.push(property("UID", &item.id));
+    props.push(start.into_property("DTSTART"));
+    if let Some(end) = item.end.as_ref().and_then(time) {
+        props.push(end.into_property("DTEND"));
+    }
+    for (name, text) in [("SUMMARY", &item.summary), ("DESCRIPTION", &item.description), ("LOCATION", &item.location)] {
+        if let Some(text) = text {
+            props.push(property(name, &Ical::escape_text(text)));
+        }
+    }
+    if let Some(status) = &item.status {
+        props.push(property("STATUS", &status.to_uppercase()));
+    }
+    if let Some(transparency) = &item.transparency {
+        props.push(property("TRANSP", &transparency.to_uppercase()));
+    }
+    if let Some(updated) = item.updated.as_deref().and_then(utc) {
+        props.push(property("LAST-MODIFIED", &updated));
+    }
+    if let Some(sequence) = item.sequence {
+        props.push(property("SEQUENCE", &sequence.to_string()));
+    }
+    props.push(property("END", "VEVENT"));
+}
+
+/// An event time in iCalendar form
+enum Value {
+    /// `YYYYMMDD` of an all-day event
+    Date(String),
+    /// `YYYYMMDDTHHMMSSZ` of a timed event
+    DateTime(String),
+}
+
+impl Value {
+    /// Builds the datetime property, marking dates with `VALUE=DATE`
+    fn into_property(self, name: &str) -> Property {
+        match self {
+            Value::Date(date) => Property {
+                name: name.to_string(),
+                params: Some(vec![("VALUE".to_string(), vec!["DATE".to_string()])]),
+                value: Some(date),
+            },
+            Value::DateTime(date_time) => property(name, &date_time),
+        }
+    }
+}
+
+/// Converts an API start or end to iCalendar form
+fn time(time: &Time) -> Option<Value> {
+    match (&time.date, &time.date_time) {
+        (_, Some(date_time)) => utc(date_time).map(Value::DateTime),
+        (Some(date), None) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .ok()
+            .map(|d| Value::Date(d.format("%Y%m%d").to_string())),
+        (None, None) => None,
+    }
+}
+
+/// Converts an RFC 3339 time to an iCalendar UTC time
+fn utc(value: &str) -> Option<String> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string())
+}
+
+/// Formats milliseconds as RFC 3339 in UTC
+fn rfc3339(ts: i64) -> String {
+    DateTime::<Utc>::from_timestamp_millis(ts)
+        .unwrap_or_default()
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Builds a property without parameters
+fn property(name: &str, value: &str) -> Property {
+    Property {
+        name: name.to_string(),
+        params: None,
+        value: Some(value.to_string()),
+    }
+}
@@ -22,13 +22,45 @@
 //! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 //! SOFTWARE.
 
-use std::{fs::File, io::{BufRead, BufReader, Cursor}, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    io::{BufRead, Cursor},
+    str::FromStr,
+    sync::{Arc, PoisonError, RwLock},
+    time::{Duration, Instant},
+};
 
-use chrono::{DateTime, Datelike, NaiveDateTime, TimeZone, Timelike};
-use chrono_tz::Tz;
-use ical::property::Property;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc, offset::LocalResult};
+use chrono_tz::{TZ_VARIANTS, Tz};
+use ical::{
+    PropertyParser,
+    parser::ical::component::{IcalTimeZone, IcalTimeZoneTransition, IcalTimeZoneTransitionType::{DAYLIGHT, STANDARD}},
+    property::Property,
+};
+use log::{debug, info, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::task;
 
-use crate::conf::{Conf, Priority};
+use crate::{
+    cache::{Cache, fnv1a},
+    caldav,
+    changes::{self, Change, DaySnapshot},
+    conf::{Conf, DstAmbiguous, DstGap, Location, MatchField, OnConflict, Source, SourceFormat, TargetZone},
+    csv,
+    decision::{ContradictingEvent, Contradiction, DecisionSource, Provenance, PublicEvent, RestKind, TraceOutcome, TracedEvent},
+    exec,
+    google,
+    interval::IntervalIndex,
+    jcal,
+    nager,
+    resolver::{Candidate, ConflictResolver},
+    source::{self, SharedFetches, SourceReport, SourceStats, SourceStatus, redact_url},
+    rrule::{RRule, Until},
+    vtimezone::VTimeZone,
+    windows_zones,
+};
 
 /// iCalendar property key for event summary
 const KEY_SUMMARY: &str = "SUMMARY";
@@ -38,29 +70,339 @@ const KEY_DTSTART: &str = "DTSTART";
 const KEY_DTEND: &str = "DTEND";
 /// iCalendar property key for event description
 const KEY_DESCRIPTION: &str = "DESCRIPTION";
+/// iCalendar property key for recurrence exception dates
+const KEY_EXDATE: &str = "EXDATE";
+/// iCalendar property key for the recurrence rule of an event
+const KEY_RRULE: &str = "RRULE";
+/// iCalendar property key for event status
+const KEY_STATUS: &str = "STATUS";
+/// iCalendar property key for time transparency
+const KEY_TRANSP: &str = "TRANSP";
+/// iCalendar property key for event location
+const KEY_LOCATION: &str = "LOCATION";
+/// iCalendar property key for the event unique identifier
+const KEY_UID: &str = "UID";
+/// iCalendar property key for the event revision sequence number
+const KEY_SEQUENCE: &str = "SEQUENCE";
+/// iCalendar property key for the event last modification time
+const KEY_LAST_MODIFIED: &str = "LAST-MODIFIED";
+/// iCalendar property key for the time the event copy was created
+const KEY_DTSTAMP: &str = "DTSTAMP";
+/// iCalendar property key identifying an overridden recurring instance
+const KEY_RECURRENCE_ID: &str = "RECURRENCE-ID";
+/// Calendar-level property key for the default timezone (Google Calendar exports)
+const KEY_X_WR_TIMEZONE: &str = "X-WR-TIMEZONE";
+/// iCalendar property key for event categories
+const KEY_CATEGORIES: &str = "CATEGORIES";
+/// iCalendar property key for the due time of a to-do
+const KEY_DUE: &str = "DUE";
+/// iCalendar property key for the duration of a component
+const KEY_DURATION: &str = "DURATION";
+/// iCalendar property key for the completion of a to-do
+const KEY_PERCENT_COMPLETE: &str = "PERCENT-COMPLETE";
 
 /// iCalendar datetime format: YYYYMMDDTHHMMSS
 const DT_FMT: &str = "%Y%m%dT%H%M%S";
+/// iCalendar date format: YYYYMMDD
+const DATE_FMT: &str = "%Y%m%d";
+
+/// Milliseconds in one day
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+/// Duration given to timed events without DTEND: one hour in milliseconds
+const DEFAULT_TIMED_DURATION: i64 = 60 * 60 * 1000;
+/// How far past the current time recurring events without COUNT or UNTIL
+/// are expanded when all events are read: ten years in milliseconds
+const RECURRENCE_HORIZON: i64 = 3653 * DAY_MILLIS;
 
 /// Day type classification based on calendar events
-#[derive(PartialEq, Debug)]
+/// 
+/// # Note
+/// * Serialized, displayed and parsed as `normal_day`, `work_day`, `rest_day`,
+///   `conflict_day` and `unknown`. These names are stable and safe to persist; unknown
+///   names are rejected rather than mapped to a variant
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
 pub enum DayType {
     /// No relevant events found
+    #[serde(rename = "normal_day")]
     NormalDay,
     /// Day classified as work day
+    #[serde(rename = "work_day")]
     WorkDay,
     /// Day classified as rest day
+    #[serde(rename = "rest_day")]
     RestDay,
     /// Conflicting classifications (both work and rest indicators found)
+    #[serde(rename = "conflict_day")]
     ConflictDay,
+    /// No data to classify the day by: calendar sources missing under strict
+    /// mode or `min_sources`, or no event with `require_calendar`
+    #[serde(rename = "unknown")]
+    Unknown,
+}
+
+impl DayType {
+    /// Every day type, in declaration order
+    pub const ALL: [DayType; 5] =
+        [DayType::NormalDay, DayType::WorkDay, DayType::RestDay, DayType::ConflictDay, DayType::Unknown];
+
+    /// Returns the stable name of the day type, as serialized
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DayType::NormalDay => "normal_day",
+            DayType::WorkDay => "work_day",
+            DayType::RestDay => "rest_day",
+            DayType::ConflictDay => "conflict_day",
+            DayType::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for DayType {
+    /// Writes the stable name of the day type
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DayType {
+    type Err = String;
+
+    /// Parses a stable day type name, as written by `Display` and serde
+    /// 
+    /// # Returns
+    /// * `Err(String)` naming the accepted values for unknown names
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DayType::ALL
+            .into_iter()
+            .find(|day_type| day_type.as_str() == s)
+            .ok_or_else(|| format!(
+                "Unknown day type '{}', expected one of: {}",
+                s,
+                DayType::ALL.map(|day_type| day_type.as_str()).join(", ")
+            ))
+    }
+}
+
+/// What the classification of an event rests on
+enum Match<'a> {
+    /// Nothing identified the event as work or rest
+    None,
+    /// The configured explicit classification property
+    Explicit,
+    /// The first matching work and rest category or pattern
+    Pattern {
+        work: Option<&'a str>,
+        rest: Option<&'a str>,
+        /// `categories` or the name of the matched field
+        field: &'static str,
+    },
+}
+
+impl Match<'_> {
+    /// Returns the kind of rest day the match marks
+    /// 
+    /// # Arguments
+    /// * `day_type` - Classification the day was given
+    /// * `conf` - Configuration tagging rest patterns and categories
+    /// 
+    /// # Returns
+    /// * `None` unless `day_type` is `RestDay`, the kind tagging the matched
+    ///   rest pattern or category otherwise
+    fn rest_kind(&self, day_type: DayType, conf: &Conf) -> Option<RestKind> {
+        if day_type != DayType::RestDay {
+            return None;
+        }
+        match self {
+            Match::Pattern { rest: Some(rest), .. } => Some(conf.rest_kind(rest)),
+            _ => Some(RestKind::Rest),
+        }
+    }
+}
+
+/// Classification of an instant by calendar events
+pub(crate) struct Resolution {
+    /// Day type, kind of rest day and the event deciding them, `None` when
+    /// no event classifies the instant
+    pub decided: Option<(DayType, Option<RestKind>, DecisionSource)>,
+    /// [DTSTART, DTEND) of the deciding event when it is timed
+    pub span: Option<(i64, i64)>,
+    /// Events classifying the instant in opposite ways, settled by the priority rule
+    pub contradiction: Option<Contradiction>,
+    /// Audit record of the deciding event, `None` when no event classifies the instant
+    pub provenance: Option<Provenance>,
+}
+
+/// Value type of a datetime property
+#[derive(PartialEq, Debug)]
+enum ValueType {
+    /// Date-only value with all-day semantics
+    Date,
+    /// Date with time of day
+    DateTime,
+}
+
+/// Configured settings that affect how calendar data is parsed
+#[derive(Clone)]
+struct ParseOptions {
+    /// Zone for floating times, overriding X-WR-TIMEZONE
+    default_tz: Option<Tz>,
+    /// Name of the property holding an explicit classification
+    explicit_property: String,
+    /// Whether VTODO components are read as all-day events on their due date
+    include_todos: bool,
+    /// Whether events are recovered from malformed calendar structure
+    lenient: bool,
+    /// Whether events ending before they start are repaired rather than skipped
+    fix_inverted: bool,
+    /// Reading of ambiguous wall-clock times, `None` to span the overlap
+    dst_ambiguous: Option<DstAmbiguous>,
+    /// Reading of wall-clock times inside a spring-forward gap
+    dst_gap: DstGap,
+}
+
+/// Events read from the data of one source
+#[derive(Default)]
+struct Parsed {
+    /// Events passing the filter
+    events: Vec<Event>,
+    /// Number of events and to-dos read, including those not passing the filter
+    read: usize,
+    /// Number of events skipped for a missing or unreadable DTSTART, or a
+    /// DTEND preceding it that is not repaired
+    malformed: usize,
+    /// Number of events read in lenient mode despite missing BEGIN/END lines
+    recovered: usize,
+    /// Number of unterminated components dropped in lenient mode
+    discarded: usize,
+}
+
+/// Zone a TZID parameter refers to
+enum TzidZone<'a> {
+    /// Zone of the IANA database, possibly mapped from a Windows name
+    Iana(Tz),
+    /// Zone defined by a VTIMEZONE of the calendar
+    Defined(&'a VTimeZone),
+    /// Zone defined by a VTIMEZONE that could not be interpreted, read as UTC
+    Broken,
+    /// Identifier that is neither known nor defined by the calendar
+    Unknown,
+}
+
+/// Calendar-wide settings used when interpreting datetime values
+struct ParseContext {
+    /// Zone for floating times (no `Z` suffix and no TZID)
+    floating_tz: Option<Tz>,
+    /// Zones defined by VTIMEZONE components, keyed by TZID
+    /// (`None` when the definition could not be interpreted)
+    timezones: HashMap<String, Option<VTimeZone>>,
+    /// Name of the property holding an explicit classification
+    explicit_property: String,
+    /// Reading of ambiguous wall-clock times, `None` to span the overlap
+    dst_ambiguous: Option<DstAmbiguous>,
+    /// Reading of wall-clock times inside a spring-forward gap
+    dst_gap: DstGap,
+}
+
+/// Streaming state of one VCALENDAR being read property by property
+struct CalendarReader {
+    /// Settings for interpreting datetimes, completed as the calendar is read
+    ctx: ParseContext,
+    /// Whether `floating_tz` is fixed by configuration, ignoring X-WR-TIMEZONE
+    tz_configured: bool,
+    /// Whether VTODO components are read as events
+    include_todos: bool,
+    /// Names of the open components below VCALENDAR
+    components: Vec<String>,
+    /// VEVENT currently being read
+    event: Option<PendingEvent>,
+    /// VTIMEZONE currently being read
+    timezone: Option<IcalTimeZone>,
+    /// STANDARD/DAYLIGHT block currently being read
+    transition: Option<IcalTimeZoneTransition>,
+    /// Events passing the filter, with their position in the calendar
+    events: Vec<(usize, Event)>,
+    /// Number of VEVENTs read so far
+    event_count: usize,
+    /// Events referencing a TZID whose VTIMEZONE has not been read yet
+    deferred: Vec<PendingEvent>,
+    /// (UID, RECURRENCE-ID) of every override, including filtered ones
+    overrides: Vec<(String, RecurrenceId)>,
+    /// Unknown TZIDs already warned about
+    unknown_tzids: HashSet<String>,
+    /// Number of events skipped for a missing or unreadable DTSTART, or a
+    /// DTEND preceding it that is not repaired
+    malformed: usize,
+    /// Whether components left open by missing END lines are recovered
+    lenient: bool,
+    /// Whether events ending before they start get the default duration
+    fix_inverted: bool,
+    /// Whether the events were found outside any VCALENDAR (lenient mode only)
+    implicit: bool,
+    /// Number of events closed by recovery instead of their END line
+    recovered: usize,
+    /// Number of unterminated components dropped by recovery
+    discarded: usize,
+}
+
+/// Properties of a VEVENT collected until its END line
+struct PendingEvent {
+    /// Position of the event in the calendar, keeping the calendar order
+    /// when deferred events are completed last
+    index: usize,
+    /// Properties in order of appearance
+    properties: Vec<Property>,
+    /// Set once DTSTART/DTEND show the event misses the filter; from then on
+    /// only UID and RECURRENCE-ID are kept
+    skipped: bool,
+    /// Whether the component is a VTODO, classified on its due date
+    todo: bool,
+    /// DTSTART once read; DTEND only rules out the filter after a start
+    /// that precedes it, as an inverted event may be repaired
+    start: Option<i64>,
+    /// Whether DTEND shows the event ends over a day before the filter; it
+    /// is skipped at its END line unless an RRULE repeats it later
+    ends_early: bool,
 }
 
 /// Main iCalendar parser and analyzer
 pub struct Ical {
     /// Configuration for prediction and analysis
     conf: Conf,
-    /// Parsed calendar events (cached after first read)
-    events: Option<Vec<Event>>,
+    /// Events of the last read, shared with the queries still using them
+    loaded: RwLock<Option<Arc<Loaded>>>,
+    /// Whether remote sources are cached on disk
+    use_cache: bool,
+    /// Whether fresh cached copies are revalidated with the server anyway
+    revalidate: bool,
+    /// Whether questionable data is rejected rather than repaired
+    strict: bool,
+    /// Resolver used instead of the configured priority rule
+    resolver: Option<Box<dyn ConflictResolver>>,
+    /// Downloads shared with other analyzers
+    shared: Option<SharedFetches>,
+}
+
+/// Span of queried instants a read keeps the events for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Window {
+    /// First queried instant in milliseconds
+    pub(crate) start: i64,
+    /// Last queried instant in milliseconds, included
+    pub(crate) end: i64,
+}
+
+/// Events read from the configured sources in one go
+pub(crate) struct Loaded {
+    /// Parsed calendar events
+    events: Vec<Event>,
+    /// Spans of the events, by position in `events`
+    index: IntervalIndex,
+    /// Span of instants the events were filtered by, `None` for all events
+    filter: Option<Window>,
+    /// Outcome of loading each source
+    reports: Vec<SourceReport>,
 }
 
 /// Individual calendar event representation
@@ -70,10 +412,81 @@ struct Event {
     summary: String,
     /// Optional event description
     description: Option<String>,
+    /// Optional event location
+    location: Option<String>,
     /// Start timestamp in milliseconds since Unix epoch
     dtstart: i64,
     /// End timestamp in milliseconds since Unix epoch
     dtend: i64,
+    /// Milliseconds the wall clock DTSTART was written in is ahead of UTC,
+    /// 0 for UTC, date-only and unreadable values
+    start_offset: i64,
+    /// Whether DTSTART is a date-only value
+    all_day: bool,
+    /// Event status
+    status: Status,
+    /// Whether the event is marked TRANSP:TRANSPARENT (does not block time)
+    transparent: bool,
+    /// Categories from all CATEGORIES properties
+    categories: Vec<String>,
+    /// Occurrence start times excluded through EXDATE properties
+    exdates: Vec<ExDate>,
+    /// Globally unique event identifier
+    uid: Option<String>,
+    /// Revision sequence number (SEQUENCE)
+    sequence: Option<i64>,
+    /// Last modification timestamp in milliseconds (LAST-MODIFIED)
+    last_modified: Option<i64>,
+    /// Creation timestamp of this copy in milliseconds (DTSTAMP)
+    dtstamp: Option<i64>,
+    /// Calendar sources the event was read from
+    sources: Vec<String>,
+    /// Instance of a recurring event this event overrides (RECURRENCE-ID)
+    recurrence_id: Option<RecurrenceId>,
+    /// Start of the instance when generated from a recurrence rule
+    occurrence: Option<i64>,
+    /// Classification given explicitly through the configured property
+    explicit_type: Option<DayType>,
+}
+
+/// A RECURRENCE-ID value identifying overridden instances
+#[derive(Clone)]
+struct RecurrenceId {
+    /// Start of the overridden instance in milliseconds since Unix epoch
+    timestamp: i64,
+    /// Whether the override also replaces all later instances (RANGE=THISANDFUTURE)
+    this_and_future: bool,
+}
+
+impl RecurrenceId {
+    /// Checks whether an instance starting at the given timestamp is replaced
+    fn replaces(&self, occurrence_start: i64) -> bool {
+        if self.this_and_future {
+            occurrence_start >= self.timestamp
+        } else {
+            occurrence_start == self.timestamp
+        }
+    }
+}
+
+/// Event status from the STATUS property
+#[derive(Clone, PartialEq)]
+enum Status {
+    /// Confirmed or no status given
+    Confirmed,
+    /// Tentative event, kept but flagged
+    Tentative,
+    /// Cancelled event, never classified
+    Cancelled,
+}
+
+/// A single EXDATE value
+#[derive(Clone)]
+struct ExDate {
+    /// Excluded occurrence start in milliseconds since Unix epoch
+    timestamp: i64,
+    /// Whether the value was date-only, excluding any occurrence on that day
+    all_day: bool,
 }
 
 impl Event {
@@ -82,8 +495,23 @@ impl Event {
         Event {
             summary: "".to_string(),
             description: None,
+            location: None,
             dtstart: 0,
             dtend: 0,
+            start_offset: 0,
+            all_day: false,
+            status: Status::Confirmed,
+            transparent: false,
+            categories: Vec::new(),
+            exdates: Vec::new(),
+            uid: None,
+            sequence: None,
+            last_modified: None,
+            dtstamp: None,
+            explicit_type: None,
+            sources: Vec::new(),
+            recurrence_id: None,
+            occurrence: None,
         }
     }
 
@@ -101,6 +529,25 @@ impl Event {
     pub fn description(&self) -> Option<&str> {
         self.description.as_deref()
     }
+
+    /// Returns the event location if available
+    fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    /// Sets the event location
+    fn set_location(&mut self, location: impl Into<String>) {
+        self.location = Some(location.into());
+    }
+
+    /// Returns the text of a matchable field if present
+    fn field(&self, field: &MatchField) -> Option<&str> {
+        match field {
+            MatchField::Summary => Some(self.summary()),
+            MatchField::Description => self.description(),
+            MatchField::Location => self.location(),
+        }
+    }
     
     /// Sets the event description
     pub fn set_description(&mut self, desc: impl Into<String>) {
@@ -116,321 +563,3401 @@ impl Event {
     pub fn set_dtend(&mut self, dtend: i64) {
         self.dtend = dtend;
     }
+
+    /// Marks the event as an all-day event
+    fn set_all_day(&mut self, all_day: bool) {
+        self.all_day = all_day;
+    }
     
-    /// Checks if a specific timestamp falls within this event's timeframe
+    /// Sets the event status from a STATUS value
+    fn set_status(&mut self, status: &str) {
+        self.status = match status.to_uppercase().as_str() {
+            "CANCELLED" => Status::Cancelled,
+            "TENTATIVE" => Status::Tentative,
+            _ => Status::Confirmed,
+        };
+    }
+
+    /// Sets the time transparency from a TRANSP value
+    fn set_transp(&mut self, transp: &str) {
+        self.transparent = transp.eq_ignore_ascii_case("TRANSPARENT");
+    }
+
+    /// Returns whether the event is marked transparent
+    fn is_transparent(&self) -> bool {
+        self.transparent
+    }
+
+    /// Adds the comma-separated values of a CATEGORIES property
+    fn add_categories(&mut self, categories: &str) {
+        self.categories.extend(
+            Ical::split_text_list(categories)
+                .into_iter()
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty()),
+        );
+    }
+
+    /// Records a calendar source the event appeared in
+    fn add_source(&mut self, source: &str) {
+        if !self.sources.iter().any(|s| s == source) {
+            self.sources.push(source.to_string());
+        }
+    }
+
+    /// Returns the revision key used to pick between copies of the same UID
+    fn revision(&self) -> (i64, Option<i64>) {
+        (self.sequence.unwrap_or_default(), self.last_modified)
+    }
+
+    /// Adds an excluded occurrence start
+    fn add_exdate(&mut self, exdate: ExDate) {
+        self.exdates.push(exdate);
+    }
+
+    /// Checks if an occurrence starting at the given timestamp is excluded by EXDATE
+    /// 
+    /// # Arguments
+    /// * `occurrence_start` - Occurrence start in milliseconds
+    /// 
+    /// # Returns
+    /// * `true` if an EXDATE matches the occurrence start exactly, or a date-only
+    ///   EXDATE names the day the occurrence starts on, on the wall clock of
+    ///   its DTSTART zone
+    fn is_excluded(&self, occurrence_start: i64) -> bool {
+        let local_start = occurrence_start + self.start_offset;
+        self.exdates.iter().any(|ex| {
+            if ex.all_day {
+                ex.timestamp <= local_start && local_start < ex.timestamp + DAY_MILLIS
+            } else {
+                ex.timestamp == occurrence_start
+            }
+        })
+    }
+
+    /// Checks if the event overlaps the days around a window
     /// 
     /// # Arguments
-    /// * `dt` - Timestamp in milliseconds to check
+    /// * `window` - Span of queried instants
+    /// 
+    /// # Returns
+    /// * `true` if [dtstart, dtend) overlaps the window widened by a day either side
+    /// 
+    /// # Note
+    /// * Wide enough for any zone offset, so parsing can drop events before
+    ///   the target zone is applied
+    fn near(&self, window: Window) -> bool {
+        self.dtstart <= window.end + DAY_MILLIS && window.start - DAY_MILLIS < self.dtend
+    }
+
+    /// Returns the end of the event when DTEND is missing or unusable
     /// 
     /// # Returns
-    /// * `true` if timestamp is within [dtstart, dtend)
-    /// * `false` otherwise
-    pub fn contains_timestamp(&self, dt: i64) -> bool {
-        self.dtstart <= dt && dt < self.dtend
+    /// * End timestamp in milliseconds: all-day events cover their whole day,
+    ///   timed events get a default duration
+    fn default_dtend(&self) -> i64 {
+        if self.all_day {
+            self.dtstart + DAY_MILLIS
+        } else {
+            self.dtstart + DEFAULT_TIMED_DURATION
+        }
     }
 
     /// Classifies the event day type based on prediction patterns
     /// 
     /// # Arguments
-    /// * `predict_work` - Patterns indicating work days
-    /// * `predict_rest` - Patterns indicating rest days
+    /// * `conf` - Configuration holding work/rest patterns and categories
+    /// 
+    /// # Returns
+    /// * `(DayType, Option<RestKind>)` - Classification based on event content,
+    ///   and for rest days the kind tagging the matched pattern or category
+    /// 
+    /// # Note
+    /// * An explicit classification property decides on its own, as a generic rest day
+    /// * When category lists are configured, a category match takes precedence
+    ///   over summary and description patterns
+    pub fn day_type(&self, conf: &Conf) -> (DayType, Option<RestKind>) {
+        let (day_type, matched) = self.matched(conf);
+        (day_type, matched.rest_kind(day_type, conf))
+    }
+
+    /// Classifies the event and reports what the classification rests on
+    /// 
+    /// # Arguments
+    /// * `conf` - Configuration holding work/rest patterns and categories
     /// 
     /// # Returns
-    /// * `DayType` classification based on event content
-    pub fn day_type(&self, predict_work: &[String], predict_rest: &[String]) -> DayType {
-        let summary = self.summary();
+    /// * `(DayType, Match)` - Classification as in [`Event::day_type`], with
+    ///   the property, category or pattern that produced it
+    fn matched<'a>(&self, conf: &'a Conf) -> (DayType, Match<'a>) {
+        if let Some(day_type) = self.explicit_type {
+            debug!("Event '{}' explicitly classified as {:?}", self.summary(), day_type);
+            return (day_type, Match::Explicit);
+        }
 
-        // Check summary for work/rest patterns
-        let work_day_summary = predict_work.iter().any(|x| summary.contains(x));
-        let rest_day_summary = predict_rest.iter().any(|x| summary.contains(x));
+        let work_categories = conf.work_categories();
+        let rest_categories = conf.rest_categories();
 
-        match (work_day_summary, rest_day_summary) {
-            (true, true) => return DayType::ConflictDay,
-            (true, false) => return DayType::WorkDay,
-            (false, true) => return DayType::RestDay,
-            (false, false) => (),
+        // Check categories for exact (case-insensitive) matches
+        if !work_categories.is_empty() || !rest_categories.is_empty() {
+            let find_category = |wanted: &'a [String]| {
+                wanted
+                    .iter()
+                    .find(|w| self.categories.iter().any(|c| Self::eq_ignore_case(w, c)))
+                    .map(String::as_str)
+            };
+            let (work, rest) = (find_category(work_categories), find_category(rest_categories));
+            let day_type = Self::classify(work.is_some(), rest.is_some());
+            if day_type != DayType::NormalDay {
+                debug!("Event '{}' classified as {:?} by category", self.summary(), day_type);
+                return (day_type, Match::Pattern { work, rest, field: "categories" });
+            }
         }
 
-        // If summary didn't match, check description
-        if let Some(description) = self.description() {
-            let work_day_description = predict_work.iter().any(|x| description.contains(x));
-            let rest_day_description = predict_rest.iter().any(|x| description.contains(x));
+        let predict_work = conf.predict_work();
 
-            match (work_day_description, rest_day_description) {
-                (true, true) => DayType::ConflictDay,
-                (true, false) => DayType::WorkDay,
-                (false, true) => DayType::RestDay,
-                (false, false) => DayType::NormalDay,
+        // Check each configured field in order, the first field with a match decides
+        for field in conf.match_fields() {
+            let Some(text) = self.field(field) else {
+                continue;
+            };
+
+            let work = predict_work.iter().find(|x| text.contains(x.as_str())).map(String::as_str);
+            let rest = conf.rest_patterns().find(|x| text.contains(x.as_str())).map(String::as_str);
+
+            let day_type = Self::classify(work.is_some(), rest.is_some());
+            if day_type != DayType::NormalDay {
+                return (day_type, Match::Pattern { work, rest, field: field.as_str() });
             }
-        } else {
-            DayType::NormalDay
         }
+
+        (DayType::NormalDay, Match::None)
     }
-}
 
-impl Ical {
-    /// Creates a new iCalendar analyzer with the given configuration
-    pub fn new(conf: Conf) -> Self {
-        Ical {
-            conf,
-            events: None,
+    /// Describes the event as the source of a day's classification
+    /// 
+    /// # Arguments
+    /// * `matched` - What the event's classification rests on
+    /// * `day_type` - Classification the day was given, picking the pattern
+    ///   reported for events matching both work and rest patterns
+    /// * `conf` - Configuration naming the sources and the explicit property
+    fn decision_source(&self, matched: &Match, day_type: DayType, conf: &Conf) -> DecisionSource {
+        let summary = self.summary().to_string();
+        let source = self.source_name(conf);
+        match matched {
+            Match::Pattern { work, rest, .. } if day_type == DayType::ConflictDay => DecisionSource::ConflictingEvent {
+                summary,
+                source,
+                work_pattern: work.map(str::to_string),
+                rest_pattern: rest.map(str::to_string),
+            },
+            Match::Explicit => DecisionSource::ExplicitOverride {
+                summary,
+                source,
+                property: conf.explicit_property().to_string(),
+            },
+            Match::Pattern { work, rest, .. } => {
+                let pattern = if day_type == DayType::RestDay { rest.or(*work) } else { work.or(*rest) };
+                DecisionSource::CalendarEvent { summary, source, pattern: pattern.map(str::to_string) }
+            },
+            Match::None => DecisionSource::CalendarEvent { summary, source, pattern: None },
         }
     }
 
-    /// Reads and parses calendar events from configured sources
+    /// Names the configured source the event was read from
     /// 
     /// # Arguments
-    /// * `dest_day_ts` - Optional timestamp to filter events for a specific day
+    /// * `conf` - Configuration listing the sources
     /// 
-    /// # Note
-    /// Supports both HTTP URLs and local file paths
-    pub async fn read_events(&mut self, dest_day_ts: Option<i64>) {
-        let client = reqwest::Client::new();
-        let client = Arc::new(client);
+    /// # Returns
+    /// * Name of the first configured source listing the event, if any, with
+    ///   credentials embedded in a URL hidden
+    fn source_name(&self, conf: &Conf) -> Option<String> {
+        self.source_str(conf).map(redact_url)
+    }
 
-        let tasks = self.conf
-            .get_describe_calendar()
-            .unwrap_or_else(|| &[])
-            .iter()
-            .map(|uri| {
-                let client = Arc::clone(&client);
-                async move {
-                    if uri.starts_with("http") {
-                        // Fetch from remote URL
-                        if let Ok(resp) = client.get(uri).send().await {
-                            if let Ok(bytes) = resp.bytes().await {
-                                return Self::parse_calendar(Cursor::new(bytes), dest_day_ts);
-                            }
-                        }
-                        Vec::new()
-                    } else {
-                        // Read from local file
-                        if let Ok(file) = File::open(uri) {
-                            return Self::parse_calendar(BufReader::new(file), dest_day_ts);
-                        }
-                        Vec::new()
-                    }
-                }
-            });
+    /// Borrows the name of the configured source the event was read from, see [`Event::source_name`]
+    fn source_str<'a>(&'a self, conf: &'a Conf) -> Option<&'a str> {
+        let sources = conf.get_describe_calendar().unwrap_or(&[]);
+        sources
+            .get(self.source_index(sources))
+            .map(Source::name)
+            .or_else(|| self.sources.first().map(String::as_str))
+    }
 
-        let mut all_events = Vec::new();
-        for task in tasks {
-            let events = task.await;
-            all_events.extend(events);
+    /// Records why the event decided its day, for audits
+    /// 
+    /// # Arguments
+    /// * `matched` - What the event's classification rests on
+    /// * `day_type` - Classification the day was given
+    /// * `rule` - Description of the resolver that chose the event
+    /// * `conflict` - Whether classifying events disagreed
+    /// * `conf` - Configuration naming the sources and the explicit property
+    fn provenance(&self, matched: &Match, day_type: DayType, rule: String, conflict: bool, conf: &Conf) -> Provenance {
+        let (pattern, field) = match matched {
+            Match::None => (None, None),
+            Match::Explicit => (None, Some(conf.explicit_property().to_string())),
+            Match::Pattern { work, rest, field } => {
+                let pattern = if day_type == DayType::RestDay { rest.or(*work) } else { work.or(*rest) };
+                (pattern.map(str::to_string), Some(field.to_string()))
+            },
+        };
+        Provenance {
+            source: self.source_name(conf),
+            uid: self.uid.clone(),
+            summary: Some(self.summary().to_string()),
+            pattern,
+            field,
+            rule,
+            conflict,
+            explicit_override: matches!(matched, Match::Explicit),
+            fallback: false,
         }
+    }
 
-        self.events = Some(all_events);
+    /// Describes the event for a decision trace
+    /// 
+    /// # Arguments
+    /// * `matched` - What the event's classification rests on
+    /// * `day_type` - How the event alone classifies the day
+    /// * `outcome` - What priority resolution did with the event
+    /// * `conf` - Configuration naming the sources, the explicit property and the target zone
+    fn traced(&self, matched: &Match, day_type: DayType, outcome: TraceOutcome, conf: &Conf) -> TracedEvent {
+        let wall_clock = |timestamp: i64| {
+            if self.all_day {
+                DateTime::from_timestamp_millis(timestamp).unwrap_or_default().naive_utc()
+            } else {
+                conf.target_zone().to_wall_clock(timestamp)
+            }
+        };
+        let (work_pattern, rest_pattern, field) = match matched {
+            Match::None => (None, None, None),
+            Match::Explicit => (None, None, Some(conf.explicit_property().to_string())),
+            Match::Pattern { work, rest, field } => {
+                (work.map(str::to_string), rest.map(str::to_string), Some(field.to_string()))
+            },
+        };
+        let zone = conf.target_zone();
+        let zoned = |timestamp: i64| (!self.all_day).then(|| zone.to_zoned(timestamp));
+        TracedEvent {
+            summary: self.summary().to_string(),
+            source: self.source_name(conf),
+            start: wall_clock(self.dtstart),
+            end: wall_clock(self.dtend),
+            start_time: zoned(self.dtstart),
+            end_time: zoned(self.dtend),
+            zone: zone.name().map(str::to_string),
+            all_day: self.all_day,
+            day_type,
+            work_pattern,
+            rest_pattern,
+            field,
+            outcome,
+        }
     }
 
-    /// Determines the day type by applying priority rules to calendar events
+    /// Describes the event for library users
+    /// 
+    /// # Arguments
+    /// * `matched` - What the event's classification rests on
+    /// * `day_type` - How the event alone classifies its days
+    /// * `conf` - Configuration naming the sources and the target zone
+    fn public(&self, matched: &Match, day_type: DayType, conf: &Conf) -> PublicEvent {
+        let ((start, local_start), (end, local_end)) = self.bounds(conf);
+        PublicEvent {
+            summary: self.summary().to_string(),
+            description: self.description.clone(),
+            start,
+            end,
+            local_start,
+            local_end,
+            all_day: self.all_day,
+            source: self.source_name(conf),
+            day_type,
+            rest_kind: matched.rest_kind(day_type, conf),
+        }
+    }
+
+    /// Describes the event for a conflict resolver
+    /// 
+    /// # Arguments
+    /// * `day_type` - How the event alone classifies the day
+    /// * `conf` - Configuration naming the sources and the target zone
+    fn candidate<'a>(&'a self, day_type: DayType, conf: &'a Conf) -> Candidate<'a> {
+        let ((start, _), (end, _)) = self.bounds(conf);
+        let instant = |timestamp: Option<i64>| timestamp.and_then(DateTime::from_timestamp_millis);
+        Candidate {
+            summary: self.summary(),
+            source: self.source_str(conf),
+            start,
+            end,
+            all_day: self.all_day,
+            day_type,
+            explicit: self.explicit_type.is_some(),
+            last_modified: instant(self.last_modified),
+            dtstamp: instant(self.dtstamp),
+            sequence: self.sequence,
+        }
+    }
+
+    /// Returns the start and end as instants and on the wall clock of the target zone
+    /// 
+    /// # Note
+    /// * All-day events start and end at midnight of the target zone
+    fn bounds(&self, conf: &Conf) -> ((DateTime<Utc>, NaiveDateTime), (DateTime<Utc>, NaiveDateTime)) {
+        let zone = conf.target_zone();
+        let floating = |timestamp: i64| DateTime::from_timestamp_millis(timestamp).unwrap_or_default();
+        if self.all_day {
+            let local = |timestamp: i64| {
+                let wall = floating(timestamp).naive_utc();
+                (floating(zone.to_instant(wall)), wall)
+            };
+            (local(self.dtstart), local(self.dtend))
+        } else {
+            let instant = |timestamp: i64| (floating(timestamp), zone.to_wall_clock(timestamp));
+            (instant(self.dtstart), instant(self.dtend))
+        }
+    }
+
+    /// Records an explicit classification value
+    /// 
+    /// # Arguments
+    /// * `value` - `WORK` or `REST` (case-insensitive)
+    /// * `property` - Name of the property, for the warning on unknown values
+    /// 
+    /// # Note
+    /// * Unknown values are ignored with a warning, leaving pattern matching in charge
+    fn set_explicit_type(&mut self, value: &str, property: &str) {
+        match value.trim().to_uppercase().as_str() {
+            "WORK" => self.explicit_type = Some(DayType::WorkDay),
+            "REST" => self.explicit_type = Some(DayType::RestDay),
+            _ => warn!(
+                "Ignoring unknown {} '{}' on event '{}', expected WORK or REST",
+                property,
+                value.trim(),
+                self.summary()
+            ),
+        }
+    }
+
+    /// Returns the position of the event's earliest source in the configuration
     /// 
     /// # Arguments
-    /// * `dest_day_ts` - Optional timestamp for specific day analysis
+    /// * `sources` - Configured calendar sources
     /// 
     /// # Returns
-    /// * `DayType` based on priority rules and event analysis
-    pub async fn judge_by_priority(&mut self, dest_day_ts: Option<i64>) -> DayType {
-        // Ensure events are loaded
-        if self.events.is_none() {
-            self.read_events(dest_day_ts).await;
+    /// * Index of the first configured source the event came from,
+    ///   `usize::MAX` if it came from none of them
+    fn source_index(&self, sources: &[Source]) -> usize {
+        self.sources
+            .iter()
+            .filter_map(|source| sources.iter().position(|s| s.name() == source))
+            .min()
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Compares two strings case-insensitively without allocating
+    fn eq_ignore_case(a: &str, b: &str) -> bool {
+        a.chars().flat_map(char::to_lowercase).eq(b.chars().flat_map(char::to_lowercase))
+    }
+
+    /// Maps work/rest match flags to a day type
+    fn classify(work: bool, rest: bool) -> DayType {
+        match (work, rest) {
+            (true, true) => DayType::ConflictDay,
+            (true, false) => DayType::WorkDay,
+            (false, true) => DayType::RestDay,
+            (false, false) => DayType::NormalDay,
         }
-        
-        let events = self.events.clone().unwrap();
-        let predict_work = self.conf.predict_work();
-        let predict_rest = self.conf.predict_rest();
-        
-        // Filter and classify events
-        let day_types: Vec<DayType> = match dest_day_ts {
-            Some(dts) => events
-                .iter()
-                .filter(|e| e.contains_timestamp(dts))
-                .map(|e| e.day_type(predict_work, predict_rest))
-                .filter(|x| *x != DayType::NormalDay)
-                .collect(),
-            None => events
-                .iter()
-                .map(|e| e.day_type(predict_work, predict_rest))
-                .filter(|x| *x != DayType::NormalDay)
-                .collect(),
-        };
-        
-        if day_types.is_empty() {
-            return DayType::NormalDay;
+    }
+}
+
+impl ParseContext {
+    /// Checks whether the TZID of a property, if any, can be resolved as
+    /// it would be at the end of the calendar
+    /// 
+    /// # Note
+    /// * Windows names only count once their VTIMEZONE is known, because a
+    ///   definition later in the calendar takes precedence over the mapping
+    fn knows_tzid(&self, prop: &Property) -> bool {
+        match Ical::param(prop.params.as_deref(), "TZID") {
+            Some(tzid) => Ical::lookup_tz(tzid).is_some() || self.timezones.contains_key(Ical::unquote(tzid)),
+            None => true,
         }
+    }
 
-        match self.conf.priority() {
-            Priority::WorkOverRest => {
-                if day_types.iter().any(|x| *x == DayType::WorkDay || *x == DayType::ConflictDay) {
-                    DayType::WorkDay
-                } else {
-                    DayType::RestDay
-                }
-            },
-            Priority::RestOverWork => {
-                if day_types.iter().any(|x| *x == DayType::RestDay || *x == DayType::ConflictDay) {
-                    DayType::RestDay
-                } else {
-                    DayType::WorkDay
-                }
-            },
-            Priority::KeepCurrent => {
-                match day_types.first() {
-                    Some(DayType::RestDay) => DayType::RestDay,
-                    _ => DayType::WorkDay,
-                }
+    /// Finds the zone a TZID parameter refers to
+    /// 
+    /// # Note
+    /// * IANA names take precedence over VTIMEZONE definitions, which take
+    ///   precedence over the Windows name mapping
+    fn tzid_zone(&self, tzid: &str) -> TzidZone<'_> {
+        if let Some(tz) = Ical::lookup_tz(tzid) {
+            return TzidZone::Iana(tz);
+        }
+        let tzid = Ical::unquote(tzid);
+        match (self.timezones.get(tzid), windows_zones::lookup(tzid)) {
+            (Some(Some(vtz)), _) => TzidZone::Defined(vtz),
+            (_, Some(tz)) => {
+                info!("Mapped Windows timezone '{}' to '{}'", tzid, tz.name());
+                TzidZone::Iana(tz)
             },
-            Priority::UseLatest => {
-                match day_types.last() {
-                    Some(DayType::RestDay) => DayType::RestDay,
-                    _ => DayType::WorkDay,
-                }
+            (Some(None), None) => TzidZone::Broken,
+            (None, None) => TzidZone::Unknown,
+        }
+    }
+}
+
+impl CalendarReader {
+    /// Starts reading a calendar after its BEGIN:VCALENDAR line
+    fn new(options: &ParseOptions) -> Self {
+        CalendarReader {
+            ctx: ParseContext {
+                floating_tz: options.default_tz,
+                timezones: HashMap::new(),
+                explicit_property: options.explicit_property.clone(),
+                dst_ambiguous: options.dst_ambiguous,
+                dst_gap: options.dst_gap,
             },
+            tz_configured: options.default_tz.is_some(),
+            include_todos: options.include_todos,
+            components: Vec::new(),
+            event: None,
+            timezone: None,
+            transition: None,
+            events: Vec::new(),
+            event_count: 0,
+            deferred: Vec::new(),
+            overrides: Vec::new(),
+            unknown_tzids: HashSet::new(),
+            malformed: 0,
+            lenient: options.lenient,
+            fix_inverted: options.fix_inverted,
+            implicit: false,
+            recovered: 0,
+            discarded: 0,
         }
     }
 
-    /// Parses iCalendar data from a reader
+    /// Starts reading events found outside any VCALENDAR, in lenient mode
+    fn implicit(options: &ParseOptions) -> Self {
+        CalendarReader { implicit: true, ..CalendarReader::new(options) }
+    }
+
+    /// Consumes the next property of the calendar
     /// 
     /// # Arguments
-    /// * `reader` - Buffered reader containing iCalendar data
-    /// * `filter` - Optional timestamp to filter events
+    /// * `prop` - Property following the previous one in the data
+    /// * `filter` - Optional window events must come within a day of
     /// 
     /// # Returns
-    /// * `Vec<Event>` - Parsed events
-    fn parse_calendar<T: BufRead>(reader: T, filter: Option<i64>) -> Vec<Event> {
-        let mut events = Vec::new();
-        let parser = ical::IcalParser::new(reader);
-        
-        for calendar in parser {
-            if let Ok(cal) = calendar {
-                for event in cal.events {
-                    let mut my_event = Event::new();
-                    
-                    for prop in event.properties {
-                        match prop.name.as_str() {
-                            KEY_SUMMARY => {
-                                if let Some(summary) = prop.value {
-                                    my_event.set_summary(&summary);
-                                } else {
-                                    my_event.set_summary("NO_SUMMARY");
-                                }
-                            },
-                            KEY_DESCRIPTION => {
-                                if let Some(desc) = prop.value {
-                                    my_event.set_description(desc);
-                                }
-                            },
-                            KEY_DTSTART => {
-                                if let Ok(timestamp) = Self::parse_datetime(&prop, true) {
-                                    my_event.set_dtstart(timestamp);
-                                }
-                            },
-                            KEY_DTEND => {
-                                if let Ok(timestamp) = Self::parse_datetime(&prop, false) {
-                                    my_event.set_dtend(timestamp);
-                                }
-                            },
-                            _ => {}
-                        }
+    /// * `true` once the calendar's END:VCALENDAR line was read
+    fn feed(&mut self, prop: Property, filter: Option<Window>) -> bool {
+        if prop.name.eq_ignore_ascii_case("BEGIN") {
+            let component = prop.value.as_deref().unwrap_or_default().trim().to_uppercase();
+            // Events never nest, so an open component lost its END line
+            if self.lenient && !self.components.is_empty() && matches!(component.as_str(), "VEVENT" | "VTODO") {
+                self.recover(filter);
+            }
+            self.components.push(component);
+            match self.path().as_slice() {
+                [component @ ("VEVENT" | "VTODO")] if *component == "VEVENT" || self.include_todos => {
+                    self.event = Some(PendingEvent {
+                        index: self.event_count,
+                        properties: Vec::new(),
+                        skipped: false,
+                        todo: *component == "VTODO",
+                        start: None,
+                        ends_early: false,
+                    });
+                    self.event_count += 1;
+                },
+                ["VTIMEZONE"] => self.timezone = Some(IcalTimeZone::new()),
+                ["VTIMEZONE", "STANDARD"] => self.transition = Some(IcalTimeZoneTransition::new(STANDARD)),
+                ["VTIMEZONE", "DAYLIGHT"] => self.transition = Some(IcalTimeZoneTransition::new(DAYLIGHT)),
+                _ => {},
+            }
+            return false;
+        }
+
+        if prop.name.eq_ignore_ascii_case("END") {
+            match self.path().as_slice() {
+                [] => return true,
+                ["VEVENT" | "VTODO"] => {
+                    if let Some(event) = self.event.take() {
+                        self.finish_event(event, filter, false);
                     }
-                    
-                    // Handle events with no explicit end time
-                    if my_event.dtend == 0 {
-                        my_event.dtend = my_event.dtstart;
+                },
+                ["VTIMEZONE"] => {
+                    if let Some(timezone) = self.timezone.take() {
+                        self.add_timezone(&timezone);
                     }
-                    
-                    // Apply filter if specified
-                    if let Some(filter_ts) = filter {
-                        if my_event.contains_timestamp(filter_ts) {
-                            events.push(my_event);
-                        }
-                    } else {
-                        events.push(my_event);
+                },
+                ["VTIMEZONE", _] => {
+                    if let (Some(timezone), Some(transition)) = (self.timezone.as_mut(), self.transition.take()) {
+                        timezone.transitions.push(transition);
                     }
-                }
+                },
+                _ => {},
             }
+            self.components.pop();
+            return false;
         }
-        events
+
+        match self.path().as_slice() {
+            [] if prop.name == KEY_X_WR_TIMEZONE => self.set_calendar_tz(&prop),
+            ["VEVENT" | "VTODO"] => {
+                if let Some(event) = self.event.as_mut() {
+                    event.push(prop, filter, &self.ctx);
+                }
+            },
+            ["VTIMEZONE"] => {
+                if let Some(timezone) = self.timezone.as_mut() {
+                    timezone.properties.push(prop);
+                }
+            },
+            ["VTIMEZONE", _] => {
+                if let Some(transition) = self.transition.as_mut() {
+                    transition.properties.push(prop);
+                }
+            },
+            // Alarms, journals and other components do not classify days
+            _ => {},
+        }
+        false
     }
 
-    /// Parses iCalendar datetime strings into Unix timestamps
-    /// 
-    /// # Arguments
-    /// * `prop` - iCalendar property containing datetime
-    /// * `is_dt_start` - Whether this is a DTSTART (true) or DTEND (false)
-    /// 
-    /// # Returns
-    /// * `Result<i64, &str>` - Unix timestamp in milliseconds or error message
+    /// Returns the names of the open components as string slices
+    fn path(&self) -> Vec<&str> {
+        self.components.iter().map(String::as_str).collect()
+    }
+
+    /// Uses the calendar's X-WR-TIMEZONE for floating times unless a zone is configured
     /// 
-    /// # Supported Formats
-    /// * YYYYMMDD (all-day events)
-    /// * YYYYMMDDTHHMMSS (local time)
-    /// * YYYYMMDDTHHMMSSZ (UTC time)
-    /// * YYYYMMDDTHHMMSS with TZID parameter
-    fn parse_datetime(prop: &Property, is_dt_start: bool) -> Result<i64, &'static str> {
+    /// # Note
+    /// * Calendar properties precede all components (RFC 5545 section 3.4),
+    ///   so the zone is known before the first event is read
+    fn set_calendar_tz(&mut self, prop: &Property) {
+        let Some(tz) = prop.value.as_deref() else {
+            return;
+        };
+        match Ical::lookup_tz(tz) {
+            Some(tz) if !self.tz_configured => self.ctx.floating_tz = Some(tz),
+            Some(_) => {},
+            None => warn!("Ignoring unknown {} '{}'", KEY_X_WR_TIMEZONE, tz),
+        }
+    }
+
+    /// Registers a VTIMEZONE, used for TZIDs unknown to the IANA database
+    fn add_timezone(&mut self, timezone: &IcalTimeZone) {
+        match VTimeZone::parse(timezone) {
+            Ok(vtz) => {
+                self.ctx.timezones.insert(vtz.tzid().to_string(), Some(vtz));
+            },
+            Err((tzid, reason)) => {
+                warn!(
+                    "Cannot interpret VTIMEZONE '{}' ({}), falling back to the Windows name mapping or UTC",
+                    tzid.as_deref().unwrap_or("?"),
+                    reason
+                );
+                if let Some(tzid) = tzid {
+                    self.ctx.timezones.insert(tzid, None);
+                }
+            },
+        }
+    }
+
+    /// Handles a completely read VEVENT
+    /// 
+    /// # Arguments
+    /// * `event` - Collected properties of the event
+    /// * `filter` - Optional window events must come within a day of
+    /// * `at_end` - Whether the whole calendar has been read, so no more
+    ///   VTIMEZONE definitions can appear
+    fn finish_event(&mut self, mut event: PendingEvent, filter: Option<Window>, at_end: bool) {
+        if !at_end && !event.properties.iter().all(|prop| self.ctx.knows_tzid(prop)) {
+            self.deferred.push(event);
+            return;
+        }
+        if event.ends_early && !event.properties.iter().any(|prop| prop.name == KEY_RRULE) {
+            event.skip();
+        }
+
+        self.warn_unknown_tzids(&event.properties);
+
+        let (index, skipped) = (event.index, event.skipped);
+        let properties = if event.todo {
+            match Ical::todo_as_event(event.properties) {
+                Some(properties) => properties,
+                None => return,
+            }
+        } else {
+            event.properties
+        };
+        // Events dropped by the filter keep no DTSTART, only what overrides need
+        let start = if skipped { Ok(()) } else { Ical::check_dtstart(&properties, &self.ctx) };
+        let recurrence = Ical::recurrence(&properties);
+        let mut event = Ical::build_event(properties, &self.ctx);
+        if let Err(e) = start {
+            warn!("Skipping event '{}': {}", event.summary(), e);
+            self.malformed += 1;
+            return;
+        }
+        if !skipped && event.dtend < event.dtstart {
+            if !self.fix_inverted {
+                warn!("Skipping event '{}': DTEND precedes DTSTART", event.summary());
+                self.malformed += 1;
+                return;
+            }
+            warn!("Event '{}' ends before it starts, giving it the default duration", event.summary());
+            event.dtend = event.default_dtend();
+        }
+        if let (Some(uid), Some(recurrence_id)) = (&event.uid, &event.recurrence_id) {
+            self.overrides.push((uid.clone(), recurrence_id.clone()));
+        }
+        if skipped {
+            return;
+        }
+        let instances = match recurrence {
+            Ok(Some((rule, dtstart))) => self.expand(event, &rule, &dtstart, filter),
+            Ok(None) => vec![event],
+            Err(e) => {
+                warn!("Reading only the first instance of event '{}': {}", event.summary(), e);
+                vec![event]
+            },
+        };
+        for instance in instances {
+            if filter.is_none_or(|window| instance.near(window)) {
+                self.events.push((index, instance));
+            }
+        }
+    }
+
+    /// Generates the instances of a recurring event
+    /// 
+    /// # Arguments
+    /// * `event` - The event as written, its DTSTART being the first instance
+    /// * `rule` - Its recurrence rule
+    /// * `dtstart` - Its DTSTART property, giving the zone of the instances
+    /// * `filter` - Optional window instances must come within a day of
+    /// 
+    /// # Returns
+    /// * One event per instance with the duration of `event`, up to a day
+    ///   past the filter, or up to [`RECURRENCE_HORIZON`] past the current
+    ///   time without a filter
+    /// 
+    /// # Note
+    /// * Instances keep their wall-clock time in the zone of DTSTART across
+    ///   DST changes, and a time that does not exist on a day is resolved
+    ///   like any other DTSTART
+    fn expand(&self, event: Event, rule: &RRule, dtstart: &Property, filter: Option<Window>) -> Vec<Event> {
+        let value = dtstart.value.as_deref().unwrap_or_default().trim().to_uppercase();
+        let Some(start) = Ical::wall_clock(&value) else {
+            return vec![event];
+        };
+        let (utc, params) = (value.ends_with('Z'), dtstart.params.as_deref());
+        let limit = match filter {
+            Some(window) => window.end + DAY_MILLIS,
+            None => Utc::now().timestamp_millis().max(event.dtstart).saturating_add(RECURRENCE_HORIZON),
+        };
+        let until = match rule.until() {
+            Some(Until::Utc(until)) => until,
+            _ => i64::MAX,
+        };
+        let duration = event.dtend - event.dtstart;
+
+        let mut instances = Vec::new();
+        for wall in rule.occurrences(start) {
+            let value = match (event.all_day, utc) {
+                (true, _) => wall.format(DATE_FMT).to_string(),
+                (false, true) => format!("{}Z", wall.format(DT_FMT)),
+                (false, false) => wall.format(DT_FMT).to_string(),
+            };
+            let Ok(instant) = Ical::parse_datetime_value(&value, params, true, &self.ctx) else {
+                continue;
+            };
+            if instant > limit || instant > until {
+                break;
+            }
+            let mut instance = event.clone();
+            instance.dtstart = instant;
+            instance.dtend = instant + duration;
+            instance.start_offset = if event.all_day { 0 } else { wall.and_utc().timestamp_millis() - instant };
+            instance.occurrence = Some(instant);
+            instances.push(instance);
+        }
+        instances
+    }
+
+    /// Closes the components left open by missing END lines
+    /// 
+    /// # Note
+    /// * An open event keeps the properties read so far, other open
+    ///   components such as an unterminated VTIMEZONE are dropped
+    fn recover(&mut self, filter: Option<Window>) {
+        if let Some(event) = self.event.take() {
+            debug!("Recovering an event without END line");
+            if !self.implicit {
+                self.recovered += 1;
+            }
+            self.finish_event(event, filter, false);
+        }
+        if let Some(component) = self.components.first().filter(|c| !matches!(c.as_str(), "VEVENT" | "VTODO")) {
+            warn!("Discarding {} without END line", component);
+            self.discarded += 1;
+        }
+        self.components.clear();
+        self.timezone = None;
+        self.transition = None;
+    }
+
+    /// Warns once per calendar about each TZID of an event that cannot be
+    /// resolved, since its times are read in the default zone instead
+    fn warn_unknown_tzids(&mut self, properties: &[Property]) {
+        for prop in properties {
+            if let Some(tzid) = Ical::param(prop.params.as_deref(), "TZID")
+                && matches!(self.ctx.tzid_zone(tzid), TzidZone::Unknown)
+                && self.unknown_tzids.insert(tzid.to_string())
+            {
+                warn!(
+                    "Unknown timezone '{}', reading its times in {}",
+                    tzid,
+                    self.ctx.floating_tz.map_or("UTC", |tz| tz.name())
+                );
+            }
+        }
+    }
+
+    /// Completes the calendar after its last property
+    /// 
+    /// # Arguments
+    /// * `filter` - Optional window events must come within a day of
+    /// 
+    /// # Returns
+    /// * `Parsed` - Events passing the filter, with overridden instances,
+    ///   cancelled events and excluded instances removed
+    fn finish(mut self, filter: Option<Window>) -> Parsed {
+        if self.lenient {
+            self.recover(filter);
+        }
+        if self.implicit {
+            self.recovered = self.event_count;
+        }
+        for event in std::mem::take(&mut self.deferred) {
+            self.finish_event(event, filter, true);
+        }
+        self.events.sort_by_key(|(index, _)| *index);
+        let events = self.events.into_iter().map(|(_, event)| event).collect();
+
+        let events = Ical::apply_overrides(events, &self.overrides)
+            .into_iter()
+            .filter(|event| {
+                // Cancelled events stay in feeds but must not classify any day
+                if event.status == Status::Cancelled {
+                    debug!("Skipping cancelled event '{}'", event.summary());
+                    return false;
+                }
+
+                // Recurring events were expanded into one event per instance
+                !event.is_excluded(event.dtstart)
+            })
+            .collect();
+        Parsed {
+            events,
+            read: self.event_count,
+            malformed: self.malformed,
+            recovered: self.recovered,
+            discarded: self.discarded,
+        }
+    }
+}
+
+impl PendingEvent {
+    /// Drops the event, keeping only what RECURRENCE-ID overrides need
+    fn skip(&mut self) {
+        self.skipped = true;
+        self.properties.retain(|p| p.name == KEY_UID || p.name == KEY_RECURRENCE_ID);
+    }
+
+    /// Collects a property of the event, dropping the event early when it
+    /// cannot contain the filtered instant
+    /// 
+    /// # Arguments
+    /// * `prop` - Property of the VEVENT
+    /// * `filter` - Optional window events must come within a day of
+    /// * `ctx` - Calendar-wide parse settings read so far
+    fn push(&mut self, prop: Property, filter: Option<Window>, ctx: &ParseContext) {
+        let needed_when_skipped = prop.name == KEY_UID || prop.name == KEY_RECURRENCE_ID;
+        if self.skipped {
+            if needed_when_skipped {
+                self.properties.push(prop);
+            }
+            return;
+        }
+
+        // The day of a to-do is only known once DUE or DURATION was read
+        let is_dt_start = prop.name == KEY_DTSTART;
+        if let Some(window) = filter
+            && !self.todo
+            && (is_dt_start || prop.name == KEY_DTEND)
+            && ctx.knows_tzid(&prop)
+            && let Ok(timestamp) = Ical::parse_datetime(&prop, is_dt_start, ctx)
+        {
+            if is_dt_start {
+                self.start = Some(timestamp);
+                // Later occurrences of a recurring event start later still
+                if timestamp > window.end + DAY_MILLIS {
+                    self.skip();
+                    return;
+                }
+            } else {
+                self.ends_early = timestamp <= window.start - DAY_MILLIS && self.start.is_some_and(|start| start <= timestamp);
+            }
+        }
+
+        self.properties.push(prop);
+    }
+}
+
+impl Window {
+    /// Creates a window holding a single instant
+    pub(crate) fn at(timestamp: i64) -> Self {
+        Window { start: timestamp, end: timestamp }
+    }
+
+    /// Creates a window holding every instant of a civil day
+    /// 
+    /// # Arguments
+    /// * `zone` - Zone the day is a civil day of
+    /// * `date` - The day, from midnight to the next midnight excluded
+    pub(crate) fn day(zone: &TargetZone, date: NaiveDate) -> Self {
+        let start = zone.to_instant(date.and_time(NaiveTime::MIN));
+        let next = zone.to_instant((date + TimeDelta::days(1)).and_time(NaiveTime::MIN));
+        Window { start, end: (next - 1).max(start) }
+    }
+}
+
+impl Loaded {
+    /// Indexes the events read
+    /// 
+    /// # Arguments
+    /// * `events` - Parsed calendar events
+    /// * `filter` - Span of instants the events were filtered by, `None` for all events
+    /// * `reports` - Outcome of loading each source
+    fn new(events: Vec<Event>, filter: Option<Window>, reports: Vec<SourceReport>) -> Self {
+        let index = IntervalIndex::new(events.iter().map(|e| (e.dtstart, e.dtend)));
+        Loaded { events, index, filter, reports }
+    }
+
+    /// Finds the events covering any instant of a window, reading all-day events on the wall clock
+    /// 
+    /// # Arguments
+    /// * `window` - Queried instants, a single one or e.g. a whole civil day
+    /// * `zone` - Zone whose wall clock all-day events are read on
+    /// 
+    /// # Returns
+    /// * The timed events whose [dtstart, dtend) overlaps `window` and the
+    ///   all-day events whose span overlaps it on the wall clock, in the order they were read
+    fn covering(&self, window: Window, zone: &TargetZone) -> Vec<&Event> {
+        let wall = |instant: i64| zone.to_wall_clock(instant).and_utc().timestamp_millis();
+        self.overlapping(
+            (window.start, window.end.saturating_add(1)),
+            (wall(window.start), wall(window.end).saturating_add(1)),
+        )
+    }
+
+    /// Finds the events overlapping a range, reading all-day events on the wall clock
+    /// 
+    /// # Arguments
+    /// * `instants` - Start and end of the range in milliseconds, end excluded
+    /// * `wall` - The same range on the wall clock of the target zone, as
+    ///   milliseconds of floating times
+    /// 
+    /// # Returns
+    /// * The overlapping events in the order they were read
+    fn overlapping(&self, instants: (i64, i64), wall: (i64, i64)) -> Vec<&Event> {
+        let timed = self.index.overlapping(instants.0, instants.1).into_iter().filter(|&i| !self.events[i].all_day);
+        let all_day = self.index.overlapping(wall.0, wall.1).into_iter().filter(|&i| self.events[i].all_day);
+        let mut found: Vec<usize> = timed.chain(all_day).collect();
+        found.sort_unstable();
+        found.into_iter().map(|i| &self.events[i]).collect()
+    }
+
+    /// Returns the outcome of loading each configured source, in configuration order
+    pub(crate) fn reports(&self) -> &[SourceReport] {
+        &self.reports
+    }
+
+    /// Checks whether the events can answer queries
+    /// 
+    /// # Arguments
+    /// * `window` - Span of the queried instants, `None` for all events
+    /// 
+    /// # Returns
+    /// * `true` if all events were read, or they were filtered by a window holding the queried one
+    fn answers(&self, window: Option<Window>) -> bool {
+        match (self.filter, window) {
+            (None, _) => true,
+            (Some(filter), Some(window)) => filter.start <= window.start && window.end <= filter.end,
+            (Some(_), None) => false,
+        }
+    }
+}
+
+impl Parsed {
+    /// Adds the events and counters read from further data of the same source
+    fn extend(&mut self, other: Parsed) {
+        self.events.extend(other.events);
+        self.read += other.read;
+        self.malformed += other.malformed;
+        self.recovered += other.recovered;
+        self.discarded += other.discarded;
+    }
+}
+
+impl Ical {
+    /// Creates a new iCalendar analyzer with the given configuration
+    pub fn new(conf: Conf) -> Self {
+        Ical {
+            conf,
+            loaded: RwLock::new(None),
+            use_cache: true,
+            revalidate: false,
+            strict: false,
+            resolver: None,
+            shared: None,
+        }
+    }
+
+    /// Returns the configuration the events are classified by
+    pub(crate) fn conf(&self) -> &Conf {
+        &self.conf
+    }
+
+    /// Decides days classified by calendar events with a custom resolver
+    /// instead of `predict.priority`
+    pub(crate) fn set_resolver(&mut self, resolver: Box<dyn ConflictResolver>) {
+        self.resolver = Some(resolver);
+    }
+
+    /// Reuses remote calendar data other analyzers holding the same handle downloaded
+    pub(crate) fn set_shared(&mut self, shared: SharedFetches) {
+        self.shared = Some(shared);
+    }
+
+    /// Enables or disables the on-disk cache of remote sources
+    /// 
+    /// # Note
+    /// * Without the cache, every fetch downloads the full calendar and
+    ///   failed sources have no stale copy to fall back to
+    pub fn set_use_cache(&mut self, use_cache: bool) {
+        self.use_cache = use_cache;
+    }
+
+    /// Asks servers for every remote source, even when the cached copy is
+    /// still fresh by `Cache-Control`, `Expires` or `cache.ttl`
+    pub fn set_revalidate(&mut self, revalidate: bool) {
+        self.revalidate = revalidate;
+    }
+
+    /// Enables or disables strict handling of questionable event data
+    /// 
+    /// # Note
+    /// * In strict mode events whose DTEND precedes DTSTART are skipped as
+    ///   malformed, regardless of `calendar.fix_inverted`
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Reads and parses calendar events from configured sources
+    /// 
+    /// # Arguments
+    /// * `dest_day_ts` - Optional window to filter events by for a specific day
+    /// 
+    /// # Note
+    /// * Supports both HTTP URLs and local file paths
+    /// * The outcome of every source is recorded, see [`Ical::source_reports`]
+    /// * Events read with a timestamp only answer queries for that timestamp;
+    ///   other queries read the sources again
+    pub async fn read_events(&self, dest_day_ts: Option<i64>) {
+        self.read(dest_day_ts.map(Window::at)).await;
+    }
+
+    /// Reads the configured sources and keeps the events for later queries
+    /// 
+    /// # Arguments
+    /// * `window` - Optional span of queried instants to filter events by
+    /// 
+    /// # Returns
+    /// * The events read, still valid for the caller when another read
+    ///   replaces them in the meantime
+    pub(crate) async fn read(&self, window: Option<Window>) -> Arc<Loaded> {
+        let loaded = Arc::new(self.fetch(window).await);
+        *self.loaded.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::clone(&loaded));
+        loaded
+    }
+
+    /// Reads all configured sources and swaps in their events only when every
+    /// source was fetched
+    /// 
+    /// # Returns
+    /// * `Ok(Vec<SourceReport>)` - Reports of the new events, now answering queries
+    /// * `Err(Vec<SourceReport>)` - Reports of the failed read; queries keep
+    ///   being answered from the events read before
+    /// 
+    /// # Note
+    /// * Sources falling back to a cached copy count as failed, the events in
+    ///   memory are at least as recent
+    /// * Without events read before, the result is kept whatever the outcome
+    pub(crate) async fn reload(&self) -> Result<Vec<SourceReport>, Vec<SourceReport>> {
+        let fetched = self.fetch(None).await;
+        let complete = fetched.reports.iter().all(|report| matches!(report.status(), SourceStatus::Loaded { .. }));
+        let mut loaded = self.loaded.write().unwrap_or_else(PoisonError::into_inner);
+        if !complete && loaded.is_some() {
+            return Err(fetched.reports);
+        }
+        let reports = fetched.reports.clone();
+        *loaded = Some(Arc::new(fetched));
+        Ok(reports)
+    }
+
+    /// Fetches and parses the configured sources without keeping the events
+    /// 
+    /// # Arguments
+    /// * `window` - Optional span of queried instants to filter events by
+    async fn fetch(&self, window: Option<Window>) -> Loaded {
+        let http = self.conf.http();
+        let client = source::client(http);
+        let client = Arc::new(client);
+
+        let cache = if self.use_cache {
+            Cache::open(&self.conf).map(|cache| cache.revalidate(self.revalidate))
+        } else {
+            None
+        };
+        let cache = cache.as_ref();
+        let shared = self.shared.as_ref();
+
+        let sources = self.conf.get_describe_calendar().unwrap_or(&[]);
+        let conf = &self.conf;
+        let conf_dir = self.conf.conf_dir();
+        let tasks = sources
+            .iter()
+            .map(|source| {
+                let client = Arc::clone(&client);
+                let options = Arc::new(self.parse_options(conf.lenient(source)));
+                async move {
+                    let started = Instant::now();
+                    let mut stats = SourceStats::default();
+                    let (parsing, final_url, stale) = match source.location() {
+                        Location::Url(uri) => {
+                            let fetched = source::fetch_shared(&client, uri, http, cache, conf_dir, shared).await?;
+                            stats.record(&fetched);
+                            let content_type = fetched.content_type().map(str::to_string);
+                            let final_url = fetched.final_url().map(str::to_string);
+                            let stale = fetched.stale().cloned();
+                            let body = source::decode(fetched.into_body(), content_type.as_deref(), uri);
+                            let parsing = Self::spawn_parse(source, body, content_type.as_deref(), window, &options);
+                            (parsing, final_url, stale)
+                        },
+                        Location::Exec(exec) => {
+                            let output = exec::run(exec, conf_dir, http.max_body_bytes()).await?;
+                            stats.bytes = output.len() as u64;
+                            let body = source::decode(output, None, source.name());
+                            let parsing = Self::spawn_parse(source, body, None, window, &options);
+                            (parsing, None, None)
+                        },
+                        Location::Nager(nager) => {
+                            let mut props = Vec::new();
+                            let mut stale: Option<(Duration, String)> = None;
+                            for year in nager::years(window) {
+                                let url = nager::url(nager, year);
+                                let fetched = source::fetch_shared(&client, &url, http, cache, conf_dir, shared)
+                                    .await
+                                    .map_err(|cause| format!("{} (holidays of {})", cause, year))?;
+                                stats.record(&fetched);
+                                // Report the oldest copy when several years are stale
+                                if let Some(year_stale) = fetched.stale()
+                                    && stale.as_ref().is_none_or(|(age, _)| year_stale.0 > *age)
+                                {
+                                    stale = Some(year_stale.clone());
+                                }
+                                let content_type = fetched.content_type().map(str::to_string);
+                                let body = source::decode(fetched.into_body(), content_type.as_deref(), &url);
+                                props.extend(nager::properties(&body, nager, &options.explicit_property)?);
+                            }
+                            let options = Arc::clone(&options);
+                            let parsing = task::spawn_blocking(move || {
+                                Self::read_properties(props.into_iter().map(Ok), window, &options)
+                            });
+                            (parsing, None, stale)
+                        },
+                        Location::CalDav(caldav) => {
+                            let credentials = caldav.credentials()?;
+                            let credentials = credentials.as_ref().map(|(user, pass)| (user.as_str(), pass.as_str()));
+                            let query = caldav::query(window);
+                            let fetched = source::report(&client, caldav.url(), http, &query, credentials).await?;
+                            stats.record(&fetched);
+                            let final_url = fetched.final_url().map(str::to_string);
+                            let content_type = fetched.content_type().map(str::to_string);
+                            let body = source::decode(fetched.into_body(), content_type.as_deref(), caldav.url());
+
+                            // Each calendar object is a VCALENDAR of its own
+                            let objects = caldav::calendar_data(&body)?;
+                            let url = caldav.url().to_string();
+                            let options = Arc::clone(&options);
+                            let parsing = task::spawn_blocking(move || {
+                                let mut parsed = Parsed::default();
+                                for data in objects {
+                                    match Self::parse_calendar(Cursor::new(data), window, &options) {
+                                        Ok(object) => parsed.extend(object),
+                                        Err(e) => warn!("Skipping unreadable calendar object from '{}': {}", url, e),
+                                    }
+                                }
+                                Ok(parsed)
+                            });
+                            (parsing, final_url, None)
+                        },
+                        Location::Google(google) => {
+                            let props = google::properties(&client, google, http, conf_dir, window, &mut stats).await?;
+                            let options = Arc::clone(&options);
+                            let parsing = task::spawn_blocking(move || {
+                                Self::read_properties(props.into_iter().map(Ok), window, &options)
+                            });
+                            (parsing, None, None)
+                        },
+                    };
+                    stats.fetch_time = started.elapsed();
+                    Ok((parsing, final_url, stale, stats))
+                }
+            });
+
+        // Sources are fetched one after another while earlier ones are parsed
+        // on the blocking pool, then merged in configuration order
+        let mut started = Vec::with_capacity(sources.len());
+        for task in tasks {
+            started.push(task.await);
+        }
+
+        let ignore_transparent = self.conf.ignore_transparent();
+        let mut all_events = Vec::new();
+        let mut reports = Vec::with_capacity(sources.len());
+        for (source, started) in sources.iter().zip(started) {
+            let uri = source.name();
+            let result = match started {
+                Ok((parsing, final_url, stale, stats)) => match parsing.await {
+                    Ok(parsed) => parsed.map(|parsed| (parsed, final_url, stale, stats)),
+                    Err(e) => Err(format!("Parsing failed: {}", e)),
+                },
+                Err(cause) => Err(cause),
+            };
+            match result {
+                Ok((Parsed { mut events, read, malformed, recovered, discarded }, final_url, stale, mut stats)) => {
+                    for event in events.iter_mut() {
+                        event.add_source(uri);
+                    }
+                    stats.parsed = read;
+                    stats.kept = events.len();
+                    if let (Some(cache), Location::Url(uri)) = (cache, source.location()) {
+                        let url = source::normalize(uri, http);
+                        if url.starts_with("http") {
+                            cache.record_events(&url, read);
+                        }
+                    }
+                    stats.malformed = malformed;
+                    stats.recovered = recovered;
+                    stats.discarded = discarded;
+                    for event in events.iter().filter(|e| !(ignore_transparent && e.is_transparent())) {
+                        match event.day_type(&self.conf).0 {
+                            DayType::WorkDay => stats.work += 1,
+                            DayType::RestDay => stats.rest += 1,
+                            DayType::ConflictDay => stats.conflict += 1,
+                            DayType::NormalDay | DayType::Unknown => {},
+                        }
+                    }
+                    let status = match stale {
+                        Some((age, cause)) => SourceStatus::Stale { events: events.len(), age, cause },
+                        None => SourceStatus::Loaded { events: events.len() },
+                    };
+                    reports.push(
+                        SourceReport::new(uri, status)
+                            .with_final_url(final_url)
+                            .with_stats(stats),
+                    );
+                    all_events.extend(events);
+                },
+                Err(cause) => {
+                    reports.push(SourceReport::new(uri, SourceStatus::Failed(cause)));
+                },
+            }
+        }
+
+        if let Some(cache) = cache {
+            cache.save();
+        }
+
+        Loaded::new(Self::dedup_by_uid(all_events), window, reports)
+    }
+
+    /// Parses the data of a source on the blocking pool in its format
+    /// 
+    /// # Arguments
+    /// * `source` - Source the data came from, naming its format and CSV columns
+    /// * `body` - Decoded data
+    /// * `content_type` - Media type the data was served with, if any
+    /// * `window` - Optional span of queried instants to filter events by
+    /// * `options` - Parsing options of the source
+    fn spawn_parse(
+        source: &Source,
+        body: Vec<u8>,
+        content_type: Option<&str>,
+        window: Option<Window>,
+        options: &Arc<ParseOptions>,
+    ) -> task::JoinHandle<Result<Parsed, String>> {
+        let format = source::format(source, content_type, &body);
+        let columns: Vec<String> = source.columns().into_iter().map(str::to_string).collect();
+        let options = Arc::clone(options);
+        task::spawn_blocking(move || match format {
+            SourceFormat::Ics => Self::parse_calendar(Cursor::new(body), window, &options),
+            SourceFormat::Jcal => Self::parse_jcal(&body, window, &options),
+            SourceFormat::Csv => {
+                let columns: Vec<&str> = columns.iter().map(String::as_str).collect();
+                Self::parse_csv(&body, &columns, window, &options)
+            },
+        })
+    }
+
+    /// Downloads every remote source and compares it with its cached copy
+    /// 
+    /// # Arguments
+    /// * `first` - First day to compare
+    /// * `last` - Last day to compare, included
+    /// 
+    /// # Returns
+    /// * `Ok(Vec<Change>)` - Days added, removed or reclassified by the
+    ///   downloads, by source in configuration order, then by date
+    /// * `Err(String)` - The cache is disabled or unavailable, or a source
+    ///   failed to download or parse
+    /// 
+    /// # Note
+    /// * The download replaces the cached copy, so the next call compares with it
+    /// * Sources never cached before have nothing to compare with; their
+    ///   download becomes the first snapshot
+    /// * Only HTTP(S) URL sources are compared; local files, holiday APIs,
+    ///   CalDAV and Google sources are skipped
+    pub(crate) async fn changes(&self, first: NaiveDate, last: NaiveDate) -> Result<Vec<Change>, String> {
+        let Some(cache) = Cache::open(&self.conf).filter(|_| self.use_cache).map(|cache| cache.revalidate(true)) else {
+            return Err("Detecting changes needs the on-disk cache, which is disabled or unavailable".to_string());
+        };
+        let http = self.conf.http();
+        let client = source::client(http);
+        let zone = self.conf.target_zone();
+        let window = Window {
+            start: zone.to_instant(first.and_time(NaiveTime::MIN)),
+            end: zone.to_instant(last.and_time(NaiveTime::MIN)) + DAY_MILLIS - 1,
+        };
+
+        let mut found = Vec::new();
+        for source in self.conf.get_describe_calendar().unwrap_or(&[]) {
+            let Location::Url(uri) = source.location() else {
+                continue;
+            };
+            let url = source::normalize(uri, http);
+            if !url.starts_with("http") {
+                continue;
+            }
+            let cached = cache.fallback(&url).map(|(body, _)| body);
+            let fetched = source::fetch(&client, uri, http, Some(&cache), self.conf.conf_dir())
+                .await
+                .map_err(|e| format!("Calendar source '{}' failed: {}", source.name(), e))?;
+            let Some(cached) = cached else {
+                info!("No cached copy of '{}' to compare with, keeping the download as the first snapshot", source.name());
+                continue;
+            };
+
+            let content_type = fetched.content_type().map(str::to_string);
+            let before = self.snapshot(source, cached, None, window, first, last)?;
+            let after = self.snapshot(source, fetched.into_body(), content_type.as_deref(), window, first, last)?;
+            found.extend(changes::compare(source.name(), &before, &after));
+        }
+        cache.save();
+        Ok(found)
+    }
+
+    /// Classifies each day the events of a copy of a source cover
+    /// 
+    /// # Arguments
+    /// * `source` - Configured source the copy belongs to
+    /// * `body` - Raw calendar data
+    /// * `content_type` - `Content-Type` the data was served with, if known
+    /// * `window` - Span of instants to parse events for
+    /// * `first` / `last` - Days to keep, both included
+    /// 
+    /// # Returns
+    /// * `Ok(DaySnapshot)` - Classification by day and event identity: the
+    ///   UID, or a hash of the start and summary for events without one
+    /// * `Err(String)` - The data contains no readable calendar
+    fn snapshot(
+        &self,
+        source: &Source,
+        body: Vec<u8>,
+        content_type: Option<&str>,
+        window: Window,
+        first: NaiveDate,
+        last: NaiveDate,
+    ) -> Result<DaySnapshot, String> {
+        let body = source::decode(body, content_type, source.name());
+        let options = self.parse_options(self.conf.lenient(source));
+        let parsed = match source::format(source, content_type, &body) {
+            SourceFormat::Ics => Self::parse_calendar(Cursor::new(body), Some(window), &options),
+            SourceFormat::Jcal => Self::parse_jcal(&body, Some(window), &options),
+            SourceFormat::Csv => Self::parse_csv(&body, &source.columns(), Some(window), &options),
+        }
+        .map_err(|e| format!("Calendar source '{}' unreadable: {}", source.name(), e))?;
+
+        let ignore_transparent = self.conf.ignore_transparent();
+        let mut snapshot = DaySnapshot::new();
+        for event in Self::dedup_by_uid(parsed.events) {
+            if ignore_transparent && event.is_transparent() {
+                continue;
+            }
+            let identity = event.uid.clone().unwrap_or_else(|| {
+                format!("{:016x}", fnv1a(format!("{}\n{}", event.dtstart, event.summary()).as_bytes()))
+            });
+            let day_type = event.day_type(&self.conf).0;
+            let ((_, start), (_, end)) = event.bounds(&self.conf);
+            let end = (end - TimeDelta::milliseconds(1)).date().max(start.date());
+            for date in start.date().max(first).iter_days().take_while(|date| *date <= end.min(last)) {
+                snapshot.insert((date, identity.clone()), (event.summary().to_string(), day_type));
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Returns the outcome of loading each configured source
+    /// 
+    /// # Returns
+    /// * Reports of the last read in configuration order, empty before events
+    ///   were read and after [`Ical::invalidate`]
+    pub fn source_reports(&self) -> Vec<SourceReport> {
+        self.current().map(|loaded| loaded.reports.clone()).unwrap_or_default()
+    }
+
+    /// Returns the events of the last read, if any
+    fn current(&self) -> Option<Arc<Loaded>> {
+        self.loaded.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Removes duplicate copies of events sharing a UID (and instance start)
+    /// 
+    /// # Arguments
+    /// * `events` - Merged events from all sources
+    /// 
+    /// # Returns
+    /// * `Vec<Event>` - Events with one copy per UID, in first-seen order
+    /// 
+    /// # Note
+    /// * The copy with the highest SEQUENCE wins, then the latest LAST-MODIFIED
+    /// * The kept copy lists every source the UID appeared in
+    /// * Instances of a recurring event and their RECURRENCE-ID overrides are
+    ///   told apart by their start, so only copies of the same instance merge
+    /// * Events without a UID are never deduplicated
+    fn dedup_by_uid(events: Vec<Event>) -> Vec<Event> {
+        let mut kept: Vec<Event> = Vec::with_capacity(events.len());
+        let mut by_uid: HashMap<(String, Option<i64>), usize> = HashMap::new();
+
+        for mut event in events {
+            let Some(uid) = event.uid.clone() else {
+                kept.push(event);
+                continue;
+            };
+            // Overrides of different instances share the UID of their series
+            let key = (uid, event.recurrence_id.as_ref().map(|rid| rid.timestamp).or(event.occurrence));
+
+            match by_uid.get(&key) {
+                Some(&index) => {
+                    let existing = &mut kept[index];
+                    if event.revision() > existing.revision() {
+                        for source in existing.sources.drain(..) {
+                            event.add_source(&source);
+                        }
+                        *existing = event;
+                    } else {
+                        for source in &event.sources {
+                            existing.add_source(source);
+                        }
+                    }
+                },
+                None => {
+                    by_uid.insert(key, kept.len());
+                    kept.push(event);
+                },
+            }
+        }
+        kept
+    }
+
+    /// Drops the cached events, so the next query reads the sources again
+    /// 
+    /// # Note
+    /// * The reports of the last read are dropped with the events
+    /// * The on-disk cache is left alone: remote sources are always revalidated
+    ///   with conditional requests, so the next read sees changed data anyway
+    /// * Queries already running keep using the events they started with
+    pub fn invalidate(&self) {
+        *self.loaded.write().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    /// Reads all events from the configured sources unless they are cached
+    /// 
+    /// # Note
+    /// * Afterwards every query is answered from the cache, without fetching
+    pub async fn load(&self) {
+        self.loaded_for(None).await;
+    }
+
+    /// Uses events from iCalendar data instead of reading the configured sources
+    /// 
+    /// # Arguments
+    /// * `data` - iCalendar data, parsed with the configured settings
+    /// 
+    /// # Returns
+    /// * `Err(String)` if the data contains no readable calendar
+    /// 
+    /// # Note
+    /// * The events answer every query until `invalidate` drops them; only
+    ///   then are the configured sources read
+    pub(crate) fn preload(&self, data: &str) -> Result<(), String> {
+        let parsed = Self::parse_calendar(Cursor::new(data.as_bytes()), None, &self.parse_options(false))?;
+        let loaded = Loaded::new(Self::dedup_by_uid(parsed.events), None, Vec::new());
+        *self.loaded.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(loaded));
+        Ok(())
+    }
+
+    /// Lists the events overlapping a range of days
+    /// 
+    /// # Arguments
+    /// * `start` - First day of the range, in the target zone
+    /// * `end` - Last day of the range, included
+    /// 
+    /// # Returns
+    /// * Events overlapping the range, classified or not, ordered by start;
+    ///   transparent events are left out under `calendar.ignore_transparent`
+    /// 
+    /// # Note
+    /// * Only events around the range are read, unless all events were read already
+    /// * An inverted range is swapped
+    /// 
+    /// # Examples
+    /// ```no_run
+    /// use chrono::NaiveDate;
+    /// use choliday::{Conf, DayType, Ical};
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), String> {
+    /// let ical = Ical::new(Conf::load("config.toml")?);
+    /// let start = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+    /// let end = NaiveDate::from_ymd_opt(2026, 10, 31).unwrap();
+    /// for event in ical.events_between(start, end).await {
+    ///     if event.day_type() == DayType::RestDay {
+    ///         println!("{} {}", event.local_start().date(), event.summary());
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn events_between(&self, start: NaiveDate, end: NaiveDate) -> Vec<PublicEvent> {
+        let from = start.min(end).and_time(NaiveTime::MIN);
+        let to = start.max(end).succ_opt().unwrap_or(NaiveDate::MAX).and_time(NaiveTime::MIN);
+        let zone = self.conf.target_zone();
+        let loaded = self.loaded_within(Some(Window { start: zone.to_instant(from), end: zone.to_instant(to) })).await;
+        self.public_events(&loaded, from, to, |_| true, usize::MAX)
+    }
+
+    /// Describes the events overlapping a span of wall-clock time
+    /// 
+    /// # Arguments
+    /// * `loaded` - Events read for the span
+    /// * `from` - Start of the span, on the wall clock of the target zone
+    /// * `to` - End of the span, excluded
+    /// * `keep` - Selects events by how they classify their days
+    /// * `limit` - Number of events to describe at most, the earliest first
+    /// 
+    /// # Returns
+    /// * Selected events ordered by start, then by configured source order and summary
+    pub(crate) fn public_events(
+        &self,
+        loaded: &Loaded,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        keep: impl Fn(DayType) -> bool,
+        limit: usize,
+    ) -> Vec<PublicEvent> {
+        let zone = self.conf.target_zone();
+        let ignore_transparent = self.conf.ignore_transparent();
+        let (from_ts, to_ts) = (zone.to_instant(from), zone.to_instant(to));
+        let (from_floating, to_floating) = (from.and_utc().timestamp_millis(), to.and_utc().timestamp_millis());
+        let local_start = |event: &Event| {
+            if event.all_day {
+                DateTime::from_timestamp_millis(event.dtstart).unwrap_or_default().naive_utc()
+            } else {
+                zone.to_wall_clock(event.dtstart)
+            }
+        };
+
+        let sources = self.conf.get_describe_calendar().unwrap_or(&[]);
+        let mut selected: Vec<(&Event, DayType, Match)> = loaded
+            .overlapping((from_ts, to_ts), (from_floating, to_floating))
+            .into_iter()
+            .filter(|e| !(ignore_transparent && e.is_transparent()))
+            .map(|e| {
+                let (day_type, matched) = e.matched(&self.conf);
+                (e, day_type, matched)
+            })
+            .filter(|(_, day_type, _)| keep(*day_type))
+            .collect();
+        selected.sort_by_cached_key(|(e, _, _)| (local_start(e), e.source_index(sources), e.summary().to_string()));
+
+        // Only the events returned are copied out of the cache
+        selected
+            .into_iter()
+            .take(limit)
+            .map(|(event, day_type, matched)| event.public(&matched, day_type, &self.conf))
+            .collect()
+    }
+
+    /// Collects the configured settings that affect parsing
+    /// 
+    /// # Arguments
+    /// * `lenient` - Whether the source is read leniently
+    fn parse_options(&self, lenient: bool) -> ParseOptions {
+        ParseOptions {
+            default_tz: self.conf.timezone(),
+            explicit_property: self.conf.explicit_property().to_string(),
+            include_todos: self.conf.include_todos(),
+            lenient,
+            fix_inverted: self.conf.fix_inverted() && !self.strict,
+            dst_ambiguous: self.conf.dst_ambiguous(),
+            dst_gap: self.conf.dst_gap(),
+        }
+    }
+
+    /// Returns events able to answer a query, reading the sources if the
+    /// cached ones cannot
+    /// 
+    /// # Arguments
+    /// * `dest_day_ts` - Timestamp of the query, `None` for all events
+    /// 
+    /// # Note
+    /// * Concurrent queries may read the sources at the same time; each
+    ///   classifies with the events it got, the last read is cached
+    pub(crate) async fn loaded_for(&self, dest_day_ts: Option<i64>) -> Arc<Loaded> {
+        self.loaded_within(dest_day_ts.map(Window::at)).await
+    }
+
+    /// Returns events able to answer queries for a span of instants, reading
+    /// the sources if the cached ones cannot
+    /// 
+    /// # Arguments
+    /// * `window` - Span of the queried instants, `None` for all events
+    pub(crate) async fn loaded_within(&self, window: Option<Window>) -> Arc<Loaded> {
+        match self.current() {
+            Some(loaded) if loaded.answers(window) => loaded,
+            _ => self.read(window).await,
+        }
+    }
+
+    /// Determines the day type by applying priority rules to calendar events
+    /// 
+    /// # Arguments
+    /// * `range` - Optional start and end of the queried instants in
+    ///   milliseconds, end excluded: a single instant or e.g. a whole civil day
+    /// 
+    /// # Returns
+    /// * `DayType` based on priority rules and every event overlapping the
+    ///   range, `NormalDay` when no event classifies it
+    pub async fn judge_by_priority(&self, range: Option<(i64, i64)>) -> DayType {
+        let window = range.map(|(start, end)| Window { start, end: (end - 1).max(start) });
+        match self.resolve(window).await.decided {
+            Some((day_type, _, _)) => day_type,
+            None => DayType::NormalDay,
+        }
+    }
+
+    /// Applies priority rules to calendar events and finds the deciding event
+    /// 
+    /// # Arguments
+    /// * `window` - Optional span of queried instants, a single one or a whole day
+    /// 
+    /// # Returns
+    /// * `Resolution` with the day type, `WorkDay` or `RestDay`, and the event
+    ///   that decided it, plus a note when events disagreed
+    /// 
+    /// # Note
+    /// * Every event overlapping the window takes part, so over a whole day an
+    ///   event covering only some hours of it counts like one covering all of them
+    /// * Matching events are ordered by DTSTART, then by configured source
+    ///   order and summary, so `KeepCurrent` keeps the earliest-starting event
+    /// * `UseLatest` uses the most recently authored event by
+    ///   (LAST-MODIFIED, DTSTAMP, SEQUENCE), falling back to the latest-starting one
+    /// * When some events carry an explicit classification, only those are considered
+    /// * Under `WorkOverRest` and `RestOverWork` the earliest event of the
+    ///   winning kind is reported as deciding
+    /// * A resolver set with `set_resolver` replaces the priority rule
+    pub(crate) async fn resolve(&self, window: Option<Window>) -> Resolution {
+        // Ensure events for this query are loaded
+        let loaded = self.loaded_within(window).await;
+        self.classify(&loaded, window)
+    }
+
+    /// Applies priority rules to events already read, without reading any source
+    /// 
+    /// # Arguments
+    /// * `loaded` - Events to classify by, read for this window or for all events
+    /// * `window` - Optional span of queried instants, a single one or a whole day
+    /// 
+    /// # Returns
+    /// * `Resolution` as described for [`Ical::resolve`]
+    pub(crate) fn classify(&self, loaded: &Loaded, window: Option<Window>) -> Resolution {
+        self.examine(loaded, window, false).0
+    }
+
+    /// Applies priority rules like [`Ical::classify`], also describing every
+    /// event covering the window and what priority resolution did with it
+    /// 
+    /// # Arguments
+    /// * `loaded` - Events to classify by, read for this window
+    /// * `window` - Span of queried instants, a single one or a whole day
+    /// 
+    /// # Returns
+    /// * `Resolution` as described for [`Ical::resolve`] and the covering
+    ///   events in resolution order, transparent ones included
+    pub(crate) fn trace(&self, loaded: &Loaded, window: Window) -> (Resolution, Vec<TracedEvent>) {
+        self.examine(loaded, Some(window), true)
+    }
+
+    /// Shared implementation of [`Ical::classify`] and [`Ical::trace`]
+    /// 
+    /// # Arguments
+    /// * `loaded` - Events to classify by
+    /// * `window` - Optional span of queried instants, a single one or a whole day
+    /// * `traced` - Whether to describe the covering events
+    fn examine(&self, loaded: &Loaded, window: Option<Window>, traced: bool) -> (Resolution, Vec<TracedEvent>) {
+        // Borrow the events, classification only reads them
+        let events = &loaded.events;
+        let ignore_transparent = self.conf.ignore_transparent();
+        let zone = self.conf.target_zone();
+        let ignored = |e: &Event| ignore_transparent && e.is_transparent();
+        
+        // Filter the events relevant to this query; all-day events cover the
+        // civil day of the target zone. A trace keeps ignored events to list them
+        let covering = match window {
+            Some(window) => loaded.covering(window, &zone),
+            None => events.iter().collect(),
+        };
+        let mut candidates: Vec<&Event> = covering
+            .into_iter()
+            .filter(|e| traced || !ignored(e))
+            .collect();
+
+        // Order by start so "first" and "latest" do not depend on feed or source order
+        let sources = self.conf.get_describe_calendar().unwrap_or(&[]);
+        candidates.sort_by(|a, b| {
+            a.dtstart
+                .cmp(&b.dtstart)
+                .then_with(|| a.source_index(sources).cmp(&b.source_index(sources)))
+                .then_with(|| a.summary().cmp(b.summary()))
+        });
+
+        // Classify events
+        let examined: Vec<(&Event, DayType, Match)> = candidates
+            .into_iter()
+            .map(|e| {
+                let (day_type, matched) = e.matched(&self.conf);
+                (e, day_type, matched)
+            })
+            .collect();
+        let mut classified: Vec<&(&Event, DayType, Match)> = examined
+            .iter()
+            .filter(|(e, x, _)| *x != DayType::NormalDay && !ignored(e))
+            .collect();
+
+        // Explicitly classified events outrank pattern matches
+        if classified.iter().any(|(e, _, _)| e.explicit_type.is_some()) {
+            classified.retain(|(e, _, _)| e.explicit_type.is_some());
+        }
+
+        // Let the resolver decide, the configured priority rule by default
+        let priority = *self.conf.priority();
+        let resolver: &dyn ConflictResolver = self.resolver.as_deref().unwrap_or(&priority);
+        let candidates: Vec<Candidate> = classified.iter().map(|(e, x, _)| e.candidate(*x, &self.conf)).collect();
+        let decisive = if candidates.is_empty() {
+            None
+        } else {
+            match resolver.resolve(&candidates) {
+                day_type @ (DayType::WorkDay | DayType::RestDay) => resolver
+                    .decisive(&candidates, day_type)
+                    .and_then(|index| classified.get(index))
+                    .map(|c| (*c, day_type)),
+                _ => None,
+            }
+        };
+
+        // An event matching both work and rest may be left unresolved instead
+        let decisive = match decisive {
+            Some((entry, _)) if entry.1 == DayType::ConflictDay && self.conf.on_conflict() == OnConflict::Error => {
+                Some((entry, DayType::ConflictDay))
+            },
+            decisive => decisive,
+        };
+
+        // An unresolved day is explained by its source, priority settled nothing
+        let unresolved = decisive.is_some_and(|(_, day_type)| day_type == DayType::ConflictDay);
+        let disagree = !unresolved && candidates.iter().any(Candidate::is_work) && candidates.iter().any(Candidate::is_rest);
+        let contradiction = window.filter(|_| disagree).map(|window| Contradiction {
+            date: zone.to_wall_clock(window.start).date(),
+            events: classified
+                .iter()
+                .map(|entry @ (event, day_type, _)| ContradictingEvent {
+                    summary: event.summary().to_string(),
+                    uid: event.uid.clone(),
+                    source: event.source_name(&self.conf),
+                    day_type: *day_type,
+                    won: decisive.is_some_and(|(d, _)| std::ptr::eq(d, *entry)),
+                })
+                .collect(),
+            rule: resolver.describe(),
+            winner: decisive.map(|(_, day_type)| day_type),
+        });
+
+        let trace = if traced {
+            let decided = decisive.map(|(_, day_type)| day_type);
+            examined
+                .iter()
+                .map(|entry @ (event, day_type, matched)| {
+                    let outcome = if ignored(event) {
+                        TraceOutcome::Transparent
+                    } else if *day_type == DayType::NormalDay {
+                        TraceOutcome::Unclassified
+                    } else if decisive.is_some_and(|(d, _)| std::ptr::eq(d, entry)) {
+                        TraceOutcome::Decisive
+                    } else if classified.iter().any(|c| std::ptr::eq(*c, entry))
+                        && (*day_type == DayType::ConflictDay || Some(*day_type) == decided)
+                    {
+                        TraceOutcome::Kept
+                    } else {
+                        TraceOutcome::Discarded
+                    };
+                    event.traced(matched, *day_type, outcome, &self.conf)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let resolution = Resolution {
+            span: decisive
+                .and_then(|((event, _, _), _)| (!event.all_day).then_some((event.dtstart, event.dtend))),
+            decided: decisive.map(|((event, _, matched), day_type)| {
+                (day_type, matched.rest_kind(day_type, &self.conf), event.decision_source(matched, day_type, &self.conf))
+            }),
+            provenance: decisive.map(|((event, _, matched), day_type)| {
+                event.provenance(matched, day_type, resolver.describe(), disagree, &self.conf)
+            }),
+            contradiction,
+        };
+        (resolution, trace)
+    }
+
+    /// Parses iCalendar data from a reader
+    /// 
+    /// # Arguments
+    /// * `reader` - Buffered reader containing iCalendar data
+    /// * `filter` - Optional window to filter events by
+    /// * `options` - Configured settings such as the zone for floating times
+    /// 
+    /// # Returns
+    /// * `Ok(Parsed)` - Parsed events
+    /// * `Err(String)` - Parse error when the data contains no readable calendar
+    /// 
+    /// # Note
+    /// * Data is streamed property by property, so only events passing the
+    ///   filter are ever held in memory
+    /// * With a filter, an event whose DTSTART lies over a day after (or DTEND
+    ///   over a day before) the filtered instant is dropped as soon as that property is read, keeping
+    ///   only what RECURRENCE-ID overrides need
+    fn parse_calendar<T: BufRead>(reader: T, filter: Option<Window>, options: &ParseOptions) -> Result<Parsed, String> {
+        let props = PropertyParser::from_reader(reader).map(|prop| prop.map_err(|e| e.to_string()));
+        Self::read_properties(props, filter, options)
+    }
+
+    /// Parses jCal (RFC 7265) data
+    /// 
+    /// # Arguments
+    /// * `body` - jCal data, already decoded to UTF-8
+    /// * `filter` - Optional window to filter events by
+    /// * `options` - Configured settings such as the zone for floating times
+    /// 
+    /// # Returns
+    /// * `Ok(Parsed)` - Parsed events
+    /// * `Err(String)` - Parse error when the data contains no readable calendar
+    /// 
+    /// # Note
+    /// * The jCal data is read as the iCalendar properties it stands for, so
+    ///   dates, timezones and overrides mean the same as in an `.ics` file
+    fn parse_jcal(body: &[u8], filter: Option<Window>, options: &ParseOptions) -> Result<Parsed, String> {
+        let props = jcal::properties(body)?;
+        Self::read_properties(props.into_iter().map(Ok), filter, options)
+    }
+
+    /// Parses CSV rows or a plain list of dates
+    /// 
+    /// # Arguments
+    /// * `body` - CSV data, already decoded to UTF-8
+    /// * `columns` - Column names of the data
+    /// * `filter` - Optional window to filter events by
+    /// * `options` - Configured settings such as the explicit classification property
+    /// 
+    /// # Returns
+    /// * `Ok(Parsed)` - One all-day event per readable row
+    /// * `Err(String)` - The columns have no `date` column
+    fn parse_csv(body: &[u8], columns: &[&str], filter: Option<Window>, options: &ParseOptions) -> Result<Parsed, String> {
+        let props = csv::properties(body, columns, &options.explicit_property)?;
+        Self::read_properties(props.into_iter().map(Ok), filter, options)
+    }
+
+    /// Reads the calendars in a stream of iCalendar properties
+    /// 
+    /// # Arguments
+    /// * `props` - Properties in document order, or errors for unreadable lines
+    /// * `filter` - Optional window to filter events by
+    /// * `options` - Configured settings such as the zone for floating times
+    /// 
+    /// # Returns
+    /// * `Ok(Parsed)` - Parsed events
+    /// * `Err(String)` - Parse error when the data contains no readable calendar
+    fn read_properties(
+        props: impl IntoIterator<Item = Result<Property, String>>,
+        filter: Option<Window>,
+        options: &ParseOptions,
+    ) -> Result<Parsed, String> {
+        let mut parsed = Parsed::default();
+        let mut calendars = 0;
+        let mut last_error = None;
+        let mut stray_events = false;
+        let mut calendar: Option<CalendarReader> = None;
+
+        for prop in props {
+            let prop = match prop {
+                Ok(prop) => prop,
+                Err(e) => {
+                    warn!("Skipping unreadable calendar line: {}", e);
+                    last_error = Some(e);
+                    continue;
+                },
+            };
+
+            let is_event = Self::is_delimiter(&prop, "BEGIN", "VEVENT") || Self::is_delimiter(&prop, "BEGIN", "VTODO");
+            match calendar.as_mut() {
+                // Events found outside a VCALENDAR end where a real calendar starts
+                Some(reader) if reader.implicit && Self::is_delimiter(&prop, "BEGIN", "VCALENDAR") => {
+                    calendars += 1;
+                    if let Some(reader) = calendar.replace(CalendarReader::new(options)) {
+                        parsed.extend(reader.finish(filter));
+                    }
+                },
+                Some(reader) => {
+                    if reader.feed(prop, filter)
+                        && let Some(reader) = calendar.take()
+                    {
+                        parsed.extend(reader.finish(filter));
+                    }
+                },
+                None if Self::is_delimiter(&prop, "BEGIN", "VCALENDAR") => {
+                    calendars += 1;
+                    calendar = Some(CalendarReader::new(options));
+                },
+                None if is_event && options.lenient => {
+                    debug!("Reading events outside a VCALENDAR");
+                    calendars += 1;
+                    let mut reader = CalendarReader::implicit(options);
+                    reader.feed(prop, filter);
+                    calendar = Some(reader);
+                },
+                // Anything else outside a VCALENDAR is not calendar data
+                None => stray_events |= is_event,
+            }
+        }
+
+        if let Some(reader) = calendar {
+            if !reader.implicit {
+                warn!("Calendar data ends without END:VCALENDAR, keeping the events read so far");
+            }
+            parsed.extend(reader.finish(filter));
+        }
+
+        if calendars == 0 {
+            return Err(match last_error {
+                Some(e) => format!("Invalid calendar data: {}", e),
+                None if stray_events => "No calendar found in data, only events outside a VCALENDAR \
+                                         (enable lenient parsing to read them)".to_string(),
+                None => "No calendar found in data".to_string(),
+            });
+        }
+
+        Ok(parsed)
+    }
+
+    /// Checks whether a property is a `BEGIN`/`END` line for a component
+    fn is_delimiter(prop: &Property, name: &str, component: &str) -> bool {
+        prop.name.eq_ignore_ascii_case(name)
+            && prop.value.as_deref().is_some_and(|v| v.trim().eq_ignore_ascii_case(component))
+    }
+
+    /// Checks that a VEVENT has a DTSTART that can be read
+    /// 
+    /// # Arguments
+    /// * `properties` - Properties of the VEVENT
+    /// * `ctx` - Calendar-wide parse settings
+    /// 
+    /// # Returns
+    /// * `Err(&str)` - Why the event has no usable start, it would otherwise
+    ///   start at the Unix epoch
+    fn check_dtstart(properties: &[Property], ctx: &ParseContext) -> Result<(), &'static str> {
+        match properties.iter().rfind(|prop| prop.name == KEY_DTSTART) {
+            Some(prop) => Self::parse_datetime(prop, true, ctx).map(|_| ()),
+            None => Err("Missing DTSTART"),
+        }
+    }
+
+    /// Turns the properties of a VTODO into those of an all-day event on its due date
+    /// 
+    /// # Arguments
+    /// * `properties` - Properties of the VTODO, in order
+    /// 
+    /// # Returns
+    /// * `Some(Vec<Property>)` - Properties with DTSTART set to the due date
+    /// * `None` - The to-do is completed or has no readable due date
+    /// 
+    /// # Note
+    /// * The due date is DUE, or DTSTART plus DURATION, taken as written in
+    ///   the data without converting it to another zone
+    fn todo_as_event(mut properties: Vec<Property>) -> Option<Vec<Property>> {
+        let value = |name: &str| {
+            properties
+                .iter()
+                .rfind(|prop| prop.name == name)
+                .and_then(|prop| prop.value.as_deref())
+                .map(str::trim)
+        };
+        let summary = value(KEY_SUMMARY).map(Self::unescape_text).unwrap_or_default();
+
+        let completed = value(KEY_STATUS).is_some_and(|status| status.eq_ignore_ascii_case("COMPLETED"))
+            || value(KEY_PERCENT_COMPLETE).and_then(|percent| percent.parse::<u8>().ok()) == Some(100);
+        if completed {
+            debug!("Skipping completed to-do '{}'", summary);
+            return None;
+        }
+
+        let due = match (value(KEY_DUE), value(KEY_DTSTART), value(KEY_DURATION)) {
+            (Some(due), _, _) => Self::wall_clock(due).map(|due| due.date()),
+            (None, Some(start), Some(duration)) => Self::wall_clock(start)
+                .zip(Self::parse_duration(duration))
+                .and_then(|(start, duration)| start.checked_add_signed(duration))
+                .map(|due| due.date()),
+            _ => {
+                debug!("Skipping to-do '{}' without DUE or DTSTART and DURATION", summary);
+                return None;
+            },
+        };
+        let Some(due) = due else {
+            warn!("Skipping to-do '{}': unreadable due date", summary);
+            return None;
+        };
+
+        properties.retain(|prop| ![KEY_DTSTART, KEY_DTEND, KEY_DUE, KEY_DURATION].contains(&prop.name.as_str()));
+        properties.push(Property {
+            name: KEY_DTSTART.to_string(),
+            params: Some(vec![("VALUE".to_string(), vec!["DATE".to_string()])]),
+            value: Some(due.format(DATE_FMT).to_string()),
+        });
+        Some(properties)
+    }
+
+    /// Reads a DATE or DATE-TIME value as written, ignoring any zone
+    fn wall_clock(value: &str) -> Option<NaiveDateTime> {
+        let value = value.to_uppercase();
+        let value = value.strip_suffix('Z').unwrap_or(&value);
+        NaiveDateTime::parse_from_str(value, DT_FMT).ok().or_else(|| {
+            NaiveDate::parse_from_str(value, DATE_FMT)
+                .ok()
+                .map(|date| date.and_time(NaiveTime::MIN))
+        })
+    }
+
+    /// Parses a DURATION value such as `P1D`, `PT36H` or `-P1W`
+    /// 
+    /// # Returns
+    /// * `Some(TimeDelta)` - Duration of the value
+    /// * `None` - Not a duration (RFC 5545 section 3.3.6)
+    fn parse_duration(value: &str) -> Option<TimeDelta> {
+        let value = value.trim().to_uppercase();
+        let (sign, value) = match value.strip_prefix('-') {
+            Some(rest) => (-1, rest),
+            None => (1, value.strip_prefix('+').unwrap_or(&value)),
+        };
+        let value = value.strip_prefix('P')?;
+
+        let mut seconds = 0i64;
+        let mut number = String::new();
+        let mut in_time = false;
+        let mut parts = 0;
+        for c in value.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            if c == 'T' && number.is_empty() && !in_time {
+                in_time = true;
+                continue;
+            }
+            let n: i64 = std::mem::take(&mut number).parse().ok()?;
+            let unit = match (c, in_time) {
+                ('W', false) => 7 * 86_400,
+                ('D', false) => 86_400,
+                ('H', true) => 3_600,
+                ('M', true) => 60,
+                ('S', true) => 1,
+                _ => return None,
+            };
+            seconds = seconds.checked_add(n.checked_mul(unit)?)?;
+            parts += 1;
+        }
+        if !number.is_empty() || parts == 0 {
+            return None;
+        }
+        TimeDelta::try_seconds(sign * seconds)
+    }
+
+    /// Reads the recurrence rule of a VEVENT
+    /// 
+    /// # Arguments
+    /// * `properties` - Properties of the VEVENT
+    /// 
+    /// # Returns
+    /// * `Ok(Some((RRule, Property)))` - The rule and the DTSTART it repeats
+    /// * `Ok(None)` - The event does not recur
+    /// * `Err(String)` - The rule cannot be read
+    fn recurrence(properties: &[Property]) -> Result<Option<(RRule, Property)>, String> {
+        let rrule = properties.iter().rfind(|prop| prop.name == KEY_RRULE).and_then(|prop| prop.value.as_deref());
+        let dtstart = properties.iter().rfind(|prop| prop.name == KEY_DTSTART);
+        match (rrule, dtstart) {
+            (Some(rrule), Some(dtstart)) => RRule::parse(rrule).map(|rule| Some((rule, dtstart.clone()))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Builds an event from the properties of a VEVENT
+    /// 
+    /// # Arguments
+    /// * `properties` - Properties of the VEVENT, in order
+    /// * `ctx` - Calendar-wide parse settings
+    /// 
+    /// # Returns
+    /// * `Event` - Event with a default end applied when DTEND is missing
+    fn build_event(properties: Vec<Property>, ctx: &ParseContext) -> Event {
+        let mut my_event = Event::new();
+        let mut explicit = None;
+        
+        for prop in properties {
+            match prop.name.as_str() {
+                KEY_SUMMARY => {
+                    if let Some(summary) = prop.value {
+                        my_event.set_summary(&Self::unescape_text(&summary));
+                    } else {
+                        my_event.set_summary("NO_SUMMARY");
+                    }
+                },
+                KEY_DESCRIPTION => {
+                    if let Some(desc) = prop.value {
+                        my_event.set_description(Self::unescape_text(&desc));
+                    }
+                },
+                KEY_LOCATION => {
+                    if let Some(location) = prop.value {
+                        my_event.set_location(Self::unescape_text(&location));
+                    }
+                },
+                KEY_DTSTART => {
+                    if let Ok(timestamp) = Self::parse_datetime(&prop, true, ctx) {
+                        my_event.set_dtstart(timestamp);
+                        my_event.start_offset = Self::wall_clock_offset(&prop, timestamp);
+                        my_event.set_all_day(Self::is_date_only(&prop));
+                    }
+                },
+                KEY_DTEND => {
+                    if let Ok(timestamp) = Self::parse_datetime(&prop, false, ctx) {
+                        my_event.set_dtend(timestamp);
+                    }
+                },
+                KEY_STATUS => {
+                    if let Some(status) = prop.value {
+                        my_event.set_status(&status);
+                    }
+                },
+                KEY_TRANSP => {
+                    if let Some(transp) = prop.value {
+                        my_event.set_transp(&transp);
+                    }
+                },
+                KEY_UID => {
+                    my_event.uid = prop.value;
+                },
+                KEY_SEQUENCE => {
+                    if let Some(sequence) = prop.value.and_then(|v| v.trim().parse().ok()) {
+                        my_event.sequence = Some(sequence);
+                    }
+                },
+                KEY_LAST_MODIFIED => {
+                    if let Ok(timestamp) = Self::parse_datetime(&prop, true, ctx) {
+                        my_event.last_modified = Some(timestamp);
+                    }
+                },
+                KEY_DTSTAMP => {
+                    if let Ok(timestamp) = Self::parse_datetime(&prop, true, ctx) {
+                        my_event.dtstamp = Some(timestamp);
+                    }
+                },
+                KEY_RECURRENCE_ID => {
+                    if let Ok(timestamp) = Self::parse_datetime(&prop, true, ctx) {
+                        let this_and_future = Self::param(prop.params.as_deref(), "RANGE")
+                            .is_some_and(|r| r.eq_ignore_ascii_case("THISANDFUTURE"));
+                        my_event.recurrence_id = Some(RecurrenceId { timestamp, this_and_future });
+                    }
+                },
+                KEY_CATEGORIES => {
+                    // CATEGORIES may be repeated, each holding several values
+                    if let Some(categories) = prop.value {
+                        my_event.add_categories(&categories);
+                    }
+                },
+                KEY_EXDATE => {
+                    // EXDATE may carry several comma-separated values
+                    for exdate in Self::parse_exdates(&prop, ctx) {
+                        my_event.add_exdate(exdate);
+                    }
+                },
+                name if name.eq_ignore_ascii_case(&ctx.explicit_property) => {
+                    if let Some(value) = prop.value {
+                        explicit = Some(value);
+                    }
+                },
+                _ => {}
+            }
+        }
+        
+        // Applied last so warnings can name the event
+        if let Some(value) = explicit {
+            my_event.set_explicit_type(&value, &ctx.explicit_property);
+        }
+
+        // Handle events with no explicit end time, and all-day events ending
+        // on their start date, which would otherwise cover no day at all
+        if my_event.dtend == 0 || (my_event.all_day && my_event.dtend == my_event.dtstart) {
+            my_event.dtend = my_event.default_dtend();
+        }
+
+        my_event
+    }
+
+    /// Replaces recurring instances with their RECURRENCE-ID overrides
+    /// 
+    /// # Arguments
+    /// * `events` - Events of one calendar, masters and overrides
+    /// * `overrides` - (UID, RECURRENCE-ID) of every override in the calendar,
+    ///   including those not among `events`
+    /// 
+    /// # Returns
+    /// * `Vec<Event>` - Events with overridden instances removed, in original order
+    /// 
+    /// # Note
+    /// * Instances are keyed by (UID, occurrence start), a recurring event
+    ///   contributing one event per instance its RRULE generates
+    /// * `RANGE=THISANDFUTURE` overrides replace every instance from their start on
+    fn apply_overrides(events: Vec<Event>, overrides: &[(String, RecurrenceId)]) -> Vec<Event> {
+        if overrides.is_empty() {
+            return events;
+        }
+
+        let replaced: Vec<bool> = events
+            .iter()
+            .map(|e| {
+                let Some(uid) = e.uid.as_deref() else {
+                    return false;
+                };
+                e.recurrence_id.is_none()
+                    && overrides.iter().any(|(override_uid, rid)| {
+                        *override_uid == uid && rid.replaces(e.dtstart)
+                    })
+            })
+            .collect();
+
+        events
+            .into_iter()
+            .zip(replaced)
+            .filter_map(|(event, replaced)| (!replaced).then_some(event))
+            .collect()
+    }
+
+    /// Parses all values of an EXDATE property
+    /// 
+    /// # Arguments
+    /// * `prop` - EXDATE property, possibly holding comma-separated values
+    /// * `ctx` - Calendar-wide parse settings
+    /// 
+    /// # Returns
+    /// * `Vec<ExDate>` - Successfully parsed exception dates, invalid values are skipped
+    fn parse_exdates(prop: &Property, ctx: &ParseContext) -> Vec<ExDate> {
+        let Some(value) = &prop.value else {
+            return Vec::new();
+        };
+
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .filter_map(|v| {
+                Self::parse_datetime_value(v, prop.params.as_deref(), true, ctx)
+                    .ok()
+                    .map(|timestamp| ExDate {
+                        timestamp,
+                        all_day: Self::value_type(v, prop.params.as_deref()) == Ok(ValueType::Date),
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns how far ahead of UTC the wall clock of a datetime property is
+    /// 
+    /// # Arguments
+    /// * `prop` - DTSTART or another datetime property
+    /// * `timestamp` - The instant the property was parsed into, in milliseconds
+    /// 
+    /// # Returns
+    /// * Milliseconds between the written time and the instant, e.g. 8 hours
+    ///   for a time with `TZID=Asia/Shanghai`; 0 for UTC and date-only values
+    fn wall_clock_offset(prop: &Property, timestamp: i64) -> i64 {
+        prop.value
+            .as_deref()
+            .filter(|_| !Self::is_date_only(prop))
+            .and_then(|value| NaiveDateTime::parse_from_str(value.trim().trim_end_matches(['Z', 'z']), DT_FMT).ok())
+            .map_or(0, |wall| wall.and_utc().timestamp_millis() - timestamp)
+    }
+
+    /// Checks whether a datetime property holds a date-only value
+    fn is_date_only(prop: &Property) -> bool {
+        prop.value.as_ref().is_some_and(|v| {
+            Self::value_type(v, prop.params.as_deref()) == Ok(ValueType::Date)
+        })
+    }
+
+    /// Parses iCalendar datetime strings into Unix timestamps
+    /// 
+    /// # Arguments
+    /// * `prop` - iCalendar property containing datetime
+    /// * `is_dt_start` - Whether this is a DTSTART (true) or DTEND (false)
+    /// * `ctx` - Calendar-wide parse settings
+    /// 
+    /// # Returns
+    /// * `Result<i64, &str>` - Unix timestamp in milliseconds or error message
+    fn parse_datetime(prop: &Property, is_dt_start: bool, ctx: &ParseContext) -> Result<i64, &'static str> {
         let Some(value) = &prop.value else {
             return Err("Missing datetime value");
         };
+
+        Self::parse_datetime_value(value, prop.params.as_deref(), is_dt_start, ctx)
+    }
+
+    /// Parses a single iCalendar datetime value into a Unix timestamp
+    /// 
+    /// # Arguments
+    /// * `value` - Raw datetime value
+    /// * `params` - Parameters of the property the value belongs to
+    /// * `is_dt_start` - Whether this is a DTSTART (true) or DTEND (false)
+    /// * `ctx` - Calendar-wide parse settings
+    /// 
+    /// # Returns
+    /// * `Result<i64, &str>` - Unix timestamp in milliseconds or error message
+    /// 
+    /// # Supported Formats
+    /// * YYYYMMDD (all-day events, midnight of that day; exclusive when used as DTEND)
+    /// * YYYYMMDDTHHMMSS (floating time, in the calendar's default zone or UTC)
+    /// * YYYYMMDDTHHMMSSZ (UTC time)
+    /// * YYYYMMDDTHHMMSS with TZID parameter
+    fn parse_datetime_value(
+        value: &str,
+        params: Option<&[(String, Vec<String>)]>,
+        is_dt_start: bool,
+        ctx: &ParseContext,
+    ) -> Result<i64, &'static str> {
+        let value = value.to_uppercase();
         
-        let mut value = value.to_uppercase();
-        
-        match value.len() {
-            8 => {
-                // All-day event: YYYYMMDD
-                let fill_with = if is_dt_start { "T000000" } else { "T235959" };
-                value.push_str(fill_with);
+        match Self::value_type(&value, params)? {
+            ValueType::Date => {
+                // All-day event: YYYYMMDD, any time part or TZID is ignored for DATE values
+                // Both ends anchor at midnight: a date-only DTEND is the day after
+                // the last day (RFC 5545), which the half-open interval excludes
+                let date = value.split('T').next().unwrap_or_default();
                 
-                NaiveDateTime::parse_from_str(&value, DT_FMT)
-                    .map(|dt| dt.and_utc().timestamp_millis())
-                    .map_err(|_| "Invalid datetime format")
+                NaiveDate::parse_from_str(date, DATE_FMT)
+                    .map(|d| d.and_time(NaiveTime::MIN).and_utc().timestamp_millis())
+                    .map_err(|_| "Invalid date format")
             },
-            _ => {
-                if value.ends_with('Z') {
-                    // UTC timezone
-                    DateTime::parse_from_str(&value, DT_FMT)
-                        .map(|dt| dt.timestamp_millis())
+            ValueType::DateTime => {
+                if let Some(utc_value) = value.strip_suffix('Z') {
+                    // UTC timezone: DT_FMT has no offset specifier, so parse without the suffix
+                    NaiveDateTime::parse_from_str(utc_value, DT_FMT)
+                        .map(|dt| dt.and_utc().timestamp_millis())
                         .map_err(|_| "Invalid datetime format")
                 } else {
-                    // Check for timezone parameter
-                    if let Some(params) = &prop.params {
-                        for (name, field) in params {
-                            if name.to_uppercase() == "TZID" && !field.is_empty() {
-                                if let Ok(tz) = field[0].parse::<Tz>() {
-                                    if let Ok(dt) = NaiveDateTime::parse_from_str(&value, DT_FMT) {
-                                        let dt_result = tz.with_ymd_and_hms(
-                                            dt.year(),
-                                            dt.month(),
-                                            dt.day(),
-                                            dt.hour(),
-                                            dt.minute(),
-                                            dt.second(),
-                                        );
-                                        
-                                        match dt_result {
-                                            chrono::offset::LocalResult::Single(tz_dt) => {
-                                                return Ok(tz_dt.timestamp_millis());
-                                            },
-                                            chrono::offset::LocalResult::Ambiguous(early, later) => {
-                                                let tz_dt = if is_dt_start { early } else { later };
-                                                return Ok(tz_dt.timestamp_millis());
-                                            },
-                                            chrono::offset::LocalResult::None => {
-                                                return Err("Invalid datetime for timezone");
-                                            },
-                                        }
-                                    }
-                                } else {
-                                    return Err("Invalid timezone identifier");
-                                }
-                            }
-                        }
-                    }
+                    let dt = NaiveDateTime::parse_from_str(&value, DT_FMT)
+                        .map_err(|_| "Invalid datetime format")?;
                     
-                    // Fallback to UTC if no timezone specified
-                    NaiveDateTime::parse_from_str(&value, DT_FMT)
-                        .map(|dt| dt.and_utc().timestamp_millis())
-                        .map_err(|_| "Invalid datetime format")
+                    // Check for timezone parameter, then the calendar's default zone
+                    // Non-IANA identifiers may be defined by a VTIMEZONE in the calendar,
+                    // or be Windows names with a well-known IANA equivalent
+                    let zone = match Self::param(params, "TZID") {
+                        Some(tzid) => ctx.tzid_zone(tzid),
+                        None => TzidZone::Unknown,
+                    };
+                    let to_utc = |dt: NaiveDateTime| match zone {
+                        TzidZone::Iana(tz) => tz.from_local_datetime(&dt).map(|d| d.timestamp_millis()),
+                        TzidZone::Defined(vtz) => vtz.to_utc(dt),
+                        TzidZone::Broken => LocalResult::Single(dt.and_utc().timestamp_millis()),
+                        // Floating times and unknown zones use the calendar's default zone
+                        TzidZone::Unknown => match ctx.floating_tz {
+                            Some(tz) => tz.from_local_datetime(&dt).map(|d| d.timestamp_millis()),
+                            // Fallback to UTC if no timezone specified
+                            None => LocalResult::Single(dt.and_utc().timestamp_millis()),
+                        },
+                    };
+                    
+                    Self::resolve_local(dt, to_utc, is_dt_start, ctx)
                 }
             }
         }
     }
-}
\ No newline at end of file
+
+    /// Picks a timestamp for a wall-clock time resolved in a timezone
+    /// 
+    /// # Arguments
+    /// * `local` - Wall-clock time
+    /// * `to_utc` - Resolves a wall-clock time to candidate timestamps in milliseconds
+    /// * `is_dt_start` - Unless `dst_ambiguous` is configured, picks the earlier
+    ///   instant of an ambiguous time for DTSTART, the later one for DTEND
+    /// * `ctx` - Calendar-wide parse settings
+    /// 
+    /// # Returns
+    /// * `Result<i64, &str>` - Unix timestamp in milliseconds or error message
+    fn resolve_local(
+        local: NaiveDateTime,
+        to_utc: impl Fn(NaiveDateTime) -> LocalResult<i64>,
+        is_dt_start: bool,
+        ctx: &ParseContext,
+    ) -> Result<i64, &'static str> {
+        match to_utc(local) {
+            LocalResult::Single(timestamp) => Ok(timestamp),
+            LocalResult::Ambiguous(early, later) => Ok(match ctx.dst_ambiguous {
+                Some(DstAmbiguous::Earlier) => early,
+                Some(DstAmbiguous::Later) => later,
+                None if is_dt_start => early,
+                None => later,
+            }),
+            LocalResult::None => match ctx.dst_gap {
+                DstGap::ShiftForward => Self::after_gap(local, to_utc).ok_or("Invalid datetime for timezone"),
+                DstGap::Error => Err("Invalid datetime for timezone"),
+            },
+        }
+    }
+
+    /// Finds the first valid instant after the gap a wall-clock time falls into
+    /// 
+    /// # Arguments
+    /// * `local` - Wall-clock time skipped by a spring-forward transition
+    /// * `to_utc` - Resolves a wall-clock time to candidate timestamps in milliseconds
+    /// 
+    /// # Returns
+    /// * `Option<i64>` - Timestamp in milliseconds of the transition, `None` if no
+    ///   valid time follows within a day
+    /// 
+    /// # Note
+    /// * Scans forward by minutes, then back by seconds, so transitions at any
+    ///   second are found
+    fn after_gap(local: NaiveDateTime, to_utc: impl Fn(NaiveDateTime) -> LocalResult<i64>) -> Option<i64> {
+        let valid = |dt: NaiveDateTime| to_utc(dt).earliest();
+        let (mut end, mut timestamp) = (1..=24 * 60)
+            .map(|minutes| local + TimeDelta::minutes(minutes))
+            .find_map(|dt| valid(dt).map(|timestamp| (dt, timestamp)))?;
+        while let Some(earlier) = valid(end - TimeDelta::seconds(1)) {
+            end -= TimeDelta::seconds(1);
+            timestamp = earlier;
+        }
+        Some(timestamp)
+    }
+
+    /// Determines whether a value is a DATE or a DATE-TIME
+    /// 
+    /// # Arguments
+    /// * `value` - Raw datetime value
+    /// * `params` - Parameters of the property the value belongs to
+    /// 
+    /// # Returns
+    /// * `Result<ValueType, &str>` - The explicit VALUE parameter when present,
+    ///   otherwise a guess based on the value length
+    fn value_type(
+        value: &str,
+        params: Option<&[(String, Vec<String>)]>,
+    ) -> Result<ValueType, &'static str> {
+        match Self::param(params, "VALUE").map(str::to_uppercase).as_deref() {
+            Some("DATE") => Ok(ValueType::Date),
+            Some("DATE-TIME") if !value.contains(['T', 't']) => Err("DATE-TIME value without time"),
+            Some("DATE-TIME") => Ok(ValueType::DateTime),
+            _ if value.len() == 8 => Ok(ValueType::Date),
+            _ => Ok(ValueType::DateTime),
+        }
+    }
+
+    /// Unescapes an iCalendar TEXT value (RFC 5545 section 3.3.11)
+    /// 
+    /// # Arguments
+    /// * `value` - Raw property value, already unfolded
+    /// 
+    /// # Returns
+    /// * `String` - Value with `\\`, `\;`, `\,` and `\n`/`\N` resolved;
+    ///   any other backslash is kept as-is
+    fn unescape_text(value: &str) -> String {
+        let mut text = String::with_capacity(value.len());
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                text.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n' | 'N') => text.push('\n'),
+                Some(escaped @ ('\\' | ';' | ',')) => text.push(escaped),
+                Some(other) => {
+                    text.push('\\');
+                    text.push(other);
+                },
+                None => text.push('\\'),
+            }
+        }
+        text
+    }
+
+    /// Escapes a TEXT value (RFC 5545 section 3.3.11)
+    /// 
+    /// # Arguments
+    /// * `text` - Plain text
+    /// 
+    /// # Returns
+    /// * `String` - Text with `\`, `;`, `,` and newlines escaped, the
+    ///   inverse of [`Ical::unescape_text`]
+    pub(crate) fn escape_text(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '\\' | ';' | ',' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                },
+                '\n' => escaped.push_str("\\n"),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    /// Splits a TEXT list on unescaped commas and unescapes each item
+    /// 
+    /// # Arguments
+    /// * `value` - Raw property value such as a CATEGORIES list
+    /// 
+    /// # Returns
+    /// * `Vec<String>` - Unescaped items, `\,` stays part of its item
+    fn split_text_list(value: &str) -> Vec<String> {
+        let mut items = Vec::new();
+        let mut start = 0;
+        let mut escaped = false;
+        for (i, c) in value.char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                ',' => {
+                    items.push(Self::unescape_text(&value[start..i]));
+                    start = i + 1;
+                },
+                _ => {},
+            }
+        }
+        items.push(Self::unescape_text(&value[start..]));
+        items
+    }
+
+    /// Looks up an IANA zone, tolerating the variants found in real feeds
+    /// 
+    /// # Arguments
+    /// * `name` - Zone name, e.g. `Europe/Paris`, `"europe/paris"` or the
+    ///   globally unique `/freeassociation.sourceforge.net/Europe/Paris`
+    /// 
+    /// # Returns
+    /// * `Some(Tz)` - Zone whose name matches, ignoring case and quotes
+    /// * `None` - No zone of the IANA database matches
+    /// 
+    /// # Note
+    /// * A name starting with `/` belongs to a namespace (RFC 5545 section
+    ///   3.2.19); the longest trailing part naming a zone is used
+    fn lookup_tz(name: &str) -> Option<Tz> {
+        let name = Self::unquote(name);
+        let find = |candidate: &str| {
+            candidate.parse::<Tz>().ok().or_else(|| {
+                TZ_VARIANTS.iter().copied().find(|tz| tz.name().eq_ignore_ascii_case(candidate))
+            })
+        };
+
+        match name.strip_prefix('/') {
+            Some(unique) => std::iter::once(unique)
+                .chain(unique.match_indices('/').map(|(slash, _)| &unique[slash + 1..]))
+                .find_map(find),
+            None => find(name),
+        }
+    }
+
+    /// Removes whitespace and surrounding quotes from a parameter value
+    fn unquote(value: &str) -> &str {
+        let value = value.trim();
+        value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+            .map_or(value, str::trim)
+    }
+
+    /// Returns the first value of a property parameter (case-insensitive name)
+    fn param<'a>(params: Option<&'a [(String, Vec<String>)]>, name: &str) -> Option<&'a str> {
+        params?
+            .iter()
+            .find(|(key, field)| key.eq_ignore_ascii_case(name) && !field.is_empty())
+            .map(|(_, field)| field[0].as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use chrono_tz::Asia::Shanghai;
+
+    use super::*;
+    use crate::{Choliday, DayDecision, SourceStatus, conf::Priority};
+
+    /// Wraps the properties of one event, marked as a holiday, in a calendar
+    fn calendar(properties: &[&str]) -> String {
+        let mut ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:test@choliday\r\nSUMMARY:Holiday\r\n".to_string();
+        for property in properties {
+            ics += property;
+            ics += "\r\n";
+        }
+        ics + "END:VEVENT\r\nEND:VCALENDAR\r\n"
+    }
+
+    /// Parses a calendar of one event
+    fn read(properties: &[&str]) -> Parsed {
+        let conf = Conf::builder(Priority::RestOverWork).build().unwrap();
+        let options = Ical::new(conf).parse_options(false);
+        Ical::parse_calendar(calendar(properties).as_bytes(), None, &options).unwrap()
+    }
+
+    /// Parses a calendar of one event and returns the event
+    fn parse(properties: &[&str]) -> Event {
+        let mut parsed = read(properties);
+        assert_eq!(parsed.events.len(), 1);
+        parsed.events.remove(0)
+    }
+
+    /// Milliseconds since the Unix epoch of a UTC date and time
+    fn millis(date: &str) -> i64 {
+        NaiveDateTime::parse_from_str(date, DT_FMT).unwrap().and_utc().timestamp_millis()
+    }
+
+    /// Classifies a date in Asia/Shanghai from a calendar of one holiday event,
+    /// Monday to Friday being work days otherwise
+    fn is_work_day(properties: &[&str], year: i32, month: u32, day: u32) -> bool {
+        let conf = Conf::builder(Priority::RestOverWork).rest("Holiday").build().unwrap();
+        let choliday = Choliday::builder(conf)
+            .date(NaiveDate::from_ymd_opt(year, month, day).unwrap())
+            .timezone(Shanghai)
+            .events(calendar(properties))
+            .build()
+            .unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        runtime.block_on(choliday.is_work_day()).unwrap()
+    }
+
+    #[test]
+    fn one_day_all_day_event_ends_at_the_next_midnight() {
+        let event = parse(&["DTSTART;VALUE=DATE:20251001", "DTEND;VALUE=DATE:20251002"]);
+        assert!(event.all_day);
+        assert_eq!(event.dtstart, millis("20251001T000000"));
+        assert_eq!(event.dtend, millis("20251002T000000"));
+    }
+
+    #[test]
+    fn one_day_all_day_event_covers_only_its_day() {
+        let event = ["DTSTART;VALUE=DATE:20251001", "DTEND;VALUE=DATE:20251002"];
+        assert!(is_work_day(&event, 2025, 9, 30));
+        assert!(!is_work_day(&event, 2025, 10, 1));
+        assert!(is_work_day(&event, 2025, 10, 2));
+    }
+
+    #[test]
+    fn week_long_all_day_event_excludes_its_end_date() {
+        let event = ["DTSTART;VALUE=DATE:20251001", "DTEND;VALUE=DATE:20251008"];
+        let parsed = parse(&event);
+        assert_eq!(parsed.dtend - parsed.dtstart, 7 * DAY_MILLIS);
+        assert!(is_work_day(&event, 2025, 9, 30));
+        assert!(!is_work_day(&event, 2025, 10, 1));
+        assert!(!is_work_day(&event, 2025, 10, 7));
+        assert!(is_work_day(&event, 2025, 10, 8));
+    }
+
+    #[test]
+    fn all_day_event_without_dtend_spans_its_day() {
+        let event = ["DTSTART;VALUE=DATE:20251001"];
+        let parsed = parse(&event);
+        assert_eq!(parsed.dtend, millis("20251002T000000"));
+        assert!(!is_work_day(&event, 2025, 10, 1));
+        assert!(is_work_day(&event, 2025, 10, 2));
+    }
+
+    #[test]
+    fn timed_event_without_dtend_gets_the_default_duration() {
+        let event = ["DTSTART:20251001T020000Z"];
+        let parsed = parse(&event);
+        assert_eq!(parsed.dtend - parsed.dtstart, DEFAULT_TIMED_DURATION);
+        assert!(!is_work_day(&event, 2025, 10, 1));
+    }
+
+    #[test]
+    fn zero_length_timed_event_classifies_its_day() {
+        let event = ["DTSTART;TZID=Asia/Shanghai:20251001T100000", "DTEND;TZID=Asia/Shanghai:20251001T100000"];
+        let parsed = parse(&event);
+        assert_eq!(parsed.dtend, parsed.dtstart);
+        assert!(!is_work_day(&event, 2025, 10, 1));
+        assert!(is_work_day(&event, 2025, 10, 2));
+    }
+
+    #[test]
+    fn all_day_event_ending_on_its_start_date_spans_its_day() {
+        let event = ["DTSTART;VALUE=DATE:20251001", "DTEND;VALUE=DATE:20251001"];
+        assert_eq!(parse(&event).dtend, millis("20251002T000000"));
+        assert!(!is_work_day(&event, 2025, 10, 1));
+        assert!(is_work_day(&event, 2025, 10, 2));
+    }
+
+    #[test]
+    fn utc_dtstart_and_dtend_parse() {
+        let event = ["DTSTART:20251001T000000Z", "DTEND:20251002T000000Z"];
+        let parsed = parse(&event);
+        assert!(!parsed.all_day);
+        assert_eq!(parsed.dtstart, millis("20251001T000000"));
+        assert_eq!(parsed.dtend, millis("20251002T000000"));
+        assert_eq!(parsed.start_offset, 0);
+    }
+
+    #[test]
+    fn utc_times_classify_the_days_they_reach_in_the_target_zone() {
+        // 2025-10-01 08:00 to 2025-10-02 08:00 in Asia/Shanghai
+        let event = ["DTSTART:20251001T000000Z", "DTEND:20251002T000000Z"];
+        assert!(is_work_day(&event, 2025, 9, 30));
+        assert!(!is_work_day(&event, 2025, 10, 1));
+        assert!(!is_work_day(&event, 2025, 10, 2));
+        assert!(is_work_day(&event, 2025, 10, 3));
+    }
+
+    #[test]
+    fn value_date_parameter_decides_all_day_semantics() {
+        // TZID is ignored for DATE values, which name a day in the target zone
+        let event = parse(&["DTSTART;VALUE=DATE;TZID=America/New_York:20251001", "DTEND;VALUE=DATE;TZID=America/New_York:20251002"]);
+        assert!(event.all_day);
+        assert_eq!((event.dtstart, event.dtend), (millis("20251001T000000"), millis("20251002T000000")));
+        assert!(parse(&["DTSTART;value=date:20251001"]).all_day);
+
+        // A declared DATE-TIME needs its time
+        let parsed = read(&["DTSTART;VALUE=DATE-TIME:20251001"]);
+        assert!(parsed.events.is_empty());
+        assert_eq!(parsed.malformed, 1);
+        let event = parse(&["DTSTART;VALUE=DATE-TIME:20251001T000000Z"]);
+        assert!(!event.all_day);
+
+        // Without a parameter eight characters still make a date, and garbage is malformed
+        assert!(parse(&["DTSTART:20251001"]).all_day);
+        assert_eq!(read(&["DTSTART:2025100A"]).malformed, 1);
+    }
+
+    #[test]
+    fn tzid_variants_resolve_to_the_iana_zone() {
+        // 09:00 in Paris is 07:00 UTC in summer
+        let expected = millis("20251001T070000");
+        for tzid in ["Europe/Paris", "\"Europe/Paris\"", "europe/paris", "/freeassociation.sourceforge.net/Europe/Paris",
+                     "/freeassociation.sourceforge.net/Tzfile/Europe/Paris", "/mozilla.org/20050126_1/Europe/Paris"]
+        {
+            let event = parse(&[&format!("DTSTART;TZID={}:20251001T090000", tzid)]);
+            assert_eq!(event.dtstart, expected, "{}", tzid);
+        }
+    }
+
+    #[test]
+    fn unknown_tzid_falls_back_to_the_default_zone() {
+        let event = parse(&["DTSTART;TZID=Mars/Olympus_Mons:20251001T090000"]);
+        assert_eq!(event.dtstart, millis("20251001T090000"));
+
+        let conf = Conf::builder(Priority::RestOverWork).timezone(Shanghai).build().unwrap();
+        let options = Ical::new(conf).parse_options(false);
+        let ics = calendar(&["DTSTART;TZID=Mars/Olympus_Mons:20251001T090000"]);
+        let parsed = Ical::parse_calendar(ics.as_bytes(), None, &options).unwrap();
+        assert_eq!(parsed.events[0].dtstart, millis("20251001T010000"));
+    }
+
+    /// Parses a calendar of one event with `[calendar]` settings
+    fn read_with(calendar_settings: &str, properties: &[&str]) -> Parsed {
+        let conf = Conf::from_toml(&format!("[calendar]\n{}\n{}", calendar_settings, PREDICT)).unwrap();
+        let options = Ical::new(conf).parse_options(false);
+        Ical::parse_calendar(calendar(properties).as_bytes(), None, &options).unwrap()
+    }
+
+    #[test]
+    fn berlin_spring_gap_shifts_forward_or_fails() {
+        // 2025-03-30 02:00 to 03:00 does not exist in Berlin
+        let event = ["DTSTART;TZID=Europe/Berlin:20250330T023000", "DTEND;TZID=Europe/Berlin:20250330T043000"];
+        let parsed = read_with("", &event);
+        assert_eq!(parsed.events[0].dtstart, millis("20250330T010000"));
+        assert_eq!(parsed.events[0].dtend, millis("20250330T023000"));
+        assert_eq!(read_with("dst_gap = \"shift-forward\"", &event).events[0].dtstart, millis("20250330T010000"));
+
+        let parsed = read_with("dst_gap = \"error\"", &event);
+        assert!(parsed.events.is_empty());
+        assert_eq!(parsed.malformed, 1);
+    }
+
+    #[test]
+    fn berlin_autumn_fold_takes_the_configured_occurrence() {
+        // 2025-10-26 02:30 happens twice in Berlin, at 00:30 and 01:30 UTC
+        let event = ["DTSTART;TZID=Europe/Berlin:20251026T023000", "DTEND;TZID=Europe/Berlin:20251026T023000"];
+        let span = |settings: &str| {
+            let event = read_with(settings, &event).events.remove(0);
+            (event.dtstart, event.dtend)
+        };
+        // By default DTSTART takes the first and DTEND the second, covering the whole fold
+        assert_eq!(span(""), (millis("20251026T003000"), millis("20251026T013000")));
+        assert_eq!(span("dst_ambiguous = \"earlier\""), (millis("20251026T003000"), millis("20251026T003000")));
+        assert_eq!(span("dst_ambiguous = \"later\""), (millis("20251026T013000"), millis("20251026T013000")));
+    }
+
+    #[test]
+    fn inverted_dtend_is_repaired_unless_disabled_or_strict() {
+        let event = ["DTSTART:20251001T100000Z", "DTEND:20251001T090000Z"];
+        let parsed = read_with("", &event);
+        assert_eq!(parsed.events[0].dtstart, millis("20251001T100000"));
+        assert_eq!(parsed.events[0].dtend, millis("20251001T100000") + DEFAULT_TIMED_DURATION);
+
+        let parsed = read_with("fix_inverted = false", &event);
+        assert!(parsed.events.is_empty());
+        assert_eq!(parsed.malformed, 1);
+
+        // Strict mode reports the event as malformed whatever the setting
+        let conf = Conf::from_toml(&format!("[calendar]\nfix_inverted = true\n{}", PREDICT)).unwrap();
+        let mut ical = Ical::new(conf);
+        ical.set_strict(true);
+        let parsed = Ical::parse_calendar(calendar(&event).as_bytes(), None, &ical.parse_options(false)).unwrap();
+        assert_eq!(parsed.malformed, 1);
+    }
+
+    #[test]
+    fn lowercase_utc_suffix_parses() {
+        assert_eq!(parse(&["DTSTART:20251001T000000z"]).dtstart, millis("20251001T000000"));
+    }
+
+    /// Writes a calendar with a holiday on 2025-10-01 and one on 2025-10-10,
+    /// returning the configuration reading it in Asia/Shanghai
+    fn two_holidays(dir: &std::path::Path) -> Conf {
+        let path = dir.join("holidays.ics");
+        let event = |day: &str| {
+            format!("BEGIN:VEVENT\r\nUID:{}@choliday\r\nDTSTART;VALUE=DATE:{}\r\nSUMMARY:Holiday\r\nEND:VEVENT\r\n", day, day)
+        };
+        let ics = format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}{}END:VCALENDAR\r\n", event("20251001"), event("20251010"));
+        std::fs::write(&path, ics).unwrap();
+        Conf::from_toml(&format!(
+            "[base]\ntimezone = \"Asia/Shanghai\"\n[calendar]\nsource = [{:?}]\n\
+             [predict]\nwork = []\nrest = [\"Holiday\"]\npriority = \"RestOverWork\"\n",
+            path
+        ))
+        .unwrap()
+    }
+
+    /// `[predict]` table shared by the tests reading calendar files
+    const PREDICT: &str = "[predict]\nwork = [\"Workday\"]\nrest = [\"Holiday\"]\npriority = \"RestOverWork\"\n";
+
+    /// Runs a future to completion on a new runtime
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(future)
+    }
+
+    /// Creates an analyzer of a date in Asia/Shanghai reading a calendar file
+    /// 
+    /// # Arguments
+    /// * `ics` - Calendar data
+    /// * `settings` - TOML following the `source` key of `[calendar]`, usually
+    ///   more `[calendar]` keys and then a `[predict]` table such as [`PREDICT`]
+    /// * `date` - Target date as `YYYYmmDD`
+    /// 
+    /// # Returns
+    /// * The directory holding the file, which must outlive the analyzer, and the analyzer
+    fn analyzer(ics: impl AsRef<[u8]>, settings: &str, date: &str) -> (tempfile::TempDir, Choliday) {
+        sources(&[ics], settings, date)
+    }
+
+    /// Creates an analyzer reading a calendar file per source, in order, see [`analyzer`]
+    fn sources(calendars: &[impl AsRef<[u8]>], settings: &str, date: &str) -> (tempfile::TempDir, Choliday) {
+        let dir = tempfile::tempdir().unwrap();
+        let paths: Vec<_> = calendars
+            .iter()
+            .enumerate()
+            .map(|(i, ics)| {
+                let path = dir.path().join(format!("calendar{}.ics", i));
+                std::fs::write(&path, ics).unwrap();
+                path
+            })
+            .collect();
+        let conf = Conf::from_toml(&format!(
+            "[base]\ntimezone = \"Asia/Shanghai\"\n[calendar]\nsource = {:?}\n{}",
+            paths, settings
+        ))
+        .unwrap();
+        let date = NaiveDate::parse_from_str(date, DATE_FMT).unwrap();
+        let choliday = Choliday::builder(conf).date(date).use_cache(false).build().unwrap();
+        (dir, choliday)
+    }
+
+    /// Classifies dates in Asia/Shanghai from a calendar file, see [`analyzer`]
+    fn day_types(ics: impl AsRef<[u8]>, settings: &str, dates: &[&str]) -> Vec<DayType> {
+        let (_dir, choliday) = analyzer(ics, settings, dates[0]);
+        let dates: Vec<NaiveDate> = dates.iter().map(|date| NaiveDate::parse_from_str(date, DATE_FMT).unwrap()).collect();
+        block_on(choliday.evaluate_many(&dates)).iter().map(DayDecision::day_type).collect()
+    }
+
+    /// Wraps events, each given as its property lines, in a calendar
+    fn events(events: &[&[&str]]) -> String {
+        let mut ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n".to_string();
+        for (i, properties) in events.iter().enumerate() {
+            ics += "BEGIN:VEVENT\r\n";
+            if !properties.iter().any(|p| p.starts_with("UID:")) {
+                ics += &format!("UID:{}@choliday\r\n", i);
+            }
+            for property in *properties {
+                ics += property;
+                ics += "\r\n";
+            }
+            ics += "END:VEVENT\r\n";
+        }
+        ics + "END:VCALENDAR\r\n"
+    }
+
+    #[test]
+    fn transparent_events_are_ignored_when_configured() {
+        let ics = events(&[
+            &["DTSTART;VALUE=DATE:20251001", "SUMMARY:Holiday", "TRANSP:OPAQUE"],
+            &["DTSTART;VALUE=DATE:20251002", "SUMMARY:Holiday in the Berlin office", "TRANSP:TRANSPARENT"],
+        ]);
+        let dates = ["20251001", "20251002"];
+        assert_eq!(day_types(&ics, PREDICT, &dates), [DayType::RestDay, DayType::RestDay]);
+        let settings = format!("ignore_transparent = true\n{}", PREDICT);
+        assert_eq!(day_types(&ics, &settings, &dates), [DayType::RestDay, DayType::WorkDay]);
+    }
+
+    #[test]
+    fn categories_classify_events_with_generic_summaries() {
+        // As holiday feeds publish them: the category, not the summary, says what a day is
+        let ics = events(&[
+            &["DTSTART;VALUE=DATE:20251001", "SUMMARY:National Day", "CATEGORIES:Public Holiday"],
+            &["DTSTART;VALUE=DATE:20251002", "SUMMARY:National Day", "CATEGORIES:Observance,PUBLIC HOLIDAY"],
+            &["DTSTART;VALUE=DATE:20251003", "SUMMARY:National Day", "CATEGORIES:Observance", "CATEGORIES:public holiday"],
+            &["DTSTART;VALUE=DATE:20251009", "SUMMARY:Holiday makeup", "CATEGORIES:Working Day"],
+            &["DTSTART;VALUE=DATE:20251010", "SUMMARY:Mid-autumn", "CATEGORIES:Public Holidays"],
+        ]);
+        let settings = format!("{}rest_categories = [\"public holiday\"]\nwork_categories = [\"Working Day\"]\n", PREDICT);
+        let dates = ["20251001", "20251002", "20251003", "20251009", "20251010"];
+        assert_eq!(
+            day_types(&ics, &settings, &dates),
+            [DayType::RestDay, DayType::RestDay, DayType::RestDay, DayType::WorkDay, DayType::WorkDay]
+        );
+
+        // The trace names the category and the field it was found in
+        let (_dir, choliday) = analyzer(&ics, &settings, "20251001");
+        let trace = block_on(choliday.trace()).unwrap();
+        assert_eq!(trace.events[0].rest_pattern.as_deref(), Some("public holiday"));
+        assert_eq!(trace.events[0].field.as_deref(), Some("categories"));
+    }
+
+    #[test]
+    fn location_is_matched_in_the_configured_order() {
+        let ics = events(&[
+            &["DTSTART;VALUE=DATE:20251004", "SUMMARY:Alice\\, Bob", "LOCATION:HQ Office"],
+            &["DTSTART;VALUE=DATE:20251009", "SUMMARY:Holiday", "LOCATION:HQ Office"],
+        ]);
+        let predict = |fields: &str| {
+            format!("[predict]\nwork = [\"HQ Office\"]\nrest = [\"Holiday\"]\npriority = \"RestOverWork\"\nmatch_fields = {}\n", fields)
+        };
+        // 2025-10-04 is a Saturday, 2025-10-09 a Thursday
+        let dates = ["20251004", "20251009"];
+
+        // Locations are not searched by default
+        assert_eq!(day_types(&ics, &predict(r#"["summary", "description"]"#), &dates), [DayType::RestDay, DayType::RestDay]);
+        // The first listed field with a match decides
+        assert_eq!(day_types(&ics, &predict(r#"["summary", "location"]"#), &dates), [DayType::WorkDay, DayType::RestDay]);
+        assert_eq!(day_types(&ics, &predict(r#"["location", "summary"]"#), &dates), [DayType::WorkDay, DayType::WorkDay]);
+
+        let (_dir, choliday) = analyzer(&ics, &predict(r#"["location"]"#), "20251004");
+        let trace = block_on(choliday.trace()).unwrap();
+        assert_eq!(trace.events[0].field.as_deref(), Some("location"));
+    }
+
+    #[test]
+    fn use_latest_follows_the_most_recently_authored_event() {
+        let settings = "[predict]\nwork = [\"Workday\"]\nrest = [\"Holiday\"]\npriority = \"UseLatest\"\n";
+        let rest = |stamps: &[&'static str]| [&["DTSTART;VALUE=DATE:20251004", "SUMMARY:Holiday"][..], stamps].concat();
+        let work = |stamps: &[&'static str]| [&["DTSTART;VALUE=DATE:20251004", "SUMMARY:Workday"][..], stamps].concat();
+        let latest = |first: &[&str], second: &[&str]| day_types(events(&[first, second]), settings, &["20251004"])[0];
+
+        // A rest correction with a higher SEQUENCE supersedes the work day, in either file order
+        let (old, new) = (work(&["DTSTAMP:20250901T000000Z", "SEQUENCE:0"]), rest(&["DTSTAMP:20250901T000000Z", "SEQUENCE:1"]));
+        assert_eq!(latest(&new, &old), DayType::RestDay);
+        assert_eq!(latest(&old, &new), DayType::RestDay);
+
+        // LAST-MODIFIED outranks DTSTAMP, which outranks SEQUENCE
+        let modified = work(&["LAST-MODIFIED:20250910T000000Z", "DTSTAMP:20250901T000000Z"]);
+        let stamped = rest(&["LAST-MODIFIED:20250905T000000Z", "DTSTAMP:20250920T000000Z", "SEQUENCE:5"]);
+        assert_eq!(latest(&stamped, &modified), DayType::WorkDay);
+        let stamped = work(&["DTSTAMP:20250920T000000Z"]);
+        assert_eq!(latest(&stamped, &rest(&["DTSTAMP:20250901T000000Z", "SEQUENCE:9"])), DayType::WorkDay);
+
+        // An event without any of them is the oldest
+        assert_eq!(latest(&rest(&["SEQUENCE:0"]), &work(&[])), DayType::RestDay);
+        assert_eq!(latest(&work(&[]), &rest(&["SEQUENCE:0"])), DayType::RestDay);
+    }
+
+    #[test]
+    fn explicit_type_overrides_pattern_matching() {
+        // 2025-10-04 is a Saturday, the other dates are weekdays
+        let ics = events(&[
+            &["DTSTART;VALUE=DATE:20251004", "SUMMARY:Holiday", "X-CHOLIDAY-TYPE:WORK"],
+            &["DTSTART;VALUE=DATE:20251009", "SUMMARY:Team day", "X-CHOLIDAY-TYPE:rest"],
+            &["DTSTART;VALUE=DATE:20251010", "SUMMARY:Holiday"],
+            &["DTSTART;VALUE=DATE:20251010", "SUMMARY:Release day", "X-CHOLIDAY-TYPE:WORK"],
+            &["DTSTART;VALUE=DATE:20251013", "SUMMARY:Holiday", "X-CHOLIDAY-TYPE:MAYBE"],
+            &["DTSTART;VALUE=DATE:20251014", "SUMMARY:Holiday", "X-DAY-TYPE:WORK"],
+        ]);
+        let dates = ["20251004", "20251009", "20251010", "20251013", "20251014"];
+        // A tagged event outranks an untagged one even under RestOverWork, and
+        // an unknown value leaves the patterns in charge
+        assert_eq!(
+            day_types(&ics, PREDICT, &dates),
+            [DayType::WorkDay, DayType::RestDay, DayType::WorkDay, DayType::RestDay, DayType::RestDay]
+        );
+
+        let settings = format!("{}explicit_property = \"X-DAY-TYPE\"\n", PREDICT);
+        assert_eq!(
+            day_types(&ics, &settings, &dates),
+            [DayType::RestDay, DayType::WorkDay, DayType::RestDay, DayType::RestDay, DayType::WorkDay]
+        );
+    }
+
+    #[test]
+    fn text_values_are_unescaped_before_matching() {
+        assert_eq!(Ical::unescape_text(r"a\,b"), "a,b");
+        assert_eq!(Ical::unescape_text(r"a\;b"), "a;b");
+        assert_eq!(Ical::unescape_text(r"a\\b"), r"a\b");
+        assert_eq!(Ical::unescape_text(r"a\nb\Nc"), "a\nb\nc");
+        // Unknown escapes and a trailing backslash are kept
+        assert_eq!(Ical::unescape_text(r"C:\temp\"), r"C:\temp\");
+
+        let event = parse(&["DTSTART;VALUE=DATE:20251001", r"DESCRIPTION:国庆节放假\, 10月11日（周六）上班\;\n详见 \\\\hr\\notices\, 第3条"]);
+        assert_eq!(event.description(), Some("国庆节放假, 10月11日（周六）上班;\n详见 \\\\hr\\notices, 第3条"));
+
+        let ics = events(&[&["DTSTART;VALUE=DATE:20251009", r"SUMMARY:休息\,调休"]]);
+        let settings = "[predict]\nwork = [\"上班\"]\nrest = [\"休息,调休\"]\npriority = \"RestOverWork\"\n";
+        assert_eq!(day_types(&ics, settings, &["20251009"]), [DayType::RestDay]);
+    }
+
+    #[test]
+    fn bom_and_latin1_calendars_classify() {
+        let settings = "[predict]\nwork = []\nrest = [\"Fête\"]\npriority = \"RestOverWork\"\n";
+        let ics = events(&[&["DTSTART;VALUE=DATE:20251009", "SUMMARY:Fête du personnel"]]);
+
+        let bom = [&[0xEF, 0xBB, 0xBF][..], ics.as_bytes()].concat();
+        assert_eq!(day_types(&bom, settings, &["20251009"]), [DayType::RestDay]);
+
+        // Latin-1 writes ê as the single byte 0xEA, which is not valid UTF-8
+        let latin1: Vec<u8> = ics.chars().map(|c| u8::try_from(u32::from(c)).unwrap()).collect();
+        assert!(std::str::from_utf8(&latin1).is_err());
+        assert_eq!(day_types(&latin1, settings, &["20251009"]), [DayType::RestDay]);
+    }
+
+    #[test]
+    fn events_without_a_usable_dtstart_are_skipped_and_counted() {
+        let ics = events(&[
+            &["DTSTART;VALUE=DATE:20251001", "SUMMARY:Holiday"],
+            &["DTSTART:2025-10-09", "SUMMARY:Holiday"],
+            &["SUMMARY:Holiday"],
+            &["DTSTART;TZID=Asia/Shanghai:20251010T250000", "SUMMARY:Holiday"],
+        ]);
+        let (_dir, choliday) = analyzer(&ics, PREDICT, "20251001");
+        let reports = block_on(choliday.refresh());
+        assert!(matches!(reports[0].status(), SourceStatus::Loaded { events: 1 }), "{:?}", reports[0]);
+        assert_eq!(reports[0].stats().parsed, 4);
+        assert_eq!(reports[0].stats().malformed, 3);
+
+        // Nothing lands on 1970 or on the days the broken events meant
+        let dates = ["20251001", "20251009", "20251010", "19700101"];
+        assert_eq!(day_types(&ics, PREDICT, &dates), [DayType::RestDay, DayType::WorkDay, DayType::WorkDay, DayType::WorkDay]);
+    }
+
+    #[test]
+    fn todos_mark_rest_days_when_included() {
+        let todo = |properties: &str| format!("BEGIN:VTODO\r\n{}END:VTODO\r\n", properties.replace('|', "\r\n"));
+        let ics = format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}{}{}{}END:VCALENDAR\r\n",
+            todo("UID:due@choliday|SUMMARY:Compensation Holiday|DUE;VALUE=DATE:20251009|"),
+            todo("UID:span@choliday|SUMMARY:Compensation Holiday|DTSTART;VALUE=DATE:20251013|DURATION:P2D|"),
+            todo("UID:done@choliday|SUMMARY:Compensation Holiday|DUE;VALUE=DATE:20251016|STATUS:COMPLETED|"),
+            todo("UID:full@choliday|SUMMARY:Compensation Holiday|DUE;VALUE=DATE:20251017|PERCENT-COMPLETE:100|"),
+        );
+        // A to-do is due on one day, DTSTART plus DURATION for the second
+        let dates = ["20251009", "20251013", "20251014", "20251015", "20251016", "20251017"];
+
+        assert!(day_types(&ics, PREDICT, &dates).iter().all(|day_type| *day_type == DayType::WorkDay));
+        let settings = format!("include_todos = true\n{}", PREDICT);
+        assert_eq!(
+            day_types(&ics, &settings, &dates),
+            [DayType::RestDay, DayType::WorkDay, DayType::WorkDay, DayType::RestDay, DayType::WorkDay, DayType::WorkDay]
+        );
+    }
+
+    #[test]
+    fn lenient_mode_recovers_events_of_broken_calendars() {
+        let lenient = format!("lenient = true\n{}", PREDICT);
+        let load = |ics: &str, settings: &str| {
+            let (_dir, choliday) = analyzer(ics, settings, "20251009");
+            let report = block_on(choliday.refresh()).remove(0);
+            (report, block_on(choliday.is_work_day()))
+        };
+
+        // A calendar missing its END:VCALENDAR keeps its events either way
+        let unterminated = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:nd@choliday\r\n\
+                            DTSTART;VALUE=DATE:20251009\r\nSUMMARY:Holiday\r\nEND:VEVENT\r\n";
+        for settings in [PREDICT, &lenient] {
+            let (report, work) = load(unterminated, settings);
+            assert!(matches!(report.status(), SourceStatus::Loaded { events: 1 }), "{:?}", report);
+            assert_eq!(work, Ok(false));
+        }
+
+        // Properties and an event outside any VCALENDAR are only read leniently
+        let bare = "X-WR-CALNAME:Team\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:team@choliday\r\n\
+                    DTSTART;VALUE=DATE:20251009\r\nSUMMARY:Holiday\r\nEND:VEVENT\r\n";
+        let (report, _) = load(bare, PREDICT);
+        assert!(matches!(report.status(), SourceStatus::Failed(cause) if cause.contains("enable lenient parsing")), "{:?}", report);
+        let (report, work) = load(bare, &lenient);
+        assert!(matches!(report.status(), SourceStatus::Loaded { events: 1 }), "{:?}", report);
+        assert_eq!(report.stats().recovered, 1);
+        assert_eq!(work, Ok(false));
+    }
+
+    #[test]
+    fn order_priorities_do_not_depend_on_source_or_file_order() {
+        let first: [&[&str]; 2] = [
+            &["UID:a1", "DTSTART;TZID=Asia/Shanghai:20251004T090000", "DTEND;TZID=Asia/Shanghai:20251004T100000", "SUMMARY:Workday"],
+            &["UID:a2", "DTSTART;VALUE=DATE:20251009", "SUMMARY:Holiday"],
+        ];
+        let second: [&[&str]; 4] = [
+            &["UID:b1", "DTSTART;TZID=Asia/Shanghai:20251004T130000", "DTEND;TZID=Asia/Shanghai:20251004T140000", "SUMMARY:Holiday"],
+            &["UID:b2", "DTSTART;TZID=Asia/Shanghai:20251009T100000", "DTEND;TZID=Asia/Shanghai:20251009T110000", "SUMMARY:Workday"],
+            &["UID:b3", "DTSTART;VALUE=DATE:20251010", "SUMMARY:Holiday"],
+            &["UID:b4", "DTSTART;VALUE=DATE:20251010", "SUMMARY:Workday"],
+        ];
+        let (a, b) = (events(&first), events(&second));
+        let a_rev = events(&first.iter().rev().copied().collect::<Vec<_>>());
+        let b_rev = events(&second.iter().rev().copied().collect::<Vec<_>>());
+        let dates = ["20251004", "20251009", "20251010"];
+
+        // Same-start events on the 10th fall back to summary order
+        let (work, rest) = (DayType::WorkDay, DayType::RestDay);
+        for (priority, expected) in [("KeepCurrent", [work, rest, rest]), ("UseLatest", [rest, work, work])] {
+            let settings = format!("[predict]\nwork = [\"Workday\"]\nrest = [\"Holiday\"]\npriority = \"{}\"\n", priority);
+            let classify = |calendars: &[&String]| {
+                let (_dir, choliday) = sources(calendars, &settings, dates[0]);
+                let dates: Vec<NaiveDate> = dates.iter().map(|date| NaiveDate::parse_from_str(date, DATE_FMT).unwrap()).collect();
+                block_on(choliday.evaluate_many(&dates)).iter().map(DayDecision::day_type).collect::<Vec<_>>()
+            };
+            assert_eq!(classify(&[&a, &b]), expected, "{}", priority);
+            assert_eq!(classify(&[&b, &a]), expected, "{}", priority);
+            assert_eq!(classify(&[&a_rev, &b_rev]), expected, "{}", priority);
+            assert_eq!(classify(&[&b_rev, &a]), expected, "{}", priority);
+        }
+    }
+
+    #[test]
+    fn events_read_for_one_day_do_not_answer_another() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut ical = Ical::new(two_holidays(dir.path()));
+        ical.set_use_cache(false);
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        // Noon in Shanghai, a whole civil day either way
+        let day = |date: &str| (millis(&format!("{}T040000", date)), millis(&format!("{}T040001", date)));
+
+        assert_eq!(runtime.block_on(ical.judge_by_priority(Some(day("20251001")))), DayType::RestDay);
+        assert_eq!(runtime.block_on(ical.judge_by_priority(Some(day("20251010")))), DayType::RestDay);
+        assert_eq!(runtime.block_on(ical.judge_by_priority(Some(day("20251009")))), DayType::NormalDay);
+        assert_eq!(runtime.block_on(ical.judge_by_priority(Some(day("20251001")))), DayType::RestDay);
+    }
+
+    #[test]
+    fn second_day_evaluated_sees_its_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let date = |day: u32| NaiveDate::from_ymd_opt(2025, 10, day).unwrap();
+        let choliday = Choliday::builder(two_holidays(dir.path())).date(date(1)).use_cache(false).build().unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+        assert_eq!(runtime.block_on(choliday.evaluate()).unwrap().day_type(), DayType::RestDay);
+        let later = runtime.block_on(choliday.evaluate_many(&[date(10), date(9)]));
+        assert_eq!(later[0].day_type(), DayType::RestDay);
+        assert_eq!(later[1].day_type(), DayType::WorkDay);
+    }
+
+    #[test]
+    fn mirror_with_the_higher_sequence_wins() {
+        // Two mirrors of one feed, the second with a later revision that shortens the holiday
+        let mirror = |source: &str, properties: &[&str]| {
+            let mut event = parse(properties);
+            event.add_source(source);
+            event
+        };
+        let old = mirror("a.ics", &["DTSTART;VALUE=DATE:20251001", "DTEND;VALUE=DATE:20251008", "SEQUENCE:1"]);
+        let new = mirror("b.ics", &["DTSTART;VALUE=DATE:20251001", "DTEND;VALUE=DATE:20251004", "SEQUENCE:2"]);
+
+        for events in [vec![old.clone(), new.clone()], vec![new.clone(), old.clone()]] {
+            let kept = Ical::dedup_by_uid(events);
+            assert_eq!(kept.len(), 1);
+            assert_eq!(kept[0].sequence, Some(2));
+            assert_eq!(kept[0].dtend, millis("20251004T000000"));
+            let mut sources = kept[0].sources.clone();
+            sources.sort();
+            assert_eq!(sources, ["a.ics", "b.ics"]);
+        }
+    }
+
+    #[test]
+    fn floating_times_are_read_in_the_calendar_zone() {
+        // 00:30 to 01:30 on 2025-10-02 in Berlin (CEST) is still 2025-10-01 in UTC
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nX-WR-TIMEZONE:Europe/Berlin\r\nBEGIN:VEVENT\r\nUID:late@choliday\r\n\
+                   DTSTART:20251002T003000\r\nDTEND:20251002T013000\r\nSUMMARY:Holiday\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let read = |conf: Conf| {
+            let options = Ical::new(conf).parse_options(false);
+            Ical::parse_calendar(ics.as_bytes(), None, &options).unwrap().events.remove(0)
+        };
+
+        let event = read(Conf::builder(Priority::RestOverWork).build().unwrap());
+        assert_eq!(event.dtstart, millis("20251001T223000"));
+        assert_eq!(event.dtend, millis("20251001T233000"));
+
+        // A configured zone takes precedence over the calendar's
+        let event = read(Conf::builder(Priority::RestOverWork).timezone(chrono_tz::UTC).build().unwrap());
+        assert_eq!(event.dtstart, millis("20251002T003000"));
+    }
+
+    #[test]
+    fn outlook_vtimezone_resolves_both_sides_of_the_dst_change() {
+        // Central European time falls back from +02:00 to +01:00 at 03:00 on 2025-10-26
+        let conf = Conf::builder(Priority::RestOverWork).build().unwrap();
+        let options = Ical::new(conf).parse_options(false);
+        let ics = include_str!("../tests/fixtures/outlook.ics");
+        let events = Ical::parse_calendar(ics.as_bytes(), None, &options).unwrap().events;
+        let span = |uid: &str| {
+            let event = events.iter().find(|e| e.uid.as_deref().is_some_and(|u| u.ends_with(uid))).unwrap();
+            (event.dtstart, event.dtend)
+        };
+
+        assert_eq!(span("summer"), (millis("20251024T070000"), millis("20251024T150000")));
+        assert_eq!(span("winter"), (millis("20251027T080000"), millis("20251027T160000")));
+        // Three wall-clock hours spanning the change last four
+        assert_eq!(span("night"), (millis("20251025T233000"), millis("20251026T033000")));
+    }
+
+    #[test]
+    fn day_types_round_trip_through_serde() {
+        for day_type in DayType::ALL {
+            // Exhaustive, so a new variant needs its stable name here
+            let name = match day_type {
+                DayType::NormalDay => "normal_day",
+                DayType::WorkDay => "work_day",
+                DayType::RestDay => "rest_day",
+                DayType::ConflictDay => "conflict_day",
+                DayType::Unknown => "unknown",
+            };
+            let json = serde_json::to_string(&day_type).unwrap();
+            assert_eq!(json, format!("\"{}\"", name));
+            assert_eq!(serde_json::from_str::<DayType>(&json).unwrap(), day_type);
+            assert_eq!(day_type.to_string(), name);
+        }
+        assert!(serde_json::from_str::<DayType>("\"holiday\"").is_err());
+    }
+
+    /// Generates a calendar of synthetic events, one starting each day from
+    /// 2020-01-01 for every number in `range`, mixing all-day, timed, multi-day,
+    /// recurring and foreign-zone events of both kinds
+    fn synthetic(range: std::ops::Range<usize>) -> String {
+        let start = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        let mut ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n".to_string();
+        for i in range {
+            let day = (start + TimeDelta::days(i as i64)).format(DATE_FMT);
+            let next = (start + TimeDelta::days(i as i64 + 2)).format(DATE_FMT);
+            let properties = match i % 5 {
+                0 => format!("DTSTART;VALUE=DATE:{}\r\nSUMMARY:Holiday {}", day, i),
+                1 => format!("DTSTART;TZID=Asia/Shanghai:{}T090000\r\nDTEND;TZID=Asia/Shanghai:{}T180000\r\nSUMMARY:Workday {}", day, day, i),
+                2 => format!("DTSTART:{}T230000Z\r\nDURATION:PT3H\r\nSUMMARY:Holiday {}", day, i),
+                3 => format!("DTSTART;VALUE=DATE:{}\r\nDTEND;VALUE=DATE:{}\r\nSUMMARY:Workday {}", day, next, i),
+                _ => format!("DTSTART;TZID=Europe/Berlin:{}T100000\r\nRRULE:FREQ=WEEKLY;COUNT=3\r\nSUMMARY:Holiday {}", day, i),
+            };
+            ics += &format!("BEGIN:VEVENT\r\nUID:{}@synthetic\r\n{}\r\nDESCRIPTION:Event number {} of a synthetic calendar\r\nEND:VEVENT\r\n", i, properties, i);
+        }
+        ics + "END:VCALENDAR\r\n"
+    }
+
+    /// Counts the allocations of the current thread, so tests running in
+    /// parallel do not disturb each other's counts
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            unsafe { std::alloc::System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+            unsafe { std::alloc::System.dealloc(ptr, layout) }
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    /// Returns the allocations of the current thread so far
+    fn allocations() -> usize {
+        ALLOCATIONS.with(std::cell::Cell::get)
+    }
+
+    /// Creates a calendar reader of a file per calendar in Asia/Shanghai,
+    /// classifying by the given priority
+    fn synthetic_ical(calendars: &[String], priority: &str) -> (tempfile::TempDir, Ical) {
+        let dir = tempfile::tempdir().unwrap();
+        let paths: Vec<_> = calendars
+            .iter()
+            .enumerate()
+            .map(|(i, ics)| {
+                let path = dir.path().join(format!("synthetic{}.ics", i));
+                std::fs::write(&path, ics).unwrap();
+                path
+            })
+            .collect();
+        let conf = Conf::from_toml(&format!(
+            "[base]\ntimezone = \"Asia/Shanghai\"\n[calendar]\nsource = {:?}\n{}",
+            paths, PREDICT.replace("RestOverWork", priority)
+        ))
+        .unwrap();
+        let mut ical = Ical::new(conf);
+        ical.set_use_cache(false);
+        (dir, ical)
+    }
+
+    /// Classifies a civil day of Asia/Shanghai by events already read
+    fn outcome(ical: &Ical, loaded: &Loaded, date: NaiveDate) -> (DayType, Option<Provenance>) {
+        let resolution = ical.classify(loaded, Some(Window::day(&TargetZone::Configured(Shanghai), date)));
+        (resolution.decided.map_or(DayType::NormalDay, |(x, _, _)| x), resolution.provenance)
+    }
+
+    #[test]
+    fn classification_allocates_independently_of_the_event_count() {
+        let dates: Vec<NaiveDate> = (1..=28).map(|day| NaiveDate::from_ymd_opt(2020, 2, day).unwrap()).collect();
+        let per_query = |count: usize| {
+            let (_dir, ical) = synthetic_ical(&[synthetic(0..count)], "KeepCurrent");
+            let loaded = block_on(ical.read(None));
+            assert!(loaded.events.len() >= count);
+
+            // Classify the same February days, covered alike whatever the calendar size
+            let before = allocations();
+            let day_types: Vec<DayType> = dates.iter().map(|date| outcome(&ical, &loaded, *date).0).collect();
+            (allocations() - before, day_types)
+        };
+
+        let (small, expected) = per_query(500);
+        let (large, day_types) = per_query(4000);
+        assert_eq!(day_types, expected);
+        assert!(expected.contains(&DayType::WorkDay) && expected.contains(&DayType::RestDay));
+        assert_eq!(large, small, "allocations grew with the event count");
+    }
+
+    #[test]
+    fn windowed_reads_classify_like_reading_every_event() {
+        let start = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        let dates: Vec<NaiveDate> = (0..3000).step_by(149).map(|day| start + TimeDelta::days(day)).collect();
+        for priority in ["KeepCurrent", "RestOverWork"] {
+            let (_dir, ical) = synthetic_ical(&[synthetic(0..3000)], priority);
+            let all = block_on(ical.read(None));
+            for date in &dates {
+                let window = Window::day(&TargetZone::Configured(Shanghai), *date);
+                let windowed = block_on(ical.read(Some(window)));
+                assert!(windowed.events.len() < 10, "{} events kept for {}", windowed.events.len(), date);
+                assert_eq!(outcome(&ical, &windowed, *date), outcome(&ical, &all, *date), "{} {}", priority, date);
+            }
+        }
+    }
+
+    #[test]
+    fn parallel_parsing_merges_like_parsing_in_turn() {
+        let calendars: Vec<String> = (0..6).map(|i| synthetic(i * 500..(i + 1) * 500)).collect();
+        let (_dir, ical) = synthetic_ical(&calendars, "KeepCurrent");
+        let merged = block_on(ical.read(None));
+
+        // Parse the files one after another on this thread, in configuration order
+        let options = ical.parse_options(false);
+        let sequential: Vec<Event> = calendars
+            .iter()
+            .flat_map(|ics| Ical::parse_calendar(ics.as_bytes(), None, &options).unwrap().events)
+            .collect();
+        let key = |e: &Event| (e.uid.clone(), e.dtstart, e.dtend, e.summary.clone());
+        assert_eq!(merged.events.iter().map(key).collect::<Vec<_>>(), sequential.iter().map(key).collect::<Vec<_>>());
+
+        // And classify like one file holding every event
+        let (_dir, single) = synthetic_ical(&[synthetic(0..3000)], "KeepCurrent");
+        let all = block_on(single.read(None));
+        let start = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        for date in (0..3000).step_by(149).map(|day| start + TimeDelta::days(day)) {
+            let ((x, provenance), (y, expected)) = (outcome(&ical, &merged, date), outcome(&single, &all, date));
+            let decided = |p: Option<Provenance>| p.map(|p| (p.uid, p.summary));
+            assert_eq!((x, decided(provenance)), (y, decided(expected)), "{}", date);
+        }
+    }
+}
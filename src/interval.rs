@@ -0,0 +1,144 @@
+//! Index of time spans answering which spans cover an instant or overlap a range.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+/// Index of half-open spans `[start, end)`, built once and queried many times
+/// 
+/// # Note
+/// * Spans are sorted by start and read as an implicit balanced tree, each
+///   node knowing the largest end below it, so a query visits O(log n + k)
+///   nodes for k matches regardless of how long the spans are
+/// * Spans are identified by their position in the sequence the index was
+///   built from
+#[derive(Clone, Debug, Default)]
+pub(crate) struct IntervalIndex {
+    /// Positions of the spans, ordered by start
+    order: Vec<usize>,
+    /// Starts, in that order
+    starts: Vec<i64>,
+    /// Ends, in that order
+    ends: Vec<i64>,
+    /// Largest end in the subtree rooted at each node, in that order
+    max_ends: Vec<i64>,
+}
+
+impl IntervalIndex {
+    /// Builds the index of a sequence of spans
+    /// 
+    /// # Arguments
+    /// * `spans` - `(start, end)` pairs, `end` excluded; empty and inverted
+    ///   spans are kept but match nothing
+    pub(crate) fn new(spans: impl IntoIterator<Item = (i64, i64)>) -> Self {
+        let mut spans: Vec<(usize, (i64, i64))> = spans.into_iter().enumerate().collect();
+        spans.sort_by_key(|(position, (start, _))| (*start, *position));
+        let mut index = IntervalIndex {
+            order: spans.iter().map(|(position, _)| *position).collect(),
+            starts: spans.iter().map(|(_, (start, _))| *start).collect(),
+            ends: spans.iter().map(|(_, (_, end))| *end).collect(),
+            max_ends: Vec::new(),
+        };
+        index.max_ends = index.ends.clone();
+        index.build(0, index.ends.len());
+        index
+    }
+
+    /// Finds the spans overlapping a range
+    /// 
+    /// # Arguments
+    /// * `start` - Start of the range, included
+    /// * `end` - End of the range, excluded
+    /// 
+    /// # Returns
+    /// * Positions of the spans with `span.start < end` and `start < span.end`, ascending
+    pub(crate) fn overlapping(&self, start: i64, end: i64) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.collect(0, self.order.len(), start, end, &mut found);
+        found.sort_unstable();
+        found
+    }
+
+    /// Computes the largest end of every subtree of `lo..hi`
+    /// 
+    /// # Returns
+    /// * Largest end in `lo..hi`, `i64::MIN` when empty
+    fn build(&mut self, lo: usize, hi: usize) -> i64 {
+        if lo >= hi {
+            return i64::MIN;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let max_end = self.build(lo, mid).max(self.build(mid + 1, hi)).max(self.ends[mid]);
+        self.max_ends[mid] = max_end;
+        max_end
+    }
+
+    /// Adds the positions of the spans of `lo..hi` overlapping `start..end`
+    fn collect(&self, lo: usize, hi: usize, start: i64, end: i64, found: &mut Vec<usize>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        // Nothing below ends after the range starts
+        if self.max_ends[mid] <= start {
+            return;
+        }
+        self.collect(lo, mid, start, end, found);
+        // Spans from here on start at or after the range ends
+        if self.starts[mid] >= end {
+            return;
+        }
+        if start < self.ends[mid] {
+            found.push(self.order[mid]);
+        }
+        self.collect(mid + 1, hi, start, end, found);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queries_match_a_linear_scan() {
+        // Pseudo-random spans of up to ten days, in milliseconds
+        let mut seed: u64 = 42;
+        let mut next = |bound: i64| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) as i64 % bound
+        };
+        let spans: Vec<(i64, i64)> = (0..2000)
+            .map(|_| {
+                let start = next(365 * 86_400_000);
+                (start, start + next(10 * 86_400_000))
+            })
+            .collect();
+        let index = IntervalIndex::new(spans.iter().copied());
+
+        for _ in 0..500 {
+            let (from, to) = (next(370 * 86_400_000), next(370 * 86_400_000));
+            let covering: Vec<usize> = (0..spans.len()).filter(|&i| spans[i].0 <= from && from < spans[i].1).collect();
+            assert_eq!(index.overlapping(from, from + 1), covering);
+            let overlapping: Vec<usize> = (0..spans.len()).filter(|&i| spans[i].0 < to && from < spans[i].1).collect();
+            assert_eq!(index.overlapping(from, to), overlapping);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! jCal (RFC 7265) calendar data, read as iCalendar properties.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+
+use ical::property::Property;
+use log::warn;
+
+use crate::ical::Ical;
+use serde_json::{Map, Value};
+
+/// Converts jCal data into the iCalendar properties it represents
+/// 
+/// # Arguments
+/// * `body` - jCal data, either one `vcalendar` array or an array of them
+/// 
+/// # Returns
+/// * `Ok(Vec<Property>)` - Properties in document order, with a `BEGIN`/`END`
+///   pair around each component, as an iCalendar file would yield them
+/// * `Err(String)` - The data is not JSON or not a jCal array
+/// 
+/// # Note
+/// * Values are written in their iCalendar form (e.g. `2025-10-04` becomes
+///   `20251004`, TEXT is escaped), so the iCalendar reader interprets them
+///   exactly like the same event in an `.ics` file
+/// * Malformed components and properties are skipped with a warning
+pub fn properties(body: &[u8]) -> Result<Vec<Property>, String> {
+    let data: Value = serde_json::from_slice(body).map_err(|e| format!("Invalid jCal data: {}", e))?;
+    let calendars = match &data {
+        Value::Array(items) if items.first().is_some_and(Value::is_string) => vec![&data],
+        Value::Array(items) => items.iter().collect(),
+        _ => return Err("Invalid jCal data: expected a vcalendar array".to_string()),
+    };
+
+    let mut props = Vec::new();
+    for calendar in calendars {
+        if let Err(e) = component(calendar, &mut props) {
+            warn!("Skipping unreadable jCal component: {}", e);
+        }
+    }
+    Ok(props)
+}
+
+/// Appends a component `[name, properties, components]` and its subcomponents
+fn component(value: &Value, out: &mut Vec<Property>) -> Result<(), String> {
+    let Some([Value::String(name), Value::Array(props), Value::Array(components)]) =
+        value.as_array().map(Vec::as_slice)
+    else {
+        return Err(format!("expected [name, properties, components], found {}", value));
+    };
+    let name = name.to_uppercase();
+
+    out.push(delimiter("BEGIN", &name));
+    for prop in props {
+        match property(prop) {
+            Ok(prop) => out.push(prop),
+            Err(e) => warn!("Skipping unreadable jCal property in {}: {}", name, e),
+        }
+    }
+    for sub in components {
+        if let Err(e) = component(sub, out) {
+            warn!("Skipping unreadable jCal component in {}: {}", name, e);
+        }
+    }
+    out.push(delimiter("END", &name));
+    Ok(())
+}
+
+/// Builds a `BEGIN`/`END` line for a component
+fn delimiter(name: &str, component: &str) -> Property {
+    Property {
+        name: name.to_string(),
+        params: None,
+        value: Some(component.to_string()),
+    }
+}
+
+/// Converts a property `[name, parameters, type, values...]`
+fn property(value: &Value) -> Result<Property, String> {
+    let Some([Value::String(name), Value::Object(params), Value::String(kind), values @ ..]) =
+        value.as_array().map(Vec::as_slice)
+    else {
+        return Err(format!("expected [name, parameters, type, values...], found {}", value));
+    };
+
+    let mut params: Vec<(String, Vec<String>)> = params
+        .iter()
+        .map(|(key, value)| {
+            let values = match value {
+                Value::Array(items) => items.iter().map(plain).collect(),
+                other => vec![plain(other)],
+            };
+            (key.to_uppercase(), values)
+        })
+        .collect();
+    // The type takes the place of the VALUE parameter, which tells dates from date-times
+    if matches!(kind.as_str(), "date" | "date-time" | "period")
+        && !params.iter().any(|(key, _)| key == "VALUE")
+    {
+        params.push(("VALUE".to_string(), vec![kind.to_uppercase()]));
+    }
+
+    let value = (!values.is_empty()).then(|| {
+        values
+            .iter()
+            .map(|value| convert(value, kind))
+            .collect::<Vec<_>>()
+            .join(",")
+    });
+
+    Ok(Property {
+        name: name.to_uppercase(),
+        params: (!params.is_empty()).then_some(params),
+        value,
+    })
+}
+
+/// Writes a single jCal value in its iCalendar form
+/// 
+/// # Arguments
+/// * `value` - JSON value of the property
+/// * `kind` - jCal value type, such as `date-time` or `text`
+fn convert(value: &Value, kind: &str) -> String {
+    match value {
+        Value::String(s) => match kind {
+            "date" | "date-time" | "time" => s.replace(['-', ':'], ""),
+            "utc-offset" => s.replace(':', ""),
+            "period" => s
+                .split('/')
+                .map(|part| match part.trim_start_matches(['+', '-']).starts_with('P') {
+                    true => part.to_string(),
+                    false => part.replace(['-', ':'], ""),
+                })
+                .collect::<Vec<_>>()
+                .join("/"),
+            "text" => Ical::escape_text(s),
+            _ => s.clone(),
+        },
+        Value::Object(parts) if kind == "recur" => recur(parts),
+        // Structured values, e.g. REQUEST-STATUS
+        Value::Array(parts) => parts.iter().map(|part| convert(part, kind)).collect::<Vec<_>>().join(";"),
+        other => plain(other),
+    }
+}
+
+/// Writes a recurrence rule object as `FREQ=...;KEY=VALUE`
+fn recur(parts: &Map<String, Value>) -> String {
+    // FREQ goes first for readers that expect it there
+    let mut parts: Vec<_> = parts.iter().collect();
+    parts.sort_by_key(|(key, _)| !key.eq_ignore_ascii_case("freq"));
+    parts
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::Array(items) => items.iter().map(plain).collect::<Vec<_>>().join(","),
+                Value::String(s) if key.eq_ignore_ascii_case("until") => s.replace(['-', ':'], ""),
+                other => plain(other),
+            };
+            format!("{}={}", key.to_uppercase(), value)
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Writes a scalar JSON value as text
+fn plain(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Bool(b) => b.to_string().to_uppercase(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
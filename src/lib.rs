@@ -0,0 +1,161 @@
+//! Work schedule prediction library, classifying days as work or rest days
+//! from calendar events.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+//! 
+//! # Usage
+//! A [`Conf`] is read from a TOML file with [`Conf::load`] or built in code
+//! with [`Conf::builder`]. [`Choliday`] answers whether its target date is a
+//! work day, falling back to the configured weekdays when no calendar event
+//! classifies it.
+//! 
+//! ```no_run
+//! use chrono::NaiveDate;
+//! use choliday::{Choliday, Conf, Priority};
+//! 
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), String> {
+//! let conf = Conf::builder(Priority::RestOverWork)
+//!     .source("https://example.com/holidays.ics")
+//!     .rest("Holiday")
+//!     .work("Makeup workday")
+//!     .build()?;
+//! let date = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap().and_hms_opt(23, 59, 59).unwrap();
+//! let mut choliday = Choliday::new(conf, date);
+//! choliday.set_strict(true);
+//! if choliday.is_work_day().await? {
+//!     println!("work day");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//! 
+//! With the `blocking` feature, `is_work_day_blocking` and `evaluate_blocking`
+//! answer the same without an async runtime.
+//! 
+//! With the `ffi` feature, the `extern "C"` functions declared in
+//! `include/choliday.h` answer C callers through an opaque handle, blocking
+//! like the above; calling them from Rust looks like this:
+//! 
+//! ```
+//! # #[cfg(feature = "ffi")] {
+//! use std::ffi::{CStr, CString};
+//! use choliday::{
+//!     CholidayStatus, choliday_free, choliday_is_work_day, choliday_last_error_message,
+//!     choliday_new_from_config_path,
+//! };
+//! 
+//! let temp = tempfile::tempdir().unwrap();
+//! let dir = temp.path();
+//! let calendar = dir.join("holidays.ics");
+//! std::fs::write(
+//!     &calendar,
+//!     "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20251001\r\nSUMMARY:Holiday\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+//! ).unwrap();
+//! let conf = dir.join("choliday.toml");
+//! std::fs::write(&conf, format!(
+//!     "[base]\ntimezone = \"UTC\"\n[calendar]\nsource = [{:?}]\n[predict]\nwork = []\nrest = [\"Holiday\"]\npriority = \"RestOverWork\"\n",
+//!     calendar,
+//! )).unwrap();
+//! let path = CString::new(conf.to_str().unwrap()).unwrap();
+//! 
+//! unsafe {
+//!     let handle = choliday_new_from_config_path(path.as_ptr());
+//!     assert!(!handle.is_null());
+//!     let mut work = true;
+//!     // 2025-10-01 and 2025-10-02 at noon UTC
+//!     assert_eq!(choliday_is_work_day(handle, 1_759_320_000_000, &mut work), CholidayStatus::Ok);
+//!     assert!(!work);
+//!     assert_eq!(choliday_is_work_day(handle, 1_759_406_400_000, &mut work), CholidayStatus::Ok);
+//!     assert!(work);
+//! 
+//!     let status = choliday_is_work_day(handle, 1_759_320_000_000, std::ptr::null_mut());
+//!     assert_eq!(status, CholidayStatus::InvalidArgument);
+//!     assert_eq!(CStr::from_ptr(choliday_last_error_message(handle)).to_str(), Ok("Output pointer is NULL"));
+//!     choliday_free(handle);
+//! 
+//!     let missing = CString::new("/nonexistent/choliday.toml").unwrap();
+//!     assert!(choliday_new_from_config_path(missing.as_ptr()).is_null());
+//!     assert!(!choliday_last_error_message(std::ptr::null()).is_null());
+//! }
+//! # }
+//! ```
+
+#[cfg(feature = "blocking")]
+mod blocking;
+mod cache;
+mod caldav;
+mod changes;
+mod choliday;
+mod conf;
+mod cron;
+mod csv;
+mod decision;
+mod exec;
+mod feed;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod google;
+mod ical;
+mod interval;
+mod jcal;
+mod nager;
+mod output;
+mod precompute;
+mod resolver;
+mod rrule;
+mod source;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod template;
+mod vtimezone;
+mod windows_zones;
+
+pub use cache::{Cache, CacheEntry, CacheStore, FileStore};
+pub use changes::{Change, ChangeKind};
+pub use choliday::{Choliday, CholidayBuilder, DayDecisions};
+pub use conf::{
+    CacheBackend, CalDav, Conf, ConfBuilder, ConfError, DstAmbiguous, DstGap, Exec, Google, Http, Location, MatchField, Nager,
+    OnConflict, Priority, Serve, Source, SourceFormat, TargetZone, WebcalScheme, WeekNumbering, WorkHours,
+};
+pub use cron::{GuardedJob, shell_quote};
+pub use decision::{
+    Bridge, ContradictingEvent, Contradiction, DayDecision, DayDiff, DecisionSource, DecisionTrace, Provenance,
+    PublicEvent, RestBlock, RestKind, Streak, TraceOutcome, TracedEvent, Transition, WorkDayCount,
+};
+pub use feed::Feed;
+pub use output::{BridgeList, ChangeList, DayList, DiffList, ErrorReport, RestBlockList, SCHEMA_VERSION, TransitionList, Versioned, json_schema};
+#[cfg(feature = "ffi")]
+pub use ffi::{
+    CholidayHandle, CholidayStatus, choliday_free, choliday_is_work_day, choliday_last_error_message,
+    choliday_new_from_config_path,
+};
+pub use ical::{DayType, Ical};
+pub use precompute::{DayClass, YearBitmap};
+pub use resolver::{Candidate, ConflictResolver};
+pub use source::{
+    Freshness, STDIN_SOURCE, SharedFetches, SourceReport, SourceStats, SourceStatus, format_age, freshness, redact_url,
+};
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStore;
+pub use template::{PLACEHOLDERS, render_template};
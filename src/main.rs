@@ -22,13 +22,19 @@
 //! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 //! SOFTWARE.
 
-use choliday::Choliday;
-use clap::Parser;
+use std::{path::Path, time::Duration};
+
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use choliday::{
+    BridgeList, Cache, ChangeList, Choliday, CholidayBuilder, Conf, DayClass, DayDecision, DayList, DecisionSource,
+    DiffList, ErrorReport, GuardedJob, RestBlockList, STDIN_SOURCE, SharedFetches, TransitionList, Versioned, YearBitmap,
+    format_age, redact_url, render_template,
+};
 
 mod cli;
-mod conf;
-mod ical;
-mod choliday;
+mod notify;
+mod serve;
+mod watch;
 
 /// Main entry point for the work schedule prediction tool
 /// 
@@ -42,25 +48,825 @@ mod choliday;
 /// 
 /// # Check specific date and time
 /// choliday -c config.toml -d 20241225143000
+/// 
+/// # List the events considered for a date
+/// choliday -c config.toml -d 20241225 list
+/// 
+/// # Print the third work day of July 2025
+/// choliday -c config.toml nth --month 202507 --n 3
+/// 
+/// # Remove cached remote calendars
+/// choliday -c config.toml cache clear
 /// ```
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Log warnings to stderr by default, more detail through RUST_LOG
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+    
     // Parse command-line arguments
-    let cli = cli::Cli::parse();
+    let cli = cli::Cli::load();
     
-    // Create holiday analyzer with configuration
-    let choliday = Choliday::new(&cli);
+    // The merged configuration is printed before it is validated
+    if cli.show_effective_config() {
+        let merged = Conf::merge_files(cli.conf_files())
+            .and_then(|table| toml::to_string_pretty(&table).map_err(|e| e.to_string()));
+        match merged {
+            Ok(text) => print!("{}", text),
+            Err(e) => fail(&cli, e),
+        }
+        std::process::exit(0);
+    }
+
+    // The schema does not depend on the calendars
+    if let Some(cli::Command::Schema) = cli.command() {
+        println!("{}", serde_json::to_string_pretty(&choliday::json_schema())?);
+        std::process::exit(0);
+    }
+
+    // Snippets only need the paths of the executable and the configuration
+    if let Some(cli::Command::CronSnippet { schedule, command, systemd, name }) = cli.command() {
+        cron_snippet(&cli, schedule, command, *systemd, name);
+    }
+
+    // A precomputed year answers the workday check without the calendars
+    if cli.command().is_none() && !cli.hours() && let Some(path) = cli.use_precomputed() {
+        precomputed(&cli, path);
+    }
+
+    // Create holiday analyzer with configuration, sharing downloads with the
+    // configuration diff compares against
+    let shared = SharedFetches::new();
+    let mut builder = analyzer(&cli, cli.conf());
+    if let Some(cli::Command::Diff { .. }) = cli.command() {
+        builder = builder.shared_fetches(shared.clone());
+    }
+    let choliday = builder
+        .build()
+        .unwrap_or_else(|e| fail(&cli, e));
+
+    // Run subcommands instead of the workday check
+    if let Some(command) = cli.command() {
+        match command {
+            cli::Command::Bridges { year, max_gap } => bridges(&choliday, &cli, *year, *max_gap).await,
+            cli::Command::Cache { action: cli::CacheAction::Clear } => clear_cache(cli.conf()),
+            cli::Command::Cache { action: cli::CacheAction::List } => list_cache(cli.conf()),
+            cli::Command::Changes { days, notify_url } => {
+                changes(&choliday, &cli, *days, notify_url.as_deref()).await
+            },
+            cli::Command::CronSnippet { .. } => unreachable!("printed before the analyzer is built"),
+            cli::Command::Count { from, to } => count(&choliday, &cli, *from, *to).await,
+            cli::Command::Diff { against, from, to } => diff(&choliday, &cli, against, shared, *from, *to).await,
+            cli::Command::Export { from, to, csv: false, work } if !cli.json() => {
+                export(&choliday, &cli, *from, *to, *work).await
+            },
+            cli::Command::Export { from, to, .. } => export_days(&choliday, &cli, *from, *to).await,
+            cli::Command::List => list(&choliday, &cli).await,
+            cli::Command::LongWeekends { from, to, min, sort } => {
+                long_weekends(&choliday, &cli, *from, *to, *min, *sort).await
+            },
+            cli::Command::Notify { ntfy, title, message, priority, smtp_url, to, from } => {
+                let email = smtp_url.as_deref().map(|url| (url, from.as_deref(), to.as_slice()));
+                send(&choliday, &cli, ntfy.as_deref(), title, message, priority.as_deref(), email).await
+            },
+            cli::Command::Nth { month, n, from_end } => nth(&choliday, &cli, *month, *n, *from_end).await,
+            cli::Command::Precompute { year, out } => precompute(&choliday, &cli, *year, out).await,
+            cli::Command::Schema => unreachable!("printed before the analyzer is built"),
+            cli::Command::Transitions { count } => transitions(&choliday, &cli, *count).await,
+            cli::Command::Watch { on_change, interval, state } => {
+                reject_stdin(&cli, "watch");
+                let state = state
+                    .clone()
+                    .or_else(|| Cache::dir_for(cli.conf()).map(|dir| watch::default_state(&dir)))
+                    .unwrap_or_else(|| fail(&cli, "No cache directory for the state file, set --state"));
+                let interval = Duration::from_secs(*interval);
+                let zone = cli.conf().target_zone();
+                if let Err(e) = watch::run(&choliday, zone, on_change.as_deref(), interval, &state).await {
+                    fail(&cli, e);
+                }
+                std::process::exit(2);
+            },
+            cli::Command::Serve { listen } => {
+                reject_stdin(&cli, "serve");
+                if let Err(e) = serve::run(choliday, listen, cli.conf().target_zone(), cli.conf().serve().refresh()).await {
+                    fail(&cli, e);
+                }
+                std::process::exit(2);
+            },
+        }
+    }
     
-    // Determine if target date is a work day
-    let is_work_day = choliday.is_work_day().await;
+    if cli.hours() {
+        working_hours(&choliday, &cli).await;
+    }
+
+    // Determine if target date is a work day, tracing the events when explaining
+    let result = if cli.verbose() {
+        choliday.trace().await.map(|trace| {
+            eprintln!("{}", choliday.source_table());
+            for event in &trace.events {
+                eprintln!("{}", event);
+            }
+            eprintln!("Without calendar events: {}", trace.fallback);
+            trace.decision
+        })
+    } else {
+        choliday.evaluate().await
+    };
+    let decision = match result {
+        Ok(decision) => decision,
+        Err(e) => {
+            if cli.verbose() {
+                eprintln!("{}", choliday.source_table());
+            }
+            fail(&cli, e);  // Error exit code, distinct from rest days
+        }
+    };
+    if cli.verbose() {
+        eprintln!("Decided by {}", decision.source());
+        if let Some(rest_kind) = decision.rest_kind() {
+            eprintln!("Rest day kind: {}", rest_kind);
+        }
+    }
+    check_data(&cli, [&decision]);
     
     // Output result
-    println!("{}", is_work_day);
+    if cli.json() {
+        println!("{}", serde_json::to_string_pretty(&Versioned::new(&decision))?);
+    } else if !cli.print().is_empty() {
+        let template = cli.print().iter().map(cli::PrintField::placeholder).collect::<Vec<_>>().join("\t");
+        println!("{}", render_template(&template, &decision).unwrap_or_else(|e| fail(&cli, e)));
+    } else if decision.is_unknown() {
+        println!("unknown");
+    } else if decision.is_unresolved() {
+        println!("conflict");
+    } else {
+        println!("{}", decision.is_work_day());
+    }
     
     // Exit with appropriate code for scripting use
-    if is_work_day {
+    if decision.is_unknown() {
+        if !cli.verbose() && let DecisionSource::Unavailable { reason } = decision.source() {
+            eprintln!("Unknown: {}", reason);
+        }
+        std::process::exit(4);  // Not enough calendar data to answer
+    } else if decision.is_unresolved() {
+        if !cli.verbose() && let DecisionSource::ConflictingEvent { summary, work_pattern, rest_pattern, .. } = decision.source() {
+            eprintln!(
+                "Conflict: event '{}' matches work pattern '{}' and rest pattern '{}'",
+                summary,
+                work_pattern.as_deref().unwrap_or("?"),
+                rest_pattern.as_deref().unwrap_or("?")
+            );
+        }
+        std::process::exit(3);  // An event matching both work and rest decided
+    } else if decision.is_work_day() {
         std::process::exit(0);  // Success exit code for work days
     } else {
         std::process::exit(1);  // Non-zero exit code for rest days
     }
-}
\ No newline at end of file
+}
+
+/// Fails when a calendar source is standard input, for commands reading their
+/// sources again while they run
+/// 
+/// # Arguments
+/// * `cli` - Command-line arguments
+/// * `command` - Name of the command, for the message
+fn reject_stdin(cli: &cli::Cli, command: &str) {
+    let sources = cli.conf().get_describe_calendar().unwrap_or(&[]);
+    if sources.iter().any(|source| source.name() == STDIN_SOURCE) {
+        fail(cli, format!(
+            "'{}' reads the calendar sources again while running, standard input ('{}') can only be read once",
+            command, STDIN_SOURCE
+        ));
+    }
+}
+
+/// Starts building an analyzer with the options of the command line
+/// 
+/// # Arguments
+/// * `cli` - Command-line arguments
+/// * `conf` - Configuration to classify the days by
+fn analyzer(cli: &cli::Cli, conf: &Conf) -> CholidayBuilder {
+    let builder = Choliday::builder(conf.clone())
+        .datetime(cli.date())
+        .whole_day(cli.whole_day())
+        .strict(cli.strict())
+        .use_cache(!cli.no_cache())
+        .revalidate(cli.refresh());
+    match cli.on_conflict() {
+        Some(on_conflict) => builder.on_conflict(on_conflict),
+        None => builder,
+    }
+}
+
+/// Prints a crontab line or systemd units guarding a command and exits
+/// 
+/// # Arguments
+/// * `cli` - Command-line arguments
+/// * `schedule` - Schedule in crontab syntax
+/// * `command` - Shell command to run on work days
+/// * `systemd` - Whether to print systemd units instead of a crontab line
+/// * `name` - Name of the systemd units
+fn cron_snippet(cli: &cli::Cli, schedule: &str, command: &str, systemd: bool, name: &str) -> ! {
+    let choliday = std::env::current_exe().unwrap_or_else(|e| fail(cli, format!("Cannot locate the executable: {}", e)));
+    if cli.conf_files().len() > 1 {
+        fail(cli, "cron-snippet guards with a single configuration file, merge the -c files into one");
+    }
+    let conf = cli.conf().conf_file().unwrap_or_else(|| fail(cli, "The configuration was not loaded from a file"));
+    let job = GuardedJob::new(schedule, command, choliday, conf).unwrap_or_else(|e| fail(cli, e));
+    if systemd {
+        let timer = job.systemd_timer(name).unwrap_or_else(|e| fail(cli, e));
+        println!("# {}.service", name);
+        println!("{}", job.systemd_service(name));
+        println!("# {}.timer", name);
+        print!("{}", timer);
+    } else {
+        println!("{}", job.crontab());
+    }
+    std::process::exit(0);
+}
+
+/// Prints the numbers of work and rest days of a date range and exits
+/// 
+/// # Arguments
+/// * `choliday` - Analyzer to classify the days with
+/// * `cli` - Command-line arguments selecting the output
+/// * `from` - First day of the range
+/// * `to` - Last day of the range, included
+async fn count(choliday: &Choliday, cli: &cli::Cli, from: NaiveDate, to: NaiveDate) -> ! {
+    let result = choliday.work_days_between(from, to).await;
+    if cli.verbose() {
+        eprintln!("{}", choliday.source_table());
+    }
+    if cli.strict_data() && let Ok(count) = &result && count.conflict > 0 {
+        fail(cli, format!("Days with contradicting calendar events: {}", count.conflict));
+    }
+    match result {
+        Ok(count) if cli.json() => {
+            match serde_json::to_string_pretty(&Versioned::new(&count)) {
+                Ok(json) => println!("{}", json),
+                Err(e) => fail(cli, e),
+            }
+            std::process::exit(0);
+        },
+        Ok(count) => {
+            println!("work: {}", count.work);
+            println!("rest: {}", count.rest);
+            println!("conflict: {}", count.conflict);
+            if count.unknown > 0 {
+                println!("unknown: {}", count.unknown);
+            }
+            if count.unresolved > 0 {
+                println!("unresolved: {}", count.unresolved);
+            }
+            std::process::exit(0);
+        },
+        Err(e) => fail(cli, e),
+    }
+}
+
+/// Prints every event considered for the target date and exits
+/// 
+/// # Arguments
+/// * `choliday` - Analyzer holding the target date
+/// * `cli` - Command-line arguments selecting the output
+async fn list(choliday: &Choliday, cli: &cli::Cli) -> ! {
+    let result = choliday.trace().await;
+    if cli.verbose() {
+        eprintln!("{}", choliday.source_table());
+    }
+    if let Ok(trace) = &result {
+        check_data(cli, [&trace.decision]);
+    }
+    match result {
+        Ok(trace) if cli.json() => {
+            match serde_json::to_string_pretty(&Versioned::new(&trace)) {
+                Ok(json) => println!("{}", json),
+                Err(e) => fail(cli, e),
+            }
+            std::process::exit(0);
+        },
+        Ok(trace) => {
+            for event in &trace.events {
+                println!("{}", event);
+            }
+            println!("Without calendar events: {}", trace.fallback);
+            println!("Decided by {}", trace.decision.source());
+            println!("{}", trace.decision.day_type());
+            std::process::exit(0);
+        },
+        Err(e) => fail(cli, e),
+    }
+}
+
+/// Prints the next changes of classification from the target date and exits
+/// 
+/// # Arguments
+/// * `choliday` - Analyzer to classify the days with
+/// * `cli` - Command-line arguments selecting the output
+/// * `n` - Number of transitions to print
+async fn transitions(choliday: &Choliday, cli: &cli::Cli, n: usize) -> ! {
+    let from = cli.date().date();
+    let result = choliday.transitions(from, n).await;
+    if cli.verbose() {
+        eprintln!("{}", choliday.source_table());
+    }
+    let transitions = match result {
+        Ok(transitions) => transitions,
+        Err(e) => fail(cli, e),
+    };
+    if transitions.len() < n {
+        eprintln!(
+            "Only {} of {} transitions within {} days of {} (base.horizon_days)",
+            transitions.len(), n, cli.conf().horizon_days(), from
+        );
+    }
+    if cli.json() {
+        match serde_json::to_string_pretty(&Versioned::new(TransitionList::new(from, n, transitions))) {
+            Ok(json) => println!("{}", json),
+            Err(e) => fail(cli, e),
+        }
+    } else {
+        for transition in &transitions {
+            println!("{}", transition);
+        }
+    }
+    std::process::exit(0);
+}
+
+/// Prints the work days of a year between rest days and exits
+/// 
+/// # Arguments
+/// * `choliday` - Analyzer to classify the days with
+/// * `cli` - Command-line arguments selecting the output
+/// * `year` - Year to search
+/// * `max_gap` - Longest run of work days to report
+async fn bridges(choliday: &Choliday, cli: &cli::Cli, year: i32, max_gap: u32) -> ! {
+    let result = choliday.bridges(year, max_gap).await;
+    if cli.verbose() {
+        eprintln!("{}", choliday.source_table());
+    }
+    let bridges = match result {
+        Ok(bridges) => bridges,
+        Err(e) => fail(cli, e),
+    };
+    if cli.json() {
+        match serde_json::to_string_pretty(&Versioned::new(BridgeList::new(year, max_gap, bridges))) {
+            Ok(json) => println!("{}", json),
+            Err(e) => fail(cli, e),
+        }
+    } else {
+        for bridge in &bridges {
+            println!("{}", bridge);
+        }
+    }
+    std::process::exit(0);
+}
+
+/// Prints the blocks of consecutive rest days of a range and exits
+/// 
+/// # Arguments
+/// * `choliday` - Analyzer to classify the days with
+/// * `cli` - Command-line arguments selecting the output
+/// * `from` - First day of the range
+/// * `to` - Last day of the range, included
+/// * `min` - Shortest block to print
+/// * `sort` - Order of the blocks
+async fn long_weekends(
+    choliday: &Choliday,
+    cli: &cli::Cli,
+    from: NaiveDate,
+    to: NaiveDate,
+    min: u32,
+    sort: cli::BlockOrder,
+) -> ! {
+    let result = choliday.rest_blocks(from, to, min).await;
+    if cli.verbose() {
+        eprintln!("{}", choliday.source_table());
+    }
+    let mut blocks = match result {
+        Ok(blocks) => blocks,
+        Err(e) => fail(cli, e),
+    };
+    if sort == cli::BlockOrder::Length {
+        blocks.sort_by_key(|block| std::cmp::Reverse(block.days()));
+    }
+    if cli.json() {
+        match serde_json::to_string_pretty(&Versioned::new(RestBlockList::new(from.min(to), from.max(to), min, blocks))) {
+            Ok(json) => println!("{}", json),
+            Err(e) => fail(cli, e),
+        }
+    } else {
+        for block in &blocks {
+            println!("{}", block);
+        }
+    }
+    std::process::exit(0);
+}
+
+/// Prints the days revisions of remote calendars changed, optionally posts them, and exits
+/// 
+/// # Arguments
+/// * `choliday` - Analyzer holding the target date and the configuration
+/// * `cli` - Command-line arguments selecting the output
+/// * `days` - Number of days after the target date to compare
+/// * `notify_url` - URL to post the changes to, if any were found
+/// 
+/// # Note
+/// * Exits 0 when nothing changed, 1 when changes were found and 2 on errors,
+///   including a failed notification
+async fn changes(choliday: &Choliday, cli: &cli::Cli, days: Option<u32>, notify_url: Option<&str>) -> ! {
+    let from = cli.date().date();
+    let days = days.unwrap_or_else(|| cli.conf().horizon_days());
+    let to = from.checked_add_days(chrono::Days::new(days.into())).unwrap_or(NaiveDate::MAX);
+    let changes = match choliday.changes(from, to).await {
+        Ok(changes) => changes,
+        Err(e) => fail(cli, e),
+    };
+    let changed = !changes.is_empty();
+    let report = Versioned::new(ChangeList::new(from, to, changes));
+    let json = match serde_json::to_string_pretty(&report) {
+        Ok(json) => json,
+        Err(e) => fail(cli, e),
+    };
+    if cli.json() {
+        println!("{}", json);
+    } else {
+        for change in &report.payload().changes {
+            println!("{}", change);
+        }
+    }
+
+    if changed && let Some(url) = notify_url {
+        let sent = reqwest::Client::builder()
+            .timeout(cli.conf().http().timeout())
+            .build()
+            .map_err(|e| e.to_string())
+            .map(|client| {
+                client
+                    .post(url)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(json)
+                    .send()
+            });
+        let result = match sent {
+            Ok(request) => match request.await {
+                Ok(resp) if resp.status().is_success() => Ok(()),
+                Ok(resp) => Err(format!("HTTP {}", resp.status())),
+                Err(e) => Err(e.to_string()),
+            },
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            eprintln!("Error: Cannot notify '{}': {}", url, e);
+            std::process::exit(2);
+        }
+    }
+    std::process::exit(if changed { 1 } else { 0 });
+}
+
+/// Prints the days of a range another configuration classifies differently and exits
+/// 
+/// # Arguments
+/// * `choliday` - Analyzer of the current configuration
+/// * `cli` - Command-line arguments selecting the output
+/// * `against` - Configuration to compare with
+/// * `shared` - Downloads shared with `choliday`
+/// * `from` - First day of the range
+/// * `to` - Last day of the range, included
+/// 
+/// # Note
+/// * Exits 0 when both configurations agree on every day, 1 otherwise
+async fn diff(
+    choliday: &Choliday,
+    cli: &cli::Cli,
+    against: &Conf,
+    shared: SharedFetches,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> ! {
+    let other = analyzer(cli, against)
+        .shared_fetches(shared)
+        .build()
+        .unwrap_or_else(|e| fail(cli, e));
+    let result = choliday.diff(&other, from, to).await;
+    if cli.verbose() {
+        eprintln!("{}", choliday.source_table());
+        eprintln!("{}", other.source_table());
+    }
+    let differences = match result {
+        Ok(differences) => differences,
+        Err(e) => fail(cli, e),
+    };
+    let differ = !differences.is_empty();
+    if cli.json() {
+        match serde_json::to_string_pretty(&Versioned::new(DiffList::new(from.min(to), from.max(to), differences))) {
+            Ok(json) => println!("{}", json),
+            Err(e) => fail(cli, e),
+        }
+    } else {
+        let classification = |decision: &DayDecision| match decision.rest_kind() {
+            Some(rest_kind) => format!("{} ({})", decision.day_type(), rest_kind),
+            None => decision.day_type().to_string(),
+        };
+        for day in &differences {
+            println!("{}: {} -> {}", day.date, classification(&day.current), classification(&day.against));
+            println!("  current: {}", day.current.source());
+            println!("  against: {}", day.against.source());
+        }
+    }
+    std::process::exit(if differ { 1 } else { 0 });
+}
+
+/// Prints an iCalendar feed of the predicted rest days of a range and exits
+/// 
+/// # Arguments
+/// * `choliday` - Analyzer to classify the days with
+/// * `cli` - Command-line arguments selecting the output
+/// * `from` - First day of the range
+/// * `to` - Last day of the range, included
+/// * `work` - Whether to list makeup work days too
+async fn export(choliday: &Choliday, cli: &cli::Cli, from: NaiveDate, to: NaiveDate, work: bool) -> ! {
+    if cli.strict_data() {
+        let days = choliday.days(from.min(to), from.max(to)).await.unwrap_or_else(|e| fail(cli, e));
+        check_data(cli, &days.map(|(_, decision)| decision).collect::<Vec<_>>());
+    }
+    let result = choliday.feed(from, to, work).await;
+    if cli.verbose() {
+        eprintln!("{}", choliday.source_table());
+    }
+    match result {
+        Ok(feed) => {
+            print!("{}", feed.body());
+            std::process::exit(0);
+        },
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(2);
+        },
+    }
+}
+
+/// Prints the decision and provenance of every day of a range as JSON or CSV and exits
+/// 
+/// # Arguments
+/// * `choliday` - Analyzer to classify the days with
+/// * `cli` - Command-line arguments selecting JSON, CSV otherwise
+/// * `from` - First day of the range
+/// * `to` - Last day of the range, included
+async fn export_days(choliday: &Choliday, cli: &cli::Cli, from: NaiveDate, to: NaiveDate) -> ! {
+    let result = choliday.days(from, to).await;
+    if cli.verbose() {
+        eprintln!("{}", choliday.source_table());
+    }
+    let days = DayList::new(from, to, result.unwrap_or_else(|e| fail(cli, e)).map(|(_, decision)| decision).collect());
+    check_data(cli, &days.days);
+    if cli.json() {
+        match serde_json::to_string_pretty(&Versioned::new(&days)) {
+            Ok(json) => println!("{}", json),
+            Err(e) => fail(cli, e),
+        }
+    } else {
+        print!("{}", days.to_csv());
+    }
+    std::process::exit(0);
+}
+
+/// Sends the classification of the target date as a notification and exits
+/// 
+/// # Arguments
+/// * `choliday` - Analyzer holding the target date
+/// * `cli` - Command-line arguments
+/// * `ntfy` - ntfy topic URL, if any
+/// * `title` - Template of the title and email subject
+/// * `message` - Template of the text
+/// * `priority` - ntfy priority, if any
+/// * `email` - SMTP server URL, sender and recipients, if an email is to be sent
+/// 
+/// # Note
+/// * The text sent is printed too; exits 0 once every notification was
+///   sent, 2 when the date could not be classified or a transport failed
+async fn send(
+    choliday: &Choliday,
+    cli: &cli::Cli,
+    ntfy: Option<&str>,
+    title: &str,
+    message: &str,
+    priority: Option<&str>,
+    email: Option<(&str, Option<&str>, &[String])>,
+) -> ! {
+    let result = choliday.evaluate().await;
+    if cli.verbose() {
+        eprintln!("{}", choliday.source_table());
+    }
+    let decision = result.unwrap_or_else(|e| fail(cli, e));
+    let (title, message) = match render_template(title, &decision).and_then(|title| {
+        render_template(message, &decision).map(|message| (title, message))
+    }) {
+        Ok(rendered) => rendered,
+        Err(e) => fail(cli, e),
+    };
+    println!("{}", message);
+
+    let timeout = cli.conf().http().timeout();
+    let mut failed = false;
+    if let Some(url) = ntfy
+        && let Err(e) = notify::ntfy(url, &title, priority, &message, timeout).await
+    {
+        eprintln!("Error: Cannot publish to '{}': {}", url, e);
+        failed = true;
+    }
+    if let Some((url, from, to)) = email {
+        let from = from.unwrap_or(&to[0]);
+        if let Err(e) = notify::email(url, from, to, &title, &message, timeout).await {
+            eprintln!("Error: Cannot send the email through '{}': {}", url, e);
+            failed = true;
+        }
+    }
+    std::process::exit(if failed { 2 } else { 0 });
+}
+
+/// Prints the n-th work day of a month and exits
+/// 
+/// # Arguments
+/// * `choliday` - Analyzer to classify the days with
+/// * `cli` - Command-line arguments selecting the output
+/// * `month` - First day of the month
+/// * `n` - Position of the work day, 1 for the first
+/// * `from_end` - Whether to count from the end of the month
+async fn nth(choliday: &Choliday, cli: &cli::Cli, month: NaiveDate, n: u32, from_end: bool) -> ! {
+    let result = if from_end {
+        choliday.nth_last_work_day_of_month(month.year(), month.month(), n).await
+    } else {
+        choliday.nth_work_day_of_month(month.year(), month.month(), n).await
+    };
+    if cli.verbose() {
+        eprintln!("{}", choliday.source_table());
+    }
+    match result {
+        Ok(Some(date)) => {
+            println!("{}", date);
+            std::process::exit(0);
+        },
+        Ok(None) => {
+            eprintln!("Error: {} has fewer than {} work days", month.format("%Y-%m"), n);
+            std::process::exit(2);
+        },
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(2);
+        },
+    }
+}
+
+/// Classifies every day of a year into a file for `--use-precomputed` and exits
+/// 
+/// # Arguments
+/// * `choliday` - Analyzer to classify the days with
+/// * `cli` - Command-line arguments holding the settings the file is valid for
+/// * `year` - Year to classify
+/// * `out` - File to write
+async fn precompute(choliday: &Choliday, cli: &cli::Cli, year: i32, out: &Path) -> ! {
+    let result = YearBitmap::compute(choliday, cli.conf(), year, &cli.settings()).await;
+    if cli.verbose() {
+        eprintln!("{}", choliday.source_table());
+    }
+    match result.and_then(|bitmap| bitmap.write(out)) {
+        Ok(()) => std::process::exit(0),
+        Err(e) => fail(cli, e),
+    }
+}
+
+/// Answers the workday check from a precomputed year and exits, if it can
+/// 
+/// # Arguments
+/// * `cli` - Command-line arguments holding the target date and settings
+/// * `path` - File written by `precompute`
+/// 
+/// # Note
+/// * Returns to check the calendars when the file does not cover the target
+///   time, is out of date, or holds no plain work or rest answer for the day
+fn precomputed(cli: &cli::Cli, path: &Path) {
+    let target = cli.date();
+    if target.time() != NaiveTime::from_hms_opt(23, 59, 59).unwrap_or_default() {
+        return;
+    }
+    let bitmap = YearBitmap::read(path).and_then(|bitmap| {
+        bitmap.check(cli.conf(), &cli.settings()).map(|_| bitmap)
+    });
+    let bitmap = match bitmap {
+        Ok(bitmap) => bitmap,
+        Err(e) => {
+            log::warn!("Ignoring precomputed days in '{}': {}", path.display(), e);
+            return;
+        },
+    };
+    match bitmap.get(target.date()) {
+        Some(DayClass::Work) => {
+            println!("true");
+            std::process::exit(0);
+        },
+        Some(DayClass::Rest) => {
+            println!("false");
+            std::process::exit(1);
+        },
+        _ => {},
+    }
+}
+
+/// Prints whether the target time is within working hours and exits
+/// 
+/// # Arguments
+/// * `choliday` - Analyzer holding the target time
+/// * `cli` - Command-line arguments selecting the output
+async fn working_hours(choliday: &Choliday, cli: &cli::Cli) -> ! {
+    let result = choliday.is_working_hours().await;
+    if cli.verbose() {
+        eprintln!("{}", choliday.source_table());
+    }
+    match result {
+        Ok(within) => {
+            println!("{}", within);
+            std::process::exit(if within { 0 } else { 1 });
+        },
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(2);
+        },
+    }
+}
+
+/// Prints an error, on standard output as JSON too with `--json`, and exits with code 2
+/// 
+/// # Arguments
+/// * `cli` - Command-line arguments selecting the output
+/// * `error` - Error message
+fn fail(cli: &cli::Cli, error: impl std::fmt::Display) -> ! {
+    eprintln!("Error: {}", error);
+    if cli.json() && let Ok(json) = serde_json::to_string_pretty(&Versioned::new(ErrorReport::new(error))) {
+        println!("{}", json);
+    }
+    std::process::exit(2);
+}
+
+/// Fails under `--strict-data` when calendar events contradicted each other
+/// on any of the decided days
+/// 
+/// # Arguments
+/// * `cli` - Command-line arguments
+/// * `decisions` - Decisions about to be printed
+fn check_data<'a>(cli: &cli::Cli, decisions: impl IntoIterator<Item = &'a DayDecision>) {
+    if !cli.strict_data() {
+        return;
+    }
+    let mut contradictions = decisions.into_iter().flat_map(DayDecision::contradictions);
+    if let Some(contradiction) = contradictions.next() {
+        let more = contradictions.count();
+        match more {
+            0 => fail(cli, contradiction),
+            _ => fail(cli, format!("{} (and {} more days)", contradiction, more)),
+        }
+    }
+}
+
+/// Removes all cached calendars and exits
+/// 
+/// # Arguments
+/// * `conf` - Configuration naming the cache directory
+fn clear_cache(conf: &Conf) -> ! {
+    let Some(dir) = Cache::dir_for(conf) else {
+        eprintln!("Error: Cannot determine the cache directory");
+        std::process::exit(2);
+    };
+    match Cache::clear(&dir) {
+        Ok(removed) => {
+            println!("Removed {} cached files from '{}'", removed, dir.display());
+            std::process::exit(0);
+        },
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(2);
+        },
+    }
+}
+
+/// Prints every cached calendar and exits
+/// 
+/// # Arguments
+/// * `conf` - Configuration naming the cache directory and backend
+fn list_cache(conf: &Conf) -> ! {
+    let Some(cache) = Cache::open(conf) else {
+        eprintln!("Error: Cannot determine the cache directory");
+        std::process::exit(2);
+    };
+    for entry in cache.entries() {
+        let events = entry.events().map_or_else(|| "-".to_string(), |events| events.to_string());
+        let fresh = entry.fresh_for().map_or_else(|| "stale".to_string(), |left| format!("fresh for {}", format_age(left)));
+        println!(
+            "{}\tHTTP {}\tfetched {} ago\t{}\t{} events",
+            redact_url(entry.url()), entry.status(), format_age(entry.age()), fresh, events
+        );
+    }
+    std::process::exit(0);
+}
@@ -0,0 +1,153 @@
+//! Nager.Date public holiday API, read as iCalendar properties.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+
+use std::ops::RangeInclusive;
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use ical::property::Property;
+use log::{debug, warn};
+use serde::Deserialize;
+
+use crate::{conf::Nager, ical::{Ical, Window}};
+
+/// Margin around the queried instants whose years are fetched as well,
+/// so a query near New Year in any timezone sees both years
+const YEAR_MARGIN_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+/// Holiday types that are days off
+const REST_TYPES: &[&str] = &["Public", "Bank"];
+
+/// A holiday as returned by the `PublicHolidays` endpoint
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicHoliday {
+    /// Date as `YYYY-MM-DD`
+    date: String,
+    /// Name in the country's language
+    local_name: String,
+    /// Name in English
+    name: String,
+    /// Whether the holiday applies to the whole country
+    #[serde(default = "nationwide")]
+    global: bool,
+    /// Subdivisions the holiday applies to when not global
+    counties: Option<Vec<String>>,
+    /// Kinds of the holiday, such as `Public` or `School`
+    types: Option<Vec<String>>,
+}
+
+/// Holidays without a `global` field apply to the whole country
+fn nationwide() -> bool {
+    true
+}
+
+/// Returns the API URL listing the public holidays of a year
+pub fn url(nager: &Nager, year: i32) -> String {
+    format!("{}/api/v3/PublicHolidays/{}/{}", nager.api(), year, nager.country())
+}
+
+/// Returns the years whose holidays a query needs
+/// 
+/// # Arguments
+/// * `filter` - Queried instants, if any
+/// 
+/// # Returns
+/// * The years from one day before the first queried instant to one day
+///   after the last, so usually one year for a single date and two around New Year
+/// * Without a filter, the previous, current and next year
+pub fn years(filter: Option<Window>) -> RangeInclusive<i32> {
+    let year = |ts: i64| DateTime::<Utc>::from_timestamp_millis(ts).unwrap_or_else(Utc::now).year();
+    match filter {
+        Some(window) => year(window.start.saturating_sub(YEAR_MARGIN_MILLIS))..=year(window.end.saturating_add(YEAR_MARGIN_MILLIS)),
+        None => {
+            let current = Utc::now().year();
+            current - 1..=current + 1
+        },
+    }
+}
+
+/// Converts a `PublicHolidays` response into all-day rest events
+/// 
+/// # Arguments
+/// * `body` - JSON response, already decoded to UTF-8
+/// * `nager` - Source settings selecting the subdivision
+/// * `explicit_property` - Property carrying the rest classification
+/// 
+/// # Returns
+/// * `Ok(Vec<Property>)` - One VCALENDAR holding a VEVENT per holiday
+/// * `Err(String)` - The response is not a list of holidays
+/// 
+/// # Note
+/// * Regional holidays are kept only when their `counties` name the
+///   configured subdivision; without a subdivision only nationwide ones are kept
+/// * Only `Public` and `Bank` holidays are kept, e.g. school holidays are not days off
+pub fn properties(body: &[u8], nager: &Nager, explicit_property: &str) -> Result<Vec<Property>, String> {
+    let holidays: Vec<PublicHoliday> =
+        serde_json::from_slice(body).map_err(|e| format!("Invalid Nager.Date response: {}", e))?;
+    let subdivision = nager.subdivision();
+
+    let mut props = vec![property("BEGIN", "VCALENDAR")];
+    for holiday in holidays {
+        let applies = holiday.global
+            || subdivision.as_deref().is_some_and(|sub| {
+                holiday.counties.iter().flatten().any(|county| county.eq_ignore_ascii_case(sub))
+            });
+        let rest = holiday
+            .types
+            .as_ref()
+            .is_none_or(|types| types.iter().any(|t| REST_TYPES.contains(&t.as_str())));
+        if !applies || !rest {
+            debug!("Skipping holiday '{}' on {}, not a day off here", holiday.name, holiday.date);
+            continue;
+        }
+
+        let Ok(date) = NaiveDate::parse_from_str(&holiday.date, "%Y-%m-%d") else {
+            warn!("Skipping holiday '{}' with unreadable date '{}'", holiday.name, holiday.date);
+            continue;
+        };
+
+        props.push(property("BEGIN", "VEVENT"));
+        props.push(property("SUMMARY", &Ical::escape_text(&holiday.local_name)));
+        props.push(property("DESCRIPTION", &Ical::escape_text(&holiday.name)));
+        props.push(Property {
+            name: "DTSTART".to_string(),
+            params: Some(vec![("VALUE".to_string(), vec!["DATE".to_string()])]),
+            value: Some(date.format("%Y%m%d").to_string()),
+        });
+        props.push(property(explicit_property, "REST"));
+        props.push(property("END", "VEVENT"));
+    }
+    props.push(property("END", "VCALENDAR"));
+    Ok(props)
+}
+
+/// Builds a property without parameters
+fn property(name: &str, value: &str) -> Property {
+    Property {
+        name: name.to_string(),
+        params: None,
+        value: Some(value.to_string()),
+    }
+}
@@ -0,0 +1,120 @@
+//! Push and email notifications of the classification of the target date.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use std::{env, time::Duration};
+
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+
+/// Environment variable holding the ntfy access token, if the topic needs one
+pub const NTFY_TOKEN_ENV: &str = "CHOLIDAY_NTFY_TOKEN";
+/// Environment variable holding the SMTP user name
+pub const SMTP_USER_ENV: &str = "CHOLIDAY_SMTP_USER";
+/// Environment variable holding the SMTP password
+pub const SMTP_PASSWORD_ENV: &str = "CHOLIDAY_SMTP_PASSWORD";
+
+/// Publishes a message to an ntfy topic
+/// 
+/// # Arguments
+/// * `url` - Topic URL, e.g. `https://ntfy.sh/mytopic`
+/// * `title` - Title of the notification
+/// * `priority` - ntfy priority, `1` to `5` or `min` to `urgent`, if any
+/// * `message` - Body of the notification
+/// * `timeout` - Time the request may take
+/// 
+/// # Returns
+/// * `Err(String)` - The request failed or ntfy refused the message
+/// 
+/// # Note
+/// * An access token in `CHOLIDAY_NTFY_TOKEN` is sent as a bearer token
+pub async fn ntfy(url: &str, title: &str, priority: Option<&str>, message: &str, timeout: Duration) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut request = client.post(url).header("Title", title).body(message.to_string());
+    if let Some(priority) = priority {
+        request = request.header("Priority", priority);
+    }
+    if let Ok(token) = env::var(NTFY_TOKEN_ENV) {
+        request = request.bearer_auth(token);
+    }
+    match request.send().await {
+        Ok(resp) if resp.status().is_success() => Ok(()),
+        Ok(resp) => Err(format!("HTTP {}", resp.status())),
+        Err(e) => Err(format!("Request failed: {}", e)),
+    }
+}
+
+/// Sends a plain-text email through an SMTP server
+/// 
+/// # Arguments
+/// * `smtp_url` - Server URL, e.g. `smtps://smtp.example.com` or
+///   `smtp://smtp.example.com:587?tls=required`
+/// * `from` - Sender address
+/// * `to` - Recipient addresses
+/// * `subject` - Subject of the email
+/// * `body` - Text of the email
+/// * `timeout` - Time each SMTP command may take
+/// 
+/// # Returns
+/// * `Err(String)` - An address or the URL is invalid, or the server refused the email
+/// 
+/// # Note
+/// * The user name and password in `CHOLIDAY_SMTP_USER` and
+///   `CHOLIDAY_SMTP_PASSWORD` take precedence over those in the URL
+pub async fn email(
+    smtp_url: &str,
+    from: &str,
+    to: &[String],
+    subject: &str,
+    body: &str,
+    timeout: Duration,
+) -> Result<(), String> {
+    let mailbox = |address: &str| address.parse::<Mailbox>().map_err(|e| format!("Invalid address '{}': {}", address, e));
+    let mut message = Message::builder()
+        .from(mailbox(from)?)
+        .subject(subject)
+        .header(ContentType::TEXT_PLAIN);
+    for address in to {
+        message = message.to(mailbox(address)?);
+    }
+    let message = message.body(body.to_string()).map_err(|e| format!("Cannot build the email: {}", e))?;
+
+    let mut transport = AsyncSmtpTransport::<Tokio1Executor>::from_url(smtp_url)
+        .map_err(|e| format!("Invalid SMTP URL '{}': {}", smtp_url, e))?
+        .timeout(Some(timeout));
+    if let (Ok(user), Ok(password)) = (env::var(SMTP_USER_ENV), env::var(SMTP_PASSWORD_ENV)) {
+        transport = transport.credentials(Credentials::new(user, password));
+    }
+    transport
+        .build()
+        .send(message)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,401 @@
+//! Versioned JSON output of the command line and its JSON Schema.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone};
+use chrono_tz::{OffsetName, Tz};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::{
+    changes::Change,
+    decision::{Bridge, DayDecision, DayDiff, DecisionTrace, RestBlock, TracedEvent, Transition, WorkDayCount},
+};
+
+/// Version of the JSON output shape, found as `schema_version` in every payload
+/// 
+/// # Note
+/// * New fields and new names of day types, rest kinds, source kinds and trace
+///   outcomes are added without a new version; renaming or removing anything
+///   bumps it
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// JSON payload tagged with [`SCHEMA_VERSION`]
+/// 
+/// The fields of the payload are flattened next to `schema_version`, so a
+/// [`DayDecision`](crate::DayDecision) prints as
+/// `{"schema_version": 1, "date": ..., "day_type": ..., ...}`
+/// 
+/// # Examples
+/// ```
+/// use choliday::{SCHEMA_VERSION, Versioned, WorkDayCount};
+/// 
+/// let json = serde_json::to_value(Versioned::new(WorkDayCount::default())).unwrap();
+/// assert_eq!(json["schema_version"], SCHEMA_VERSION);
+/// assert_eq!(json["work"], 0);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[schemars(inline)]
+pub struct Versioned<T> {
+    /// Version of the output shape
+    #[schemars(extend("const" = SCHEMA_VERSION))]
+    schema_version: u32,
+    /// Payload, a decision, a count, a trace or an error report
+    #[serde(flatten)]
+    payload: T,
+}
+
+impl<T> Versioned<T> {
+    /// Tags a payload with the current schema version
+    pub fn new(payload: T) -> Self {
+        Versioned { schema_version: SCHEMA_VERSION, payload }
+    }
+
+    /// Returns the schema version the payload was written with
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    /// Returns the payload
+    pub fn payload(&self) -> &T {
+        &self.payload
+    }
+
+    /// Consumes the wrapper and returns the payload
+    pub fn into_payload(self) -> T {
+        self.payload
+    }
+}
+
+/// Upcoming transitions, as printed by `transitions --json`
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct TransitionList {
+    /// Date the search started from
+    pub from: NaiveDate,
+    /// Number of transitions asked for
+    pub requested: usize,
+    /// Transitions found within the horizon, in date order
+    pub transitions: Vec<Transition>,
+}
+
+impl TransitionList {
+    /// Creates a list of transitions found from a date
+    pub fn new(from: NaiveDate, requested: usize, transitions: Vec<Transition>) -> Self {
+        TransitionList { from, requested, transitions }
+    }
+}
+
+/// Bridge days of a year, as printed by `bridges --json`
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct BridgeList {
+    /// Year searched
+    pub year: i32,
+    /// Longest run of work days reported
+    pub max_gap: u32,
+    /// Bridges found, in date order
+    pub bridges: Vec<Bridge>,
+}
+
+impl BridgeList {
+    /// Creates a list of the bridges found in a year
+    pub fn new(year: i32, max_gap: u32, bridges: Vec<Bridge>) -> Self {
+        BridgeList { year, max_gap, bridges }
+    }
+}
+
+/// Blocks of rest days of a range, as printed by `long-weekends --json`
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct RestBlockList {
+    /// First day searched
+    pub from: NaiveDate,
+    /// Last day searched, included
+    pub to: NaiveDate,
+    /// Shortest block listed
+    pub min_days: u32,
+    /// Blocks found, in the order printed
+    pub blocks: Vec<RestBlock>,
+}
+
+impl RestBlockList {
+    /// Creates a list of the rest blocks found in a range
+    pub fn new(from: NaiveDate, to: NaiveDate, min_days: u32, blocks: Vec<RestBlock>) -> Self {
+        RestBlockList { from, to, min_days, blocks }
+    }
+}
+
+/// Decision of every day of a range, as printed by `export --json` and `export --csv`
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct DayList {
+    /// First day exported
+    pub from: NaiveDate,
+    /// Last day exported, included
+    pub to: NaiveDate,
+    /// Decisions in date order, each with its provenance
+    pub days: Vec<DayDecision>,
+}
+
+/// Columns of [`DayList::to_csv`]
+const CSV_COLUMNS: [&str; 14] = [
+    "date",
+    "day_type",
+    "rest_kind",
+    "decided_by",
+    "source",
+    "uid",
+    "summary",
+    "pattern",
+    "field",
+    "rule",
+    "conflict",
+    "explicit_override",
+    "fallback",
+    "warnings",
+];
+
+impl DayList {
+    /// Creates a list of the decisions of a range
+    pub fn new(from: NaiveDate, to: NaiveDate, days: Vec<DayDecision>) -> Self {
+        DayList { from, to, days }
+    }
+
+    /// Formats the decisions as CSV (RFC 4180), one row per day after a header
+    /// 
+    /// # Returns
+    /// * Rows with the date, the classification, what decided it and the
+    ///   fields of its [`Provenance`](crate::Provenance); warnings are joined by `; `
+    /// 
+    /// # Examples
+    /// ```
+    /// use choliday::{DayDecision, DayList};
+    /// 
+    /// let decision: DayDecision = serde_json::from_str(r#"{"date": "2025-10-01T23:59:59", "day_type": "rest_day",
+    ///     "rest_kind": "public_holiday", "source": {"kind": "calendar_event", "summary": "National Day, observed",
+    ///     "source": "https://***@example.com/cal.ics", "pattern": "National"}, "warnings": [],
+    ///     "provenance": {"source": "https://***@example.com/cal.ics", "uid": "nd-2025", "summary": "National Day, observed",
+    ///     "pattern": "National", "field": "summary", "rule": "priority RestOverWork"}}"#).unwrap();
+    /// let date = decision.date().date();
+    /// let csv = DayList::new(date, date, vec![decision]).to_csv();
+    /// assert_eq!(csv.lines().nth(1).unwrap(), "2025-10-01,rest_day,public_holiday,\
+    ///     \"event 'National Day, observed' from 'https://***@example.com/cal.ics' matching 'National'\",\
+    ///     https://***@example.com/cal.ics,nd-2025,\"National Day, observed\",National,summary,\
+    ///     priority RestOverWork,false,false,false,");
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut csv = CSV_COLUMNS.join(",") + "\r\n";
+        for day in &self.days {
+            let provenance = day.provenance().cloned().unwrap_or_default();
+            let row = [
+                day.date().date().to_string(),
+                day.day_type().to_string(),
+                day.rest_kind().map(|kind| kind.as_str().to_string()).unwrap_or_default(),
+                day.source().to_string(),
+                provenance.source.unwrap_or_default(),
+                provenance.uid.unwrap_or_default(),
+                provenance.summary.unwrap_or_default(),
+                provenance.pattern.unwrap_or_default(),
+                provenance.field.unwrap_or_default(),
+                provenance.rule,
+                provenance.conflict.to_string(),
+                provenance.explicit_override.to_string(),
+                provenance.fallback.to_string(),
+                day.warnings().join("; "),
+            ];
+            csv += &row.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(",");
+            csv += "\r\n";
+        }
+        csv
+    }
+}
+
+/// Quotes a CSV field when it holds a comma, a quote or a line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Days two configurations classify differently, as printed by `diff --json`
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct DiffList {
+    /// First day compared
+    pub from: NaiveDate,
+    /// Last day compared, included
+    pub to: NaiveDate,
+    /// Days classified differently, in date order
+    pub differences: Vec<DayDiff>,
+}
+
+impl DiffList {
+    /// Creates a list of the differences found in a date range
+    pub fn new(from: NaiveDate, to: NaiveDate, differences: Vec<DayDiff>) -> Self {
+        DiffList { from, to, differences }
+    }
+}
+
+/// Revisions of remote calendars, as printed by `changes --json` and posted to `--notify-url`
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct ChangeList {
+    /// First day compared
+    pub from: NaiveDate,
+    /// Last day compared, included
+    pub to: NaiveDate,
+    /// Days changed, by source, then by date
+    pub changes: Vec<Change>,
+}
+
+impl ChangeList {
+    /// Creates a list of the changes found in a date range
+    pub fn new(from: NaiveDate, to: NaiveDate, changes: Vec<Change>) -> Self {
+        ChangeList { from, to, changes }
+    }
+}
+
+/// Error printed on standard output by `--json` when no answer can be given
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[non_exhaustive]
+pub struct ErrorReport {
+    /// Error message, as printed after `Error:` on standard error
+    pub error: String,
+}
+
+impl ErrorReport {
+    /// Creates an error report from an error message
+    pub fn new(error: impl ToString) -> Self {
+        ErrorReport { error: error.to_string() }
+    }
+}
+
+/// Formats the span of a traced event for `list` and `--explain`
+/// 
+/// # Returns
+/// * Dates alone for all-day events, e.g. `2025-10-01 - 2025-10-02`
+/// * Wall-clock times in the target zone followed by the zone abbreviation
+///   for timed events, e.g. `2025-10-01 09:00 - 2025-10-01 18:00 CST`; each
+///   end gets its own when a clock change falls within the event
+/// * The numeric offset stands in for the abbreviation in the system zone
+///   and in zones without one
+pub(crate) fn event_span(event: &TracedEvent) -> String {
+    let (Some(start), Some(end)) = (event.start_time, event.end_time) else {
+        return if event.all_day {
+            format!("{} - {}", event.start.date(), event.end.date())
+        } else {
+            format!("{} - {}", event.start.format("%Y-%m-%d %H:%M"), event.end.format("%Y-%m-%d %H:%M"))
+        };
+    };
+    let tz: Option<Tz> = event.zone.as_deref().and_then(|name| name.parse().ok());
+    let abbreviation = |time: DateTime<FixedOffset>| {
+        tz.and_then(|tz| {
+            tz.offset_from_utc_datetime(&time.naive_utc())
+                .abbreviation()
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| time.format("%:z").to_string())
+    };
+    let (start_zone, end_zone) = (abbreviation(start), abbreviation(end));
+    if start_zone == end_zone {
+        format!("{} - {} {}", start.format("%Y-%m-%d %H:%M"), end.format("%Y-%m-%d %H:%M"), end_zone)
+    } else {
+        format!(
+            "{} {} - {} {}",
+            start.format("%Y-%m-%d %H:%M"),
+            start_zone,
+            end.format("%Y-%m-%d %H:%M"),
+            end_zone
+        )
+    }
+}
+
+/// Every JSON payload printed by the command line
+#[derive(JsonSchema)]
+#[serde(untagged)]
+#[schemars(title = "choliday JSON output")]
+#[allow(dead_code)]
+enum Output {
+    /// Single-date decision, printed by --json
+    Decision(Versioned<DayDecision>),
+    /// Work and rest days of a range, printed by count --json
+    Count(Versioned<WorkDayCount>),
+    /// Events considered for a date, printed by list --json
+    Trace(Versioned<DecisionTrace>),
+    /// Next changes of classification, printed by transitions --json
+    Transitions(Versioned<TransitionList>),
+    /// Days two configurations classify differently, printed by diff --json
+    Diff(Versioned<DiffList>),
+    /// Days revisions of remote calendars changed, printed by changes --json
+    Changes(Versioned<ChangeList>),
+    /// Work days between rest days, printed by bridges --json
+    Bridges(Versioned<BridgeList>),
+    /// Blocks of rest days, printed by long-weekends --json
+    RestBlocks(Versioned<RestBlockList>),
+    /// Decision of every day of a range, printed by export --json
+    Days(Versioned<DayList>),
+    /// Error printed by --json instead of an answer
+    Error(Versioned<ErrorReport>),
+}
+
+/// Returns the JSON Schema of every JSON payload, as printed by `choliday schema`
+/// 
+/// # Returns
+/// * A JSON Schema (draft 2020-12) accepting any of the single-date decision
+///   (`--json`), the range count (`count --json`), the event trace
+///   (`list --json`), the upcoming transitions (`transitions --json`), the
+///   differences between two configurations (`diff --json`), the revisions of
+///   remote calendars (`changes --json`), the bridge days (`bridges --json`),
+///   the blocks of rest days (`long-weekends --json`), the decisions of a
+///   range (`export --json`) and the error report
+/// 
+/// # Note
+/// * The schema is derived from the structs and enums the payloads are
+///   serialized from, so it follows any change to their serde representation
+/// 
+/// # Examples
+/// ```
+/// use choliday::{DayDecision, SCHEMA_VERSION, Versioned, json_schema};
+/// 
+/// // A decision as printed by version 1 keeps deserializing and keeps every required field
+/// let v1 = r#"{"schema_version": 1, "date": "2025-01-06T23:59:59", "day_type": "rest_day",
+///     "rest_kind": "public_holiday", "source": {"kind": "calendar_event",
+///     "summary": "New Year", "source": "holidays.ics", "pattern": "Year"}, "warnings": []}"#;
+/// let decision: Versioned<DayDecision> = serde_json::from_str(v1).unwrap();
+/// assert_eq!(decision.schema_version(), SCHEMA_VERSION);
+/// 
+/// let printed = serde_json::to_value(&decision).unwrap();
+/// let schema = json_schema();
+/// for field in schema["$defs"]["DayDecision"]["required"].as_array().unwrap() {
+///     assert!(printed.get(field.as_str().unwrap()).is_some(), "missing {}", field);
+/// }
+/// ```
+pub fn json_schema() -> Value {
+    let mut schema = schemars::schema_for!(Output).to_value();
+    schema["$id"] = json!(format!("https://github.com/66f94eae/choliday/schema/v{}.json", SCHEMA_VERSION));
+    schema
+}
@@ -0,0 +1,368 @@
+//! Precomputed classification of every day of a year, answering without the sources.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use std::{
+    fs,
+    path::Path,
+    time::UNIX_EPOCH,
+};
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::{
+    cache::{Cache, fnv1a},
+    choliday::Choliday,
+    conf::{Conf, Location},
+    source::{self, resolve_path},
+};
+
+/// Magic bytes opening a year bitmap file
+const MAGIC: &[u8; 4] = b"CHYB";
+/// Version of the file layout, bumped on incompatible changes
+const VERSION: u8 = 1;
+/// Extension of files being written before they are renamed into place
+const TMP_EXT: &str = "tmp";
+
+/// Classification of a day stored in a [`YearBitmap`], two bits each
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DayClass {
+    /// Work day
+    Work = 0,
+    /// Rest day
+    Rest = 1,
+    /// Not enough calendar data to classify the day
+    Unknown = 2,
+    /// Decided by an event matching both work and rest under `on_conflict = "error"`
+    Conflict = 3,
+}
+
+impl DayClass {
+    /// Decodes the two bits of a day
+    fn from_bits(bits: u8) -> Self {
+        match bits & 0b11 {
+            0 => DayClass::Work,
+            1 => DayClass::Rest,
+            2 => DayClass::Unknown,
+            _ => DayClass::Conflict,
+        }
+    }
+}
+
+/// Classification of every day of a year, with what it was computed from
+/// 
+/// # Note
+/// * The file holds a header with the year, a hash of the configuration files
+///   and the settings given, and a validator per calendar source (the `ETag`
+///   or `Last-Modified` of cached remote calendars, the modification time of
+///   local files), followed by two bits per day
+/// * [`YearBitmap::check`] compares the header with the configuration and
+///   the sources on disk, without parsing calendars or using the network
+/// 
+/// # Examples
+/// ```
+/// use chrono::NaiveDate;
+/// use choliday::{Choliday, Conf, DayClass, YearBitmap};
+/// 
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), String> {
+/// let temp = tempfile::tempdir().map_err(|e| e.to_string())?;
+/// let dir = temp.path();
+/// let calendar = dir.join("holidays.ics");
+/// std::fs::write(
+///     &calendar,
+///     "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20261001\r\nSUMMARY:Holiday\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+/// ).map_err(|e| e.to_string())?;
+/// let path = dir.join("config.toml");
+/// let text = "[calendar]\nsource = [\"holidays.ics\"]\n[predict]\nwork = []\nrest = [\"Holiday\"]\npriority = \"RestOverWork\"\n";
+/// std::fs::write(&path, text).map_err(|e| e.to_string())?;
+/// 
+/// let conf = Conf::load(&path)?;
+/// let choliday = Choliday::builder(conf.clone()).date(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()).build()?;
+/// let bitmap = YearBitmap::compute(&choliday, &conf, 2026, "").await?;
+/// 
+/// // Written out and read back, the bitmap answers the same
+/// let file = dir.join("year.bin");
+/// bitmap.write(&file)?;
+/// let read = YearBitmap::read(&file)?;
+/// assert_eq!(read, bitmap);
+/// assert_eq!(read.get(NaiveDate::from_ymd_opt(2026, 10, 1).unwrap()), Some(DayClass::Rest));
+/// assert_eq!(read.get(NaiveDate::from_ymd_opt(2026, 10, 2).unwrap()), Some(DayClass::Work));
+/// assert_eq!(read.get(NaiveDate::from_ymd_opt(2027, 1, 1).unwrap()), None);
+/// assert!(read.check(&conf, "").is_ok());
+/// 
+/// // Other settings, an edited calendar or configuration make it stale
+/// assert!(read.check(&conf, "strict").is_err());
+/// let later = std::time::SystemTime::now() + std::time::Duration::from_secs(60);
+/// std::fs::File::options().append(true).open(&calendar).and_then(|f| f.set_modified(later)).map_err(|e| e.to_string())?;
+/// assert!(read.check(&conf, "").is_err());
+/// let bitmap = YearBitmap::compute(&choliday, &conf, 2026, "").await?;
+/// assert!(bitmap.check(&conf, "").is_ok());
+/// std::fs::write(&path, text.replace("RestOverWork", "WorkOverRest")).map_err(|e| e.to_string())?;
+/// assert!(bitmap.check(&conf, "").is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct YearBitmap {
+    /// Year classified
+    year: i32,
+    /// Hash of the configuration files and the settings
+    conf_hash: u64,
+    /// Validator of each configured source, empty when it has none
+    validators: Vec<String>,
+    /// Two bits per day from January 1st, four days per byte
+    days: Vec<u8>,
+}
+
+impl YearBitmap {
+    /// Classifies every day of a year
+    /// 
+    /// # Arguments
+    /// * `choliday` - Analyzer classifying the days, built from `conf`
+    /// * `conf` - Configuration loaded from a file with [`Conf::load`]
+    /// * `year` - Year to classify
+    /// * `settings` - Options given besides the configuration file that
+    ///   change decisions, e.g. strict mode; the bitmap is only valid for the same
+    /// 
+    /// # Returns
+    /// * `Err(String)` - The configuration was not loaded from a file, the year
+    ///   is out of range, or calendar sources failed under strict mode or `min_sources`
+    pub async fn compute(choliday: &Choliday, conf: &Conf, year: i32, settings: &str) -> Result<Self, String> {
+        let conf_hash = Self::conf_hash(conf, settings)?;
+        let (first, last) = NaiveDate::from_ymd_opt(year, 1, 1)
+            .zip(NaiveDate::from_ymd_opt(year, 12, 31))
+            .ok_or_else(|| format!("Year {} is out of range", year))?;
+        let mut days = vec![0u8; (last.ordinal() as usize).div_ceil(4)];
+        for (date, decision) in choliday.days(first, last).await? {
+            let class = if decision.is_unknown() {
+                DayClass::Unknown
+            } else if decision.is_unresolved() {
+                DayClass::Conflict
+            } else if decision.is_work_day() {
+                DayClass::Work
+            } else {
+                DayClass::Rest
+            };
+            let day = date.ordinal0() as usize;
+            days[day / 4] |= (class as u8) << (day % 4 * 2);
+        }
+        // Read after classifying, when the sources were fetched and cached
+        Ok(YearBitmap { year, conf_hash, validators: Self::validators(conf), days })
+    }
+
+    /// Returns the year classified
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Looks up the classification of a day
+    /// 
+    /// # Returns
+    /// * `None` if the day is not in the year classified
+    pub fn get(&self, date: NaiveDate) -> Option<DayClass> {
+        if date.year() != self.year {
+            return None;
+        }
+        let day = date.ordinal0() as usize;
+        self.days.get(day / 4).map(|byte| DayClass::from_bits(byte >> (day % 4 * 2)))
+    }
+
+    /// Checks whether the bitmap still answers for a configuration
+    /// 
+    /// # Arguments
+    /// * `conf` - Configuration loaded from a file with [`Conf::load`]
+    /// * `settings` - Settings given to [`YearBitmap::compute`] for the answers wanted
+    /// 
+    /// # Returns
+    /// * `Err(String)` naming what changed since the bitmap was computed: the
+    ///   configuration file or settings, or the validator of a source
+    /// 
+    /// # Note
+    /// * Remote calendars are only compared with their cached copy; changes
+    ///   upstream not fetched by another run since go unnoticed
+    pub fn check(&self, conf: &Conf, settings: &str) -> Result<(), String> {
+        if Self::conf_hash(conf, settings)? != self.conf_hash {
+            return Err("the configuration or settings changed".to_string());
+        }
+        let sources = conf.get_describe_calendar().unwrap_or(&[]);
+        let changed = Self::validators(conf)
+            .iter()
+            .zip(&self.validators)
+            .position(|(now, then)| now != then);
+        match changed {
+            Some(index) => Err(format!("calendar source '{}' changed", source::redact_url(sources[index].name()))),
+            None => Ok(()),
+        }
+    }
+
+    /// Encodes the bitmap in its file layout
+    /// 
+    /// # Returns
+    /// * Magic bytes, version, year, configuration hash, number of validators,
+    ///   each validator as a length-prefixed string, number of days, then the
+    ///   days; integers are little-endian
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(128 + self.days.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&self.year.to_le_bytes());
+        bytes.extend_from_slice(&self.conf_hash.to_le_bytes());
+        bytes.extend_from_slice(&(self.validators.len() as u16).to_le_bytes());
+        for validator in &self.validators {
+            bytes.extend_from_slice(&(validator.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(validator.as_bytes());
+        }
+        bytes.extend_from_slice(&(self.days.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(&self.days);
+        bytes
+    }
+
+    /// Decodes a bitmap from its file layout, see [`YearBitmap::to_bytes`]
+    /// 
+    /// # Returns
+    /// * `Err(String)` if the data is not a bitmap of this version or is truncated
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader(bytes);
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err("not a precomputed year file".to_string());
+        }
+        let version = reader.take(1)?[0];
+        if version != VERSION {
+            return Err(format!("unsupported version {}", version));
+        }
+        let year = i32::from_le_bytes(reader.array()?);
+        let conf_hash = u64::from_le_bytes(reader.array()?);
+        let count = u16::from_le_bytes(reader.array()?);
+        let validators = (0..count)
+            .map(|_| {
+                let len = u16::from_le_bytes(reader.array()?) as usize;
+                String::from_utf8(reader.take(len)?.to_vec()).map_err(|_| "validator is not UTF-8".to_string())
+            })
+            .collect::<Result<_, String>>()?;
+        let len = u16::from_le_bytes(reader.array()?) as usize;
+        let days = reader.take(len)?.to_vec();
+        if !reader.0.is_empty() {
+            return Err("trailing data".to_string());
+        }
+        Ok(YearBitmap { year, conf_hash, validators, days })
+    }
+
+    /// Reads a bitmap file
+    /// 
+    /// # Returns
+    /// * `Err(String)` if the file cannot be read or decoded
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|e| format!("Cannot read '{}': {}", path.display(), e))?;
+        Self::from_bytes(&bytes).map_err(|e| format!("Cannot read '{}': {}", path.display(), e))
+    }
+
+    /// Writes the bitmap to a file
+    /// 
+    /// # Note
+    /// * The file is written to a temporary name and renamed into place, so
+    ///   concurrent checks never read a partially written file
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let tmp = path.with_extension(TMP_EXT);
+        fs::write(&tmp, self.to_bytes())
+            .and_then(|_| fs::rename(&tmp, path))
+            .map_err(|e| format!("Cannot write '{}': {}", path.display(), e))
+    }
+
+    /// Hashes the configuration files together with the settings
+    /// 
+    /// # Returns
+    /// * `Err(String)` if the configuration was not loaded from a file or
+    ///   a file cannot be read
+    fn conf_hash(conf: &Conf, settings: &str) -> Result<u64, String> {
+        if conf.conf_files().is_empty() {
+            return Err("The configuration was not loaded from a file".to_string());
+        }
+        let mut data = Vec::new();
+        for path in conf.conf_files() {
+            data.extend(fs::read(path).map_err(|e| format!("Cannot read '{}': {}", path.display(), e))?);
+            data.push(0);
+        }
+        data.extend_from_slice(settings.as_bytes());
+        Ok(fnv1a(&data))
+    }
+
+    /// Looks up the validator of every configured source
+    /// 
+    /// # Returns
+    /// * One string per source in configuration order: the `ETag` or
+    ///   `Last-Modified` of the cached copy of remote calendars, the
+    ///   modification time of local files, empty when there is none
+    fn validators(conf: &Conf) -> Vec<String> {
+        let cache = Cache::open(conf);
+        let sources = conf.get_describe_calendar().unwrap_or(&[]);
+        sources
+            .iter()
+            .map(|source| {
+                let Location::Url(uri) = source.location() else {
+                    return String::new();
+                };
+                let uri = source::normalize(uri, conf.http());
+                if uri.starts_with("http") {
+                    let entry = cache.as_ref().and_then(|cache| cache.lookup(&uri));
+                    entry
+                        .and_then(|entry| {
+                            entry.etag().map(|etag| format!("etag:{}", etag)).or_else(|| {
+                                entry.last_modified().map(|modified| format!("last-modified:{}", modified))
+                            })
+                        })
+                        .unwrap_or_default()
+                } else {
+                    fs::metadata(resolve_path(&uri, conf.conf_dir()))
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                        .map(|modified| format!("mtime:{}", modified.as_nanos()))
+                        .unwrap_or_default()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Cursor over the bytes of a bitmap file
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    /// Takes the next `len` bytes
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if self.0.len() < len {
+            return Err("truncated".to_string());
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    /// Takes the next `N` bytes as an array
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        self.take(N).map(|bytes| bytes.try_into().unwrap_or([0; N]))
+    }
+}
@@ -0,0 +1,253 @@
+//! Resolution of days classified by several calendar events, pluggable for library users.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use chrono::{DateTime, Utc};
+
+use crate::{conf::Priority, ical::DayType};
+
+/// Decides a day from the calendar events classifying it
+/// 
+/// The built-in [`Priority`] rules implement this trait and are used unless
+/// [`CholidayBuilder::resolver`](crate::CholidayBuilder::resolver) installs
+/// another resolver.
+/// 
+/// # Examples
+/// "The HR calendar always wins, otherwise the majority, otherwise rest":
+/// ```
+/// use chrono::NaiveDate;
+/// use choliday::{Candidate, Choliday, ConflictResolver, Conf, DayType, Priority};
+/// 
+/// struct HrFirst;
+/// 
+/// impl ConflictResolver for HrFirst {
+///     fn resolve(&self, candidates: &[Candidate<'_>]) -> DayType {
+///         if let Some(hr) = candidates.iter().find(|c| c.source().is_some_and(|s| s.ends_with("hr.ics"))) {
+///             return if hr.is_work() { DayType::WorkDay } else { DayType::RestDay };
+///         }
+///         let work = candidates.iter().filter(|c| c.is_work()).count();
+///         let rest = candidates.iter().filter(|c| c.is_rest()).count();
+///         if work > rest { DayType::WorkDay } else { DayType::RestDay }
+///     }
+/// }
+/// 
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), String> {
+/// let event = |summary: &str| format!(
+///     "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20251011\r\nSUMMARY:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+///     summary,
+/// );
+/// let temp = tempfile::tempdir().map_err(|e| e.to_string())?;
+/// let dir = temp.path();
+/// std::fs::write(dir.join("hr.ics"), event("Makeup workday")).map_err(|e| e.to_string())?;
+/// std::fs::write(dir.join("public.ics"), event("Holiday")).map_err(|e| e.to_string())?;
+/// 
+/// let conf = Conf::builder(Priority::RestOverWork)
+///     .source(dir.join("public.ics").to_string_lossy())
+///     .source(dir.join("hr.ics").to_string_lossy())
+///     .work("Makeup workday")
+///     .rest("Holiday")
+///     .build()?;
+/// let choliday = Choliday::builder(conf)
+///     .date(NaiveDate::from_ymd_opt(2025, 10, 11).unwrap())
+///     .resolver(HrFirst)
+///     .build()?;
+/// // RestOverWork alone would make the Saturday a rest day
+/// assert!(choliday.is_work_day().await?);
+/// # Ok(())
+/// # }
+/// ```
+pub trait ConflictResolver: Send + Sync {
+    /// Decides the day type from the events classifying the day
+    /// 
+    /// # Arguments
+    /// * `candidates` - Events classifying the day, at least one, ordered by
+    ///   start, then by configured source order and summary
+    /// 
+    /// # Returns
+    /// * `WorkDay` or `RestDay`; any other day type leaves the day to the
+    ///   configured work days, as if no event classified it
+    /// 
+    /// # Note
+    /// * When some events carry an explicit classification, only those are candidates
+    fn resolve(&self, candidates: &[Candidate<'_>]) -> DayType;
+
+    /// Picks the event reported as deciding the day
+    /// 
+    /// # Arguments
+    /// * `candidates` - Events passed to `resolve`
+    /// * `day_type` - Day type returned by `resolve`
+    /// 
+    /// # Returns
+    /// * Index of the deciding event, by default the first event agreeing with
+    ///   `day_type`; `None` leaves the day to the configured work days
+    fn decisive(&self, candidates: &[Candidate<'_>], day_type: DayType) -> Option<usize> {
+        candidates.iter().position(|c| c.agrees(day_type))
+    }
+
+    /// Names the resolver in warnings about disagreeing events
+    fn describe(&self) -> String {
+        "a custom resolver".to_string()
+    }
+}
+
+/// Calendar event classifying a day, as seen by a [`ConflictResolver`]
+#[derive(Clone, Debug)]
+pub struct Candidate<'a> {
+    /// Summary of the event
+    pub(crate) summary: &'a str,
+    /// Calendar source the event was read from
+    pub(crate) source: Option<&'a str>,
+    /// Start of the event
+    pub(crate) start: DateTime<Utc>,
+    /// End of the event, excluded
+    pub(crate) end: DateTime<Utc>,
+    /// Whether the event is an all-day event
+    pub(crate) all_day: bool,
+    /// How the event alone classifies the day
+    pub(crate) day_type: DayType,
+    /// Whether the classification comes from the explicit classification property
+    pub(crate) explicit: bool,
+    /// LAST-MODIFIED of the event
+    pub(crate) last_modified: Option<DateTime<Utc>>,
+    /// DTSTAMP of the event
+    pub(crate) dtstamp: Option<DateTime<Utc>>,
+    /// SEQUENCE of the event
+    pub(crate) sequence: Option<i64>,
+}
+
+impl Candidate<'_> {
+    /// Returns the summary of the event
+    pub fn summary(&self) -> &str {
+        self.summary
+    }
+
+    /// Returns the calendar source the event was read from, as named in reports
+    pub fn source(&self) -> Option<&str> {
+        self.source
+    }
+
+    /// Returns the start of the event; all-day events start at midnight of the target zone
+    pub fn start(&self) -> DateTime<Utc> {
+        self.start
+    }
+
+    /// Returns the end of the event, excluded
+    pub fn end(&self) -> DateTime<Utc> {
+        self.end
+    }
+
+    /// Returns whether the event is an all-day event
+    pub fn is_all_day(&self) -> bool {
+        self.all_day
+    }
+
+    /// Returns how the event alone classifies the day: `WorkDay`, `RestDay`,
+    /// or `ConflictDay` when it matches both work and rest
+    pub fn day_type(&self) -> DayType {
+        self.day_type
+    }
+
+    /// Returns whether the event is classified by the explicit classification property
+    pub fn is_explicit(&self) -> bool {
+        self.explicit
+    }
+
+    /// Returns whether the event marks the day as a work day, alone or together with rest
+    pub fn is_work(&self) -> bool {
+        self.agrees(DayType::WorkDay)
+    }
+
+    /// Returns whether the event marks the day as a rest day, alone or together with work
+    pub fn is_rest(&self) -> bool {
+        self.agrees(DayType::RestDay)
+    }
+
+    /// Returns the LAST-MODIFIED time of the event, if any
+    pub fn last_modified(&self) -> Option<DateTime<Utc>> {
+        self.last_modified
+    }
+
+    /// Returns the DTSTAMP time of the event, if any
+    pub fn dtstamp(&self) -> Option<DateTime<Utc>> {
+        self.dtstamp
+    }
+
+    /// Returns the SEQUENCE number of the event, if any
+    pub fn sequence(&self) -> Option<i64> {
+        self.sequence
+    }
+
+    /// Checks whether the event supports a decision for the given day type
+    fn agrees(&self, day_type: DayType) -> bool {
+        self.day_type == day_type || (self.day_type == DayType::ConflictDay && matches!(day_type, DayType::WorkDay | DayType::RestDay))
+    }
+
+    /// Returns the authoring key (LAST-MODIFIED, DTSTAMP, SEQUENCE) used by
+    /// `UseLatest`, missing values comparing lowest
+    fn authored(&self) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>, Option<i64>) {
+        (self.last_modified, self.dtstamp, self.sequence)
+    }
+}
+
+impl ConflictResolver for Priority {
+    /// # Note
+    /// * `KeepCurrent` and `UseLatest` follow the event they pick, treating an
+    ///   event matching both work and rest as work
+    fn resolve(&self, candidates: &[Candidate<'_>]) -> DayType {
+        let follow = |candidate: Option<&Candidate<'_>>| match candidate.map(|c| c.day_type) {
+            Some(DayType::RestDay) => DayType::RestDay,
+            _ => DayType::WorkDay,
+        };
+        match self {
+            Priority::WorkOverRest if candidates.iter().any(Candidate::is_work) => DayType::WorkDay,
+            Priority::WorkOverRest => DayType::RestDay,
+            Priority::RestOverWork if candidates.iter().any(Candidate::is_rest) => DayType::RestDay,
+            Priority::RestOverWork => DayType::WorkDay,
+            Priority::KeepCurrent => follow(candidates.first()),
+            Priority::UseLatest => follow(latest(candidates).and_then(|index| candidates.get(index))),
+        }
+    }
+
+    /// # Note
+    /// * `UseLatest` picks the most recently authored event; on ties the latest-starting one
+    fn decisive(&self, candidates: &[Candidate<'_>], day_type: DayType) -> Option<usize> {
+        match self {
+            Priority::UseLatest => latest(candidates),
+            _ => candidates.iter().position(|c| c.agrees(day_type)),
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("priority {:?}", self)
+    }
+}
+
+/// Finds the most recently authored event; on ties the latest-starting one
+fn latest(candidates: &[Candidate<'_>]) -> Option<usize> {
+    candidates
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, c)| c.authored())
+        .map(|(index, _)| index)
+}
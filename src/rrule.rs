@@ -0,0 +1,420 @@
+//! Expansion of event recurrence rules (RRULE) into occurrence starts.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+
+use std::collections::VecDeque;
+
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime, TimeDelta, Weekday};
+
+use crate::vtimezone::{last_weekday_of_month, parse_weekday};
+
+/// iCalendar datetime format: YYYYMMDDTHHMMSS
+const DT_FMT: &str = "%Y%m%dT%H%M%S";
+/// iCalendar date format: YYYYMMDD
+const DATE_FMT: &str = "%Y%m%d";
+/// Consecutive periods without an occurrence after which a rule is taken
+/// to have none left, e.g. `FREQ=MONTHLY;BYMONTHDAY=31;BYMONTH=2`
+const MAX_EMPTY_PERIODS: u32 = 1000;
+
+/// Recurrence rule of an event (RFC 5545 section 3.3.10)
+/// 
+/// # Note
+/// * Supports FREQ=DAILY, WEEKLY, MONTHLY and YEARLY with INTERVAL, COUNT,
+///   UNTIL, WKST, BYDAY, BYMONTH and BYMONTHDAY
+/// * BYDAY ordinals such as `-1FR` apply within the month, for MONTHLY rules
+///   and for YEARLY rules with BYMONTH
+#[derive(Clone, Debug)]
+pub(crate) struct RRule {
+    /// Length of a period (FREQ)
+    freq: Freq,
+    /// Periods between occurrences (INTERVAL)
+    interval: u32,
+    /// Number of occurrences, DTSTART included (COUNT)
+    count: Option<u32>,
+    /// Last possible occurrence (UNTIL)
+    until: Option<Until>,
+    /// Weekdays, with their ordinal within the month or 0 for every one (BYDAY)
+    by_day: Vec<(i32, Weekday)>,
+    /// Months, 1-12 (BYMONTH)
+    by_month: Vec<u32>,
+    /// Days of the month, negative ones counting from its end (BYMONTHDAY)
+    by_month_day: Vec<i32>,
+    /// First day of the week (WKST)
+    week_start: Weekday,
+}
+
+/// Length of the periods a rule repeats in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Freq {
+    /// Every day (FREQ=DAILY)
+    Daily,
+    /// Every week, starting on WKST (FREQ=WEEKLY)
+    Weekly,
+    /// Every month (FREQ=MONTHLY)
+    Monthly,
+    /// Every year (FREQ=YEARLY)
+    Yearly,
+}
+
+/// Last possible occurrence of a rule
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Until {
+    /// A date, the occurrences on it included
+    Date(NaiveDate),
+    /// A wall-clock time in the zone of DTSTART
+    Floating(NaiveDateTime),
+    /// A UTC instant in milliseconds, only comparable once occurrences are placed in their zone
+    Utc(i64),
+}
+
+/// Wall-clock starts of the occurrences of a rule, in order
+pub(crate) struct Occurrences<'a> {
+    /// Rule being expanded
+    rule: &'a RRule,
+    /// DTSTART, the first occurrence
+    start: NaiveDateTime,
+    /// Number of the next period to expand, 0 being the one of DTSTART
+    period: u32,
+    /// Occurrences of the last expanded period not returned yet
+    pending: VecDeque<NaiveDateTime>,
+    /// Number of occurrences returned
+    returned: u32,
+    /// Consecutive periods expanded without an occurrence
+    empty_periods: u32,
+    /// Whether the rule has no occurrences left
+    done: bool,
+}
+
+impl RRule {
+    /// Parses the value of an RRULE property
+    /// 
+    /// # Arguments
+    /// * `value` - Rule parts, e.g. `FREQ=WEEKLY;BYDAY=MO,FR;UNTIL=20251231`
+    /// 
+    /// # Returns
+    /// * `Ok(RRule)` - The rule
+    /// * `Err(String)` - A part that is malformed or not supported
+    pub(crate) fn parse(value: &str) -> Result<Self, String> {
+        let mut freq = None;
+        let mut rule = RRule {
+            freq: Freq::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month: Vec::new(),
+            by_month_day: Vec::new(),
+            week_start: Weekday::Mon,
+        };
+
+        for part in value.trim().split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part.split_once('=').ok_or_else(|| format!("Malformed RRULE part '{}'", part))?;
+            let (key, value) = (key.trim().to_uppercase(), value.trim().to_uppercase());
+            let invalid = || format!("Invalid RRULE {} '{}'", key, value);
+            match key.as_str() {
+                "FREQ" => {
+                    freq = Some(match value.as_str() {
+                        "DAILY" => Freq::Daily,
+                        "WEEKLY" => Freq::Weekly,
+                        "MONTHLY" => Freq::Monthly,
+                        "YEARLY" => Freq::Yearly,
+                        _ => return Err(format!("Unsupported RRULE FREQ '{}'", value)),
+                    })
+                },
+                "INTERVAL" => rule.interval = value.parse().ok().filter(|n| *n > 0).ok_or_else(invalid)?,
+                "COUNT" => rule.count = Some(value.parse().map_err(|_| invalid())?),
+                "UNTIL" => rule.until = Some(Until::parse(&value).ok_or_else(invalid)?),
+                "WKST" => rule.week_start = parse_weekday(&value).ok_or_else(invalid)?,
+                "BYDAY" => {
+                    rule.by_day = value.split(',').map(parse_by_day).collect::<Option<_>>().ok_or_else(invalid)?;
+                },
+                "BYMONTH" => {
+                    rule.by_month = value
+                        .split(',')
+                        .map(|month| month.parse().ok().filter(|m| (1..=12).contains(m)))
+                        .collect::<Option<_>>()
+                        .ok_or_else(invalid)?;
+                },
+                "BYMONTHDAY" => {
+                    rule.by_month_day = value
+                        .split(',')
+                        .map(|day| day.parse().ok().filter(|d: &i32| (1..=31).contains(&d.abs())))
+                        .collect::<Option<_>>()
+                        .ok_or_else(invalid)?;
+                },
+                _ => return Err(format!("Unsupported RRULE part {}", key)),
+            }
+        }
+
+        rule.freq = freq.ok_or("RRULE without FREQ")?;
+        let ordinals = rule.by_day.iter().any(|(nth, _)| *nth != 0);
+        match rule.freq {
+            Freq::Daily | Freq::Weekly if ordinals => Err("RRULE BYDAY ordinals need FREQ=MONTHLY or YEARLY".to_string()),
+            Freq::Weekly if !rule.by_month_day.is_empty() => Err("RRULE BYMONTHDAY cannot be used with FREQ=WEEKLY".to_string()),
+            Freq::Yearly if !rule.by_day.is_empty() && rule.by_month.is_empty() => {
+                Err("Unsupported RRULE BYDAY without BYMONTH in a yearly rule".to_string())
+            },
+            _ if rule.count.is_some() && rule.until.is_some() => Err("RRULE with both COUNT and UNTIL".to_string()),
+            _ => Ok(rule),
+        }
+    }
+
+    /// Returns the UNTIL part of the rule
+    pub(crate) fn until(&self) -> Option<Until> {
+        self.until
+    }
+
+    /// Lists the occurrences of the rule starting at DTSTART
+    /// 
+    /// # Arguments
+    /// * `start` - DTSTART on its wall clock, midnight for a date
+    /// 
+    /// # Returns
+    /// * Wall-clock starts in order, DTSTART first; the iterator is endless
+    ///   for rules without COUNT or UNTIL, and a UTC UNTIL is left to the caller
+    pub(crate) fn occurrences(&self, start: NaiveDateTime) -> Occurrences<'_> {
+        Occurrences {
+            rule: self,
+            start,
+            period: 0,
+            pending: VecDeque::new(),
+            returned: 0,
+            empty_periods: 0,
+            done: false,
+        }
+    }
+
+    /// Lists the dates of a period, unsorted
+    /// 
+    /// # Arguments
+    /// * `start` - DTSTART date
+    /// * `period` - Number of the period, 0 being the one of DTSTART
+    fn dates_in(&self, start: NaiveDate, period: u32) -> Option<Vec<NaiveDate>> {
+        let steps = period.checked_mul(self.interval)?;
+        let dates = match self.freq {
+            Freq::Daily => {
+                let date = start.checked_add_signed(TimeDelta::try_days(i64::from(steps))?)?;
+                let weekday_ok = self.by_day.is_empty() || self.by_day.iter().any(|(_, day)| *day == date.weekday());
+                let month_day_ok = self.by_month_day.is_empty() || self.month_days(date.year(), date.month()).contains(&date);
+                if weekday_ok && month_day_ok { vec![date] } else { Vec::new() }
+            },
+            Freq::Weekly => {
+                let offset = start.weekday().days_since(self.week_start);
+                let week = start.checked_sub_signed(TimeDelta::days(i64::from(offset)))?
+                    .checked_add_signed(TimeDelta::try_weeks(i64::from(steps))?)?;
+                week.iter_days()
+                    .take(7)
+                    .filter(|date| match self.by_day.is_empty() {
+                        true => date.weekday() == start.weekday(),
+                        false => self.by_day.iter().any(|(_, day)| *day == date.weekday()),
+                    })
+                    .collect()
+            },
+            Freq::Monthly => {
+                let month = NaiveDate::from_ymd_opt(start.year(), start.month(), 1)?.checked_add_months(Months::new(steps))?;
+                self.dates_of_month(start, month.year(), month.month())
+            },
+            Freq::Yearly => {
+                let year = start.year().checked_add(i32::try_from(steps).ok()?)?;
+                match self.by_month.as_slice() {
+                    [] => self.dates_of_month(start, year, start.month()),
+                    months => months.iter().flat_map(|month| self.dates_of_month(start, year, *month)).collect(),
+                }
+            },
+        };
+        Some(dates.into_iter().filter(|date| self.by_month.is_empty() || self.by_month.contains(&date.month())).collect())
+    }
+
+    /// Lists the dates of a month the rule selects
+    /// 
+    /// # Note
+    /// * Without BYDAY and BYMONTHDAY, the day of DTSTART, skipped in months without it
+    fn dates_of_month(&self, start: NaiveDate, year: i32, month: u32) -> Vec<NaiveDate> {
+        if !self.by_month_day.is_empty() {
+            return self
+                .month_days(year, month)
+                .into_iter()
+                .filter(|date| self.by_day.is_empty() || self.by_day.iter().any(|(_, day)| *day == date.weekday()))
+                .collect();
+        }
+        if self.by_day.is_empty() {
+            return NaiveDate::from_ymd_opt(year, month, start.day()).into_iter().collect();
+        }
+        self.by_day
+            .iter()
+            .flat_map(|&(nth, weekday)| match nth {
+                0 => NaiveDate::from_weekday_of_month_opt(year, month, weekday, 1)
+                    .map(|first| first.iter_weeks().take_while(|date| date.month() == month).collect())
+                    .unwrap_or_default(),
+                1..=5 => NaiveDate::from_weekday_of_month_opt(year, month, weekday, nth as u8).into_iter().collect(),
+                _ => last_weekday_of_month(year, month, weekday)
+                    .and_then(|last| last.checked_sub_signed(TimeDelta::weeks(i64::from(-nth - 1))))
+                    .filter(|date| date.month() == month)
+                    .into_iter()
+                    .collect::<Vec<_>>(),
+            })
+            .collect()
+    }
+
+    /// Resolves BYMONTHDAY in a month, dropping days it does not have
+    fn month_days(&self, year: i32, month: u32) -> Vec<NaiveDate> {
+        let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else {
+            return Vec::new();
+        };
+        let length = first.checked_add_months(Months::new(1)).map_or(31, |next| (next - first).num_days() as i32);
+        self.by_month_day
+            .iter()
+            .map(|&day| if day < 0 { length + 1 + day } else { day })
+            .filter(|day| (1..=length).contains(day))
+            .filter_map(|day| NaiveDate::from_ymd_opt(year, month, day as u32))
+            .collect()
+    }
+}
+
+impl Until {
+    /// Parses an UNTIL value: a date, a floating time or a UTC time
+    fn parse(value: &str) -> Option<Self> {
+        if let Some(utc) = value.strip_suffix('Z') {
+            return NaiveDateTime::parse_from_str(utc, DT_FMT).ok().map(|dt| Until::Utc(dt.and_utc().timestamp_millis()));
+        }
+        NaiveDateTime::parse_from_str(value, DT_FMT)
+            .map(Until::Floating)
+            .or_else(|_| NaiveDate::parse_from_str(value, DATE_FMT).map(Until::Date))
+            .ok()
+    }
+
+    /// Checks whether a wall-clock occurrence comes after a date or floating UNTIL
+    fn passed_by(&self, occurrence: NaiveDateTime) -> bool {
+        match self {
+            Until::Date(date) => occurrence.date() > *date,
+            Until::Floating(until) => occurrence > *until,
+            Until::Utc(_) => false,
+        }
+    }
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = NaiveDateTime;
+
+    fn next(&mut self) -> Option<NaiveDateTime> {
+        while !self.done {
+            if self.rule.count.is_some_and(|count| self.returned >= count) {
+                break;
+            }
+            // DTSTART is always the first occurrence, matching the rule or not
+            let next = if self.returned == 0 {
+                Some(self.start)
+            } else {
+                self.pending.pop_front()
+            };
+            match next {
+                Some(occurrence) if self.returned > 0 && occurrence <= self.start => continue,
+                Some(occurrence) if self.rule.until.is_some_and(|until| until.passed_by(occurrence)) => break,
+                Some(occurrence) => {
+                    self.returned += 1;
+                    return Some(occurrence);
+                },
+                None if self.empty_periods >= MAX_EMPTY_PERIODS => break,
+                None => {
+                    let Some(mut dates) = self.rule.dates_in(self.start.date(), self.period) else {
+                        break;
+                    };
+                    dates.sort_unstable();
+                    dates.dedup();
+                    self.empty_periods = if dates.is_empty() { self.empty_periods + 1 } else { 0 };
+                    self.pending.extend(dates.into_iter().map(|date| date.and_time(self.start.time())));
+                    self.period += 1;
+                },
+            }
+        }
+        self.done = true;
+        None
+    }
+}
+
+/// Parses a BYDAY value such as `MO`, `2TU` or `-1FR`
+fn parse_by_day(value: &str) -> Option<(i32, Weekday)> {
+    let split = value.len().checked_sub(2)?;
+    let (ordinal, day) = value.split_at_checked(split)?;
+    let nth = match ordinal {
+        "" | "+" => 0,
+        n => n.trim_start_matches('+').parse().ok().filter(|n: &i32| (1..=5).contains(&n.abs()))?,
+    };
+    Some((nth, parse_weekday(day)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Expands a rule from a DTSTART, returning at most `n` occurrences as `YYYYmmDD` dates
+    fn dates(rule: &str, start: &str, n: usize) -> Vec<String> {
+        let start = NaiveDate::parse_from_str(start, DATE_FMT).unwrap().and_hms_opt(9, 0, 0).unwrap();
+        let rule = RRule::parse(rule).unwrap();
+        rule.occurrences(start).take(n).map(|dt| dt.format(DATE_FMT).to_string()).collect()
+    }
+
+    #[test]
+    fn weekly_on_several_days() {
+        assert_eq!(
+            dates("FREQ=WEEKLY;BYDAY=MO,FR", "20251003", 5),
+            ["20251003", "20251006", "20251010", "20251013", "20251017"]
+        );
+        assert_eq!(dates("FREQ=WEEKLY;INTERVAL=2", "20251003", 3), ["20251003", "20251017", "20251031"]);
+    }
+
+    #[test]
+    fn count_and_until_end_the_rule() {
+        assert_eq!(dates("FREQ=DAILY;COUNT=3", "20251001", 10), ["20251001", "20251002", "20251003"]);
+        assert_eq!(dates("FREQ=WEEKLY;UNTIL=20251017", "20251003", 10), ["20251003", "20251010", "20251017"]);
+        assert_eq!(dates("FREQ=WEEKLY;UNTIL=20251017T085959", "20251003", 10), ["20251003", "20251010"]);
+    }
+
+    #[test]
+    fn monthly_by_ordinal_weekday_and_month_day() {
+        assert_eq!(dates("FREQ=MONTHLY;BYDAY=-1FR", "20251031", 3), ["20251031", "20251128", "20251226"]);
+        assert_eq!(dates("FREQ=MONTHLY;BYMONTHDAY=-1", "20250131", 3), ["20250131", "20250228", "20250331"]);
+        // Months without the 31st have no occurrence
+        assert_eq!(dates("FREQ=MONTHLY", "20250131", 3), ["20250131", "20250331", "20250531"]);
+    }
+
+    #[test]
+    fn yearly_by_month_and_ordinal_weekday() {
+        // Thanksgiving, the fourth Thursday of November
+        assert_eq!(dates("FREQ=YEARLY;BYMONTH=11;BYDAY=4TH", "20251127", 3), ["20251127", "20261126", "20271125"]);
+        assert_eq!(dates("FREQ=YEARLY", "20240229", 2), ["20240229", "20280229"]);
+    }
+
+    #[test]
+    fn impossible_rule_ends() {
+        assert_eq!(dates("FREQ=MONTHLY;BYMONTH=2;BYMONTHDAY=30", "20250101", 5), ["20250101"]);
+    }
+
+    #[test]
+    fn unsupported_parts_are_rejected() {
+        for rule in ["FREQ=HOURLY", "FREQ=MONTHLY;BYSETPOS=-1;BYDAY=MO,TU", "FREQ=WEEKLY;BYDAY=2MO", "INTERVAL=2", "FREQ=DAILY;COUNT=2;UNTIL=20251231"] {
+            assert!(RRule::parse(rule).is_err(), "{}", rule);
+        }
+    }
+}
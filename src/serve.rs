@@ -0,0 +1,364 @@
+//! Minimal HTTP server publishing the predictions as an iCalendar feed.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, TimeDelta, Utc};
+use choliday::{Choliday, SourceReport, SourceStatus, TargetZone, redact_url};
+use log::{info, warn};
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    time::{Duration, Instant, MissedTickBehavior, interval_at, timeout},
+};
+
+/// Path of the feed of predicted days
+const FEED_PATH: &str = "/feed.ics";
+/// Path of the refresh status of the sources
+const STATUS_PATH: &str = "/status";
+/// Seconds subscribers may use a feed before asking again
+const FEED_MAX_AGE: u64 = 3600;
+/// Days the feed covers when the request gives no end
+const DEFAULT_FEED_DAYS: i64 = 365;
+/// Longest span of days a single request may ask for
+const MAX_FEED_DAYS: i64 = 3660;
+/// Largest request head accepted, in bytes
+const MAX_REQUEST_HEAD: usize = 8192;
+/// Time a client has to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Query date format (YYYY-mm-DD)
+const QUERY_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// State shared by the connections and the background refresh
+struct Server {
+    /// Analyzer answering every request from the events in memory
+    choliday: Choliday,
+    /// Zone whose current date a feed starts on when the request gives none
+    zone: TargetZone,
+    /// Interval between background refreshes
+    refresh: Duration,
+    /// Outcome of the refreshes so far
+    status: Mutex<RefreshStatus>,
+}
+
+/// Outcome of the background refreshes
+#[derive(Default)]
+struct RefreshStatus {
+    /// Time the events in memory were read
+    refreshed: Option<DateTime<Utc>>,
+    /// Time of the last refresh, successful or not
+    attempted: Option<DateTime<Utc>>,
+    /// Whether the last refresh swapped in new events
+    swapped: bool,
+    /// Reports of the last refresh, in configuration order
+    reports: Vec<SourceReport>,
+}
+
+impl Server {
+    /// Reads all sources again, keeping the previous events unless every
+    /// source was fetched, and records the outcome
+    async fn refresh(&self) {
+        let result = self.choliday.reload().await;
+        let now = Utc::now();
+        let mut status = self.status.lock().unwrap_or_else(PoisonError::into_inner);
+        status.attempted = Some(now);
+        status.swapped = result.is_ok();
+        status.reports = match result {
+            Ok(reports) => {
+                status.refreshed = Some(now);
+                info!("Refreshed {} calendar sources", reports.len());
+                reports
+            },
+            Err(reports) => {
+                // Without events before, the first read is kept anyway
+                if status.refreshed.is_none() {
+                    status.refreshed = Some(now);
+                }
+                warn!("Refreshing the calendar sources failed, answering from the events read before");
+                reports
+            },
+        };
+    }
+
+    /// Builds the JSON body of `/status`
+    /// 
+    /// # Note
+    /// * Credentials in source URLs are hidden, see [`redact_url`]
+    fn status_json(&self) -> Value {
+        let status = self.status.lock().unwrap_or_else(PoisonError::into_inner);
+        let time = |time: Option<DateTime<Utc>>| time.map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true));
+        let sources: Vec<Value> = status.reports
+            .iter()
+            .map(|report| {
+                let name = redact_url(report.source());
+                let redact = |cause: &str| redact_urls(&cause.replace(report.source(), &name));
+                let mut source = match report.status() {
+                    SourceStatus::Loaded { events } => json!({"status": "loaded", "events": events}),
+                    SourceStatus::Stale { events, age, cause } => json!({
+                        "status": "stale",
+                        "events": events,
+                        "age_secs": age.as_secs(),
+                        "error": redact(cause),
+                    }),
+                    SourceStatus::Failed(cause) => json!({"status": "failed", "error": redact(cause)}),
+                    _ => json!({}),
+                };
+                source["source"] = Value::String(name);
+                source
+            })
+            .collect();
+        json!({
+            "last_refresh": time(status.refreshed),
+            "last_attempt": time(status.attempted),
+            "swapped": status.swapped,
+            "refresh_secs": self.refresh.as_secs(),
+            "sources": sources,
+        })
+    }
+}
+
+/// Hides credentials in every URL of a message, see [`redact_url`]
+/// 
+/// # Note
+/// * Errors of the HTTP client quote the requested URL without its user
+///   name and password, but with its query
+fn redact_urls(message: &str) -> String {
+    message
+        .split(' ')
+        .map(|word| {
+            let url = word.trim_matches(|c| matches!(c, '(' | ')' | '\'' | '"' | ',' | ';'));
+            if url.starts_with("http://") || url.starts_with("https://") {
+                word.replace(url, &redact_url(url))
+            } else {
+                word.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// HTTP response about to be written
+struct Response {
+    /// Status code and reason phrase, e.g. `200 OK`
+    status: &'static str,
+    /// Header lines besides the length and connection ones
+    headers: Vec<(&'static str, String)>,
+    /// Body, also counted for HEAD requests but not sent
+    body: String,
+}
+
+impl Response {
+    /// Creates a plain text response
+    fn text(status: &'static str, body: impl Into<String>) -> Self {
+        Response {
+            status,
+            headers: vec![("Content-Type", "text/plain; charset=utf-8".to_string())],
+            body: body.into() + "\n",
+        }
+    }
+}
+
+/// Accepts connections until the process is stopped
+/// 
+/// # Arguments
+/// * `choliday` - Analyzer answering every request, keeping the events it read
+/// * `listen` - Address and port to listen on
+/// * `zone` - Zone whose current date, on each request, a feed starts on when the request gives none
+/// * `refresh` - Interval between background refreshes of all sources
+/// 
+/// # Returns
+/// * `Err(String)` if the address cannot be listened on; otherwise never returns
+/// 
+/// # Note
+/// * All sources are read before the first connection is accepted; requests
+///   are then answered from memory while a background task refreshes them
+pub async fn run(choliday: Choliday, listen: &str, zone: TargetZone, refresh: Duration) -> Result<(), String> {
+    let listener = TcpListener::bind(listen)
+        .await
+        .map_err(|e| format!("Cannot listen on '{}': {}", listen, e))?;
+    let server = Arc::new(Server { choliday, zone, refresh, status: Mutex::default() });
+    server.refresh().await;
+
+    let refresher = Arc::clone(&server);
+    tokio::spawn(async move {
+        let mut ticks = interval_at(Instant::now() + refresh, refresh);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticks.tick().await;
+            refresher.refresh().await;
+        }
+    });
+
+    let address = listener.local_addr().map_or_else(|_| listen.to_string(), |address| address.to_string());
+    eprintln!("Serving http://{}{}", address, FEED_PATH);
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                let server = Arc::clone(&server);
+                tokio::spawn(async move {
+                    if let Err(e) = handle(stream, &server).await {
+                        warn!("Request from {} failed: {}", peer, e);
+                    }
+                });
+            },
+            Err(e) => warn!("Cannot accept a connection: {}", e),
+        }
+    }
+}
+
+/// Answers the single request of a connection, then closes it
+/// 
+/// # Arguments
+/// * `stream` - Connection to the client
+/// * `server` - State answering the request
+async fn handle(mut stream: TcpStream, server: &Server) -> std::io::Result<()> {
+    let (response, with_body) = match timeout(REQUEST_TIMEOUT, read_head(&mut stream)).await {
+        Err(_) => (Response::text("408 Request Timeout", "Request not received in time"), true),
+        Ok(Err(e)) => return Err(e),
+        // Closed before sending a request
+        Ok(Ok(None)) => return Ok(()),
+        Ok(Ok(Some(head))) if head.len() > MAX_REQUEST_HEAD => {
+            (Response::text("431 Request Header Fields Too Large", "Request head too large"), true)
+        },
+        Ok(Ok(Some(head))) => {
+            let head = String::from_utf8_lossy(&head);
+            let mut lines = head.split("\r\n");
+            let mut request = lines.next().unwrap_or_default().split(' ');
+            let (method, target) = (request.next().unwrap_or_default(), request.next().unwrap_or_default());
+            let if_none_match = lines
+                .filter_map(|line| line.split_once(':'))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("If-None-Match"))
+                .map(|(_, value)| value.trim().to_string());
+            info!("{} {}", method, target);
+            match method {
+                "GET" | "HEAD" => (route(server, target, if_none_match.as_deref()).await, method == "GET"),
+                _ => (Response::text("405 Method Not Allowed", "Only GET and HEAD are supported"), true),
+            }
+        },
+    };
+
+    let mut out = format!("HTTP/1.1 {}\r\n", response.status);
+    for (name, value) in &response.headers {
+        out.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    out.push_str(&format!("Content-Length: {}\r\nConnection: close\r\n\r\n", response.body.len()));
+    if with_body {
+        out.push_str(&response.body);
+    }
+    stream.write_all(out.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Reads a request head, up to the empty line ending it
+/// 
+/// # Returns
+/// * `Ok(Some(Vec<u8>))` - The head, longer than `MAX_REQUEST_HEAD` if it was cut short
+/// * `Ok(None)` - The client closed the connection first
+async fn read_head(stream: &mut TcpStream) -> std::io::Result<Option<Vec<u8>>> {
+    let mut head = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") && head.len() <= MAX_REQUEST_HEAD {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        head.extend_from_slice(&buffer[..read]);
+    }
+    Ok(Some(head))
+}
+
+/// Builds the response to a request target
+/// 
+/// # Arguments
+/// * `server` - State answering the request
+/// * `target` - Path and query of the request
+/// * `if_none_match` - Entity tag the client already holds, if any
+/// 
+/// # Note
+/// * `/feed.ics` takes `from` and `to` as `YYYY-mm-DD`, both included and
+///   defaulting to the current date in the target zone and a year after `from`, and `work=true` to list
+///   makeup work days too
+/// * `/status` reports the last refresh and the outcome of every source as JSON
+async fn route(server: &Server, target: &str, if_none_match: Option<&str>) -> Response {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    if path == STATUS_PATH {
+        return Response {
+            status: "200 OK",
+            headers: vec![
+                ("Content-Type", "application/json".to_string()),
+                ("Cache-Control", "no-store".to_string()),
+            ],
+            body: server.status_json().to_string() + "\n",
+        };
+    }
+    if path != FEED_PATH {
+        return Response::text("404 Not Found", format!("Not found, the feed is at {}", FEED_PATH));
+    }
+
+    let (mut from, mut to, mut work) = (None, None, false);
+    for (name, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        let date = || {
+            NaiveDate::parse_from_str(value, QUERY_DATE_FORMAT)
+                .map_err(|_| format!("Query parameter '{}' must be a YYYY-mm-DD date, got '{}'", name, value))
+        };
+        let parsed = match name {
+            "from" => date().map(|date| from = Some(date)),
+            "to" => date().map(|date| to = Some(date)),
+            "work" => match value {
+                "true" | "1" | "false" | "0" => {
+                    work = matches!(value, "true" | "1");
+                    Ok(())
+                },
+                _ => Err(format!("Query parameter 'work' must be true or false, got '{}'", value)),
+            },
+            _ => Ok(()),
+        };
+        if let Err(e) = parsed {
+            return Response::text("400 Bad Request", e);
+        }
+    }
+    let from = from.unwrap_or_else(|| server.zone.today(Utc::now()));
+    let to = to.unwrap_or(from + TimeDelta::days(DEFAULT_FEED_DAYS));
+    if (to - from).num_days().abs() >= MAX_FEED_DAYS {
+        return Response::text("400 Bad Request", format!("A feed covers at most {} days", MAX_FEED_DAYS));
+    }
+
+    match server.choliday.feed(from, to, work).await {
+        Ok(feed) => {
+            let headers = vec![
+                ("ETag", feed.etag().to_string()),
+                ("Cache-Control", format!("public, max-age={}", FEED_MAX_AGE)),
+            ];
+            if if_none_match.is_some_and(|tags| tags.split(',').any(|tag| tag.trim() == feed.etag() || tag.trim() == "*")) {
+                Response { status: "304 Not Modified", headers, body: String::new() }
+            } else {
+                let mut headers = headers;
+                headers.insert(0, ("Content-Type", "text/calendar; charset=utf-8".to_string()));
+                Response { status: "200 OK", headers, body: feed.body().to_string() }
+            }
+        },
+        Err(e) => Response::text("503 Service Unavailable", e),
+    }
+}
@@ -0,0 +1,232 @@
+//! SQLite backend of the calendar cache (`sqlite` feature).
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use std::{fs, path::Path, sync::Mutex, time::Duration};
+
+use log::{debug, warn};
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::cache::{CacheStore, DB_FILE, CacheEntry, now_secs};
+
+/// How long to wait for another run holding a lock on the database
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Schema changes in order; the database's `user_version` counts those applied
+const MIGRATIONS: &[&str] = &[
+    // 1: cached responses with their validators, fetch metadata and event counts
+    "CREATE TABLE sources (
+        url TEXT PRIMARY KEY,
+        fetched_at INTEGER NOT NULL,
+        status INTEGER NOT NULL,
+        etag TEXT,
+        last_modified TEXT,
+        body BLOB NOT NULL,
+        events INTEGER
+    );",
+    // 2: time until which each body is used without asking the server
+    "ALTER TABLE sources ADD COLUMN fresh_until INTEGER;",
+];
+
+/// Cache backend of one SQLite database in the cache directory
+/// 
+/// # Note
+/// * Every change is written at once, so [`CacheStore::save`] has nothing to do
+/// * The schema is created and migrated on open; a database written by a
+///   newer version with an unknown schema is refused
+/// * The database is `cache.sqlite` in the cache directory and can be
+///   queried directly, e.g. `SELECT url, fetched_at, events FROM sources`
+pub struct SqliteStore {
+    /// Connection to the database
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Opens, creating if needed, the database in a cache directory
+    /// 
+    /// # Arguments
+    /// * `dir` - Cache directory
+    /// 
+    /// # Returns
+    /// * `Err(String)` - The database cannot be created, opened or migrated
+    pub fn open(dir: &Path) -> Result<Self, String> {
+        let path = dir.join(DB_FILE);
+        let error = |e: rusqlite::Error| format!("Cannot open cache database '{}': {}", path.display(), e);
+        fs::create_dir_all(dir).map_err(|e| format!("Cannot create cache directory '{}': {}", dir.display(), e))?;
+        let mut conn = Connection::open(&path).map_err(error)?;
+        conn.busy_timeout(BUSY_TIMEOUT).map_err(error)?;
+        migrate(&mut conn).map_err(|e| format!("Cannot migrate cache database '{}': {}", path.display(), e))?;
+        Ok(SqliteStore { conn: Mutex::new(conn) })
+    }
+
+    /// Runs a statement, logging a failure as caching is best-effort
+    fn execute(&self, url: &str, sql: &str, params: impl rusqlite::Params) {
+        let Ok(conn) = self.conn.lock() else {
+            return;
+        };
+        if let Err(e) = conn.execute(sql, params) {
+            warn!("Cannot update the cache of '{}': {}", url, e);
+        }
+    }
+}
+
+impl CacheStore for SqliteStore {
+    fn lookup(&self, url: &str) -> Option<CacheEntry> {
+        let conn = self.conn.lock().ok()?;
+        conn.query_row(
+            "SELECT url, fetched_at, status, etag, last_modified, events, fresh_until FROM sources WHERE url = ?1",
+            params![url],
+            entry,
+        )
+        .optional()
+        .unwrap_or_else(|e| {
+            warn!("Cannot read the cache of '{}': {}", url, e);
+            None
+        })
+    }
+
+    fn body(&self, url: &str) -> Result<Vec<u8>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.query_row("SELECT body FROM sources WHERE url = ?1", params![url], |row| row.get(0))
+            .map_err(|e| format!("Cannot read cached body of '{}': {}", url, e))
+    }
+
+    fn store(
+        &self,
+        url: &str,
+        status: u16,
+        body: &[u8],
+        etag: Option<String>,
+        last_modified: Option<String>,
+        fresh_for: Option<Duration>,
+    ) {
+        let fresh_until = fresh_for.map(|lifetime| now_secs().saturating_add(lifetime.as_secs()));
+        self.execute(
+            url,
+            "INSERT OR REPLACE INTO sources (url, fetched_at, status, etag, last_modified, body, events, fresh_until)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, NULL, ?7)",
+            params![url, now_secs(), status, etag, last_modified, body, fresh_until],
+        );
+        debug!("Cached '{}' in the database", url);
+    }
+
+    fn touch(&self, url: &str, fresh_for: Option<Duration>) {
+        let fresh_until = fresh_for.map(|lifetime| now_secs().saturating_add(lifetime.as_secs()));
+        self.execute(
+            url,
+            "UPDATE sources SET fetched_at = ?2, fresh_until = ?3 WHERE url = ?1",
+            params![url, now_secs(), fresh_until],
+        );
+    }
+
+    fn record_events(&self, url: &str, events: usize) {
+        self.execute(url, "UPDATE sources SET events = ?2 WHERE url = ?1", params![url, events]);
+    }
+
+    fn entries(&self) -> Vec<CacheEntry> {
+        let Ok(conn) = self.conn.lock() else {
+            return Vec::new();
+        };
+        let entries = conn
+            .prepare("SELECT url, fetched_at, status, etag, last_modified, events, fresh_until FROM sources ORDER BY url")
+            .and_then(|mut statement| statement.query_map([], entry)?.collect());
+        entries.unwrap_or_else(|e| {
+            warn!("Cannot list the cached calendars: {}", e);
+            Vec::new()
+        })
+    }
+}
+
+/// Reads an entry from a row of `url, fetched_at, status, etag, last_modified, events, fresh_until`
+fn entry(row: &rusqlite::Row) -> rusqlite::Result<CacheEntry> {
+    Ok(CacheEntry::new(row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?))
+}
+
+/// Applies the migrations a database has not seen yet, each in a transaction
+fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+    let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if version > MIGRATIONS.len() {
+        return Err(rusqlite::Error::InvalidParameterName(format!(
+            "schema version {} is newer than the supported {}",
+            version,
+            MIGRATIONS.len()
+        )));
+    }
+    for (applied, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let transaction = conn.transaction()?;
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", applied + 1)?;
+        transaction.commit()?;
+        debug!("Migrated the cache database to schema version {}", applied + 1);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_round_trip_through_the_database() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("cache");
+        let (first, second) = ("https://example.com/b.ics", "https://example.com/a.ics");
+
+        let store = SqliteStore::open(&dir).unwrap();
+        store.store(first, 200, b"BEGIN:VCALENDAR", Some("\"v1\"".to_string()), None, Some(Duration::from_secs(3600)));
+        store.store(second, 203, b"old", None, Some("Wed, 01 Oct 2025 00:00:00 GMT".to_string()), None);
+        store.store(second, 200, b"new", None, Some("Thu, 02 Oct 2025 00:00:00 GMT".to_string()), None);
+        store.record_events(first, 2);
+        drop(store);
+
+        // Everything survives reopening the database
+        let store = SqliteStore::open(&dir).unwrap();
+        let entry = store.lookup(first).unwrap();
+        assert_eq!((entry.url(), entry.status(), entry.etag(), entry.events()), (first, 200, Some("\"v1\""), Some(2)));
+        assert!(entry.fresh_for().is_some_and(|fresh| fresh > Duration::from_secs(3500)));
+        assert_eq!(store.body(first).unwrap(), b"BEGIN:VCALENDAR");
+
+        let entry = store.lookup(second).unwrap();
+        assert_eq!((entry.last_modified(), entry.events(), entry.fresh_for()), (Some("Thu, 02 Oct 2025 00:00:00 GMT"), None, None));
+        assert_eq!(store.body(second).unwrap(), b"new");
+
+        store.touch(first, None);
+        assert_eq!(store.lookup(first).unwrap().fresh_for(), None);
+        let urls: Vec<String> = store.entries().iter().map(|entry| entry.url().to_string()).collect();
+        assert_eq!(urls, [second, first]);
+        assert!(store.lookup("https://example.com/missing.ics").is_none());
+        assert!(store.body("https://example.com/missing.ics").is_err());
+    }
+
+    #[test]
+    fn newer_schema_is_refused() {
+        let temp = tempfile::tempdir().unwrap();
+        drop(SqliteStore::open(temp.path()).unwrap());
+        let conn = Connection::open(temp.path().join(DB_FILE)).unwrap();
+        conn.pragma_update(None, "user_version", MIGRATIONS.len() + 1).unwrap();
+        drop(conn);
+
+        let error = SqliteStore::open(temp.path()).err().unwrap();
+        assert!(error.contains("is newer than the supported"), "{}", error);
+    }
+}
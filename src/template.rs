@@ -0,0 +1,135 @@
+//! Placeholder substitution for messages and hooks describing a decision.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use crate::{
+    decision::{DayDecision, DecisionSource},
+    ical::DayType,
+};
+
+/// Placeholders known to [`render_template`]
+pub const PLACEHOLDERS: [&str; 9] = ["date", "time", "weekday", "result", "day_type", "kind", "summary", "pattern", "source"];
+
+/// Fills the placeholders of a template with a decision
+/// 
+/// # Arguments
+/// * `template` - Text with placeholders in braces; `{{` and `}}` stand for literal braces
+/// * `decision` - Decision the placeholders describe
+/// 
+/// # Returns
+/// * `Ok(String)` - The text with every placeholder replaced:
+///   * `{date}` - Date classified, `YYYY-mm-DD`
+///   * `{time}` - Time classified, `HH:MM:SS`
+///   * `{weekday}` - Day of the week, e.g. `Mon`
+///   * `{result}` - Answer of the command line: `true`, `false`, `unknown` or `conflict`
+///   * `{day_type}` - Stable name of the classification, e.g. `rest_day`
+///   * `{kind}` - Classification for humans: `work day`, `rest day`, `weekend`,
+///     `public holiday`, `personal leave`, `unknown day` or `unresolved day`
+///   * `{summary}` - Summary of the deciding event, empty when no event decided
+///   * `{pattern}` - Pattern or category the deciding event matched, both
+///     separated by a comma for a conflicting event, empty otherwise
+///   * `{source}` - What decided the classification, for humans
+/// * `Err(String)` - The template names an unknown placeholder or leaves a brace unmatched
+/// 
+/// # Examples
+/// ```
+/// use choliday::{DayDecision, render_template};
+/// 
+/// let decision: DayDecision = serde_json::from_str(r#"{"date": "2025-10-01T23:59:59", "day_type": "rest_day",
+///     "rest_kind": "public_holiday", "source": {"kind": "calendar_event", "summary": "National Day",
+///     "source": null, "pattern": "National"}, "warnings": []}"#).unwrap();
+/// assert_eq!(
+///     render_template("{date} ({weekday}) is a {kind}: {summary}", &decision).unwrap(),
+///     "2025-10-01 (Wed) is a public holiday: National Day",
+/// );
+/// assert_eq!(render_template("{pattern}", &decision).unwrap(), "National");
+/// assert!(render_template("{tomorrow}", &decision).is_err());
+/// ```
+pub fn render_template(template: &str, decision: &DayDecision) -> Result<String, String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(at) = rest.find(['{', '}']) {
+        rendered.push_str(&rest[..at]);
+        let tail = &rest[at..];
+        if let Some(after) = tail.strip_prefix("{{") {
+            rendered.push('{');
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix("}}") {
+            rendered.push('}');
+            rest = after;
+        } else if tail.starts_with('}') {
+            return Err(format!("Unmatched '}}' in template '{}'", template));
+        } else {
+            let end = tail.find('}').ok_or_else(|| format!("Unclosed '{{' in template '{}'", template))?;
+            rendered.push_str(&value(&tail[1..end], decision)?);
+            rest = &tail[end + 1..];
+        }
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// Returns the value of a placeholder
+/// 
+/// # Arguments
+/// * `name` - Placeholder name, without the braces
+/// * `decision` - Decision the placeholder describes
+fn value(name: &str, decision: &DayDecision) -> Result<String, String> {
+    Ok(match name {
+        "date" => decision.date().date().to_string(),
+        "time" => decision.date().time().format("%H:%M:%S").to_string(),
+        "weekday" => decision.date().format("%a").to_string(),
+        "result" if decision.is_unknown() => "unknown".to_string(),
+        "result" if decision.is_unresolved() => "conflict".to_string(),
+        "result" => decision.is_work_day().to_string(),
+        "day_type" => decision.day_type().to_string(),
+        "kind" => match (decision.day_type(), decision.rest_kind()) {
+            (DayType::WorkDay, _) => "work day".to_string(),
+            (DayType::Unknown, _) => "unknown day".to_string(),
+            (DayType::ConflictDay, _) => "unresolved day".to_string(),
+            (_, Some(rest_kind)) => rest_kind.to_string(),
+            (_, None) => "rest day".to_string(),
+        },
+        "summary" => match decision.source() {
+            DecisionSource::CalendarEvent { summary, .. }
+            | DecisionSource::ExplicitOverride { summary, .. }
+            | DecisionSource::ConflictingEvent { summary, .. } => summary.clone(),
+            _ => String::new(),
+        },
+        "pattern" => match decision.source() {
+            DecisionSource::CalendarEvent { pattern, .. } => pattern.clone().unwrap_or_default(),
+            DecisionSource::ConflictingEvent { work_pattern, rest_pattern, .. } => {
+                [work_pattern, rest_pattern].into_iter().flatten().cloned().collect::<Vec<_>>().join(",")
+            },
+            _ => String::new(),
+        },
+        "source" => decision.source().to_string(),
+        _ => {
+            return Err(format!(
+                "Unknown placeholder '{{{}}}', expected one of {}",
+                name,
+                PLACEHOLDERS.map(|name| format!("{{{}}}", name)).join(", ")
+            ));
+        },
+    })
+}
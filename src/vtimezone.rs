@@ -0,0 +1,354 @@
+//! VTIMEZONE interpretation for calendars using non-IANA TZIDs.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Weekday, offset::LocalResult};
+use ical::parser::ical::component::{IcalTimeZone, IcalTimeZoneTransition};
+
+/// iCalendar property key for the timezone identifier
+const KEY_TZID: &str = "TZID";
+/// iCalendar property key for the onset of a transition
+const KEY_DTSTART: &str = "DTSTART";
+/// iCalendar property key for the offset in use before a transition
+const KEY_TZOFFSETFROM: &str = "TZOFFSETFROM";
+/// iCalendar property key for the offset in use after a transition
+const KEY_TZOFFSETTO: &str = "TZOFFSETTO";
+/// iCalendar property key for the recurrence of a transition
+const KEY_RRULE: &str = "RRULE";
+
+/// iCalendar datetime format: YYYYMMDDTHHMMSS
+const DT_FMT: &str = "%Y%m%dT%H%M%S";
+
+/// Timezone defined by a VTIMEZONE component
+#[derive(Clone)]
+pub struct VTimeZone {
+    /// Identifier referenced by TZID parameters
+    tzid: String,
+    /// STANDARD and DAYLIGHT observances
+    transitions: Vec<Transition>,
+}
+
+/// A single STANDARD or DAYLIGHT observance
+#[derive(Clone)]
+struct Transition {
+    /// First onset, as wall-clock time in the offset in use before it
+    dtstart: NaiveDateTime,
+    /// UTC offset in seconds before the onset
+    offset_from: i32,
+    /// UTC offset in seconds from the onset on
+    offset_to: i32,
+    /// Yearly recurrence of the onset, `None` for a single onset
+    rule: Option<YearlyRule>,
+}
+
+/// The yearly recurrence patterns found in real-world VTIMEZONE blocks
+#[derive(Clone)]
+struct YearlyRule {
+    /// Month of the onset (BYMONTH)
+    month: u32,
+    /// Weekday of the onset (BYDAY)
+    weekday: Weekday,
+    /// Which weekday of the month: 1-5, or -1 for the last one
+    nth: i8,
+    /// Allowed days of month when BYMONTHDAY narrows a plain BYDAY
+    month_days: Vec<u32>,
+    /// Last onset (UNTIL), as wall-clock time
+    until: Option<NaiveDateTime>,
+}
+
+impl VTimeZone {
+    /// Interprets a VTIMEZONE component
+    /// 
+    /// # Arguments
+    /// * `timezone` - Parsed VTIMEZONE component
+    /// 
+    /// # Returns
+    /// * `Ok(VTimeZone)` - Interpreted timezone
+    /// * `Err((Option<String>, &str))` - The TZID if known, and why the component
+    ///   could not be interpreted
+    pub fn parse(timezone: &IcalTimeZone) -> Result<Self, (Option<String>, &'static str)> {
+        let tzid = timezone.properties
+            .iter()
+            .find(|prop| prop.name == KEY_TZID)
+            .and_then(|prop| prop.value.clone())
+            .ok_or((None, "Missing TZID"))?;
+
+        let transitions = timezone.transitions
+            .iter()
+            .map(Transition::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| (Some(tzid.clone()), e))?;
+
+        if transitions.is_empty() {
+            return Err((Some(tzid), "No STANDARD or DAYLIGHT observance"));
+        }
+
+        Ok(VTimeZone { tzid, transitions })
+    }
+
+    /// Returns the identifier referenced by TZID parameters
+    pub fn tzid(&self) -> &str {
+        &self.tzid
+    }
+
+    /// Converts a wall-clock time in this zone to a Unix timestamp
+    /// 
+    /// # Arguments
+    /// * `local` - Wall-clock time
+    /// 
+    /// # Returns
+    /// * `LocalResult<i64>` - Timestamp in milliseconds; ambiguous during a
+    ///   fall-back overlap and `None` inside a spring-forward gap
+    pub fn to_utc(&self, local: NaiveDateTime) -> LocalResult<i64> {
+        let onsets = self.onsets_around(local.year());
+
+        let mut offsets: Vec<i32> = self.transitions
+            .iter()
+            .flat_map(|t| [t.offset_from, t.offset_to])
+            .collect();
+        offsets.sort_unstable();
+        offsets.dedup();
+
+        // A candidate instant is valid when the offset in force at it is the one used
+        let mut instants: Vec<i64> = offsets
+            .into_iter()
+            .filter_map(|offset| {
+                let utc = local - Duration::seconds(offset as i64);
+                (self.offset_at(&onsets, utc) == offset).then(|| utc.and_utc().timestamp_millis())
+            })
+            .collect();
+        instants.sort_unstable();
+        instants.dedup();
+
+        match instants.as_slice() {
+            [] => LocalResult::None,
+            [single] => LocalResult::Single(*single),
+            [earliest, .., latest] => LocalResult::Ambiguous(*earliest, *latest),
+        }
+    }
+
+    /// Lists the onsets relevant to a year as (UTC instant, offset from then on), sorted
+    fn onsets_around(&self, year: i32) -> Vec<(NaiveDateTime, i32)> {
+        let mut onsets: Vec<(NaiveDateTime, i32)> = self.transitions
+            .iter()
+            .flat_map(|t| {
+                (year - 1..=year + 1)
+                    .filter_map(|y| t.onset_in(y))
+                    .map(|onset| (onset - Duration::seconds(t.offset_from as i64), t.offset_to))
+            })
+            .collect();
+        onsets.sort_by_key(|(instant, _)| *instant);
+        onsets
+    }
+
+    /// Returns the UTC offset in seconds in force at a UTC instant
+    fn offset_at(&self, onsets: &[(NaiveDateTime, i32)], utc: NaiveDateTime) -> i32 {
+        onsets
+            .iter()
+            .rev()
+            .find(|(instant, _)| *instant <= utc)
+            .map(|(_, offset)| *offset)
+            .unwrap_or_else(|| {
+                // Before every known onset the zone uses the earliest observance's prior offset
+                self.transitions
+                    .iter()
+                    .min_by_key(|t| t.dtstart)
+                    .map(|t| t.offset_from)
+                    .unwrap_or_default()
+            })
+    }
+}
+
+impl Transition {
+    /// Interprets a STANDARD or DAYLIGHT observance
+    fn parse(transition: &IcalTimeZoneTransition) -> Result<Self, &'static str> {
+        let value = |name: &str| {
+            transition.properties
+                .iter()
+                .find(|prop| prop.name == name)
+                .and_then(|prop| prop.value.as_deref())
+        };
+
+        let dtstart = value(KEY_DTSTART)
+            .and_then(|v| NaiveDateTime::parse_from_str(v, DT_FMT).ok())
+            .ok_or("Missing or invalid DTSTART in observance")?;
+        let offset_from = value(KEY_TZOFFSETFROM)
+            .and_then(parse_offset)
+            .ok_or("Missing or invalid TZOFFSETFROM")?;
+        let offset_to = value(KEY_TZOFFSETTO)
+            .and_then(parse_offset)
+            .ok_or("Missing or invalid TZOFFSETTO")?;
+        let rule = value(KEY_RRULE)
+            .map(|rrule| YearlyRule::parse(rrule, dtstart))
+            .transpose()?;
+
+        Ok(Transition { dtstart, offset_from, offset_to, rule })
+    }
+
+    /// Returns the onset falling in a year as wall-clock time, if any
+    fn onset_in(&self, year: i32) -> Option<NaiveDateTime> {
+        match &self.rule {
+            None => (self.dtstart.year() == year).then_some(self.dtstart),
+            Some(rule) => {
+                if year < self.dtstart.year() {
+                    return None;
+                }
+                let onset = rule.date_in(year)?.and_time(self.dtstart.time());
+                match rule.until {
+                    Some(until) if onset > until => None,
+                    _ => Some(onset),
+                }
+            },
+        }
+    }
+}
+
+impl YearlyRule {
+    /// Interprets an observance RRULE
+    /// 
+    /// # Supported Patterns
+    /// * `FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU` (ordinal weekday of the month)
+    /// * `FREQ=YEARLY;BYMONTH=3;BYMONTHDAY=8,9,10,11,12,13,14;BYDAY=SU`
+    /// * `FREQ=YEARLY` alone, repeating the DTSTART month and day
+    fn parse(rrule: &str, dtstart: NaiveDateTime) -> Result<Self, &'static str> {
+        let mut freq = None;
+        let mut month = dtstart.month();
+        let mut by_day = None;
+        let mut month_days = Vec::new();
+        let mut until = None;
+
+        for part in rrule.split(';') {
+            let Some((key, value)) = part.split_once('=') else {
+                continue;
+            };
+            match key.to_uppercase().as_str() {
+                "FREQ" => freq = Some(value.to_uppercase()),
+                "BYMONTH" => month = value.parse().map_err(|_| "Unsupported BYMONTH")?,
+                "BYDAY" => by_day = Some(value.to_uppercase()),
+                "BYMONTHDAY" => {
+                    month_days = value
+                        .split(',')
+                        .map(|d| d.parse::<u32>())
+                        .collect::<Result<_, _>>()
+                        .map_err(|_| "Unsupported BYMONTHDAY")?;
+                },
+                "UNTIL" => {
+                    let value = value.trim_end_matches(['Z', 'z']);
+                    until = NaiveDateTime::parse_from_str(value, DT_FMT).ok();
+                },
+                "INTERVAL" if value == "1" => {},
+                "WKST" => {},
+                _ => return Err("Unsupported RRULE part in observance"),
+            }
+        }
+
+        if freq.as_deref() != Some("YEARLY") {
+            return Err("Only yearly observance rules are supported");
+        }
+
+        let (weekday, nth) = match by_day {
+            Some(by_day) => {
+                // Split "-1SU" / "2SU" / "SU" into ordinal and weekday
+                let split = by_day.len().checked_sub(2).ok_or("Unsupported BYDAY")?;
+                let (ordinal, day) = by_day.split_at(split);
+                let weekday = parse_weekday(day).ok_or("Unsupported BYDAY")?;
+                let nth = match ordinal {
+                    "" | "+" => 0,
+                    n => n.trim_start_matches('+').parse().map_err(|_| "Unsupported BYDAY")?,
+                };
+                let supported = match nth {
+                    -1 | 1..=5 => true,
+                    // A plain weekday needs BYMONTHDAY to pick the week
+                    0 => !month_days.is_empty(),
+                    _ => false,
+                };
+                if !supported {
+                    return Err("Unsupported BYDAY ordinal");
+                }
+                (weekday, nth)
+            },
+            None => {
+                // Plain yearly repetition of the DTSTART date
+                month_days = vec![dtstart.day()];
+                (dtstart.weekday(), 0)
+            },
+        };
+
+        Ok(YearlyRule { month, weekday, nth, month_days, until })
+    }
+
+    /// Returns the onset date in a year
+    fn date_in(&self, year: i32) -> Option<NaiveDate> {
+        match self.nth {
+            -1 => return last_weekday_of_month(year, self.month, self.weekday),
+            1..=5 => return NaiveDate::from_weekday_of_month_opt(year, self.month, self.weekday, self.nth as u8),
+            _ => {},
+        }
+
+        self.month_days
+            .iter()
+            .filter_map(|day| NaiveDate::from_ymd_opt(year, self.month, *day))
+            .find(|date| self.month_days.len() == 1 || date.weekday() == self.weekday)
+    }
+}
+
+/// Parses a UTC offset like `+0100`, `-0530` or `+013045` into seconds
+fn parse_offset(value: &str) -> Option<i32> {
+    let value = value.trim();
+    let (sign, digits) = match value.split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    if !(digits.len() == 4 || digits.len() == 6) || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[0..2].parse().ok()?;
+    let minutes: i32 = digits[2..4].parse().ok()?;
+    let seconds: i32 = digits.get(4..6).map_or(Some(0), |s| s.parse().ok())?;
+    Some(sign * (hours * 3600 + minutes * 60 + seconds))
+}
+
+/// Parses a two-letter iCalendar weekday
+pub(crate) fn parse_weekday(day: &str) -> Option<Weekday> {
+    match day {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Returns the last given weekday of a month
+pub(crate) fn last_weekday_of_month(year: i32, month: u32, weekday: Weekday) -> Option<NaiveDate> {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    let mut date = NaiveDate::from_ymd_opt(next_year, next_month, 1)?.pred_opt()?;
+    while date.weekday() != weekday {
+        date = date.pred_opt()?;
+    }
+    Some(date)
+}
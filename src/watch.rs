@@ -0,0 +1,236 @@
+//! Daemon following the classification of the current day and running a hook on changes.
+//! 
+//! MIT License
+//! 
+//! Copyright (c) 2026 66f94eae
+//! 
+//! Permission is hereby granted, free of charge, to any person obtaining a copy
+//! of this software and associated documentation files (the "Software"), to deal
+//! in the Software without restriction, including without limitation the rights
+//! to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+//! copies of the Software, and to permit persons to whom the Software is
+//! furnished to do so, subject to the following conditions:
+//! 
+//! The above copyright notice and this permission notice shall be included in all
+//! copies or substantial portions of the Software.
+//! 
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+//! IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+//! FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+//! AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+//! LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+//! OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+//! SOFTWARE.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::{Duration, Instant},
+};
+
+use chrono::{NaiveTime, TimeDelta, Utc};
+use choliday::{Choliday, DayDecision, SourceStatus, TargetZone, render_template};
+use log::{info, warn};
+use tokio::process::Command;
+
+/// Name of the state file in the cache directory
+pub const STATE_FILE: &str = "watch-state.json";
+
+/// Template of the line printed for each change
+const CHANGE_LINE: &str = "{date} ({weekday}) is a {kind}, decided by {source}";
+
+/// Follows the classification of the current day until interrupted
+/// 
+/// # Arguments
+/// * `choliday` - Analyzer to classify the days with
+/// * `zone` - Zone whose midnight starts a new day
+/// * `hook` - Command to run on each change, with placeholders, if any
+/// * `interval` - Time between reads of the calendar sources
+/// * `state` - File remembering the last classification across restarts
+/// 
+/// # Returns
+/// * `Err(String)` - The hook is not a valid command template; otherwise
+///   this runs until the process is stopped
+/// 
+/// # Note
+/// * The day is classified again at every midnight of `zone` and after every
+///   read of the sources; each change from the remembered classification is
+///   printed and runs the hook. The first run, without a state file, counts
+///   as a change
+/// * The hook is split into words like a shell would (quotes and backslashes,
+///   no expansions) before the placeholders are filled in, so a summary from
+///   a calendar cannot inject arguments; it runs without a shell
+/// * A failed hook is logged and runs again at the next check; the state is
+///   only saved once it succeeded
+/// * Unknown days are skipped, keeping the last classification
+pub async fn run(
+    choliday: &Choliday,
+    zone: TargetZone,
+    hook: Option<&str>,
+    interval: Duration,
+    state: &Path,
+) -> Result<(), String> {
+    let hook = hook.map(words).transpose()?;
+    let mut last = load_state(state);
+    let mut failures = 0u32;
+    let mut next_refresh = Instant::now() + interval;
+    loop {
+        let now = zone.to_wall_clock(Utc::now().timestamp_millis());
+        let today = now.date();
+        let decision = choliday.evaluate_many(&[today]).await.remove(0);
+        let args = match &hook {
+            Some(hook) => hook
+                .iter()
+                .map(|word| render_template(word, &decision))
+                .collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+
+        if decision.is_unknown() {
+            warn!("Cannot classify {}, keeping the last classification: {}", today, decision.source());
+        } else if last.as_ref().is_none_or(|last| !same_class(last, &decision)) {
+            if failures == 0 {
+                println!("{}", render_template(CHANGE_LINE, &decision)?);
+            }
+            if args.is_empty() || run_hook(&args).await {
+                failures = 0;
+                save_state(state, &decision);
+                last = Some(decision);
+            } else {
+                failures += 1;
+                warn!("Hook failed {} time(s) in a row, retrying at the next check", failures);
+            }
+        }
+
+        // Wake up for whichever comes first: the next day or the next read
+        let midnight = zone.to_instant((today + TimeDelta::days(1)).and_time(NaiveTime::MIN));
+        let until_midnight = Duration::from_millis((midnight - Utc::now().timestamp_millis()).max(0) as u64 + 1000);
+        let until_refresh = next_refresh.saturating_duration_since(Instant::now());
+        tokio::time::sleep(until_midnight.min(until_refresh)).await;
+        if Instant::now() >= next_refresh {
+            for report in choliday.refresh().await {
+                match report.status() {
+                    SourceStatus::Loaded { events } => info!("Read {} events from '{}'", events, report.source()),
+                    SourceStatus::Stale { cause, .. } => warn!("Using the cached copy of '{}': {}", report.source(), cause),
+                    SourceStatus::Failed(cause) => warn!("Cannot read '{}': {}", report.source(), cause),
+                    _ => {},
+                }
+            }
+            next_refresh = Instant::now() + interval;
+        }
+    }
+}
+
+/// Returns the default state file of a cache directory
+/// 
+/// # Arguments
+/// * `cache_dir` - Cache directory of the configuration
+pub fn default_state(cache_dir: &Path) -> PathBuf {
+    cache_dir.join(STATE_FILE)
+}
+
+/// Tells whether two decisions classify their days alike
+fn same_class(a: &DayDecision, b: &DayDecision) -> bool {
+    a.day_type() == b.day_type() && a.rest_kind() == b.rest_kind()
+}
+
+/// Runs the hook and logs its exit status and output
+/// 
+/// # Returns
+/// * Whether the hook ran and exited successfully
+async fn run_hook(args: &[String]) -> bool {
+    let output = match Command::new(&args[0]).args(&args[1..]).stdin(Stdio::null()).output().await {
+        Ok(output) => output,
+        Err(e) => {
+            warn!("Cannot run hook '{}': {}", args[0], e);
+            return false;
+        },
+    };
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        info!("Hook stdout: {}", line);
+    }
+    for line in String::from_utf8_lossy(&output.stderr).lines() {
+        if output.status.success() {
+            info!("Hook stderr: {}", line);
+        } else {
+            warn!("Hook stderr: {}", line);
+        }
+    }
+    if output.status.success() {
+        info!("Hook '{}' exited with {}", args[0], output.status);
+    } else {
+        warn!("Hook '{}' exited with {}", args[0], output.status);
+    }
+    output.status.success()
+}
+
+/// Splits a command line into words
+/// 
+/// # Arguments
+/// * `command` - Words separated by whitespace; single quotes keep everything
+///   literally, double quotes and backslashes keep whitespace and quotes
+/// 
+/// # Returns
+/// * `Err(String)` - The command is empty or leaves a quote open
+fn words(command: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(format!("Unclosed quote in command '{}'", command)),
+                    }
+                }
+            },
+            '"' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => word.extend(chars.next()),
+                        Some(c) => word.push(c),
+                        None => return Err(format!("Unclosed quote in command '{}'", command)),
+                    }
+                }
+            },
+            '\\' => word.get_or_insert_default().extend(chars.next()),
+            c => word.get_or_insert_default().push(c),
+        }
+    }
+    words.extend(word);
+    if words.is_empty() {
+        return Err("The hook command is empty".to_string());
+    }
+    Ok(words)
+}
+
+/// Reads the classification remembered by a previous run
+fn load_state(path: &Path) -> Option<DayDecision> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content)
+        .inspect_err(|e| warn!("Ignoring state file '{}': {}", path.display(), e))
+        .ok()
+}
+
+/// Remembers a classification for later runs, replacing the file atomically
+fn save_state(path: &Path, decision: &DayDecision) {
+    let write = || -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(decision)?)?;
+        fs::rename(&tmp, path)
+    };
+    if let Err(e) = write() {
+        warn!("Cannot save state file '{}': {}", path.display(), e);
+    }
+}
@@ -0,0 +1,30 @@
+//! Tests of the `blocking` feature: classifying without an async runtime.
+#![cfg(feature = "blocking")]
+
+mod common;
+
+use chrono::NaiveDate;
+use choliday::{Choliday, Conf, DayType};
+use common::fixture;
+
+/// Creates an analyzer of the holidays fixture targeting a day of October 2025
+fn analyzer(day: u32) -> Choliday {
+    let conf = Conf::load(fixture("holidays.toml")).unwrap();
+    let date = NaiveDate::from_ymd_opt(2025, 10, day).unwrap();
+    Choliday::builder(conf).date(date).use_cache(false).build().unwrap()
+}
+
+#[test]
+fn evaluate_blocking_classifies_without_a_runtime() {
+    let decision = analyzer(1).evaluate_blocking().unwrap();
+    assert_eq!(decision.day_type(), DayType::RestDay);
+    assert_eq!(analyzer(1).is_work_day_blocking(), Ok(false));
+    assert_eq!(analyzer(6).is_work_day_blocking(), Ok(true));
+}
+
+#[test]
+fn evaluate_blocking_within_a_runtime_is_an_error() {
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let result = runtime.block_on(async { analyzer(1).evaluate_blocking() });
+    assert_eq!(result.unwrap_err(), "Blocking calls cannot run within an async runtime, use the async API instead");
+}
@@ -0,0 +1,338 @@
+//! Tests of the business-day API of the library over the holidays fixture.
+
+mod common;
+
+use std::{fs, future::Future};
+
+use chrono::NaiveDate;
+use choliday::{Choliday, Conf, DayType, Ical, PublicEvent};
+use common::fixture;
+
+/// Parses a `YYYYmmDD` date
+fn date(date: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(date, "%Y%m%d").unwrap()
+}
+
+/// Runs a future to completion on a new runtime
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(future)
+}
+
+/// Reads the holidays fixture configuration
+/// 
+/// # Arguments
+/// * `base` - Extra `[base]` settings, e.g. `horizon_days = 3`
+fn conf(base: &str) -> Conf {
+    let conf = fs::read_to_string(fixture("holidays.toml"))
+        .unwrap()
+        .replace("[base]\n", &format!("[base]\n{}\n", base))
+        .replace("\"holidays.ics\"", &format!("{:?}", fixture("holidays.ics")));
+    Conf::from_toml(&conf).unwrap()
+}
+
+/// Creates an analyzer of the holidays fixture targeting 2025-09-30, see [`conf`]
+fn analyzer(base: &str) -> Choliday {
+    Choliday::builder(conf(base)).date(date("20250930")).use_cache(false).build().unwrap()
+}
+
+/// Checks whether the analyzer classifies a date as a work day
+fn is_work_day(choliday: &Choliday, date: NaiveDate) -> bool {
+    block_on(choliday.evaluate_many(&[date]))[0].day_type() == DayType::WorkDay
+}
+
+#[test]
+fn next_work_day_skips_holidays_and_weekends() {
+    let choliday = analyzer("");
+    // 2025-10-01 to 2025-10-03 are holidays, then comes a weekend
+    assert_eq!(block_on(choliday.next_work_day(date("20250930"))), Ok(Some(date("20251006"))));
+    // Sunday 2025-09-28 is a makeup work day
+    assert_eq!(block_on(choliday.next_work_day(date("20250926"))), Ok(Some(date("20250928"))));
+}
+
+#[test]
+fn add_work_days_walks_both_ways() {
+    let choliday = analyzer("");
+    assert_eq!(block_on(choliday.add_work_days(date("20251002"), 0)), Ok(Some(date("20251002"))));
+    assert_eq!(block_on(choliday.add_work_days(date("20250929"), 2)), Ok(Some(date("20251006"))));
+    assert_eq!(block_on(choliday.add_work_days(date("20251006"), -1)), Ok(Some(date("20250930"))));
+    assert_eq!(block_on(choliday.add_work_days(date("20251006"), -3)), Ok(Some(date("20250928"))));
+}
+
+#[test]
+fn search_stops_at_the_horizon() {
+    // The three holidays fill a horizon of three days
+    let choliday = analyzer("horizon_days = 3");
+    assert_eq!(block_on(choliday.next_work_day(date("20250930"))), Ok(None));
+    assert_eq!(block_on(choliday.add_work_days(date("20251006"), -1)), Ok(None));
+
+    let choliday = analyzer("horizon_days = 6");
+    assert_eq!(block_on(choliday.next_work_day(date("20250930"))), Ok(Some(date("20251006"))));
+}
+
+#[test]
+fn adding_then_subtracting_returns_to_a_work_day() {
+    let choliday = analyzer("");
+    for from in date("20250920").iter_days().take(25) {
+        for n in 1..=6 {
+            let there = block_on(choliday.add_work_days(from, n)).unwrap().unwrap();
+            let back = block_on(choliday.add_work_days(there, -n)).unwrap().unwrap();
+            assert!(is_work_day(&choliday, back), "{} {}", from, n);
+            // A rest day returns to the last work day before it
+            if is_work_day(&choliday, from) {
+                assert_eq!(back, from, "{} {}", from, n);
+            } else {
+                assert!(back < from && back.iter_days().skip(1).take_while(|day| *day < from).all(|day| !is_work_day(&choliday, day)));
+            }
+        }
+    }
+}
+
+#[test]
+fn work_days_between_counts_either_order() {
+    let choliday = analyzer("");
+    // Monday 2025-09-29 to Wednesday 2025-10-08: three holidays and a weekend
+    let count = block_on(choliday.work_days_between(date("20250929"), date("20251008"))).unwrap();
+    assert_eq!((count.work, count.rest, count.conflict, count.unknown), (5, 5, 0, 0));
+    assert_eq!(block_on(choliday.work_days_between(date("20251008"), date("20250929"))), Ok(count));
+
+    // Both bounds are included
+    let count = block_on(choliday.work_days_between(date("20250928"), date("20250928"))).unwrap();
+    assert_eq!((count.work, count.rest), (1, 0));
+}
+
+#[test]
+fn days_equal_evaluating_each_date() {
+    let choliday = analyzer("");
+    let days = block_on(choliday.days(date("20250926"), date("20251007"))).unwrap();
+    let json = |decision| serde_json::to_value(decision).unwrap();
+    let expected: Vec<_> = date("20250926")
+        .iter_days()
+        .take(12)
+        .map(|day| {
+            let choliday = Choliday::builder(conf("")).date(day).use_cache(false).build().unwrap();
+            (day, json(block_on(choliday.evaluate()).unwrap()))
+        })
+        .collect();
+    assert_eq!(days.len(), 12);
+    assert_eq!(days.clone().map(|(day, decision)| (day, json(decision))).collect::<Vec<_>>(), expected);
+
+    // Walked again and backwards, e.g. for the last rest day
+    let reversed: Vec<_> = days.rev().map(|(day, decision)| (day, json(decision))).collect();
+    assert!(reversed.iter().eq(expected.iter().rev()));
+    let last_rest_day = block_on(choliday.days(date("20251007"), date("20250926"))).unwrap().rev().find(|(_, d)| !d.is_work_day());
+    assert_eq!(last_rest_day.map(|(day, _)| day), Some(date("20251005")));
+}
+
+#[test]
+fn evening_and_midnight_in_utc_plus_8_fall_on_their_own_day() {
+    let at = |dt: &str| {
+        let dt = chrono::NaiveDateTime::parse_from_str(dt, "%Y%m%dT%H%M%S").unwrap();
+        let choliday = Choliday::builder(conf("")).datetime(dt).use_cache(false).build().unwrap();
+        block_on(choliday.is_work_day()).unwrap()
+    };
+    // Midnight in Shanghai is 16:00 UTC the day before
+    assert!(at("20250930T235959"));
+    assert!(!at("20251001T000000"));
+    assert!(!at("20251003T220000"));
+    assert!(!at("20251005T235959"));
+    assert!(at("20251006T000000"));
+}
+
+#[test]
+fn evening_query_matches_a_single_day_holiday() {
+    let holiday = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20251001\r\nSUMMARY:Holiday\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    let at = |hour: u32, day: u32| {
+        let dt = NaiveDate::from_ymd_opt(2025, 10, day).unwrap().and_hms_opt(hour, 0, 0).unwrap();
+        let conf = Conf::builder(choliday::Priority::RestOverWork).rest("Holiday").build().unwrap();
+        let choliday = Choliday::builder(conf)
+            .datetime(dt)
+            .timezone(chrono_tz::Asia::Shanghai)
+            .events(holiday)
+            .use_cache(false)
+            .build()
+            .unwrap();
+        block_on(choliday.is_work_day()).unwrap()
+    };
+    assert!(!at(0, 1));
+    assert!(!at(20, 1));
+    assert!(!at(23, 1));
+    assert!(at(0, 2));
+    assert!(at(8, 2));
+}
+
+#[test]
+fn working_hours_follow_configured_hours_and_overnight_shifts() {
+    let base = "work_hours = { \"1-5\" = \"09:00-18:00\", \"7\" = \"22:00-06:00\" }";
+    let at = |dt: &str| {
+        let dt = chrono::NaiveDateTime::parse_from_str(dt, "%Y%m%dT%H%M").unwrap();
+        let choliday = Choliday::builder(conf(base)).datetime(dt).use_cache(false).build().unwrap();
+        block_on(choliday.is_working_hours()).unwrap()
+    };
+    assert!(at("20250930T1000"));
+    assert!(!at("20250930T0859"));
+    assert!(!at("20250930T1800"));
+    // Never on a holiday
+    assert!(!at("20251001T1000"));
+    // The Sunday makeup work day works the night into Monday
+    assert!(at("20250928T2300"));
+    assert!(at("20250929T0300"));
+    // An ordinary Sunday rests, so does its night
+    assert!(!at("20251005T2300"));
+    assert!(!at("20251006T0300"));
+}
+
+#[test]
+fn timed_work_event_counts_on_both_days_it_covers() {
+    let shift = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;TZID=Asia/Shanghai:20251004T220000\r\n\
+                 DTEND;TZID=Asia/Shanghai:20251005T060000\r\nSUMMARY:Workday night shift\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    let at = |day: u32, hour: u32| {
+        let dt = NaiveDate::from_ymd_opt(2025, 10, day).unwrap().and_hms_opt(hour, 0, 0).unwrap();
+        let conf = Conf::builder(choliday::Priority::RestOverWork).work("Workday").build().unwrap();
+        let choliday = Choliday::builder(conf)
+            .datetime(dt)
+            .timezone(chrono_tz::Asia::Shanghai)
+            .events(shift)
+            .use_cache(false)
+            .build()
+            .unwrap();
+        block_on(choliday.is_working_hours()).unwrap()
+    };
+    assert!(!at(4, 21));
+    assert!(at(4, 23));
+    assert!(at(5, 5));
+    assert!(!at(5, 7));
+}
+
+#[test]
+fn evaluate_many_equals_evaluating_each_date() {
+    let json = |decision| serde_json::to_value(decision).unwrap();
+    let start = date("20250901");
+    // A fixed linear congruential sequence picks unordered dates, repeats included
+    let mut state: u64 = 20251001;
+    let mut offset = || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        chrono::TimeDelta::days((state >> 33) as i64 % 61)
+    };
+    let expected: Vec<_> = start
+        .iter_days()
+        .take(61)
+        .map(|day| {
+            let choliday = Choliday::builder(conf("")).date(day).use_cache(false).build().unwrap();
+            json(block_on(choliday.evaluate()).unwrap())
+        })
+        .collect();
+    for size in [1, 5, 30, 90] {
+        let dates: Vec<NaiveDate> = (0..size).map(|_| start + offset()).collect();
+        let decisions = block_on(analyzer("").evaluate_many(&dates));
+        assert_eq!(decisions.len(), dates.len());
+        for (day, decision) in dates.iter().zip(decisions) {
+            assert_eq!(json(decision), expected[(*day - start).num_days() as usize], "{}", day);
+        }
+    }
+}
+
+#[test]
+fn nth_work_day_of_month_skips_mid_week_holidays() {
+    let choliday = analyzer("");
+    let nth = |month: u32, n: u32| block_on(choliday.nth_work_day_of_month(2025, month, n)).unwrap();
+    // October 2025 starts with holidays from Wednesday to Friday and has 20 work days
+    assert_eq!(nth(10, 1), Some(date("20251006")));
+    assert_eq!(nth(10, 3), Some(date("20251008")));
+    assert_eq!(nth(10, 20), Some(date("20251031")));
+    assert_eq!(nth(10, 21), None);
+    assert_eq!(nth(10, 0), None);
+    assert!(block_on(choliday.nth_work_day_of_month(2025, 13, 1)).is_err());
+}
+
+#[test]
+fn nth_last_work_day_of_month_counts_from_the_end() {
+    let choliday = analyzer("");
+    let nth_last = |month: u32, n: u32| block_on(choliday.nth_last_work_day_of_month(2025, month, n)).unwrap();
+    assert_eq!(nth_last(10, 1), Some(date("20251031")));
+    assert_eq!(nth_last(10, 20), Some(date("20251006")));
+    assert_eq!(nth_last(10, 21), None);
+    assert_eq!(nth_last(10, 0), None);
+    // The makeup work day on Sunday 2025-09-28 counts
+    assert_eq!(nth_last(9, 3), Some(date("20250928")));
+}
+
+/// Describes an event by its public view
+fn describe(event: &PublicEvent) -> (String, String, String, bool, DayType) {
+    (event.summary().to_string(), event.start().to_rfc3339(), event.local_end().to_string(), event.is_all_day(), event.day_type())
+}
+
+#[test]
+fn events_between_lists_the_public_view_of_each_event() {
+    let mut ical = Ical::new(conf(""));
+    ical.set_use_cache(false);
+    let events = block_on(ical.events_between(date("20250901"), date("20251031")));
+    let described: Vec<_> = events.iter().map(describe).collect();
+    assert_eq!(
+        described,
+        [
+            ("Makeup Workday".to_string(), "2025-09-27T16:00:00+00:00".to_string(), "2025-09-29 00:00:00".to_string(), true, DayType::WorkDay),
+            ("National Day Holiday".to_string(), "2025-09-30T16:00:00+00:00".to_string(), "2025-10-04 00:00:00".to_string(), true, DayType::RestDay),
+        ]
+    );
+    assert_eq!(events[1].local_start(), date("20251001").and_hms_opt(0, 0, 0).unwrap());
+    assert_eq!(events[1].description(), None);
+    assert!(events[1].source().unwrap().ends_with("holidays.ics"));
+
+    // Either order of the bounds, and nothing outside them
+    let inverted: Vec<_> = block_on(ical.events_between(date("20251031"), date("20250901"))).iter().map(describe).collect();
+    assert_eq!(inverted, described);
+    assert!(block_on(ical.events_between(date("20251005"), date("20251031"))).is_empty());
+}
+
+#[test]
+fn upcoming_rest_days_lists_rest_events_within_the_horizon() {
+    let upcoming = |base: &str, n: usize| {
+        let events = block_on(analyzer(base).upcoming_rest_days(n)).unwrap();
+        events.iter().map(|event| event.summary().to_string()).collect::<Vec<_>>()
+    };
+    assert_eq!(upcoming("", 5), ["National Day Holiday"]);
+    assert!(upcoming("", 0).is_empty());
+    assert!(upcoming("horizon_days = 0", 5).is_empty());
+}
+
+#[test]
+fn memoized_decisions_last_until_refresh() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("holidays.ics");
+    fs::copy(fixture("holidays.ics"), &path).unwrap();
+    let conf = fs::read_to_string(fixture("holidays.toml")).unwrap().replace("\"holidays.ics\"", &format!("{:?}", path));
+    let choliday = Choliday::builder(Conf::from_toml(&conf).unwrap()).date(date("20251001")).use_cache(false).build().unwrap();
+
+    assert_eq!(block_on(choliday.is_work_day()), Ok(false));
+    assert_eq!(block_on(choliday.is_work_day()), Ok(false));
+    assert!(choliday.source_table().ends_with("Memoized decisions: 1 (1 hits, 1 misses)"), "{}", choliday.source_table());
+
+    // Without the holidays the memoized answers stand until the sources are read again
+    let calendar = fs::read_to_string(&path).unwrap().replace("National Day Holiday", "National Day");
+    fs::write(&path, calendar).unwrap();
+    assert_eq!(block_on(choliday.is_work_day()), Ok(false));
+    assert!(!block_on(choliday.evaluate_many(&[date("20251001")]))[0].is_work_day());
+
+    block_on(choliday.refresh());
+    assert_eq!(block_on(choliday.is_work_day()), Ok(true));
+    assert!(block_on(choliday.evaluate_many(&[date("20251002")]))[0].is_work_day());
+}
+
+#[test]
+fn transitions_list_each_change_until_the_horizon() {
+    let transitions = |base: &str, n: usize| {
+        let transitions = block_on(analyzer(base).transitions(date("20250925"), n)).unwrap();
+        let source = fixture("holidays.ics").to_string_lossy().to_string();
+        transitions.iter().map(|t| t.to_string().replace(&source, "holidays.ics")).collect::<Vec<_>>()
+    };
+    let expected = [
+        "work_day -> rest_day on 2025-09-27 (configured work days (Sat))",
+        "rest_day -> work_day on 2025-09-28 (event 'Makeup Workday' from 'holidays.ics' matching 'Workday')",
+        "work_day -> rest_day on 2025-10-01 (event 'National Day Holiday' from 'holidays.ics' matching 'Holiday')",
+        "rest_day -> work_day on 2025-10-06 (configured work days (Mon))",
+    ];
+    assert_eq!(transitions("", 4), expected);
+    assert!(transitions("", 0).is_empty());
+    // Ten days from 2025-09-25 reach 2025-10-05, before the fourth change
+    assert_eq!(transitions("horizon_days = 10", 4), expected[..3]);
+}
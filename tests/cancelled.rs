@@ -0,0 +1,33 @@
+//! Tests of events with STATUS:CANCELLED and STATUS:TENTATIVE.
+
+mod common;
+
+use common::choliday;
+
+/// Classifies a date with the cancelled fixture, returning what the binary prints
+fn classify(date: &str) -> String {
+    let output = choliday("cancelled.toml").args(["-d", date]).output().unwrap();
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test]
+fn confirmed_holiday_is_a_rest_day() {
+    assert_eq!(classify("20251224"), "false");
+}
+
+#[test]
+fn cancelled_holiday_reverts_to_a_weekday() {
+    // 2025-12-26 is a Friday
+    assert_eq!(classify("20251226"), "true");
+}
+
+#[test]
+fn cancelled_workday_reverts_to_the_weekend() {
+    // 2025-12-27 is a Saturday
+    assert_eq!(classify("20251227"), "false");
+}
+
+#[test]
+fn tentative_holiday_is_kept() {
+    assert_eq!(classify("20251231"), "false");
+}
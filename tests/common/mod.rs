@@ -0,0 +1,20 @@
+//! Helpers shared by the integration tests.
+
+// Each test crate uses only some of the helpers
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use assert_cmd::Command;
+
+/// Returns the path of a file in `tests/fixtures`
+pub fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+}
+
+/// Starts the binary with a fixture configuration, skipping the on-disk cache
+pub fn choliday(conf: &str) -> Command {
+    let mut cmd = Command::cargo_bin("choliday").unwrap();
+    cmd.arg("-c").arg(fixture(conf)).arg("--no-cache");
+    cmd
+}
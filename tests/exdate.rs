@@ -0,0 +1,56 @@
+//! Tests of EXDATE against a fixture calendar of events in several zones.
+
+mod common;
+
+use common::choliday;
+
+/// Classifies a date with the EXDATE fixture, returning what the binary prints
+fn classify(date: &str) -> String {
+    let output = choliday("exdate.toml").args(["-d", date]).output().unwrap();
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test]
+fn date_only_exdate_matches_the_shanghai_date() {
+    // 06:00 in Shanghai is still 2025-09-30 in UTC
+    assert_eq!(classify("20251001"), "true");
+}
+
+#[test]
+fn date_only_exdate_matches_the_new_york_date() {
+    // 20:00 in New York is already 2025-10-03 in UTC and in Shanghai
+    assert_eq!(classify("20251003"), "true");
+}
+
+#[test]
+fn exdate_with_a_time_matches_the_start() {
+    assert_eq!(classify("20251006"), "true");
+}
+
+#[test]
+fn date_only_exdate_of_another_day_keeps_the_event() {
+    assert_eq!(classify("20251007"), "false");
+}
+
+#[test]
+fn date_only_exdate_removes_an_all_day_event() {
+    assert_eq!(classify("20251009"), "true");
+}
+
+#[test]
+fn exdate_removes_occurrences_of_a_weekly_event() {
+    // Fridays from 2025-09-26, except 10-03 and 10-17 and, by date only, 10-31
+    for (date, work) in [
+        ("20250926", "false"),
+        ("20251003", "true"),
+        ("20251010", "false"),
+        ("20251017", "true"),
+        ("20251024", "false"),
+        ("20251031", "true"),
+        ("20251107", "false"),
+    ] {
+        assert_eq!(classify(date), work, "{}", date);
+    }
+    // Other weekdays are untouched
+    assert_eq!(classify("20251023"), "true");
+}
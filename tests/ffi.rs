@@ -0,0 +1,80 @@
+//! Tests of the `ffi` feature: the checked-in header and the C harness in `examples/ffi.c`.
+#![cfg(all(feature = "ffi", unix))]
+
+mod common;
+
+use std::{env, fs, path::PathBuf, process::Command};
+
+use common::fixture;
+
+/// Root of the package
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn checked_in_header_is_current() {
+    let generated = fs::read_to_string(concat!(env!("OUT_DIR"), "/choliday.h")).unwrap();
+    let checked_in = manifest_dir().join("include/choliday.h");
+    if env::var_os("CHOLIDAY_UPDATE_HEADER").is_some() {
+        fs::write(&checked_in, &generated).unwrap();
+    }
+    assert!(
+        fs::read_to_string(&checked_in).unwrap() == generated,
+        "include/choliday.h is out of date, run CHOLIDAY_UPDATE_HEADER=1 cargo test --features ffi --test ffi"
+    );
+}
+
+#[test]
+fn c_harness_classifies_dates() {
+    // The library as a C caller links it
+    let status = Command::new(env!("CARGO"))
+        .current_dir(manifest_dir())
+        .args(["rustc", "--quiet", "--lib", "--features", "ffi", "--crate-type", "cdylib"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let lib_dir = manifest_dir().join("target/debug");
+
+    let dir = tempfile::tempdir().unwrap();
+    let harness = dir.path().join("ffi");
+    let compiler = cc::Build::new()
+        .cargo_metadata(false)
+        .target(env!("CHOLIDAY_TARGET"))
+        .host(env!("CHOLIDAY_TARGET"))
+        .opt_level(0)
+        .get_compiler();
+    let status = compiler
+        .to_command()
+        .arg("-I")
+        .arg(manifest_dir().join("include"))
+        .arg(manifest_dir().join("examples/ffi.c"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .args(["-lcholiday", "-o"])
+        .arg(&harness)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let run = |epoch_millis: &str| {
+        Command::new(&harness)
+            .arg(fixture("holidays.toml"))
+            .arg(epoch_millis)
+            .env("LD_LIBRARY_PATH", &lib_dir)
+            .env("DYLD_LIBRARY_PATH", &lib_dir)
+            .output()
+            .unwrap()
+    };
+    // 2025-10-01 10:00 in Asia/Shanghai, National Day
+    let output = run("1759284000000");
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "rest day");
+    // 2025-09-28 10:00 in Asia/Shanghai, a Sunday made a work day
+    let output = run("1759024800000");
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "work day");
+    // Timestamps beyond chrono's range are refused
+    let output = run("9223372036854775807");
+    assert_eq!(output.status.code(), Some(1));
+}
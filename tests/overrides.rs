@@ -0,0 +1,48 @@
+//! Tests of RECURRENCE-ID overrides of a weekly recurring event.
+
+mod common;
+
+use common::choliday;
+
+/// Classifies a date with the overrides fixture, returning what the binary prints
+fn classify(date: &str) -> String {
+    let output = choliday("overrides.toml").args(["-d", date]).output().unwrap();
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test]
+fn instances_without_override_follow_the_series() {
+    assert_eq!(classify("20251003"), "false");
+    assert_eq!(classify("20251031"), "false");
+}
+
+#[test]
+fn override_retypes_one_instance() {
+    assert_eq!(classify("20251010"), "true");
+}
+
+#[test]
+fn override_moves_one_instance() {
+    assert_eq!(classify("20251016"), "false");
+    assert_eq!(classify("20251017"), "true");
+}
+
+#[test]
+fn cancelled_override_removes_one_instance() {
+    assert_eq!(classify("20251024"), "true");
+}
+
+#[test]
+fn this_and_future_override_replaces_the_rest_of_the_series() {
+    assert_eq!(classify("20251107"), "true");
+    assert_eq!(classify("20251114"), "true");
+    assert_eq!(classify("20251121"), "true");
+}
+
+#[test]
+fn overrides_apply_to_range_queries() {
+    let output = choliday("overrides.toml").args(["export", "--from", "20251001", "--to", "20251130"]).output().unwrap();
+    let feed = String::from_utf8_lossy(&output.stdout);
+    let days: Vec<&str> = feed.lines().filter_map(|line| line.trim().strip_prefix("DTSTART;VALUE=DATE:")).collect();
+    assert_eq!(days, ["20251003", "20251016", "20251031"]);
+}
@@ -0,0 +1,161 @@
+//! Tests validating the `--json` output of every subcommand against `choliday schema`.
+
+mod common;
+
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use common::{choliday, fixture};
+use serde_json::Value;
+
+/// Validates a payload against the schema the binary prints
+fn assert_valid(payload: &[u8]) {
+    let schema = choliday::json_schema();
+    let validator = jsonschema::options().should_validate_formats(true).build(&schema).unwrap();
+    let payload: Value = serde_json::from_slice(payload).unwrap();
+    let errors: Vec<String> = validator.iter_errors(&payload).map(|e| format!("{} at {}", e, e.instance_path)).collect();
+    assert!(errors.is_empty(), "{:#}\n{}", payload, errors.join("\n"));
+}
+
+/// Runs the binary with `--json` on the holidays fixture and validates what it prints
+fn assert_valid_output(date: &str, args: &[&str]) {
+    let output = choliday("holidays.toml").args(["--json", "-d", date]).args(args).output().unwrap();
+    assert!(!output.stdout.is_empty(), "{:?} printed nothing", args);
+    assert_valid(&output.stdout);
+}
+
+#[test]
+fn schema_command_prints_the_library_schema() {
+    let output = choliday("holidays.toml").arg("schema").output().unwrap();
+    assert!(output.status.success());
+    let printed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(printed, choliday::json_schema());
+    assert!(jsonschema::meta::is_valid(&printed));
+
+    let validator = jsonschema::validator_for(&printed).unwrap();
+    let output = choliday("holidays.toml").args(["--json", "-d", "20251001"]).output().unwrap();
+    let mut decision: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(validator.is_valid(&decision));
+    decision["day_type"] = "holiday".into();
+    assert!(!validator.is_valid(&decision));
+    decision["day_type"] = "rest_day".into();
+    decision["schema_version"] = 2.into();
+    assert!(!validator.is_valid(&decision));
+}
+
+#[test]
+fn decision_matches_the_schema() {
+    assert_valid_output("20251001", &[]);
+    assert_valid_output("20250928", &[]);
+    assert_valid_output("20251006", &[]);
+}
+
+#[test]
+fn conflict_matches_the_schema() {
+    let output = choliday("conflict.toml")
+        .args(["--json", "--on-conflict", "error", "-d", "20251001"])
+        .output()
+        .unwrap();
+    assert_valid(&output.stdout);
+    let output = choliday("conflict.toml").args(["--json", "-d", "20251001"]).output().unwrap();
+    assert_valid(&output.stdout);
+}
+
+#[test]
+fn count_matches_the_schema() {
+    assert_valid_output("20251001", &["count", "--from", "20250925", "--to", "20251008"]);
+}
+
+#[test]
+fn trace_matches_the_schema() {
+    assert_valid_output("20251001", &["list"]);
+}
+
+#[test]
+fn transitions_match_the_schema() {
+    assert_valid_output("20251001", &["transitions"]);
+}
+
+#[test]
+fn diff_matches_the_schema() {
+    let against = fixture("conflict.toml");
+    assert_valid_output("20251001", &["diff", "--against", against.to_str().unwrap(), "--from", "20250925", "--to", "20251008"]);
+}
+
+#[test]
+fn bridges_match_the_schema() {
+    assert_valid_output("20251001", &["bridges", "--year", "2025", "--max-gap", "3"]);
+}
+
+#[test]
+fn rest_blocks_match_the_schema() {
+    assert_valid_output("20251001", &["long-weekends", "--from", "20250925", "--to", "20251008"]);
+}
+
+#[test]
+fn export_matches_the_schema() {
+    assert_valid_output("20251001", &["export", "--from", "20250925", "--to", "20251008"]);
+}
+
+#[test]
+fn error_matches_the_schema() {
+    let output = choliday("holidays.toml").args(["--json", "changes"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert_valid(&output.stdout);
+}
+
+#[test]
+fn changes_match_the_schema() {
+    let calendar = Arc::new(Mutex::new(fs::read_to_string(fixture("holidays.ics")).unwrap()));
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let served = Arc::clone(&calendar);
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            let body = served.lock().unwrap().clone();
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: text/calendar\r\nCache-Control: no-cache\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+        }
+    });
+
+    let dir = tempfile::tempdir().unwrap();
+    let conf = dir.path().join("remote.toml");
+    fs::write(
+        &conf,
+        format!(
+            "[base]\ntimezone = \"Asia/Shanghai\"\n\n[calendar]\nsource = [\"http://{}/holidays.ics\"]\n\n\
+             [predict]\nwork = [\"Workday\"]\nrest = [\"Holiday\"]\npriority = \"RestOverWork\"\n\n[cache]\ndir = \"{}\"\n",
+            address,
+            dir.path().join("cache").display()
+        ),
+    )
+    .unwrap();
+    let run = |args: &[&str]| {
+        let mut cmd = assert_cmd::Command::cargo_bin("choliday").unwrap();
+        cmd.arg("-c").arg(&conf).args(["-d", "20250920"]).args(args).output().unwrap()
+    };
+    assert!(run(&[]).status.code().is_some());
+
+    let revised = calendar.lock().unwrap().replace("DTEND;VALUE=DATE:20251004", "DTEND;VALUE=DATE:20251008");
+    *calendar.lock().unwrap() = revised;
+    let output = run(&["--json", "changes", "--days", "30"]);
+    assert_eq!(output.status.code(), Some(1), "{}", String::from_utf8_lossy(&output.stderr));
+    let changes: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(changes["changes"].as_array().unwrap().len(), 4);
+    assert_valid(&output.stdout);
+}
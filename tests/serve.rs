@@ -0,0 +1,187 @@
+//! Tests of `serve`, fetching the feed over HTTP from the running binary.
+
+mod common;
+
+use std::{
+    fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::Path,
+    process::{Child, Stdio},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+use chrono::NaiveDate;
+use choliday::{Choliday, Conf, Priority};
+use common::{choliday, fixture};
+
+/// Server process, stopped when dropped
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Starts `serve` on a free port and returns it with its address
+fn serve(conf: &str) -> (Server, String) {
+    serve_with(&fixture(conf), &["--no-cache"])
+}
+
+/// Starts `serve` with a configuration file and global options
+fn serve_with(conf: &Path, args: &[&str]) -> (Server, String) {
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("choliday"))
+        .arg("-c")
+        .arg(conf)
+        .args(args)
+        .args(["serve", "--listen", "127.0.0.1:0"])
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let stderr = BufReader::new(child.stderr.take().unwrap());
+    let server = Server(child);
+    for line in stderr.lines() {
+        let line = line.unwrap();
+        if let Some(url) = line.strip_prefix("Serving http://") {
+            let address = url.split('/').next().unwrap().to_string();
+            return (server, address);
+        }
+    }
+    panic!("serve exited before listening");
+}
+
+/// Sends a GET request and returns the status line and the body
+fn get(address: &str, target: &str) -> (String, String) {
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(stream, "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n", target, address).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    (head.lines().next().unwrap().to_string(), body.to_string())
+}
+
+#[test]
+fn feed_parses_with_the_crate_parser() {
+    let (_server, address) = serve("holidays.toml");
+    let (status, body) = get(&address, "/feed.ics?from=2025-09-27&to=2025-10-05&work=true");
+    assert_eq!(status, "HTTP/1.1 200 OK");
+
+    // The feed classifies the days through X-CHOLIDAY-TYPE alone, no patterns needed
+    let day = |date: NaiveDate| {
+        let conf = Conf::builder(Priority::RestOverWork).build().unwrap();
+        Choliday::builder(conf).date(date).timezone(chrono_tz::Asia::Shanghai).events(body.as_str()).build().unwrap()
+    };
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        for holiday in 1..=3 {
+            let date = NaiveDate::from_ymd_opt(2025, 10, holiday).unwrap();
+            assert!(!day(date).is_work_day().await.unwrap(), "{}", date);
+        }
+        let makeup = day(NaiveDate::from_ymd_opt(2025, 9, 28).unwrap()).evaluate().await.unwrap();
+        assert!(makeup.is_work_day());
+        assert!(makeup.source().to_string().contains("X-CHOLIDAY-TYPE"), "{}", makeup.source());
+    });
+}
+
+#[test]
+fn feed_defaults_to_today() {
+    let (_server, address) = serve("holidays.toml");
+    let (status, body) = get(&address, "/feed.ics");
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert!(body.starts_with("BEGIN:VCALENDAR"), "{}", body);
+    // The fixture holidays lie before the current date
+    assert!(!body.contains("DTSTART;VALUE=DATE:20251001"), "{}", body);
+}
+
+#[test]
+fn serve_rejects_stdin() {
+    let output = choliday("stdin.toml").args(["--json", "serve", "--listen", "127.0.0.1:0"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["error"].as_str().unwrap().contains("standard input"), "{}", json);
+}
+
+/// Serves the holidays fixture calendar over HTTP, answering 500 while `up` is false
+fn upstream(up: Arc<AtomicBool>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let calendar = fs::read_to_string(fixture("holidays.ics")).unwrap();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            // Skip the request head, the answer does not depend on it
+            BufReader::new(&stream).lines().map_while(Result::ok).take_while(|line| !line.is_empty()).for_each(drop);
+            let (status, body) = if up.load(Ordering::SeqCst) { ("200 OK", calendar.as_str()) } else { ("500 Internal Server Error", "") };
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: text/calendar\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+        }
+    });
+    address
+}
+
+/// Asks `/status` until its first source has the given status, returning the body
+fn wait_for_status(address: &str, expected: &str) -> serde_json::Value {
+    for _ in 0..40 {
+        let (_, body) = get(address, "/status");
+        let status: serde_json::Value = serde_json::from_str(&body).unwrap();
+        if status["sources"][0]["status"] == expected {
+            return status;
+        }
+        thread::sleep(Duration::from_millis(250));
+    }
+    panic!("source never became {}", expected);
+}
+
+#[test]
+fn feed_is_served_from_the_last_good_copy_while_the_upstream_is_down() {
+    let up = Arc::new(AtomicBool::new(true));
+    let upstream = upstream(Arc::clone(&up));
+    let dir = tempfile::tempdir().unwrap();
+    let conf = dir.path().join("config.toml");
+    let source = format!("http://user:secret@{}/holidays.ics", upstream);
+    fs::write(
+        &conf,
+        format!(
+            "[base]\ntimezone = \"Asia/Shanghai\"\n[calendar]\nsource = [{:?}]\n\
+             [predict]\nwork = [\"Workday\"]\nrest = [\"Holiday\"]\npriority = \"RestOverWork\"\n\
+             [cache]\ndir = {:?}\n[http]\nretries = 0\n[serve]\nrefresh = \"1s\"\n",
+            source,
+            dir.path().join("cache")
+        ),
+    )
+    .unwrap();
+    let (_server, address) = serve_with(&conf, &[]);
+    let feed = "/feed.ics?from=2025-10-01&to=2025-10-03";
+    assert!(get(&address, feed).1.contains("DTSTART;VALUE=DATE:20251001"));
+
+    // While the upstream fails, the feed and status answer from the cached copy
+    up.store(false, Ordering::SeqCst);
+    let status = wait_for_status(&address, "stale");
+    let stale = &status["sources"][0];
+    assert_eq!(stale["source"], format!("http://***@{}/holidays.ics", upstream));
+    assert_eq!(stale["events"], 2);
+    assert_eq!(status["swapped"], false);
+    assert!(stale["error"].as_str().unwrap().contains("HTTP 500"), "{}", status);
+    assert!(!status.to_string().contains("secret"), "{}", status);
+    let (code, body) = get(&address, feed);
+    assert_eq!(code, "HTTP/1.1 200 OK");
+    assert!(body.contains("DTSTART;VALUE=DATE:20251001"), "{}", body);
+
+    // And recover once it is back
+    up.store(true, Ordering::SeqCst);
+    let status = wait_for_status(&address, "loaded");
+    assert_eq!(status["swapped"], true);
+    assert!(get(&address, feed).1.contains("DTSTART;VALUE=DATE:20251001"));
+}
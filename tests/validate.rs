@@ -0,0 +1,63 @@
+//! Tests of `Conf::validate`, one fixture configuration per check.
+
+mod common;
+
+use choliday::Conf;
+use common::{choliday, fixture};
+
+/// Loads a fixture of `tests/fixtures/validate` and returns the keys of its problems
+fn problems(name: &str) -> Vec<String> {
+    let conf = Conf::load(fixture(&format!("validate/{}", name)).to_str().unwrap()).unwrap();
+    match conf.validate() {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.iter().map(|e| e.key().to_string()).collect(),
+    }
+}
+
+#[test]
+fn valid() {
+    assert!(problems("valid.toml").is_empty());
+}
+
+#[test]
+fn pattern_in_work_and_rest() {
+    assert_eq!(problems("pattern_overlap.toml"), ["predict.rest_public"]);
+}
+
+#[test]
+fn category_in_work_and_rest() {
+    assert_eq!(problems("category_overlap.toml"), ["predict.rest_categories"]);
+}
+
+#[test]
+fn workday_listing_no_day() {
+    assert_eq!(problems("empty_workday.toml"), ["base.workday"]);
+}
+
+#[test]
+fn workday_none_is_accepted() {
+    assert!(problems("no_workdays.toml").is_empty());
+    let output = choliday("validate/no_workdays.toml").args(["-d", "20251006"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "false");
+}
+
+#[test]
+fn require_calendar_without_sources() {
+    assert_eq!(problems("require_calendar.toml"), ["predict.require_calendar"]);
+}
+
+#[test]
+fn min_sources_above_configured() {
+    assert_eq!(problems("min_sources.toml"), ["calendar.min_sources"]);
+}
+
+#[test]
+fn several_problems_reported_at_once() {
+    assert_eq!(problems("several.toml"), ["predict.rest", "predict.require_calendar"]);
+    let output = choliday("validate/several.toml").output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("predict.rest: pattern 'Workday' is also listed in predict.work"), "{}", stderr);
+    assert!(stderr.contains("predict.require_calendar: no calendar source"), "{}", stderr);
+}